@@ -1,9 +1,10 @@
 //! Contains the [`ArrowSumConstraint`] struct for representing an arrow sum constraint.
 
+use std::sync::Arc;
 use sudoku_solver_lib::prelude::*;
 
 /// A [`Constraint`] implementation for representing an arrow sum constraint.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SudokuSolverConstraint {
     specific_name: String,
     circle_cells: Vec<CellIndex>,
@@ -36,6 +37,10 @@ impl Constraint for SudokuSolverConstraint {
         &self.specific_name
     }
 
+    fn clone_constraint(&self) -> Option<Arc<dyn Constraint>> {
+        Some(Arc::new(self.clone()))
+    }
+
     fn init_board(&mut self, board: &mut Board) -> LogicalStepResult {
         // let mut changed = false;
 
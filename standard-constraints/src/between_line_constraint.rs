@@ -0,0 +1,277 @@
+//! Contains the [`BetweenLineConstraint`] struct for representing a between line.
+
+use itertools::Itertools;
+use std::sync::Arc;
+use sudoku_solver_lib::prelude::*;
+
+/// A [`Constraint`] implementation for representing a between line, whose cells all have values
+/// strictly between the values of the two ends of the line, in either order.
+///
+/// # Example
+/// ```
+/// # use standard_constraints::prelude::*;
+/// # use sudoku_solver_lib::prelude::*;
+/// # use std::sync::Arc;
+/// let cu = CellUtility::new(9);
+/// let line = BetweenLineConstraint::new(vec![cu.cell(0, 0), cu.cell(0, 1), cu.cell(0, 2)]);
+/// let solver = SolverBuilder::default().with_constraint(Arc::new(line)).with_given(cu.cell(0, 0), 1).build().unwrap();
+/// assert_eq!(solver.board().cell(cu.cell(0, 2)), ValueMask::from_between_inclusive(3, 9, 9));
+/// ```
+#[derive(Debug, Clone)]
+pub struct BetweenLineConstraint {
+    specific_name: String,
+    ends: (CellIndex, CellIndex),
+    inner_cells: Vec<CellIndex>,
+    is_inner_grouped: bool,
+}
+
+impl BetweenLineConstraint {
+    /// Creates a new [`BetweenLineConstraint`] from the cells of the line in order, where the first and last
+    /// cells are the ends of the line.
+    ///
+    /// # Panics
+    /// Panics if the line has fewer than two cells.
+    pub fn new(cells: Vec<CellIndex>) -> Self {
+        assert!(cells.len() >= 2, "A between line needs two ends");
+        let specific_name = format!("Between Line at {}", cells[0]);
+        let ends = (cells[0], cells[cells.len() - 1]);
+        let inner_cells = cells[1..cells.len() - 1]
+            .iter()
+            .copied()
+            .filter(|&cell| cell != ends.0 && cell != ends.1)
+            .unique()
+            .collect();
+        Self { specific_name, ends, inner_cells, is_inner_grouped: false }
+    }
+
+    /// The cells at the ends of the line.
+    pub fn ends(&self) -> (CellIndex, CellIndex) {
+        self.ends
+    }
+
+    /// The cells of the line other than the ends.
+    pub fn inner_cells(&self) -> &[CellIndex] {
+        &self.inner_cells
+    }
+
+    /// Whether the inner cells can all have values strictly between the values of the ends.
+    fn fits_between(&self, board: &Board, value0: usize, value1: usize) -> bool {
+        let between = ValueMask::from_between_exclusive(value0.min(value1), value0.max(value1), board.size());
+        let masks = self.inner_cells.iter().map(|&cell| board.cell(cell).unsolved() & between).collect::<Vec<_>>();
+        if masks.iter().any(|mask| mask.is_empty()) {
+            return false;
+        }
+
+        // Grouped cells need a different value each.
+        !self.is_inner_grouped
+            || masks.iter().fold(ValueMask::new(), |all, &mask| all | mask).count() >= self.inner_cells.len()
+    }
+
+    /// Finds the values each cell can have for at least one pair of values of the ends,
+    /// in the order of the ends followed by the inner cells.
+    fn possible_values(&self, board: &Board) -> Vec<ValueMask> {
+        let (end0, end1) = self.ends;
+        let size = board.size();
+        let mut possible = vec![ValueMask::new(); self.inner_cells.len() + 2];
+        for value0 in board.cell(end0).unsolved() {
+            for value1 in board.cell(end1).unsolved() {
+                if end0 == end1 && value0 != value1 {
+                    continue;
+                }
+                if value0.abs_diff(value1) < 2 && !self.inner_cells.is_empty() {
+                    continue;
+                }
+                if board.has_weak_link(end0.candidate(value0), end1.candidate(value1)) {
+                    continue;
+                }
+                if !self.fits_between(board, value0, value1) {
+                    continue;
+                }
+
+                possible[0] = possible[0].with(value0);
+                possible[1] = possible[1].with(value1);
+                let between = ValueMask::from_between_exclusive(value0.min(value1), value0.max(value1), size);
+                for (possible, &cell) in possible[2..].iter_mut().zip(self.inner_cells.iter()) {
+                    *possible = *possible | (board.cell(cell).unsolved() & between);
+                }
+            }
+        }
+        possible
+    }
+
+    /// Removes the candidates which aren't part of any valid filling of the line.
+    fn eliminate_impossible(&self, board: &mut Board) -> (LogicalStepResult, EliminationList) {
+        let possible = self.possible_values(board);
+        let cells = [self.ends.0, self.ends.1].into_iter().chain(self.inner_cells.iter().copied()).collect::<Vec<_>>();
+        let elims = EliminationList::from_possible(&cells, &possible, board);
+        (elims.execute(board), elims)
+    }
+}
+
+impl Constraint for BetweenLineConstraint {
+    fn name(&self) -> &str {
+        &self.specific_name
+    }
+
+    fn clone_constraint(&self) -> Option<Arc<dyn Constraint>> {
+        Some(Arc::new(self.clone()))
+    }
+
+    fn init_board(&mut self, board: &mut Board) -> LogicalStepResult {
+        self.is_inner_grouped = self.inner_cells.len() > 1 && board.is_grouped(&self.inner_cells);
+        self.eliminate_impossible(board).0
+    }
+
+    fn enforce(&self, board: &Board, _cell: CellIndex, _val: usize) -> LogicalStepResult {
+        // Inner values equal to an end are enforced by the weak links, but not those outside of the ends.
+        let (mask0, mask1) = (board.cell(self.ends.0), board.cell(self.ends.1));
+        if !mask0.is_single() || !mask1.is_single() {
+            return LogicalStepResult::None;
+        }
+
+        let between = ValueMask::from_between_exclusive(
+            mask0.value().min(mask1.value()),
+            mask0.value().max(mask1.value()),
+            board.size(),
+        );
+        if self.inner_cells.iter().any(|&cell| (board.cell(cell) & between).is_empty()) {
+            return LogicalStepResult::Invalid(None);
+        }
+        LogicalStepResult::None
+    }
+
+    fn step_logic(&self, board: &mut Board, is_brute_forcing: bool, _cancellation: &Cancellation) -> LogicalStepResult {
+        // The solved ends are what narrow down the rest of the line.
+        let solved: Vec<CandidateIndex> = if is_brute_forcing {
+            Vec::new()
+        } else {
+            [self.ends.0, self.ends.1]
+                .into_iter()
+                .unique()
+                .filter(|&cell| board.cell(cell).is_solved())
+                .map(|cell| cell.candidate(board.cell(cell).value()))
+                .collect()
+        };
+
+        let (result, elims) = self.eliminate_impossible(board);
+        if is_brute_forcing {
+            return result;
+        }
+        match result {
+            LogicalStepResult::Invalid(_) => {
+                LogicalStepResult::Invalid(Some("The line has no valid values for its ends.".into()))
+            }
+            LogicalStepResult::Changed(_) => {
+                let mut desc = LogicalStepDesc::from_elims("Not between the ends of the line", &elims);
+                if !solved.is_empty() {
+                    desc = desc.with_cause(StepCause::new(solved));
+                }
+                LogicalStepResult::Changed(Some(desc))
+            }
+            LogicalStepResult::None => LogicalStepResult::None,
+        }
+    }
+
+    fn relevant_cells(&self) -> Vec<CellIndex> {
+        [self.ends.0, self.ends.1].into_iter().chain(self.inner_cells.iter().copied()).unique().collect()
+    }
+
+    fn get_weak_links(&self, size: usize) -> Vec<(CandidateIndex, CandidateIndex)> {
+        if self.inner_cells.is_empty() {
+            return Vec::new();
+        }
+
+        let (end0, end1) = self.ends;
+        let mut result = Vec::new();
+        for value in 1..=size {
+            // An inner cell is strictly between the ends, so it can't have the value of either of them.
+            for &cell in self.inner_cells.iter() {
+                result.push((end0.candidate(value), cell.candidate(value)));
+                if end1 != end0 {
+                    result.push((end1.candidate(value), cell.candidate(value)));
+                }
+            }
+
+            // The ends need at least one value between them.
+            if end1 != end0 {
+                for other_value in value.saturating_sub(1).max(1)..=(value + 1).min(size) {
+                    result.push((end0.candidate(value), end1.candidate(other_value)));
+                }
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[test]
+    fn test_between_line() {
+        let cu = CellUtility::new(9);
+        let line = BetweenLineConstraint::new(vec![cu.cell(0, 0), cu.cell(0, 1), cu.cell(0, 2), cu.cell(0, 3)]);
+        assert_eq!(line.name(), "Between Line at r1c1");
+        let mut solver = SolverBuilder::default().with_constraint(Arc::new(line)).build().unwrap();
+        assert_eq!(solver.board().cell(cu.cell(0, 1)), ValueMask::from_between_inclusive(2, 8, 9));
+
+        // The two grouped inner cells need two values between the ends.
+        assert!(solver.set_solved(cu.cell(0, 0), 3));
+        assert!(solver.run_logical_solve().is_changed());
+        assert_eq!(solver.board().cell(cu.cell(0, 3)), ValueMask::from_between_inclusive(6, 9, 9));
+        assert_eq!(solver.board().cell(cu.cell(0, 1)), ValueMask::from_between_inclusive(4, 8, 9));
+
+        assert!(solver.set_solved(cu.cell(0, 3), 6));
+        let result = solver.run_single_logical_step();
+        assert!(result.to_string().starts_with("Between Line at r1c1: Not between the ends of the line"), "{result}");
+        assert_eq!(solver.board().cell(cu.cell(0, 2)), ValueMask::from_values(&[4, 5]));
+
+        // Values outside of the ends are invalid.
+        let line = BetweenLineConstraint::new(vec![cu.cell(0, 0), cu.cell(0, 1), cu.cell(0, 2), cu.cell(0, 3)]);
+        let mut solver = SolverBuilder::default().with_constraint(Arc::new(line)).build().unwrap();
+        assert!(solver.set_solved(cu.cell(0, 1), 7));
+        assert!(solver.set_solved(cu.cell(0, 0), 3));
+        assert!(!solver.set_solved(cu.cell(0, 3), 6));
+    }
+
+    #[test]
+    fn test_weak_links() {
+        let cu = CellUtility::new(9);
+        let (end0, inner, end1) = (cu.cell(0, 0), cu.cell(4, 4), cu.cell(8, 8));
+        let line = BetweenLineConstraint::new(vec![end0, inner, end1]);
+        let solver = SolverBuilder::default().with_constraint(Arc::new(line)).build().unwrap();
+        let board = solver.board();
+        assert!(board.has_weak_link(end0.candidate(5), inner.candidate(5)));
+        assert!(board.has_weak_link(end1.candidate(5), inner.candidate(5)));
+        assert!(board.has_weak_link(end0.candidate(4), end1.candidate(5)));
+        assert!(!board.has_weak_link(end0.candidate(4), end1.candidate(6)));
+        assert!(!board.has_weak_link(end0.candidate(4), inner.candidate(5)));
+        assert_eq!(board.cell(inner), ValueMask::from_between_inclusive(2, 8, 9));
+    }
+
+    #[test]
+    fn test_ends() {
+        let cu = CellUtility::new(9);
+        let (end0, inner, end1) = (cu.cell(0, 0), cu.cell(4, 4), cu.cell(8, 8));
+        let make_solver = || {
+            let line = BetweenLineConstraint::new(vec![end0, inner, end1]);
+            SolverBuilder::default().with_constraint(Arc::new(line)).build().unwrap()
+        };
+
+        // The other end can't be the same value or one away.
+        let mut solver = make_solver();
+        assert!(solver.set_solved(end0, 3));
+        solver.run_logical_solve();
+        assert_eq!(solver.board().cell(end1), ValueMask::from_values(&[1, 5, 6, 7, 8, 9]));
+
+        // The ends are on either side of the inner value.
+        let mut solver = make_solver();
+        assert!(solver.set_solved(inner, 5));
+        assert!(!solver.board().cell(end0).has(5));
+        assert!(solver.set_solved(end0, 7));
+        solver.run_logical_solve();
+        assert_eq!(solver.board().cell(end1), ValueMask::from_between_inclusive(1, 4, 9));
+    }
+}
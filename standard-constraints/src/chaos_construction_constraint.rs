@@ -0,0 +1,419 @@
+//! Contains the [`ChaosConstructionConstraint`] struct for representing regions which must be deduced.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use sudoku_solver_lib::prelude::*;
+
+/// A [`Constraint`] implementation for chaos construction, where the grid must be divided into
+/// orthogonally connected regions of `size` cells, and no digit may repeat within a region.
+///
+/// The board must be built with [`SolverBuilder::with_unknown_regions`], so that it has a
+/// [`RegionMembership`] to narrow down. Other constraints which depend on the regions, such as
+/// region sum lines, can narrow it down further.
+///
+/// A solution is a grid of digits which can be divided into valid regions. The regions of the
+/// solution are one such division, so a grid with more than one division is only found once.
+#[derive(Debug, Clone)]
+pub struct ChaosConstructionConstraint {
+    specific_name: String,
+}
+
+impl ChaosConstructionConstraint {
+    pub fn new() -> Self {
+        Self { specific_name: "Chaos Construction".to_owned() }
+    }
+}
+
+impl Default for ChaosConstructionConstraint {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Constraint for ChaosConstructionConstraint {
+    fn name(&self) -> &str {
+        &self.specific_name
+    }
+
+    fn clone_constraint(&self) -> Option<Arc<dyn Constraint>> {
+        Some(Arc::new(self.clone()))
+    }
+
+    fn init_board(&mut self, board: &mut Board) -> LogicalStepResult {
+        if board.region_membership().is_none() {
+            return LogicalStepResult::Invalid(Some("The regions of the board must be unknown.".into()));
+        }
+        LogicalStepResult::None
+    }
+
+    fn enforce(&self, board: &Board, cell: CellIndex, val: usize) -> LogicalStepResult {
+        let Some(membership) = board.region_membership() else {
+            return LogicalStepResult::None;
+        };
+        let Some(region) = membership.region(cell) else {
+            return LogicalStepResult::None;
+        };
+
+        let repeats = membership.cells_in(region).into_iter().any(|other| {
+            let mask = board.cell(other);
+            other != cell && mask.is_single() && mask.value() == val
+        });
+        if repeats {
+            LogicalStepResult::Invalid(None)
+        } else {
+            LogicalStepResult::None
+        }
+    }
+
+    fn step_logic(&self, board: &mut Board, is_brute_forcing: bool, _cancellation: &Cancellation) -> LogicalStepResult {
+        let Some(original) = board.region_membership().cloned() else {
+            return LogicalStepResult::None;
+        };
+
+        let mut membership = original.clone();
+        let mut elims = EliminationList::new();
+        loop {
+            let changed = match Self::step_regions(board, &mut membership, &mut elims) {
+                Ok(changed) => changed,
+                Err(reason) => return LogicalStepResult::Invalid(Some(reason.into())),
+            };
+            if !changed {
+                break;
+            }
+        }
+
+        // Once every digit is placed, the regions only need to be divided up.
+        if board.all_cell_masks().all(|(_, mask)| mask.is_single()) && !membership.is_determined() {
+            match Self::find_regions(board, &membership) {
+                Some(found) => membership = found,
+                None => return LogicalStepResult::Invalid(Some("The digits can't be divided into regions.".into())),
+            }
+        }
+
+        if membership == original && elims.is_empty() {
+            return LogicalStepResult::None;
+        }
+
+        let changed_cells = board
+            .all_cells()
+            .filter(|&cell| original.num_possible_regions(cell) != membership.num_possible_regions(cell))
+            .collect::<Vec<_>>();
+        if let Some(region_membership) = board.region_membership_mut() {
+            *region_membership = membership;
+        }
+
+        if is_brute_forcing {
+            return LogicalStepResult::Changed(None);
+        }
+
+        let desc = if changed_cells.is_empty() {
+            "Digits can't repeat in a region".to_owned()
+        } else {
+            let cells = changed_cells.iter().map(|cell| cell.to_string()).collect::<Vec<_>>().join(", ");
+            format!("Fewer possible regions for {cells}")
+        };
+        LogicalStepResult::Changed(Some(LogicalStepDesc::from_elims(&desc, &elims)))
+    }
+}
+
+impl ChaosConstructionConstraint {
+    /// Runs one pass of every region rule, returning whether anything changed or why the board is invalid.
+    fn step_regions(
+        board: &mut Board,
+        membership: &mut RegionMembership,
+        elims: &mut EliminationList,
+    ) -> Result<bool, String> {
+        let size = board.size();
+        let mut changed = false;
+
+        // A digit can't repeat within a region.
+        for cell in board.all_cells() {
+            let (Some(region), mask) = (membership.region(cell), board.cell(cell)) else {
+                continue;
+            };
+            if !mask.is_single() {
+                continue;
+            }
+            let value = mask.value();
+            for other in membership.cells_possibly_in(region) {
+                let other_mask = board.cell(other);
+                if other == cell || !other_mask.has(value) {
+                    continue;
+                }
+                if membership.region(other) == Some(region) {
+                    if !board.clear_value(other, value) {
+                        return Err(format!("{value} repeats in the region of {cell} and {other}"));
+                    }
+                    elims.add(other.candidate(value));
+                    changed = true;
+                } else if other_mask.is_single() {
+                    if !membership.remove_region(other, region) {
+                        return Err(format!("{other} can't be in any region"));
+                    }
+                    changed = true;
+                }
+            }
+        }
+
+        for region in 0..size {
+            let known = membership.cells_in(region);
+            let possible = membership.cells_possibly_in(region);
+            if known.len() > size || possible.len() < size {
+                return Err(format!("Region {} can't have {size} cells", region + 1));
+            }
+
+            // A region with all of its cells can't have any others, and a region with only enough cells needs them all.
+            if known.len() == size && possible.len() > size {
+                for &cell in possible.iter().filter(|cell| !known.contains(cell)) {
+                    if !membership.remove_region(cell, region) {
+                        return Err(format!("{cell} can't be in any region"));
+                    }
+                }
+                changed = true;
+            } else if possible.len() == size && known.len() < size {
+                for &cell in possible.iter() {
+                    membership.set_region(cell, region);
+                }
+                changed = true;
+            }
+
+            // A region of `size` cells can't reach further than `size - 1` steps from any of its cells.
+            for (index, &cell) in known.iter().enumerate() {
+                if known[index + 1..].iter().any(|&other| cell.taxicab_distance(other) >= size) {
+                    return Err(format!("Region {} can't reach from {cell} to every cell in it", region + 1));
+                }
+            }
+            for &cell in possible.iter().filter(|cell| !known.contains(cell)) {
+                if known.iter().any(|&other| cell.taxicab_distance(other) >= size) {
+                    if !membership.remove_region(cell, region) {
+                        return Err(format!("{cell} can't be in any region"));
+                    }
+                    changed = true;
+                }
+            }
+            let known = membership.cells_in(region);
+            let possible = membership.cells_possibly_in(region);
+
+            // A region is connected, so cells which can't join up with enough others, or with its known cells, aren't in it.
+            let components = connected_components(&possible);
+            let known_components = components.iter().filter(|component| component.contains_any(&known)).count();
+            if known_components > 1 {
+                return Err(format!("Region {} can't be connected", region + 1));
+            }
+            for component in components {
+                let is_too_small = component.cells.len() < size;
+                let is_separate = !known.is_empty() && !component.contains_any(&known);
+                if !is_too_small && !is_separate {
+                    continue;
+                }
+                if component.contains_any(&known) {
+                    return Err(format!("Region {} can't have {size} cells", region + 1));
+                }
+                for cell in component.cells {
+                    if !membership.remove_region(cell, region) {
+                        return Err(format!("{cell} can't be in any region"));
+                    }
+                }
+                changed = true;
+            }
+
+            // A region with a known cell has every digit in one of the cells it can have.
+            if !known.is_empty() {
+                let possible = membership.cells_possibly_in(region);
+                for value in 1..=size {
+                    if !possible.iter().any(|&cell| board.cell(cell).has(value)) {
+                        return Err(format!("Region {} has no place for {value}", region + 1));
+                    }
+                }
+            }
+        }
+
+        Ok(changed)
+    }
+
+    /// Divides a grid of placed digits into regions, using the lowest unused region number
+    /// for the first cell of each region in reading order.
+    fn find_regions(board: &Board, membership: &RegionMembership) -> Option<RegionMembership> {
+        let size = board.size();
+        let cells = board.all_cells().collect::<Vec<_>>();
+        let mut search = RegionSearch {
+            board,
+            membership,
+            cells: &cells,
+            regions: vec![usize::MAX; cells.len()],
+            region_values: vec![ValueMask::new(); size],
+            region_counts: vec![0; size],
+        };
+        if !search.assign(0, 0) {
+            return None;
+        }
+
+        let mut found = membership.clone();
+        for (&cell, &region) in cells.iter().zip(search.regions.iter()) {
+            found.set_region(cell, region);
+        }
+        Some(found)
+    }
+}
+
+/// A group of orthogonally connected cells.
+struct Component {
+    cells: Vec<CellIndex>,
+}
+
+impl Component {
+    fn contains_any(&self, cells: &[CellIndex]) -> bool {
+        cells.iter().any(|cell| self.cells.contains(cell))
+    }
+}
+
+/// Splits the cells into groups which are orthogonally connected.
+fn connected_components(cells: &[CellIndex]) -> Vec<Component> {
+    let mut remaining = cells.to_vec();
+    let mut components = Vec::new();
+    while let Some(start) = remaining.pop() {
+        let mut component = vec![start];
+        let mut queue = VecDeque::from([start]);
+        while let Some(cell) = queue.pop_front() {
+            for neighbor in cell.orthogonally_adjacent_cells() {
+                if let Some(index) = remaining.iter().position(|&other| other == neighbor) {
+                    remaining.swap_remove(index);
+                    component.push(neighbor);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        components.push(Component { cells: component });
+    }
+    components
+}
+
+/// A backtracking search for regions which fit a grid of placed digits.
+struct RegionSearch<'a> {
+    board: &'a Board,
+    membership: &'a RegionMembership,
+    cells: &'a [CellIndex],
+    regions: Vec<usize>,
+    region_values: Vec<ValueMask>,
+    region_counts: Vec<usize>,
+}
+
+impl RegionSearch<'_> {
+    fn assign(&mut self, index: usize, regions_used: usize) -> bool {
+        let Some(&cell) = self.cells.get(index) else {
+            return true;
+        };
+
+        let size = self.board.size();
+        let value = self.board.cell(cell).value();
+        for region in self.membership.possible_regions(cell) {
+            // Region numbers are given out in order, so no later region can be skipped.
+            if region > regions_used || self.region_counts[region] == size || self.region_values[region].has(value) {
+                continue;
+            }
+
+            self.regions[index] = region;
+            self.region_values[region] = self.region_values[region].with(value);
+            self.region_counts[region] += 1;
+
+            let is_connected = self.region_counts[region] < size || {
+                let region_cells = self
+                    .cells
+                    .iter()
+                    .zip(self.regions.iter())
+                    .take(index + 1)
+                    .filter(|&(_, &other)| other == region)
+                    .map(|(&cell, _)| cell)
+                    .collect::<Vec<_>>();
+                connected_components(&region_cells).len() == 1
+            };
+            if is_connected && self.assign(index + 1, regions_used.max(region + 1)) {
+                return true;
+            }
+
+            self.region_counts[region] -= 1;
+            self.region_values[region] = self.region_values[region].without(value);
+            self.regions[index] = usize::MAX;
+        }
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[test]
+    fn test_chaos_construction() {
+        // Only the first row is given, so there are as many solutions as 4x4 latin squares with that row.
+        let solver = SolverBuilder::new(4)
+            .with_unknown_regions()
+            .with_constraint(Arc::new(ChaosConstructionConstraint::new()))
+            .with_givens_string("1234............")
+            .build()
+            .unwrap();
+        assert_eq!(solver.find_solution_count(1000, None, None), SolutionCountResult::ExactCount(24));
+
+        let solution = solver.find_first_solution().board().unwrap();
+        let regions = solution.region_membership().unwrap().to_regions().unwrap();
+        for region in 0..4 {
+            let cells = solution.all_cells().filter(|cell| regions[cell.index()] == region).collect::<Vec<_>>();
+            assert_eq!(cells.len(), 4);
+            assert_eq!(connected_components(&cells).len(), 1);
+            let values = cells.iter().fold(ValueMask::new(), |values, &cell| values.with(solution.cell(cell).value()));
+            assert_eq!(values.count(), 4);
+        }
+
+        let result = SolverBuilder::new(4).with_constraint(Arc::new(ChaosConstructionConstraint::new())).build();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_known_regions() {
+        let solver = SolverBuilder::new(4)
+            .with_unknown_regions()
+            .with_constraint(Arc::new(ChaosConstructionConstraint::new()))
+            .with_givens_string("1...............")
+            .build()
+            .unwrap();
+        let cu = solver.cell_utility();
+
+        // Region 0 has r1c1, so it can't reach as far as r4c4 with only four cells.
+        let mut board = solver.board().clone();
+        board.region_membership_mut().unwrap().set_region(cu.cell(3, 3), 0);
+        let result = ChaosConstructionConstraint::new().step_logic(&mut board, false, &Cancellation::default());
+        assert!(result.is_invalid());
+
+        // A cell in the same region as r1c1 can't be 1, and the far corner can't be in its region.
+        let mut board = solver.board().clone();
+        board.region_membership_mut().unwrap().set_region(cu.cell(0, 1), 0);
+        let result = ChaosConstructionConstraint::new().step_logic(&mut board, false, &Cancellation::default());
+        assert!(result.is_changed());
+        assert!(!board.cell(cu.cell(0, 1)).has(1));
+        assert!(!board.region_membership().unwrap().can_be_in(cu.cell(3, 3), 0));
+    }
+
+    #[test]
+    fn test_region_edit_runs_logic_again() {
+        let solver = SolverBuilder::new(4)
+            .with_unknown_regions()
+            .with_constraint(Arc::new(ChaosConstructionConstraint::new()))
+            .with_givens_string("1...............")
+            .build()
+            .unwrap();
+        let cu = solver.cell_utility();
+
+        let mut board = solver.board().clone();
+        while StepConstraints.run(&mut board, true, &Cancellation::default()).is_changed() {}
+
+        // Editing the regions is a change the constraint must look at, even though no candidate changed.
+        board.region_membership_mut().unwrap().set_region(cu.cell(0, 1), 0);
+        let result = StepConstraints.run(&mut board, true, &Cancellation::default());
+        assert!(result.is_changed());
+        assert!(!board.cell(cu.cell(0, 1)).has(1));
+    }
+}
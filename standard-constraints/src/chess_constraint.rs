@@ -1,9 +1,10 @@
 //! Contains the [`ChessConstraint`] struct for representing a chess constraint.
 
+use std::sync::Arc;
 use sudoku_solver_lib::prelude::*;
 
 /// A [`Constraint`] implementation for representing a chess constraint.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ChessConstraint {
     specific_name: String,
     offsets: Vec<(isize, isize)>,
@@ -70,6 +71,10 @@ impl Constraint for ChessConstraint {
         &self.specific_name
     }
 
+    fn clone_constraint(&self) -> Option<Arc<dyn Constraint>> {
+        Some(Arc::new(self.clone()))
+    }
+
     fn get_weak_links(&self, size: usize) -> Vec<(CandidateIndex, CandidateIndex)> {
         let mut result = Vec::new();
         let cu = CellUtility::new(size);
@@ -127,4 +132,37 @@ mod test {
             assert_eq!(solution_count.count().unwrap(), 1);
         }
     }
+
+    #[test]
+    fn test_satisfied_constraints() {
+        let constraints = || -> Vec<Arc<dyn Constraint>> {
+            vec![Arc::new(ChessConstraint::anti_king()), Arc::new(ChessConstraint::anti_knight())]
+        };
+
+        let solution = Solver::default().find_first_solution().board().unwrap();
+        let statistics = SolutionStatistics::new(&solution).unwrap();
+        assert!(statistics.satisfied_constraints(constraints()).unwrap().is_empty());
+
+        let solver = SolverBuilder::default().with_constraint(Arc::new(ChessConstraint::anti_king())).build().unwrap();
+        let solution = solver.find_random_solution().board().unwrap();
+        let statistics = SolutionStatistics::new(&solution).unwrap();
+        assert!(statistics.satisfied_constraints(constraints()).unwrap().contains(&"Anti-King".to_owned()));
+    }
+
+    #[test]
+    fn test_eliminations() {
+        let cu = CellUtility::new(9);
+        let mut solver =
+            SolverBuilder::default().with_constraint(Arc::new(ChessConstraint::anti_knight())).build().unwrap();
+        assert!(solver.set_solved(cu.cell(4, 4), 5));
+        assert!(!solver.board().cell(cu.cell(2, 3)).has(5));
+        assert!(!solver.board().cell(cu.cell(6, 5)).has(5));
+        assert!(solver.board().cell(cu.cell(2, 2)).has(5));
+
+        let mut solver =
+            SolverBuilder::default().with_constraint(Arc::new(ChessConstraint::anti_king())).build().unwrap();
+        assert!(solver.set_solved(cu.cell(2, 2), 5));
+        assert!(!solver.board().cell(cu.cell(3, 3)).has(5));
+        assert!(solver.board().cell(cu.cell(3, 4)).has(5));
+    }
 }
@@ -6,7 +6,7 @@ pub mod prelude;
 
 use itertools::Itertools;
 use regex::Regex;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 use super::fpuzzles_parser::prelude::*;
 use crate::prelude::*;
@@ -15,19 +15,69 @@ use sudoku_solver_lib::prelude::*;
 /// A utility struct for parsing the f-puzzles format.
 #[derive(Clone, Debug)]
 pub struct FPuzzlesParser {
-    parse_cell_regex: Regex,
+    logical_steps: Vec<Arc<dyn LogicalStep>>,
+    pair_rules: PairRuleRegistry,
+    /// The least difference of whispers lines, if not the German whispers difference.
+    whispers_difference: Option<usize>,
 }
 
 impl FPuzzlesParser {
     /// Creates a new [`FPuzzlesParser`].
     pub fn new() -> Self {
-        Self { parse_cell_regex: Regex::new(r"^[rR](\d+)[cC](\d+)$").unwrap() }
+        Self { logical_steps: Vec::new(), pair_rules: PairRuleRegistry::new(), whispers_difference: None }
+    }
+
+    /// The regex for cell names such as "R1C2", compiled once and shared by every parser.
+    fn parse_cell_regex() -> &'static Regex {
+        static PARSE_CELL_REGEX: OnceLock<Regex> = OnceLock::new();
+        PARSE_CELL_REGEX.get_or_init(|| Regex::new(r"^[rR](\d+)[cC](\d+)$").unwrap())
+    }
+
+    /// Use these logical steps for parsed puzzles instead of the standard logic.
+    /// See [`SolverBuilder::with_logical_steps`].
+    #[must_use]
+    pub fn with_logical_steps(mut self, logical_steps: Vec<Arc<dyn LogicalStep>>) -> Self {
+        self.logical_steps = logical_steps;
+        self
+    }
+
+    /// Register a named rule which the `pairrule` entries of parsed puzzles can use.
+    /// See [`PairRuleRegistry::with_rule`].
+    #[must_use]
+    pub fn with_pair_rule(mut self, name: &str, rule: impl Fn(usize, usize) -> bool + Send + Sync + 'static) -> Self {
+        self.pair_rules = self.pair_rules.with_rule(name, rule);
+        self
+    }
+
+    /// Require adjacent values of the `whispers` lines of parsed puzzles to differ by at least the difference,
+    /// rather than by [`LineConstraint::german_whispers_difference`], such as 4 for Dutch whispers.
+    #[must_use]
+    pub fn with_whispers_difference(mut self, difference: usize) -> Self {
+        self.whispers_difference = Some(difference);
+        self
+    }
+
+    /// The logical steps to use for parsed puzzles. Empty if the standard logic should be used.
+    pub fn logical_steps(&self) -> &[Arc<dyn LogicalStep>] {
+        &self.logical_steps
     }
 
     /// Parses the given [`FPuzzlesBoard`] into a [`Solver`].
     /// Treating the center pencilmarks as given is optional.
     /// Generally, brute force solves use `false` and logical solves use `true`.
     pub fn parse_board(&self, board: &FPuzzlesBoard, treat_pencilmarks_as_given: bool) -> Result<Solver, String> {
+        self.parse_board_builder(board, treat_pencilmarks_as_given).build()
+    }
+
+    /// Parses the given [`FPuzzlesBoard`] into a [`SolverBuilder`], so that more can be added to the
+    /// puzzle before it is built. See [`FPuzzlesParser::parse_board`].
+    ///
+    /// A board whose grid doesn't match its size gives a builder which fails to build.
+    pub fn parse_board_builder(&self, board: &FPuzzlesBoard, treat_pencilmarks_as_given: bool) -> SolverBuilder {
+        if let Err(error) = board.validate() {
+            return SolverBuilder::new(board.size.max(0) as usize).with_error(&error);
+        }
+
         let size = board.size as usize;
         let cu = CellUtility::new(size);
         let all_values_mask = ValueMask::from_all_values(size);
@@ -59,15 +109,15 @@ impl FPuzzlesParser {
                         pencilmarks = pencilmarks & ValueMask::from_values(&center_pencil_marks);
                     }
                     if pencilmarks != all_values_mask {
-                        solver = solver.with_constraint(Arc::new(PencilmarkConstraint::new(cell, pencilmarks)));
+                        solver = solver.with_cell_mask(cell, pencilmarks);
                     }
                 }
             }
         }
         solver = solver.with_givens(&givens);
 
-        // Start with default regions
-        let mut regions = default_regions(size);
+        // Start with default regions, which are none for sizes with no rectangular boxes
+        let mut regions = FPuzzlesBoard::default_regions(size);
 
         // Override the regions
         for i in 0..size {
@@ -81,13 +131,7 @@ impl FPuzzlesParser {
         solver = solver.with_regions(regions.clone());
 
         // Add solving options
-        for option in board.truecandidatesoptions.iter() {
-            if option == "colored" {
-                solver = solver.with_custom_info("truecandidatescolored", "true");
-            } else if option == "logical" {
-                solver = solver.with_custom_info("truecandidateslogical", "true");
-            }
-        }
+        solver = solver.with_option_strings(&board.truecandidatesoptions);
 
         // Store the original center marks if they are treated as given
         if treat_pencilmarks_as_given {
@@ -104,16 +148,16 @@ impl FPuzzlesParser {
 
         // Add global constraints
         if board.diagonal_p {
-            solver = solver.with_constraint(Arc::new(NonRepeatConstraint::from_diagonalp(size)));
+            solver = solver.with_constraint_in_group(Arc::new(NonRepeatConstraint::from_diagonalp(size)), "diagonal+");
         }
         if board.diagonal_n {
-            solver = solver.with_constraint(Arc::new(NonRepeatConstraint::from_diagonaln(size)));
+            solver = solver.with_constraint_in_group(Arc::new(NonRepeatConstraint::from_diagonaln(size)), "diagonal-");
         }
         if board.antiknight {
-            solver = solver.with_constraint(Arc::new(ChessConstraint::anti_knight()));
+            solver = solver.with_constraint_in_group(Arc::new(ChessConstraint::anti_knight()), "antiknight");
         }
         if board.antiking {
-            solver = solver.with_constraint(Arc::new(ChessConstraint::anti_king()));
+            solver = solver.with_constraint_in_group(Arc::new(ChessConstraint::anti_king()), "antiking");
         }
         if board.disjointgroups {
             for region_id in regions.iter().copied().unique() {
@@ -126,7 +170,8 @@ impl FPuzzlesParser {
                     .collect();
                 if cells.len() == size {
                     let name = format!("DisjointGroup{}", region_id + 1);
-                    solver = solver.with_constraint(Arc::new(NonRepeatConstraint::new(&name, cells)));
+                    let constraint = Arc::new(NonRepeatConstraint::new(&name, cells));
+                    solver = solver.with_constraint_in_group(constraint, "disjointgroups");
                 }
             }
         }
@@ -136,7 +181,25 @@ impl FPuzzlesParser {
         }
 
         if !board.killercage.is_empty() {
-            // TODO: Killer cages
+            for cage in board.killercage.iter() {
+                let cells = cage.cells.iter().filter_map(|cell| self.parse_cell(cell, size)).collect::<Vec<_>>();
+                if cells.is_empty() {
+                    return solver.with_error("Killer cage has no cells in the grid");
+                }
+
+                let value = cage.value.trim();
+                let sum = match value.parse::<usize>() {
+                    Ok(sum) => Some(sum),
+                    Err(_) if value.is_empty() => None,
+                    Err(_) => {
+                        return solver.with_error(&format!(
+                            "Killer cage at {} has a sum which is not a number: {value}",
+                            cells[0]
+                        ))
+                    }
+                };
+                solver = solver.with_constraint_in_group(Arc::new(KillerCageConstraint::new(cells, sum)), "killercage");
+            }
         }
 
         if !board.littlekillersum.is_empty() {
@@ -147,7 +210,7 @@ impl FPuzzlesParser {
             for fpuzzles_cell in board.odd.iter() {
                 let cell = self.parse_cell(&fpuzzles_cell.cell, size);
                 if let Some(cell) = cell {
-                    solver = solver.with_constraint(Arc::new(PencilmarkConstraint::odd(cell)));
+                    solver = solver.with_constraint_in_group(Arc::new(PencilmarkConstraint::odd(cell)), "odd");
                 }
             }
         }
@@ -156,7 +219,7 @@ impl FPuzzlesParser {
             for fpuzzles_cell in board.even.iter() {
                 let cell = self.parse_cell(&fpuzzles_cell.cell, size);
                 if let Some(cell) = cell {
-                    solver = solver.with_constraint(Arc::new(PencilmarkConstraint::even(cell)));
+                    solver = solver.with_constraint_in_group(Arc::new(PencilmarkConstraint::even(cell)), "even");
                 }
             }
         }
@@ -186,13 +249,20 @@ impl FPuzzlesParser {
                 let cells = self.parse_cells(extra_region, size);
                 if cells.len() == size {
                     let name = format!("ExtraRegion{}", id + 1);
-                    solver = solver.with_constraint(Arc::new(NonRepeatConstraint::new(&name, cells)));
+                    let constraint = Arc::new(NonRepeatConstraint::new(&name, cells));
+                    solver = solver.with_constraint_in_group(constraint, "extraregion");
                 }
             }
         }
 
         if !board.thermometer.is_empty() {
-            // TODO Thermometer
+            for cells in board.thermometer.iter().flat_map(|thermometer| thermometer.lines.iter()) {
+                let cells = cells.iter().filter_map(|cell| self.parse_cell(cell, size)).collect::<Vec<_>>();
+                if cells.len() > 1 {
+                    solver =
+                        solver.with_constraint_in_group(Arc::new(LineConstraint::thermometer(cells)), "thermometer");
+                }
+            }
         }
 
         if !board.palindrome.is_empty() {
@@ -200,11 +270,26 @@ impl FPuzzlesParser {
         }
 
         if !board.renban.is_empty() {
-            // TODO Renban
+            for cells in board.renban.iter().flat_map(|renban| renban.lines.iter()) {
+                let cells = cells.iter().filter_map(|cell| self.parse_cell(cell, size)).collect::<Vec<_>>();
+                if cells.len() > 1 {
+                    solver = solver.with_constraint_in_group(Arc::new(LineConstraint::renban(cells)), "renban");
+                }
+            }
         }
 
         if !board.whispers.is_empty() {
-            // TODO Whispers
+            let whispers_difference =
+                self.whispers_difference.unwrap_or_else(|| LineConstraint::german_whispers_difference(size));
+            for cells in board.whispers.iter().flat_map(|whispers| whispers.lines.iter()) {
+                let cells = cells.iter().filter_map(|cell| self.parse_cell(cell, size)).collect::<Vec<_>>();
+                if cells.len() > 1 {
+                    solver = solver.with_constraint_in_group(
+                        Arc::new(LineConstraint::whispers(cells, whispers_difference)),
+                        "whispers",
+                    );
+                }
+            }
         }
 
         if !board.regionsumline.is_empty() {
@@ -212,7 +297,13 @@ impl FPuzzlesParser {
         }
 
         if !board.betweenline.is_empty() {
-            // TODO Between line
+            for cells in board.betweenline.iter().flat_map(|between_line| between_line.lines.iter()) {
+                let cells = cells.iter().filter_map(|cell| self.parse_cell(cell, size)).collect::<Vec<_>>();
+                if cells.len() > 2 {
+                    solver =
+                        solver.with_constraint_in_group(Arc::new(BetweenLineConstraint::new(cells)), "betweenline");
+                }
+            }
         }
 
         let negative_consecutive = board.nonconsecutive;
@@ -260,9 +351,7 @@ impl FPuzzlesParser {
                 }
             }
 
-            solver = solver.with_constraint(Arc::new(OrthogonalPairsConstraint::from_standard_markers(
-                size, "Kropki", &markers, &negatives,
-            )));
+            solver = Self::with_orthogonal_pairs(solver, size, "Kropki", "kropki", &markers, &negatives);
         }
 
         let negative_xv = board.negative.iter().any(|x| x == "xv");
@@ -295,9 +384,7 @@ impl FPuzzlesParser {
                 negatives.push(StandardPairType::Sum(10));
             }
 
-            solver = solver.with_constraint(Arc::new(OrthogonalPairsConstraint::from_standard_markers(
-                size, "XV", &markers, &negatives,
-            )));
+            solver = Self::with_orthogonal_pairs(solver, size, "XV", "xv", &markers, &negatives);
         }
 
         if !board.clone.is_empty() {
@@ -312,23 +399,91 @@ impl FPuzzlesParser {
             // TODO: Sandwich sum constraint
         }
 
-        if !board.xsum.is_empty() {
-            // TODO: X-Sum constraint
+        for entry in board.xsum.iter() {
+            let Some(clue) = self.parse_outside_clue(&entry.cell, size) else {
+                continue;
+            };
+            match self.parse_clue_value(entry, "X-Sum") {
+                Ok(Some(sum)) => {
+                    solver = solver.with_constraint_in_group(Arc::new(XSumConstraint::new(&clue, sum)), "xsum");
+                }
+                Ok(None) => {}
+                Err(error) => return solver.with_error(&error),
+            }
         }
 
-        if !board.skyscraper.is_empty() {
-            // TODO: Skyscraper constraint
+        for entry in board.skyscraper.iter() {
+            let Some(clue) = self.parse_outside_clue(&entry.cell, size) else {
+                continue;
+            };
+            match self.parse_clue_value(entry, "Skyscraper") {
+                Ok(Some(count)) => {
+                    solver = solver
+                        .with_constraint_in_group(Arc::new(SkyscraperConstraint::new(&clue, count)), "skyscraper");
+                }
+                Ok(None) => {}
+                Err(error) => return solver.with_error(&error),
+            }
         }
 
         if !board.entropicline.is_empty() {
-            // TODO: Entropic line constraint
+            for cells in board.entropicline.iter().flat_map(|entropic_line| entropic_line.lines.iter()) {
+                let cells = cells.iter().filter_map(|cell| self.parse_cell(cell, size)).collect::<Vec<_>>();
+                if cells.len() > 1 {
+                    solver = solver.with_constraint_in_group(Arc::new(LineConstraint::entropic(cells)), "entropicline");
+                }
+            }
         }
 
-        solver.build()
+        for entry in board.pairrule.iter() {
+            let rule = match self.pair_rules.create(&entry.value) {
+                Ok(rule) => rule,
+                Err(error) => return solver.with_error(&error),
+            };
+            let cells = entry.cells.iter().filter_map(|cell| self.parse_cell(cell, size)).collect::<Vec<_>>();
+            let constraint = PairRuleConstraint::orthogonally_adjacent(&entry.value, &cells, rule, size);
+            solver = solver.with_constraint_in_group(Arc::new(constraint), "pairrule");
+        }
+
+        if !self.logical_steps.is_empty() {
+            solver = solver.with_logical_steps(self.logical_steps.clone());
+        }
+        if !board.disabledlogic.is_empty() {
+            solver = solver.with_disabled_logical_steps(&Self::disabled_logical_step_names(&board.disabledlogic));
+        }
+
+        solver
+    }
+
+    /// Adds an [`OrthogonalPairsConstraint`] for the markers in the given group.
+    ///
+    /// The negative constraint is added separately in the `negative` group, so that
+    /// the puzzle can be checked with only its markers or only its negative constraint.
+    fn with_orthogonal_pairs(
+        solver: SolverBuilder,
+        size: usize,
+        name: &str,
+        group: &str,
+        markers: &[StandardOrthogonalPairsMarker],
+        negatives: &[StandardPairType],
+    ) -> SolverBuilder {
+        let mut solver = solver;
+        if negatives.is_empty() || !markers.is_empty() {
+            let constraint = Arc::new(OrthogonalPairsConstraint::from_standard_markers(size, name, markers, &[]));
+            solver = solver.with_constraint_in_group(constraint, group);
+        }
+        if !negatives.is_empty() {
+            // The negative constraint needs the markers to know which pairs are unmarked, but the
+            // markers themselves are only enforced by the constraint in their own group.
+            let constraint =
+                OrthogonalPairsConstraint::from_standard_markers(size, name, markers, negatives).without_marker_rules();
+            solver = solver.with_constraint_in_group(Arc::new(constraint), "negative");
+        }
+        solver
     }
 
     fn parse_cell(&self, cell_str: &str, size: usize) -> Option<CellIndex> {
-        let captures = self.parse_cell_regex.captures(cell_str);
+        let captures = Self::parse_cell_regex().captures(cell_str);
         captures.as_ref()?;
 
         let captures = captures.unwrap();
@@ -352,12 +507,57 @@ impl FPuzzlesParser {
         let row = row.unwrap();
         let col = col.unwrap();
 
-        if row == 0 || col == 0 {
+        if row == 0 || col == 0 || row > size || col > size {
             return None;
         }
         Some(CellIndex::from_rc(row - 1, col - 1, size))
     }
 
+    /// Parses the position of a clue outside of the grid, such as `R1C0`.
+    fn parse_outside_clue(&self, cell_str: &str, size: usize) -> Option<OutsideClue> {
+        let captures = Self::parse_cell_regex().captures(cell_str)?;
+        let row = captures.get(1)?.as_str().parse::<usize>().ok()?;
+        let col = captures.get(2)?.as_str().parse::<usize>().ok()?;
+        OutsideClue::new(size, row, col)
+    }
+
+    /// Parses the value of a clue, which is `None` if the clue has no value.
+    fn parse_clue_value(&self, entry: &FPuzzlesCell, name: &str) -> Result<Option<usize>, String> {
+        let value = entry.value.trim();
+        if value.is_empty() {
+            return Ok(None);
+        }
+        match value.parse::<usize>() {
+            Ok(value) => Ok(Some(value)),
+            Err(_) => Err(format!("{name} at {} has a value which is not a number: {value}", entry.cell)),
+        }
+    }
+
+    /// Maps the `disabledlogic` values of f-puzzles data, such as "tuples" and "pointing", to the names
+    /// of the logical steps they disable in the [`logical_step_registry`].
+    ///
+    /// Values for logic which this solver doesn't have, such as "fishes", disable nothing. Any other value
+    /// is kept as it is, so that the names of this solver's steps can also be used.
+    fn disabled_logical_step_names(disabled_logic: &[String]) -> Vec<String> {
+        const FPUZZLES_DISABLED_LOGIC: [(&str, &[&str]); 6] = [
+            ("tuples", &["Naked Tuple"]),
+            ("pointing", &["Pointing Claiming"]),
+            ("fishes", &[]),
+            ("wings", &[]),
+            ("aic", &[]),
+            ("contradictions", &[]),
+        ];
+
+        let mut names = Vec::new();
+        for value in disabled_logic.iter() {
+            match FPUZZLES_DISABLED_LOGIC.iter().find(|(name, _)| name.eq_ignore_ascii_case(value.trim())) {
+                Some((_, steps)) => names.extend(steps.iter().map(|&step| step.to_owned())),
+                None => names.push(value.clone()),
+            }
+        }
+        names
+    }
+
     fn parse_cells(&self, cells: &FPuzzlesCells, size: usize) -> Vec<CellIndex> {
         cells.cells.iter().filter_map(|fpuzzles_cell| self.parse_cell(fpuzzles_cell, size)).collect()
     }
@@ -371,7 +571,7 @@ impl Default for FPuzzlesParser {
 
 #[cfg(test)]
 mod test {
-    use super::fpuzzles_test_data::FPUZZLES_CLASSICS_DATA;
+    use super::fpuzzles_test_data::{FPUZZLES_CLASSICS_DATA, FPUZZLES_TEST_DATA};
     use super::*;
 
     fn test_unqiue_solution_from_lzstring(parser: &FPuzzlesParser, lzstring: &str, expected_solution: &str) {
@@ -380,9 +580,9 @@ mod test {
         }
 
         impl SolutionReceiver for Receiver {
-            fn receive(&mut self, result: Box<Board>) -> bool {
+            fn receive(&mut self, result: Box<Board>, _statistics: &SearchStatistics) -> ReceiveAction {
                 self.solution = Some(result.to_string());
-                true
+                ReceiveAction::Continue
             }
         }
         let mut receiver = Receiver { solution: None };
@@ -445,6 +645,133 @@ mod test {
         test_unqiue_solution_from_lzstring(&parser, lzstring, expected_solution)
     }
 
+    #[test]
+    fn test_whispers() {
+        let mut board = FPuzzlesBoard::new(9);
+        let line = ["R1C1", "R1C2", "R1C3"].map(|cell| cell.to_owned()).to_vec();
+        board.whispers.push(FPuzzlesLines { lines: vec![line] });
+
+        let solver = FPuzzlesParser::new().parse_board(&board, false).unwrap();
+        assert_eq!(solver.board().constraints()[0].name(), "Whispers at r1c1");
+        assert!(!solver.board().cell(CellIndex::new(1, 9)).has(5));
+        assert!(solver.board().cell(CellIndex::new(1, 9)).has(3));
+
+        // Dutch whispers only differ by at least 4, so the middle value is allowed
+        let solver = FPuzzlesParser::new().with_whispers_difference(4).parse_board(&board, false).unwrap();
+        assert!(solver.board().cell(CellIndex::new(1, 9)).has(5));
+        assert!(solver.find_solution_count(2, None, None).is_at_least_count());
+    }
+
+    #[test]
+    fn test_disabled_logic() {
+        let parser = FPuzzlesParser::new();
+        let mut board = FPuzzlesBoard::from_lzstring_json(FPUZZLES_CLASSICS_DATA[0].0).unwrap();
+        board.disabledlogic = vec!["Hidden Single".to_owned(), "simplecellforcing".to_owned(), "Fishes".to_owned()];
+        let mut solver = parser.parse_board(&board, false).unwrap();
+        let names = solver.logical_solve_steps().iter().map(|step| step.name()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["Naked Single", "Step Constraints"]);
+
+        let result = solver.run_logical_solve();
+        assert!(!result.is_invalid());
+        assert!(!result.to_string().contains("Hidden Single"));
+    }
+
+    #[test]
+    fn test_fpuzzles_disabled_logic() {
+        let steps = logical_step_registry()
+            .create_all(&["Hidden Single", "Naked Single", "Naked Tuple", "Pointing Claiming", "Simple Cell Forcing"])
+            .unwrap();
+        let parser = FPuzzlesParser::new().with_logical_steps(steps);
+        let mut board = FPuzzlesBoard::from_lzstring_json(FPUZZLES_CLASSICS_DATA[0].0).unwrap();
+        board.disabledlogic = ["tuples", "pointing", "fishes", "wings", "aic", "contradictions"]
+            .iter()
+            .map(|&value| value.to_owned())
+            .collect();
+        let solver = parser.parse_board(&board, false).unwrap();
+        let names = solver.logical_solve_steps().iter().map(|step| step.name()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["Hidden Single", "Naked Single", "Step Constraints", "Simple Cell Forcing"]);
+
+        assert_eq!(
+            FPuzzlesParser::disabled_logical_step_names(&["Tuples".to_owned(), "Hidden Single".to_owned()]),
+            vec!["Naked Tuple", "Hidden Single"]
+        );
+    }
+
+    #[test]
+    fn test_between_line() {
+        let parser = FPuzzlesParser::new();
+
+        // "Hay Fever" has thermometers, between lines and kropki dots
+        let (lzstring, expected_solution) = FPUZZLES_TEST_DATA[14];
+        test_unqiue_solution_from_lzstring(&parser, lzstring, expected_solution);
+
+        // "Dotween" has between lines and kropki dots
+        let (lzstring, expected_solution) = FPUZZLES_TEST_DATA[15];
+        test_unqiue_solution_from_lzstring(&parser, lzstring, expected_solution)
+    }
+
+    #[test]
+    fn test_entropic_line() {
+        let parser = FPuzzlesParser::new();
+        let (lzstring, expected_solution) = FPUZZLES_TEST_DATA[32];
+        test_unqiue_solution_from_lzstring(&parser, lzstring, expected_solution)
+    }
+
+    #[test]
+    fn test_x_sums() {
+        // "Tiny Scar" has X-Sums and whispers
+        let parser = FPuzzlesParser::new();
+        let (lzstring, expected_solution) = FPUZZLES_TEST_DATA[30];
+        test_unqiue_solution_from_lzstring(&parser, lzstring, expected_solution);
+
+        let mut board = FPuzzlesBoard::new(9);
+        board.xsum.push(FPuzzlesCell { cell: "R0C1".to_owned(), value: "45".to_owned() });
+        board.xsum.push(FPuzzlesCell { cell: "R1C1".to_owned(), value: "10".to_owned() });
+        let solver = FPuzzlesParser::new().parse_board(&board, false).unwrap();
+        assert_eq!(solver.board().constraints().len(), 1);
+        assert_eq!(solver.board().constraints()[0].name(), "X-Sum 45 at r0c1");
+
+        board.xsum.push(FPuzzlesCell { cell: "R10C1".to_owned(), value: "ten".to_owned() });
+        let error = FPuzzlesParser::new().parse_board(&board, false).err().unwrap();
+        assert_eq!(error, "X-Sum at R10C1 has a value which is not a number: ten");
+    }
+
+    #[test]
+    fn test_skyscrapers() {
+        // "Renban Skyscrapers" has skyscrapers and renbans
+        let parser = FPuzzlesParser::new();
+        let (lzstring, expected_solution) = FPUZZLES_TEST_DATA[31];
+        let board = FPuzzlesBoard::from_lzstring_json(lzstring).unwrap();
+        let solver = parser.parse_board(&board, false).unwrap();
+        assert_eq!(solver.board().constraints().iter().filter(|c| c.name().starts_with("Skyscraper")).count(), 18);
+
+        // Few cells are solved at the start, so finding the true candidates is too slow for a debug build.
+        assert!(solver.find_solution_count(2, None, None).is_exact_count());
+        assert_eq!(solver.find_first_solution().board().unwrap().to_string(), expected_solution);
+    }
+
+    #[test]
+    fn test_killer_cages() {
+        // "Self-contained" has killer cages and anti-knight
+        let parser = FPuzzlesParser::new();
+        let (lzstring, expected_solution) = FPUZZLES_TEST_DATA[3];
+        let mut board = FPuzzlesBoard::from_lzstring_json(lzstring).unwrap();
+        let solver = parser.parse_board(&board, false).unwrap();
+
+        // A lexicographic search is too slow for this puzzle in a debug build.
+        assert_eq!(solver.find_solution_count(2, None, None), SolutionCountResult::ExactCount(1));
+        let solution = solver.find_solution_in_order(SolutionOrder::Fastest);
+        assert_eq!(solution.board().unwrap().to_string(), expected_solution);
+        assert!(solver.find_true_candidates().is_solved());
+
+        board.killercage[0].value = "ten".to_owned();
+        let error = parser.parse_board(&board, false).err().unwrap();
+        assert!(error.contains("has a sum which is not a number: ten"), "{error}");
+
+        board.killercage[0].cells.clear();
+        assert_eq!(parser.parse_board(&board, false).err().unwrap(), "Killer cage has no cells in the grid");
+    }
+
     #[test]
     fn test_irregular() {
         let parser = FPuzzlesParser::new();
@@ -468,4 +795,149 @@ mod test {
         let expected_solution = r#"637945218925718463418623579591482637743596182862137945154879326279361854386254791"#;
         test_unqiue_solution_from_lzstring(&parser, lzstring, expected_solution)
     }
+
+    fn fpuzzles_board(size: i32, givens: &str) -> FPuzzlesBoard {
+        let mut board = FPuzzlesBoard::new(size);
+        for (index, value) in givens.chars().enumerate() {
+            if let Some(value) = value.to_digit(10) {
+                let entry = &mut board.grid[index / size as usize][index % size as usize];
+                entry.value = value as i32;
+                entry.given = true;
+            }
+        }
+        board
+    }
+
+    #[test]
+    fn test_small_grids() {
+        let parser = FPuzzlesParser::new();
+
+        let board = fpuzzles_board(4, "......12.1.3.32.");
+        let solver = parser.parse_board(&board, false).unwrap();
+        assert_eq!(solver.board().houses().len(), 12);
+        assert_eq!(solver.find_first_solution().board().unwrap().to_string(), "1234341221434321");
+
+        // 6x6 exports list their 2x3 regions explicitly
+        let mut board = fpuzzles_board(6, ".........123..1..4..423..12.45.453..");
+        for (index, region) in default_regions(6).into_iter().enumerate() {
+            board.grid[index / 6][index % 6].region = region as i32;
+        }
+        board.odd.push(FPuzzlesCell { cell: "R7C1".to_owned(), value: String::new() });
+        let solver = parser.parse_board(&board, false).unwrap();
+        assert_eq!(solver.board().houses().len(), 18);
+        let solution = solver.find_first_solution().board().unwrap().to_string();
+        assert_eq!(solution, "123456456123231564564231312645645312");
+
+        // Sizes without rectangular boxes have no regions
+        let solver = parser.parse_board(&FPuzzlesBoard::new(5), false).unwrap();
+        assert_eq!(solver.board().houses().len(), 10);
+
+        let mut board = FPuzzlesBoard::new(6);
+        board.grid.pop();
+        assert_eq!(parser.parse_board(&board, false).err().unwrap(), "Grid has 5 rows, expected 6");
+        board.grid.push(Vec::new());
+        assert_eq!(parser.parse_board(&board, false).err().unwrap(), "Grid row 6 has 0 cells, expected 6");
+    }
+
+    #[test]
+    fn test_regions_round_trip() {
+        let parser = FPuzzlesParser::new();
+
+        // Irregular regions are written back as overrides
+        let lzstring = r#"N4IgzglgXgpiBcBOANCA5gJwgEwQbT1ADcBDAGwFc54BGVNCImAOwQBcMqBfZUDGBgHtWtHnwERhCGmNm8xIfkJEzeiiVNFqlklVwC6yQiFKVqAJnqMW7TjAU7Nq8coTmHGke+2e3H1/DewHLBhsamVAgAzFZMIhzcPgFB6sn+utHpmlFZIjlJGfD5LoXFqaUGRiWaAAyoEdQALLE28An28p3VeSG5CI198AMFmgNh3Qh1JuSR8ACsLfF2g8MTQ70jInMboVXltfUz1ABsi7aJa9ubCFeXg7f7W4PHz6/jj5OHZggA7Gdtyy6r2u8BeILBawhH3gP0GsPejhEUwaCAAHP92nCsUCutDYSD8btjIjJoNUWSKSDyVSdgZ9FwgA"#;
+        let solver = parser.parse_board(&FPuzzlesBoard::from_lzstring_json(lzstring).unwrap(), false).unwrap();
+        let mut board = FPuzzlesBoard::new(9);
+        board.set_regions(solver.regions());
+        let board = FPuzzlesBoard::from_json(&board.to_json()).unwrap();
+        assert!(board.grid.iter().flatten().any(|entry| entry.region >= 0));
+        assert_eq!(parser.parse_board(&board, false).unwrap().regions(), solver.regions());
+
+        // Default regions need no overrides
+        for size in [4, 5, 6, 9] {
+            let solver = parser.parse_board(&FPuzzlesBoard::new(size), false).unwrap();
+            let mut board = FPuzzlesBoard::new(size);
+            board.set_regions(solver.regions());
+            assert!(board.grid.iter().flatten().all(|entry| entry.region == -1), "Size {size}");
+        }
+    }
+
+    #[test]
+    fn test_constraint_groups() {
+        let lzstring = r#"N4IgzglgXgpiBcBOANCA5gJwgEwQbT2AF9ljSSzKLryBdZQmq8l54+x1p7rjtn/nQaCR3PgIm9hk0UM6zR4rssX0QAOwD26gMbawMHQFcALhABuceCYxGYqbBABmTmBhi6rhEDpgAbPzB8EAAlRABhRBBUEIAOSJBaCh9/QOCQgHZwgFZo0PjcpNIUgKD4PFCIgBY8uPCaotBfUvT4gEZa+IAmROTmtPLQgDZwgGZarPHGkoGKkJHxmOyx3uL+srmRmpiF1abUjeH62pHCvoP0rNydnL2Zw5CqhKWE6fX00efQrtfzlsGQj9YrU2uFgW8LgCfkNaj8zmtIXNPttvscIf8kbcYnC7u8AZ8YTFPvD9hjQp9FqEnlM/rMqSsYk8GrSHk9CfSMrjEaFlsCXpz0XSQst2Y9wjDBQ8sqKRhKWa1wpyYvE5QiyWEwbUIgL5QCsnz8prJZdFSdTcaASMDfNzUQ1OoYGgAIZmSzBDAuiCaRKoD1mb3lUlCoG1T7g3WY61PcNqoXdLXhHoWubxmJZJMRnlYrPM2MPZbXLOqoNSzU3GMlj6J2pPDN5hWFuq5yt601po2ZjWUjXN+4KlGZNGd2W1EVc9WfJXkjv1/HVonhDrJ0KgjoxUF1ltzUHdn40pJAA="#;
+        let board = FPuzzlesBoard::from_lzstring_json(lzstring).unwrap();
+        let parser = FPuzzlesParser::new();
+        let builder = parser.parse_board_builder(&board, false);
+        assert_eq!(builder.constraint_groups(), vec!["kropki", "negative"]);
+
+        // Without the dots, only the pairs without a dot are restricted, so the puzzle isn't unique.
+        let solver = builder.clone().with_group_disabled("kropki").build().unwrap();
+        assert_eq!(solver.find_solution_count(2, None, None).count(), Some(2));
+
+        let solver = builder.clone().with_group_disabled("negative").build().unwrap();
+        assert_eq!(solver.find_solution_count(2, None, None).count(), Some(2));
+
+        // The same builder can still be built with every group enabled.
+        let solver = builder.build().unwrap();
+        assert_eq!(solver.find_solution_count(2, None, None).count(), Some(1));
+    }
+
+    #[test]
+    fn test_pair_rules() {
+        let parser = FPuzzlesParser::new().with_pair_rule("far", |a, b| a.abs_diff(b) >= 5);
+
+        let mut board = FPuzzlesBoard::new(9);
+        board.grid[0][0].value = 1;
+        board.grid[0][0].given = true;
+        board.pairrule.push(FPuzzlesCells { cells: Vec::new(), value: "!isprime(a + b)".to_owned() });
+        board
+            .pairrule
+            .push(FPuzzlesCells { cells: vec!["R1C1".to_owned(), "R2C1".to_owned()], value: "Far".to_owned() });
+        let builder = parser.parse_board_builder(&board, false);
+        assert_eq!(builder.constraint_groups(), vec!["pairrule"]);
+
+        let solver = builder.build().unwrap();
+        let cu = solver.cell_utility();
+        assert_eq!(solver.board().cell(cu.cell(0, 1)), ValueMask::from_values(&[3, 5, 7, 8, 9]));
+        assert_eq!(solver.board().cell(cu.cell(1, 0)), ValueMask::from_values(&[7, 8, 9]));
+
+        board.pairrule.push(FPuzzlesCells { cells: Vec::new(), value: "near".to_owned() });
+        assert_eq!(parser.parse_board(&board, false).err().unwrap(), "Unknown name 'near' in pair rule: near");
+    }
+
+    #[test]
+    fn test_given_pencilmarks() {
+        let mut board = FPuzzlesBoard::new(9);
+        board.grid[0][0].given_pencil_marks = vec![1, 2];
+        board.grid[0][1].given_pencil_marks = vec![2, 3];
+        board.grid[0][1].center_pencil_marks = vec![3, 4];
+        let parser = FPuzzlesParser::new();
+
+        let solver = parser.parse_board(&board, false).unwrap();
+        let cu = solver.cell_utility();
+        assert!(solver.board().constraints().is_empty());
+        assert_eq!(solver.board().cell(cu.cell(0, 0)), ValueMask::from_values(&[1, 2]));
+        assert_eq!(solver.board().cell(cu.cell(0, 1)), ValueMask::from_values(&[2, 3]));
+
+        let solver = parser.parse_board(&board, true).unwrap();
+        assert_eq!(solver.board().cell(cu.cell(0, 1)), ValueMask::from_value(3));
+    }
+
+    #[test]
+    fn test_weak_link_tie_break() {
+        // "Bird in a Storm" and "Black Kropki X", where the kropki dots give some candidates far more weak links
+        for (lzstring, _) in [FPUZZLES_TEST_DATA[1], FPUZZLES_TEST_DATA[6]] {
+            let board = FPuzzlesBoard::from_lzstring_json(lzstring).unwrap();
+            let mut solver = FPuzzlesParser::new().parse_board(&board, false).unwrap();
+            let mut counts = Vec::new();
+            let mut boards_explored = Vec::new();
+            for weak_link_tie_break in [false, true] {
+                solver.set_weak_link_tie_break(weak_link_tie_break);
+                let mut counter = solver.solution_counter(0);
+                counts.push(counter.run(usize::MAX, None, &Cancellation::default()).unwrap().count());
+                boards_explored.push(counter.boards_explored());
+            }
+            assert_eq!(counts[0], counts[1]);
+            assert!(boards_explored[1] * 10 < boards_explored[0], "{boards_explored:?}");
+        }
+    }
 }
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Deserializer, Serialize};
+use sudoku_solver_lib::prelude::*;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FPuzzlesBoard {
@@ -80,6 +81,10 @@ pub struct FPuzzlesBoard {
     pub skyscraper: Vec<FPuzzlesCell>,
     #[serde(default)]
     pub entropicline: Vec<FPuzzlesLines>,
+    /// Custom rules between orthogonally adjacent cells, which aren't part of f-puzzles.
+    /// The value is the name of a rule or an expression. See [`crate::pair_rule_constraint::PairRuleRegistry`].
+    #[serde(default)]
+    pub pairrule: Vec<FPuzzlesCells>,
     #[serde(default)]
     pub disabledlogic: Vec<String>,
     #[serde(default)]
@@ -87,6 +92,97 @@ pub struct FPuzzlesBoard {
 }
 
 impl FPuzzlesBoard {
+    /// Creates an empty board of the given size with default regions and no constraints.
+    pub fn new(size: i32) -> FPuzzlesBoard {
+        let entry = FPuzzlesGridEntry { region: -1, ..Default::default() };
+        let grid = vec![vec![entry; size.max(0) as usize]; size.max(0) as usize];
+        FPuzzlesBoard {
+            size,
+            title: String::new(),
+            author: String::new(),
+            ruleset: String::new(),
+            grid,
+            diagonal_p: false,
+            diagonal_n: false,
+            antiknight: false,
+            antiking: false,
+            disjointgroups: false,
+            nonconsecutive: false,
+            negative: Vec::new(),
+            arrow: Vec::new(),
+            killercage: Vec::new(),
+            cage: Vec::new(),
+            littlekillersum: Vec::new(),
+            odd: Vec::new(),
+            even: Vec::new(),
+            minimum: Vec::new(),
+            maximum: Vec::new(),
+            rowindexer: Vec::new(),
+            columnindexer: Vec::new(),
+            boxindexer: Vec::new(),
+            extraregion: Vec::new(),
+            thermometer: Vec::new(),
+            palindrome: Vec::new(),
+            renban: Vec::new(),
+            whispers: Vec::new(),
+            regionsumline: Vec::new(),
+            difference: Vec::new(),
+            xv: Vec::new(),
+            ratio: Vec::new(),
+            clone: Vec::new(),
+            quadruple: Vec::new(),
+            betweenline: Vec::new(),
+            sandwichsum: Vec::new(),
+            xsum: Vec::new(),
+            skyscraper: Vec::new(),
+            entropicline: Vec::new(),
+            pairrule: Vec::new(),
+            disabledlogic: Vec::new(),
+            truecandidatesoptions: Vec::new(),
+        }
+    }
+
+    /// Checks that the size is no larger than [`ValueMask::MAX_SIZE`] and that the grid has `size` rows
+    /// of `size` entries, as the rest of the board is read from the grid by row and column.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.size <= 0 {
+            return Err(format!("Invalid grid size {}", self.size));
+        }
+        let size = self.size as usize;
+        ValueMask::check_size(size)?;
+        if self.grid.len() != size {
+            return Err(format!("Grid has {} rows, expected {}", self.grid.len(), size));
+        }
+        if let Some((row, entries)) = self.grid.iter().enumerate().find(|(_, entries)| entries.len() != size) {
+            return Err(format!("Grid row {} has {} cells, expected {}", row + 1, entries.len(), size));
+        }
+        Ok(())
+    }
+
+    /// The region of each cell of a grid of the given size which has no region overrides, as f-puzzles
+    /// draws them. Sizes with no rectangular boxes, such as 5x5 and 7x7, have every cell in region 0.
+    pub fn default_regions(size: usize) -> Vec<usize> {
+        let regions = default_regions(size);
+        if size > 1 && regions[size] != regions[0] {
+            vec![0; size * size]
+        } else {
+            regions
+        }
+    }
+
+    /// Sets the region of each cell, indexed by [`CellIndex::index`], such as from [`Solver::regions`].
+    ///
+    /// Only the regions which differ from [`FPuzzlesBoard::default_regions`] are written as overrides,
+    /// so a grid with the default regions is written the same as one with no regions given.
+    pub fn set_regions(&mut self, regions: &[usize]) {
+        let size = self.grid.len();
+        let default_regions = Self::default_regions(size);
+        for (index, (&region, &default_region)) in regions.iter().zip(default_regions.iter()).enumerate() {
+            let entry = &mut self.grid[index / size][index % size];
+            entry.region = if region == default_region { -1 } else { region as i32 };
+        }
+    }
+
     pub fn from_lzstring_json(lz_str: &str) -> Result<FPuzzlesBoard, String> {
         let decompressed = lz_str::decompress_from_base64(lz_str);
         if decompressed.is_none() {
@@ -112,6 +208,14 @@ impl FPuzzlesBoard {
     pub fn from_json(json: &str) -> Result<FPuzzlesBoard, serde_json::Error> {
         serde_json::from_str(json)
     }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    pub fn to_lzstring_json(&self) -> String {
+        lz_str::compress_to_base64(self.to_json().as_str())
+    }
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -124,7 +228,7 @@ pub struct FPuzzlesGridEntry {
     pub center_pencil_marks: Vec<i32>,
     #[serde(rename = "givenPencilMarks", default = "Vec::default", deserialize_with = "deserialize_null_default")]
     pub given_pencil_marks: Vec<i32>,
-    #[serde(default = "default_neg1", deserialize_with = "deserialize_null_as_neg1")]
+    #[serde(default = "default_neg1", deserialize_with = "deserialize_null_as_neg1", skip_serializing_if = "is_neg1")]
     pub region: i32,
 }
 
@@ -210,6 +314,10 @@ fn default_neg1() -> i32 {
     -1
 }
 
+fn is_neg1(value: &i32) -> bool {
+    *value == -1
+}
+
 fn default_false() -> bool {
     false
 }
@@ -271,4 +379,20 @@ Arrow - the circled digit constitutes the sum of the digits along the arrow.
         assert_eq!(board.arrow.len(), 5);
         assert_eq!(board.thermometer.len(), 4);
     }
+
+    #[test]
+    fn test_lzstring_round_trip() {
+        let mut board = FPuzzlesBoard::new(6);
+        board.antiknight = true;
+        board.grid[1][2].value = 4;
+        board.grid[1][2].given = true;
+
+        let round_trip = FPuzzlesBoard::from_lzstring_json(&board.to_lzstring_json()).unwrap();
+        assert_eq!(round_trip.size, 6);
+        assert_eq!(round_trip.grid.len(), 6);
+        assert!(round_trip.antiknight);
+        assert!(round_trip.grid[1][2].given);
+        assert_eq!(round_trip.grid[1][2].value, 4);
+        assert_eq!(round_trip.grid[0][0].region, -1);
+    }
 }
@@ -1,4 +1,4 @@
-#[cfg(test)]
+#[cfg(any(test, feature = "self-test"))]
 pub const FPUZZLES_TEST_DATA: &[(&str, &str)] = &[
     // "Clipped" by glum_hippo: Arrow, Thermo, Givens, King
     (
@@ -167,7 +167,7 @@ pub const FPUZZLES_TEST_DATA: &[(&str, &str)] = &[
     ),
 ];
 
-#[cfg(test)]
+#[cfg(any(test, feature = "self-test"))]
 pub const FPUZZLES_CLASSICS_DATA: &[(&str, &str)] = &[
     (
         r#"N4IgzglgXgpiBcBOANCALhNAbO8QCUIAHInMEVAQwFc0ALAewCcEQAFOiLYgAgDkYAdwC2lAHYUQTamRhpWfZqKw8w1ACYMA1tR7SyPSiSwBPAHQ8AgmPU96lNAHIwPTAEJJAcyYR1CANr+oABulFjUuCggnhDBMBLwaNIwAL7IwGkZ6ZmZIKHhuAAcqDFxCUkRufkRCADsJbHxCBWp6XlhNfAAbA1lzck5ALrIQTnZbdW4AIy9TYkDEx2Rs+ULWetDIxuLBQgATCv9lePbpynDozudAMyH88dnJ2MZF+vtu0h3Lc8/J++dM2ijVWD3+uB6QL691SrzBCGKkLm3yeKLekwQAFYvms4Z9ESDWi8trj6vijoTfmilggIaUkTj0fAEXSCVVqfAsWToZsgrjOSzyZS2R9mcDBVdllzkSF2bSxdyJXVsQ9zlshYqOcqKajcQAWLXnQYpIA==="#,
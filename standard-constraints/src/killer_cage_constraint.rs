@@ -0,0 +1,356 @@
+//! Contains the [`KillerCageConstraint`] struct for representing a killer cage.
+
+use std::sync::Arc;
+use sudoku_solver_lib::prelude::*;
+
+/// A [`Constraint`] implementation for representing a killer cage, whose cells can't repeat
+/// values and optionally must sum to a given value.
+///
+/// A cage with as many cells as the size of the grid is also a house for logical steps which use houses.
+/// Cells which other constraints state are [`CellFact::Doubled`] or [`CellFact::Negated`] count that way in the sum.
+///
+/// # Example
+/// ```
+/// # use standard_constraints::prelude::*;
+/// # use sudoku_solver_lib::prelude::*;
+/// # use std::sync::Arc;
+/// let cu = CellUtility::new(9);
+/// let cage = KillerCageConstraint::new(vec![cu.cell(0, 0), cu.cell(0, 1)], Some(17));
+/// let solver = SolverBuilder::default().with_constraint(Arc::new(cage)).build().unwrap();
+/// assert_eq!(solver.board().cell(cu.cell(0, 0)), ValueMask::from_values(&[8, 9]));
+/// ```
+#[derive(Debug, Clone)]
+pub struct KillerCageConstraint {
+    specific_name: String,
+    cells: Vec<CellIndex>,
+    sum: Option<usize>,
+    /// How many times the value of each cell counts towards the sum, from the facts about the cell.
+    weights: Vec<isize>,
+}
+
+impl KillerCageConstraint {
+    /// Creates a new [`KillerCageConstraint`] whose cells must sum to the sum, if there is one.
+    ///
+    /// # Panics
+    /// Panics if the cage has no cells.
+    pub fn new(cells: Vec<CellIndex>, sum: Option<usize>) -> Self {
+        assert!(!cells.is_empty(), "A killer cage needs at least one cell");
+        let specific_name = match sum {
+            Some(sum) => format!("Killer Cage {sum} at {}", cells[0]),
+            None => format!("Killer Cage at {}", cells[0]),
+        };
+        let weights = vec![1; cells.len()];
+        Self { specific_name, cells, sum, weights }
+    }
+
+    /// The cells of the cage.
+    pub fn cells(&self) -> &[CellIndex] {
+        &self.cells
+    }
+
+    /// The sum of the cage, if it has one.
+    pub fn sum(&self) -> Option<usize> {
+        self.sum
+    }
+
+    /// The sets of values which the cells could have between them, whether or not they fit the board.
+    fn combinations(&self, size: usize) -> Vec<ValueMask> {
+        match self.sum {
+            Some(sum) => sum_combinations(size, self.cells.len(), sum),
+            None => vec![ValueMask::from_all_values(size)],
+        }
+    }
+
+    /// Whether any cell doesn't count once towards the sum.
+    fn is_weighted(&self) -> bool {
+        self.weights.iter().any(|&weight| weight != 1)
+    }
+
+    /// Finds the values each cell can have in at least one valid filling of the cage,
+    /// and the sets of values of those fillings.
+    ///
+    /// The sets of values are only found when every cell counts once towards the sum.
+    fn possible_values(&self, board: &Board) -> (Vec<ValueMask>, Vec<ValueMask>) {
+        let masks = self.cells.iter().map(|&cell| board.cell(cell)).collect::<Vec<_>>();
+        if let (Some(sum), true) = (self.sum, self.is_weighted()) {
+            return (self.possible_weighted_values(&masks, sum), Vec::new());
+        }
+
+        let mut possible = vec![ValueMask::new(); self.cells.len()];
+        let mut valid_combinations = Vec::new();
+        for combination in self.combinations(board.size()) {
+            let combination_masks = masks.iter().map(|&mask| mask.unsolved() & combination).collect::<Vec<_>>();
+            let combination_possible =
+                possible_filling_values(&combination_masks, |values, value| !values.contains(&value), |_| true);
+            if combination_possible.first().is_some_and(|mask| !mask.is_empty()) {
+                valid_combinations.push(combination);
+                for (possible, combination_possible) in possible.iter_mut().zip(combination_possible) {
+                    *possible = *possible | combination_possible;
+                }
+            }
+        }
+        (possible, valid_combinations)
+    }
+
+    /// Finds the values each cell can have in at least one filling of the cage whose weighted values reach the sum.
+    fn possible_weighted_values(&self, masks: &[ValueMask], sum: usize) -> Vec<ValueMask> {
+        // The smallest and largest total of the cells from each index on, ignoring repeats.
+        let mut bounds = vec![(0, 0); masks.len() + 1];
+        for (index, (&mask, &weight)) in masks.iter().zip(self.weights.iter()).enumerate().rev() {
+            let (low, high) = (mask.unsolved().min() as isize * weight, mask.unsolved().max() as isize * weight);
+            bounds[index] = (bounds[index + 1].0 + low.min(high), bounds[index + 1].1 + low.max(high));
+        }
+
+        let mut possible = vec![ValueMask::new(); masks.len()];
+        let mut values = Vec::with_capacity(masks.len());
+        for (index, &mask) in masks.iter().enumerate() {
+            for value in (mask & !possible[index]).unsolved() {
+                values.clear();
+                if self.fill_weighted(masks, &bounds, sum as isize, &mut values, (index, value)) {
+                    for (possible, &value) in possible.iter_mut().zip(values.iter()) {
+                        *possible = possible.with(value);
+                    }
+                }
+            }
+        }
+        possible
+    }
+
+    /// Extends the values with a filling of the rest of the cage with different values whose weighted total
+    /// is the remaining sum, where the fixed cell has the fixed value, returning false if there is none.
+    fn fill_weighted(
+        &self,
+        masks: &[ValueMask],
+        bounds: &[(isize, isize)],
+        remaining: isize,
+        values: &mut Vec<usize>,
+        fixed: (usize, usize),
+    ) -> bool {
+        let index = values.len();
+        if remaining < bounds[index].0 || remaining > bounds[index].1 {
+            return false;
+        }
+        if index == masks.len() {
+            return true;
+        }
+
+        let used = values.iter().fold(ValueMask::new(), |used, &value| used.with(value));
+        let mask = if index == fixed.0 { ValueMask::from_value(fixed.1) } else { masks[index].unsolved() };
+        for value in mask & !used {
+            values.push(value);
+            if self.fill_weighted(masks, bounds, remaining - value as isize * self.weights[index], values, fixed) {
+                return true;
+            }
+            values.pop();
+        }
+        false
+    }
+
+    /// Removes the candidates which aren't part of any valid filling of the cage.
+    fn eliminate_impossible(&self, board: &mut Board) -> (LogicalStepResult, EliminationList) {
+        let (possible, _) = self.possible_values(board);
+        let elims = EliminationList::from_possible(&self.cells, &possible, board);
+        (elims.execute(board), elims)
+    }
+}
+
+impl Constraint for KillerCageConstraint {
+    fn name(&self) -> &str {
+        &self.specific_name
+    }
+
+    fn clone_constraint(&self) -> Option<Arc<dyn Constraint>> {
+        Some(Arc::new(self.clone()))
+    }
+
+    fn init_board(&mut self, board: &mut Board) -> LogicalStepResult {
+        for (weight, &cell) in self.weights.iter_mut().zip(self.cells.iter()) {
+            let doubled = if board.cell_has_fact(cell, CellFact::Doubled) { 2 } else { 1 };
+            let negated = if board.cell_has_fact(cell, CellFact::Negated) { -1 } else { 1 };
+            *weight = doubled * negated;
+        }
+
+        if self.sum.is_none() {
+            return LogicalStepResult::None;
+        }
+        self.eliminate_impossible(board).0
+    }
+
+    fn enforce(&self, board: &Board, _cell: CellIndex, _val: usize) -> LogicalStepResult {
+        // Repeated values are enforced by the weak links.
+        let Some(sum) = self.sum else {
+            return LogicalStepResult::None;
+        };
+
+        let masks = self.cells.iter().map(|&cell| board.cell(cell)).collect::<Vec<_>>();
+        if !masks.iter().all(|mask| mask.is_single()) {
+            return LogicalStepResult::None;
+        }
+        let total: isize =
+            masks.iter().zip(self.weights.iter()).map(|(mask, &weight)| mask.value() as isize * weight).sum();
+        if total != sum as isize {
+            return LogicalStepResult::Invalid(None);
+        }
+        LogicalStepResult::None
+    }
+
+    fn step_logic(&self, board: &mut Board, is_brute_forcing: bool, _cancellation: &Cancellation) -> LogicalStepResult {
+        if self.sum.is_none() {
+            return LogicalStepResult::None;
+        }
+
+        let (result, elims) = self.eliminate_impossible(board);
+        if is_brute_forcing {
+            return result;
+        }
+        match result {
+            LogicalStepResult::Invalid(_) => {
+                LogicalStepResult::Invalid(Some("The cage has no valid combination of values.".into()))
+            }
+            LogicalStepResult::Changed(_) => LogicalStepResult::Changed(Some(LogicalStepDesc::from_elims(
+                "Not part of any valid combination",
+                &elims,
+            ))),
+            LogicalStepResult::None => LogicalStepResult::None,
+        }
+    }
+
+    fn cells_must_contain(&self, board: &Board, val: usize) -> Vec<CellIndex> {
+        let masks = self.cells.iter().map(|&cell| board.cell(cell)).collect::<Vec<_>>();
+        if masks.iter().any(|mask| mask.is_single() && mask.value() == val) {
+            return Vec::new();
+        }
+
+        let must_contain = if self.sum.is_some() && !self.is_weighted() {
+            let (_, valid_combinations) = self.possible_values(board);
+            !valid_combinations.is_empty() && valid_combinations.iter().all(|combination| combination.has(val))
+        } else {
+            self.cells.len() == board.size()
+        };
+        if !must_contain {
+            return Vec::new();
+        }
+
+        self.cells.iter().zip(masks.iter()).filter(|(_, mask)| mask.has(val)).map(|(&cell, _)| cell).collect()
+    }
+
+    fn relevant_cells(&self) -> Vec<CellIndex> {
+        self.cells.clone()
+    }
+
+    fn get_weak_links(&self, size: usize) -> Vec<(CandidateIndex, CandidateIndex)> {
+        if self.cells.len() > 1 && self.cells.len() <= size {
+            get_weak_links_for_nonrepeat(self.cells.iter().copied())
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn get_houses(&self, context: &HouseContext) -> Vec<House> {
+        if self.cells.len() == context.size() {
+            vec![House::new(self.specific_name.as_str(), &self.cells)]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[test]
+    fn test_sums() {
+        let cu = CellUtility::new(9);
+        let cells = vec![cu.cell(0, 0), cu.cell(0, 1), cu.cell(1, 0)];
+        let cage = KillerCageConstraint::new(cells.clone(), Some(7));
+        assert_eq!(cage.name(), "Killer Cage 7 at r1c1");
+        let mut solver = SolverBuilder::default()
+            .with_logic_profile(LogicProfile::V2WithTuples)
+            .with_constraint(Arc::new(cage))
+            .build()
+            .unwrap();
+        for &cell in cells.iter() {
+            assert_eq!(solver.board().cell(cell), ValueMask::from_values(&[1, 2, 4]));
+        }
+
+        // 1, 2 and 4 must be in the cage, so they can't be anywhere else in the box
+        let cage = &solver.board().constraints()[0];
+        assert_eq!(cage.cells_must_contain(solver.board(), 4), cells);
+        assert!(cage.cells_must_contain(solver.board(), 3).is_empty());
+        assert!(solver.run_logical_solve().is_changed());
+        assert!(!solver.board().cell(cu.cell(1, 1)).has(4));
+        assert!(!solver.board().cell(cu.cell(2, 2)).has(1));
+
+        // A value narrows down the rest of the cage
+        let cage = KillerCageConstraint::new(vec![cu.cell(4, 4), cu.cell(4, 5), cu.cell(4, 6)], Some(15));
+        let mut solver = SolverBuilder::default().with_constraint(Arc::new(cage)).build().unwrap();
+        assert!(solver.set_solved(cu.cell(4, 4), 9));
+        let result = solver.run_single_logical_step();
+        assert!(
+            result.to_string().starts_with("Killer Cage 15 at r5c5: Not part of any valid combination"),
+            "{result}"
+        );
+        assert_eq!(solver.board().cell(cu.cell(4, 5)), ValueMask::from_values(&[1, 2, 4, 5]));
+
+        // The sum is enforced once the cage is full
+        let cage = KillerCageConstraint::new(vec![cu.cell(0, 0), cu.cell(0, 1)], Some(10));
+        let mut solver = SolverBuilder::default().with_constraint(Arc::new(cage)).build().unwrap();
+        assert!(solver.set_solved(cu.cell(0, 0), 3));
+        assert!(!solver.clone().set_solved(cu.cell(0, 1), 8));
+        assert!(solver.set_solved(cu.cell(0, 1), 7));
+    }
+
+    #[test]
+    fn test_no_sum_and_houses() {
+        let cu = CellUtility::new(9);
+        let cells = (0..9).map(|index| cu.cell(index / 3, index % 3 + 3 * (index / 6))).collect::<Vec<_>>();
+        let cage = KillerCageConstraint::new(cells.clone(), None);
+        assert_eq!(cage.name(), "Killer Cage at r1c1");
+        let solver = SolverBuilder::default().with_constraint(Arc::new(cage)).build().unwrap();
+        assert_eq!(solver.board().houses().len(), 28);
+
+        let board = solver.board();
+        let cage = &board.constraints()[0];
+        assert_eq!(cage.cells_must_contain(board, 5).len(), 9);
+        assert!(board.has_weak_link(cells[0].candidate(1), cells[8].candidate(1)));
+
+        // A smaller cage without a sum must contain nothing
+        let cage = KillerCageConstraint::new(vec![cu.cell(0, 0), cu.cell(0, 1)], None);
+        assert!(cage.cells_must_contain(&Board::default(), 5).is_empty());
+    }
+
+    #[test]
+    fn test_cell_facts() {
+        /// States that its cells are doubled.
+        #[derive(Debug)]
+        struct Doublers(Vec<CellIndex>);
+
+        impl Constraint for Doublers {
+            fn name(&self) -> &str {
+                "Doublers"
+            }
+
+            fn has_cell_fact(&self, cell: CellIndex, fact: CellFact) -> bool {
+                fact == CellFact::Doubled && self.0.contains(&cell)
+            }
+        }
+
+        // 1 doubled and 3, or 2 doubled and 1, sum to 5
+        let cu = CellUtility::new(9);
+        let cells = vec![cu.cell(0, 0), cu.cell(0, 1)];
+        let cage = KillerCageConstraint::new(cells.clone(), Some(5));
+        let mut solver = SolverBuilder::default()
+            .with_constraint(Arc::new(cage))
+            .with_constraint(Arc::new(Doublers(vec![cells[0]])))
+            .build()
+            .unwrap();
+        assert_eq!(solver.board().cell(cells[0]), ValueMask::from_values(&[1, 2]));
+        assert_eq!(solver.board().cell(cells[1]), ValueMask::from_values(&[1, 3]));
+        assert!(solver.set_solved(cells[0], 2));
+        assert!(!solver.clone().set_solved(cells[1], 3));
+        assert!(solver.set_solved(cells[1], 1));
+    }
+}
@@ -1,9 +1,114 @@
 pub mod arrow_sum_constraint;
+pub mod between_line_constraint;
+pub mod chaos_construction_constraint;
 pub mod chess_constraint;
 pub mod fpuzzles_parser;
+pub mod killer_cage_constraint;
+pub mod line_constraint;
 pub mod message_handler;
 pub mod non_repeat_constraint;
 pub mod orthogonal_pairs_constraint;
+pub mod outside_clue;
+pub mod pair_rule_constraint;
+pub mod pair_rule_expression;
 pub mod pencilmark_constraint;
 pub mod prelude;
+pub mod puzzle_collection;
+pub mod puzzle_input;
+#[cfg(any(test, feature = "self-test"))]
+pub mod regression_suite;
+pub mod skyscraper_constraint;
+pub mod solver_builder_ext;
+pub mod solver_builder_presets;
 pub mod standard_pair_type;
+pub mod x_sum_constraint;
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use std::sync::Arc;
+    use sudoku_solver_lib::prelude::*;
+    use sudoku_solver_lib::test_support::{PropertyTester, Rng, StdRng};
+
+    /// Makes random constraints of one kind for a 9x9 grid.
+    type MakeConstraints = fn(&mut StdRng) -> Vec<Arc<dyn Constraint>>;
+
+    /// Two to four adjacent cells of the row, starting at a random column.
+    fn random_segment(rng: &mut StdRng, row: usize) -> Vec<CellIndex> {
+        let cu = CellUtility::new(9);
+        let start = rng.gen_range(0..5);
+        (start..start + rng.gen_range(2..5)).map(|col| cu.cell(row, col)).collect()
+    }
+
+    #[test]
+    fn test_property_random_constraints() {
+        // Each kind of constraint is checked with its own seed.
+        let kinds: [(u64, MakeConstraints); 6] = [
+            (9, |rng| {
+                let constraints: [Arc<dyn Constraint>; 2] =
+                    [Arc::new(ChessConstraint::anti_king()), Arc::new(ChessConstraint::anti_knight())];
+                constraints.into_iter().filter(|_| rng.gen_bool(0.5)).collect()
+            }),
+            (33, |rng| {
+                [1, 4, 7]
+                    .into_iter()
+                    .map(|row| -> Arc<dyn Constraint> {
+                        let cells = random_segment(rng, row);
+                        Arc::new(match rng.gen_range(0..5) {
+                            0 => LineConstraint::thermometer(cells),
+                            1 => LineConstraint::whispers(cells, 5),
+                            2 => LineConstraint::renban(cells),
+                            3 => LineConstraint::entropic(cells),
+                            _ => LineConstraint::modular(cells),
+                        })
+                    })
+                    .collect()
+            }),
+            (51, |rng| {
+                [1, 4, 7]
+                    .into_iter()
+                    .map(|row| -> Arc<dyn Constraint> {
+                        let cells = random_segment(rng, row);
+                        let min_sum = (1..=cells.len()).sum::<usize>();
+                        let max_sum = (10 - cells.len()..=9).sum::<usize>();
+                        let sum = rng.gen_range(min_sum..=max_sum);
+                        Arc::new(KillerCageConstraint::new(cells, Some(sum)))
+                    })
+                    .collect()
+            }),
+            (59, |rng| {
+                [1, 4, 7]
+                    .into_iter()
+                    .map(|row| -> Arc<dyn Constraint> {
+                        Arc::new(BetweenLineConstraint::new(random_segment(rng, row)))
+                    })
+                    .collect()
+            }),
+            (64, |rng| {
+                [1, 5, 9]
+                    .into_iter()
+                    .map(|row| -> Arc<dyn Constraint> {
+                        let clue = OutsideClue::new(9, row, 0).unwrap();
+                        Arc::new(XSumConstraint::new(&clue, rng.gen_range(5..=35)))
+                    })
+                    .collect()
+            }),
+            (65, |rng| {
+                [1, 5, 9]
+                    .into_iter()
+                    .map(|col| -> Arc<dyn Constraint> {
+                        let clue = OutsideClue::new(9, 0, col).unwrap();
+                        Arc::new(SkyscraperConstraint::new(&clue, rng.gen_range(1..=5)))
+                    })
+                    .collect()
+            }),
+        ];
+        for (seed, make_constraints) in kinds {
+            PropertyTester::new(seed).with_cases(6).run(|rng| {
+                make_constraints(rng)
+                    .into_iter()
+                    .fold(SolverBuilder::default(), |builder, constraint| builder.with_constraint(constraint))
+            });
+        }
+    }
+}
@@ -0,0 +1,361 @@
+//! Contains the [`LineConstraint`] struct for representing constraints along a line of cells,
+//! such as thermometers, whispers and renbans.
+
+use std::sync::Arc;
+
+use itertools::Itertools;
+use sudoku_solver_lib::prelude::*;
+
+/// Whether two values are allowed on cells of a line which are the given distance apart along it.
+///
+/// The arguments are the value nearer the start of the line, the value nearer the end and the distance.
+pub type LinePairRule = Arc<dyn Fn(usize, usize, usize) -> bool + Send + Sync>;
+
+/// Whether the values of every cell of a line, in order, are allowed.
+pub type LineRule = Arc<dyn Fn(&[usize]) -> bool + Send + Sync>;
+
+/// A [`Constraint`] implementation for representing a rule along a path of cells.
+///
+/// The rule is a [`LinePairRule`], which becomes weak links between the cells of the line, and
+/// optionally a [`LineRule`] for rules which can't be checked pair by pair, such as a sum.
+/// Logical solves remove the candidates which aren't part of any valid filling of the line.
+///
+/// Thermometers, whispers, renbans, entropic lines and modular lines are all pair rules,
+/// and have their own constructors.
+///
+/// # Example
+/// ```
+/// # use standard_constraints::prelude::*;
+/// # use sudoku_solver_lib::prelude::*;
+/// # use std::sync::Arc;
+/// let cu = CellUtility::new(9);
+/// let thermometer = LineConstraint::thermometer((0..9).map(|col| cu.cell(0, col)).collect());
+/// let solver = SolverBuilder::default().with_constraint(Arc::new(thermometer)).build().unwrap();
+/// assert_eq!(solver.board().to_string()[..9], *"123456789");
+/// ```
+#[derive(Clone)]
+pub struct LineConstraint {
+    specific_name: String,
+    cells: Vec<CellIndex>,
+    pair_rule: LinePairRule,
+    line_rule: Option<LineRule>,
+}
+
+impl LineConstraint {
+    /// Creates a new [`LineConstraint`] where every pair of cells on the line must follow the pair rule.
+    pub fn new(
+        specific_name: &str,
+        cells: Vec<CellIndex>,
+        pair_rule: impl Fn(usize, usize, usize) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self { specific_name: specific_name.to_owned(), cells, pair_rule: Arc::new(pair_rule), line_rule: None }
+    }
+
+    /// Also require the values of the whole line to follow the rule.
+    #[must_use]
+    pub fn with_line_rule(mut self, line_rule: impl Fn(&[usize]) -> bool + Send + Sync + 'static) -> Self {
+        self.line_rule = Some(Arc::new(line_rule));
+        self
+    }
+
+    /// Creates a thermometer, where values strictly increase from the bulb, which is the first cell.
+    ///
+    /// # Panics
+    /// Panics if the line has no cells.
+    pub fn thermometer(cells: Vec<CellIndex>) -> Self {
+        let name = format!("Thermometer at {}", Self::first_cell(&cells));
+        Self::new(&name, cells, |low, high, distance| high >= low + distance)
+    }
+
+    /// Creates a whispers line, where adjacent values differ by at least the difference.
+    ///
+    /// # Panics
+    /// Panics if the line has no cells.
+    pub fn whispers(cells: Vec<CellIndex>, difference: usize) -> Self {
+        let name = format!("Whispers at {}", Self::first_cell(&cells));
+        Self::new(&name, cells, move |a, b, distance| distance > 1 || a.abs_diff(b) >= difference)
+    }
+
+    /// Creates a German whispers line, where adjacent values differ by at least
+    /// [`LineConstraint::german_whispers_difference`], which is 5 on a 9x9 grid.
+    ///
+    /// # Panics
+    /// Panics if the line has no cells.
+    pub fn german_whispers(cells: Vec<CellIndex>) -> Self {
+        let difference = Self::german_whispers_difference(Self::first_cell(&cells).size());
+        Self::whispers(cells, difference)
+    }
+
+    /// The least difference between adjacent values of a German whispers line on a grid of the size,
+    /// which is the smallest difference that the middle value can't have with any other value.
+    pub fn german_whispers_difference(size: usize) -> usize {
+        size / 2 + 1
+    }
+
+    /// Creates a renban line, whose values are a set of consecutive values in any order.
+    ///
+    /// # Panics
+    /// Panics if the line has no cells.
+    pub fn renban(cells: Vec<CellIndex>) -> Self {
+        let name = format!("Renban at {}", Self::first_cell(&cells));
+        let len = cells.len();
+        // Different values which are all less than the length apart must be consecutive.
+        Self::new(&name, cells, move |a, b, _| a != b && a.abs_diff(b) < len)
+    }
+
+    /// Creates an entropic line, where every three cells in a row have one low, one middle and one high value.
+    ///
+    /// The values are split into thirds, such as 1-3, 4-6 and 7-9 on a 9x9 grid.
+    ///
+    /// # Panics
+    /// Panics if the line has no cells.
+    pub fn entropic(cells: Vec<CellIndex>) -> Self {
+        let first_cell = Self::first_cell(&cells);
+        let name = format!("Entropic Line at {first_cell}");
+        let size = first_cell.size();
+        Self::new(&name, cells, move |a, b, distance| ((a - 1) * 3 / size == (b - 1) * 3 / size) == (distance % 3 == 0))
+    }
+
+    /// Creates a modular line, where every three cells in a row have one value from each residue modulo 3.
+    ///
+    /// # Panics
+    /// Panics if the line has no cells.
+    pub fn modular(cells: Vec<CellIndex>) -> Self {
+        let name = format!("Modular Line at {}", Self::first_cell(&cells));
+        Self::new(&name, cells, |a, b, distance| (a % 3 == b % 3) == (distance % 3 == 0))
+    }
+
+    /// The first cell of a line, which names the line.
+    fn first_cell(cells: &[CellIndex]) -> CellIndex {
+        assert!(!cells.is_empty(), "A line needs at least one cell");
+        cells[0]
+    }
+
+    /// The cells of the line, in order.
+    pub fn cells(&self) -> &[CellIndex] {
+        &self.cells
+    }
+
+    /// Finds the values each cell of the line can have in at least one valid filling of the line,
+    /// or `None` if it is cancelled first.
+    fn possible_values(&self, board: &Board, cancellation: &Cancellation) -> Option<Vec<ValueMask>> {
+        let masks = self.cells.iter().map(|&cell| board.cell(cell)).collect::<Vec<_>>();
+        // A cancelled search finds no more fillings, so its result is thrown away.
+        let possible = possible_filling_values(
+            &masks,
+            |values, value| !cancellation.check() && self.is_consistent(board, values, self.cells[values.len()], value),
+            |values| self.line_rule.as_ref().is_none_or(|line_rule| line_rule(values)),
+        );
+        (!cancellation.check()).then_some(possible)
+    }
+
+    /// Whether the value can follow the values of the earlier cells of the line.
+    fn is_consistent(&self, board: &Board, values: &[usize], cell: CellIndex, value: usize) -> bool {
+        let index = values.len();
+        values.iter().enumerate().all(|(other_index, &other_value)| {
+            let other_cell = self.cells[other_index];
+            if other_cell == cell {
+                // A line which crosses itself has the same value where it crosses.
+                other_value == value
+            } else {
+                (self.pair_rule)(other_value, value, index - other_index)
+                    && !board.has_weak_link(other_cell.candidate(other_value), cell.candidate(value))
+            }
+        })
+    }
+
+    /// Removes the candidates which aren't part of any valid filling of the line.
+    fn eliminate_impossible(
+        &self,
+        board: &mut Board,
+        cancellation: &Cancellation,
+    ) -> (LogicalStepResult, EliminationList) {
+        let Some(possible) = self.possible_values(board, cancellation) else {
+            return (LogicalStepResult::None, EliminationList::new());
+        };
+        let elims = EliminationList::from_possible(&self.cells, &possible, board);
+        (elims.execute(board), elims)
+    }
+}
+
+impl std::fmt::Debug for LineConstraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LineConstraint")
+            .field("specific_name", &self.specific_name)
+            .field("cells", &self.cells)
+            .field("line_rule", &self.line_rule.is_some())
+            .finish()
+    }
+}
+
+impl Constraint for LineConstraint {
+    fn name(&self) -> &str {
+        &self.specific_name
+    }
+
+    fn clone_constraint(&self) -> Option<Arc<dyn Constraint>> {
+        Some(Arc::new(self.clone()))
+    }
+
+    fn init_board(&mut self, board: &mut Board) -> LogicalStepResult {
+        self.eliminate_impossible(board, &Cancellation::default()).0
+    }
+
+    fn enforce(&self, board: &Board, _cell: CellIndex, _val: usize) -> LogicalStepResult {
+        // The pair rule is enforced by the weak links.
+        let Some(line_rule) = &self.line_rule else {
+            return LogicalStepResult::None;
+        };
+
+        let masks = self.cells.iter().map(|&cell| board.cell(cell)).collect::<Vec<_>>();
+        if masks.iter().all(|mask| mask.is_single()) {
+            let values = masks.iter().map(|mask| mask.value()).collect::<Vec<_>>();
+            if !line_rule(&values) {
+                return LogicalStepResult::Invalid(None);
+            }
+        }
+        LogicalStepResult::None
+    }
+
+    fn step_logic(&self, board: &mut Board, is_brute_forcing: bool, cancellation: &Cancellation) -> LogicalStepResult {
+        // The weak links already apply the pair rule, which is faster than guessing.
+        if is_brute_forcing && self.line_rule.is_none() {
+            return LogicalStepResult::None;
+        }
+
+        // The solved cells of the line are what narrow down the rest of it.
+        let solved: Vec<CandidateIndex> = if is_brute_forcing {
+            Vec::new()
+        } else {
+            self.cells
+                .iter()
+                .unique()
+                .filter(|&&cell| board.cell(cell).is_solved())
+                .map(|&cell| cell.candidate(board.cell(cell).value()))
+                .collect()
+        };
+
+        let (result, elims) = self.eliminate_impossible(board, cancellation);
+        if is_brute_forcing {
+            return result;
+        }
+        match result {
+            LogicalStepResult::Invalid(_) => LogicalStepResult::Invalid(Some("The line has no valid values.".into())),
+            LogicalStepResult::Changed(_) => {
+                let mut desc = LogicalStepDesc::from_elims("Not part of any valid values for the line", &elims);
+                if !solved.is_empty() {
+                    desc = desc.with_cause(StepCause::new(solved));
+                }
+                LogicalStepResult::Changed(Some(desc))
+            }
+            LogicalStepResult::None => LogicalStepResult::None,
+        }
+    }
+
+    fn relevant_cells(&self) -> Vec<CellIndex> {
+        self.cells.iter().copied().unique().collect()
+    }
+
+    fn get_weak_links(&self, size: usize) -> Vec<(CandidateIndex, CandidateIndex)> {
+        let mut result = Vec::new();
+        for ((index0, &cell0), (index1, &cell1)) in self.cells.iter().enumerate().tuple_combinations() {
+            if cell0 == cell1 {
+                continue;
+            }
+            for value0 in 1..=size {
+                for value1 in 1..=size {
+                    if !(self.pair_rule)(value0, value1, index1 - index0) {
+                        result.push((cell0.candidate(value0), cell1.candidate(value1)));
+                    }
+                }
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn row_cells(cu: CellUtility, row: usize, cols: std::ops::Range<usize>) -> Vec<CellIndex> {
+        cols.map(|col| cu.cell(row, col)).collect()
+    }
+
+    #[test]
+    fn test_thermometer() {
+        let cu = CellUtility::new(9);
+        let thermometer = LineConstraint::thermometer(row_cells(cu, 0, 0..4));
+        let solver = SolverBuilder::default().with_constraint(Arc::new(thermometer)).build().unwrap();
+        let board = solver.board();
+        assert_eq!(board.cell(cu.cell(0, 0)), ValueMask::from_between_inclusive(1, 6, 9));
+        assert_eq!(board.cell(cu.cell(0, 3)), ValueMask::from_between_inclusive(4, 9, 9));
+
+        let thermometer = LineConstraint::thermometer(row_cells(cu, 0, 0..4));
+        let solver = SolverBuilder::default()
+            .with_constraint(Arc::new(thermometer))
+            .with_given(cu.cell(0, 3), 4)
+            .build()
+            .unwrap();
+        assert!(solver.board().all_cell_masks().take(4).map(|(_, mask)| mask.value()).eq(1..=4));
+    }
+
+    #[test]
+    fn test_whispers_and_renban() {
+        let cu = CellUtility::new(9);
+        assert_eq!(LineConstraint::german_whispers_difference(6), 4);
+        let whispers = LineConstraint::german_whispers(row_cells(cu, 0, 0..3));
+        let solver = SolverBuilder::default().with_constraint(Arc::new(whispers)).build().unwrap();
+        assert!(!solver.board().cell(cu.cell(0, 1)).has(5));
+
+        let renban = LineConstraint::renban(row_cells(cu, 4, 0..3));
+        let solver =
+            SolverBuilder::default().with_constraint(Arc::new(renban)).with_given(cu.cell(4, 0), 1).build().unwrap();
+        assert_eq!(solver.board().cell(cu.cell(4, 1)), ValueMask::from_values(&[2, 3]));
+        assert!(solver.find_solution_count(2, None, None).count().unwrap() > 1);
+    }
+
+    #[test]
+    fn test_entropic_and_modular() {
+        let cu = CellUtility::new(9);
+        let entropic = LineConstraint::entropic(row_cells(cu, 0, 0..4));
+        let solver = SolverBuilder::default()
+            .with_constraint(Arc::new(entropic))
+            .with_givens(&[(cu.cell(0, 0), 1), (cu.cell(0, 1), 5)])
+            .build()
+            .unwrap();
+        assert_eq!(solver.board().cell(cu.cell(0, 2)), ValueMask::from_values(&[7, 8, 9]));
+        assert_eq!(solver.board().cell(cu.cell(0, 3)), ValueMask::from_values(&[2, 3]));
+
+        let modular = LineConstraint::modular(row_cells(cu, 0, 0..3));
+        let solver = SolverBuilder::default()
+            .with_constraint(Arc::new(modular))
+            .with_givens(&[(cu.cell(0, 0), 1), (cu.cell(0, 1), 5)])
+            .build()
+            .unwrap();
+        assert_eq!(solver.board().cell(cu.cell(0, 2)), ValueMask::from_values(&[3, 6, 9]));
+    }
+
+    #[test]
+    fn test_line_rule() {
+        // A line whose values sum to 10, with no pair rule.
+        let cu = CellUtility::new(9);
+        let line = LineConstraint::new("Sum", row_cells(cu, 0, 0..3), |_, _, _| true)
+            .with_line_rule(|values| values.iter().sum::<usize>() == 10);
+        let mut solver = SolverBuilder::default().with_constraint(Arc::new(line)).build().unwrap();
+        assert_eq!(solver.board().cell(cu.cell(0, 0)), ValueMask::from_lower_equal(7));
+
+        assert!(solver.set_solved(cu.cell(0, 0), 7));
+        let result = solver.run_single_logical_step();
+        assert_eq!(
+            result.description().unwrap().to_string(),
+            "Sum: Not part of any valid values for the line => -3r1c23;-4r1c23;-5r1c23;-6r1c23 because r1c1=7"
+        );
+        assert_eq!(solver.board().cell(cu.cell(0, 1)), ValueMask::from_values(&[1, 2]));
+
+        let line = LineConstraint::new("Sum", row_cells(cu, 0, 0..3), |_, _, _| true)
+            .with_line_rule(|values| values.iter().sum::<usize>() == 10);
+        let solver = SolverBuilder::default().with_constraint(Arc::new(line)).build().unwrap();
+        assert!(sudoku_solver_lib::test_support::check_counts_agree(&solver, 1000).is_ok());
+    }
+}
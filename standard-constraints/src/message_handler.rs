@@ -1,16 +1,24 @@
 pub mod message;
 pub mod responses;
 
+use std::future::Future;
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::OnceLock;
+use std::time::Duration;
 #[cfg(not(target_arch = "wasm32"))]
 use std::time::Instant;
 
 use crate::prelude::*;
 use itertools::Itertools;
 use sudoku_solver_lib::prelude::*;
+use tracing::Instrument;
 
 use self::message::*;
 use self::responses::*;
 
+/// The commands which [`MessageHandler`] understands, as listed in its capabilities.
+const COMMANDS: &[&str] = &["capabilities", "cancel", "truecandidates", "solve", "check", "count", "solvepath", "step"];
+
 pub trait SendResult {
     fn send_result(&mut self, result: &str);
 }
@@ -18,11 +26,42 @@ pub trait SendResult {
 pub struct MessageHandler {
     send_result: Box<dyn SendResult>,
     cancellation: Cancellation,
+    parser: FPuzzlesParser,
+    /// The time since some fixed point, used for the timeout of [`SolverOptions`].
+    clock: Option<fn() -> Duration>,
+}
+
+/// The time since the clock was first read.
+#[cfg(not(target_arch = "wasm32"))]
+fn instant_clock() -> Duration {
+    static START: OnceLock<Instant> = OnceLock::new();
+    START.get_or_init(Instant::now).elapsed()
 }
 
 impl MessageHandler {
     pub fn new(send_result: Box<dyn SendResult>) -> Self {
-        Self { send_result, cancellation: Cancellation::default() }
+        #[cfg(not(target_arch = "wasm32"))]
+        let clock = Some(instant_clock as fn() -> Duration);
+        #[cfg(target_arch = "wasm32")]
+        let clock = None;
+        Self { send_result, cancellation: Cancellation::default(), parser: FPuzzlesParser::new(), clock }
+    }
+
+    /// Use this clock for the timeouts of puzzles. It returns the time since any fixed point.
+    ///
+    /// Outside of wasm the handler measures time on its own, but wasm has no clock
+    /// in the standard library, so timeouts are ignored there unless a clock is given.
+    #[must_use]
+    pub fn with_clock(mut self, clock: fn() -> Duration) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Use this parser for incoming puzzles, such as one with a custom set of logical steps.
+    #[must_use]
+    pub fn with_parser(mut self, parser: FPuzzlesParser) -> Self {
+        self.parser = parser;
+        self
     }
 
     fn send_result(&mut self, result: &str) {
@@ -30,51 +69,133 @@ impl MessageHandler {
     }
 
     pub fn handle_message(&mut self, message: &str, cancellation: Cancellation) {
+        let message = match self.start_message(message, cancellation) {
+            Some(message) => message,
+            None => return,
+        };
+
+        let span = tracing::info_span!("request", nonce = message.nonce(), command = message.command());
+        let _enter = span.enter();
+        #[cfg(not(target_arch = "wasm32"))]
+        let start_time = Instant::now();
+
+        let result = self.handle_command(&message);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        tracing::info!(elapsed_ms = start_time.elapsed().as_secs_f64() * 1000.0, "Request finished");
+
+        self.send_result(result.as_str());
+    }
+
+    /// Handles a message the same way as [`Self::handle_message`], but without blocking
+    /// for the whole operation.
+    ///
+    /// `yield_now` is awaited before the message is handled and between chunks of work
+    /// while counting solutions, so that a single threaded caller such as a web page
+    /// can stay responsive. Progress of full counts is reported after each chunk.
+    /// Other commands run to completion after the first yield.
+    pub async fn handle_message_async<F, Fut>(&mut self, message: &str, cancellation: Cancellation, mut yield_now: F)
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        let message = match self.start_message(message, cancellation) {
+            Some(message) => message,
+            None => return,
+        };
+
+        let span = tracing::info_span!("request", nonce = message.nonce(), command = message.command());
+        async {
+            yield_now().await;
+
+            let nonce = message.nonce();
+            let max_solutions = match message.command() {
+                "check" => Some(2),
+                "count" => Some(0),
+                _ => None,
+            };
+            let result = match max_solutions {
+                Some(max_solutions) => match self.parse_solver(&message) {
+                    Ok(solver) => self.count_async(nonce, solver, max_solutions, &mut yield_now).await,
+                    Err(response) => response,
+                },
+                None => self.handle_command(&message),
+            };
+
+            self.send_result(result.as_str());
+        }
+        .instrument(span)
+        .await
+    }
+
+    /// Stores the cancellation and parses the message.
+    /// Returns `None` if there is nothing more to do.
+    fn start_message(&mut self, message: &str, cancellation: Cancellation) -> Option<Message> {
         self.cancellation = cancellation;
 
         if self.cancellation.check() {
-            return;
+            return None;
         }
 
-        let message = match Message::from_json(message) {
-            Ok(message) => message,
+        match Message::from_json(message) {
+            Ok(message) => Some(message),
             Err(error) => {
                 self.send_result(InvalidResponse::new(0, &error.to_string()).to_json().as_str());
-                return;
+                None
             }
-        };
+        }
+    }
+
+    /// Parses the puzzle in the message, and starts its timeout if it has one.
+    /// On failure, returns the response to send.
+    fn parse_solver(&mut self, message: &Message) -> Result<Solver, String> {
         let nonce = message.nonce();
 
-        if message.command() == "cancel" {
-            self.send_result(CanceledResponse::new(nonce).to_json().as_str());
-            return;
+        let only_givens = matches!(message.command(), "solve" | "truecandidates" | "check" | "count");
+        let input_parser =
+            PuzzleInputParser::new().with_fpuzzles_parser(self.parser.clone()).with_pencilmarks_as_given(!only_givens);
+
+        // "auto" accepts any input which the puzzle input parser can detect
+        let builder = match message.data_type() {
+            "fpuzzles" => input_parser.parse_as(message.data(), PuzzleInputKind::FPuzzles),
+            "auto" => input_parser.parse(message.data()),
+            _ => return Err(InvalidResponse::new(nonce, "Invalid data type. Expected 'fpuzzles' or 'auto'.").to_json()),
+        };
+
+        // A grid too large for the masks is reported with the largest size which would work.
+        if let Ok(builder) = &builder {
+            if let Err(message) = ValueMask::check_size(builder.size()) {
+                return Err(UnsupportedSizeResponse::new(nonce, builder.size(), &message).to_json());
+            }
         }
 
-        if message.data_type() != "fpuzzles" {
-            self.send_result(InvalidResponse::new(nonce, "Invalid data type. Expected 'fpuzzles'.").to_json().as_str());
-            return;
+        let solver =
+            builder.and_then(SolverBuilder::build).map_err(|error| InvalidResponse::new(nonce, &error).to_json())?;
+        tracing::info!(memory = %solver.memory_footprint(), "Solver built");
+
+        if let (Some(timeout), Some(clock)) = (solver.options().timeout, self.clock) {
+            let deadline = clock() + timeout;
+            self.cancellation = self.cancellation.with_expiry(move || clock() >= deadline);
         }
 
-        let only_givens = matches!(message.command(), "solve" | "truecandidates" | "check" | "count");
+        Ok(solver)
+    }
 
-        let board = match FPuzzlesBoard::from_lzstring_json(message.data()) {
-            Ok(board) => board,
-            Err(error) => {
-                self.send_result(InvalidResponse::new(nonce, &error).to_json().as_str());
-                return;
-            }
-        };
+    fn handle_command(&mut self, message: &Message) -> String {
+        let nonce = message.nonce();
 
-        let parser = FPuzzlesParser::new();
-        let solver = match parser.parse_board(&board, !only_givens) {
-            Ok(puzzle) => puzzle,
-            Err(error) => {
-                self.send_result(InvalidResponse::new(nonce, &error).to_json().as_str());
-                return;
-            }
+        match message.command() {
+            "cancel" => return CanceledResponse::new(nonce).to_json(),
+            "capabilities" | "hello" => return CapabilitiesResponse::new(nonce, COMMANDS).to_json(),
+            _ => {}
+        }
+
+        let solver = match self.parse_solver(message) {
+            Ok(solver) => solver,
+            Err(response) => return response,
         };
 
-        let result = match message.command() {
+        match message.command() {
             "truecandidates" => self.true_candidates(nonce, solver),
             "solve" => self.find_solution(nonce, solver),
             "check" => self.count(nonce, solver, 2),
@@ -83,9 +204,7 @@ impl MessageHandler {
             "step" => self.step(nonce, solver),
             _ => InvalidResponse::new(message.nonce(), format!("Unknown command: {}", message.command()).as_str())
                 .to_json(),
-        };
-
-        self.send_result(result.as_str());
+        }
     }
 
     #[allow(dead_code)]
@@ -94,88 +213,31 @@ impl MessageHandler {
         self.send_result(response.as_str());
     }
 
-    fn get_bool_option(solver: &Solver, option: &str) -> bool {
-        match solver.get_custom_info(option) {
-            Some(value) => value == "true",
-            None => false,
-        }
-    }
-
     fn true_candidates(&mut self, nonce: i32, solver: Solver) -> String {
-        let colored = Self::get_bool_option(&solver, "truecandidatescolored");
-        let logical = Self::get_bool_option(&solver, "truecandidateslogical");
-
-        let mut logical_solver: Option<Solver> = if logical { Some(solver.clone()) } else { None };
-        if let Some(solver) = logical_solver.as_mut() {
-            let logical_result = solver.run_logical_solve();
-            if logical_result.is_invalid() {
-                return InvalidResponse::new(nonce, "No solutions found.").to_json();
-            }
-        }
-
-        let size = solver.size();
-        let total_candidates = size * size * size;
-        let mut solutions_per_candidate: Vec<i32> = vec![0; total_candidates];
-        let real_cells: Vec<ValueMask>;
-        let mut candidate_counts: Option<Vec<usize>> = None;
-        if colored {
-            let result = solver.find_true_candidates_with_count(8, self.cancellation.clone());
-            match result {
-                TrueCandidatesCountResult::None => {
-                    return InvalidResponse::new(nonce, "No solutions found.").to_json();
-                }
-                TrueCandidatesCountResult::Error(error) => {
-                    return InvalidResponse::new(nonce, &error).to_json();
-                }
-                TrueCandidatesCountResult::Solved(board) => {
-                    real_cells = board.all_cell_masks().map(|(_, mask)| mask).collect();
-                }
-                TrueCandidatesCountResult::Candidates(board, counts) => {
-                    real_cells = board.all_cell_masks().map(|(_, mask)| mask).collect();
-                    candidate_counts = Some(counts);
-                }
+        let comparison = match solver.find_true_candidates_with_logical_comparison(self.cancellation.clone()) {
+            TrueCandidatesComparisonResult::None => {
+                return InvalidResponse::new(nonce, "No solutions found.").to_json()
             }
-        } else {
-            let result = solver.find_true_candidates();
-            match result {
-                SingleSolutionResult::None => {
-                    return InvalidResponse::new(nonce, "No solutions found.").to_json();
-                }
-                SingleSolutionResult::Error(error) => {
-                    return InvalidResponse::new(nonce, &error).to_json();
-                }
-                SingleSolutionResult::Solved(board) => {
-                    real_cells = board.all_cell_masks().map(|(_, mask)| mask).collect();
-                }
-            }
-        }
-
-        let logical_cells: Vec<ValueMask> = if let Some(logical_solver) = logical_solver.as_ref() {
-            logical_solver.board().all_cell_masks().map(|(_, mask)| mask).collect()
-        } else {
-            real_cells.clone()
+            TrueCandidatesComparisonResult::Error(error) => return InvalidResponse::new(nonce, &error).to_json(),
+            TrueCandidatesComparisonResult::Candidates(comparison) => comparison,
         };
 
-        for cell_index in 0..real_cells.len() {
-            let real_mask = real_cells[cell_index];
-            let logical_mask = logical_cells[cell_index];
-            for value in 1..=size {
-                let solution_index = cell_index * size + value - 1;
-                let have_value_real = real_mask.has(value);
-                let have_value_logical = logical_mask.has(value);
-                if !have_value_real && have_value_logical {
-                    solutions_per_candidate[solution_index] = -1;
-                } else if have_value_real {
-                    if let Some(candidate_counts) = candidate_counts.as_ref() {
-                        solutions_per_candidate[solution_index] = candidate_counts[solution_index] as i32;
-                    } else {
-                        solutions_per_candidate[solution_index] = 1;
-                    }
-                }
-            }
-        }
-
-        TrueCandidatesResponse::new(nonce, &solutions_per_candidate).to_json()
+        let solutions_per_candidate: Vec<i32> = comparison
+            .classes
+            .iter()
+            .map(|class| match class {
+                CandidateClass::Removed => 0,
+                CandidateClass::LogicalOnly => -1,
+                CandidateClass::True(count) => count.map_or(1, |count| count.count() as i32),
+            })
+            .collect();
+        let capped_per_candidate: Vec<bool> = comparison
+            .classes
+            .iter()
+            .map(|class| matches!(class, CandidateClass::True(Some(count)) if !count.is_exact()))
+            .collect();
+
+        TrueCandidatesResponse::new(nonce, &solutions_per_candidate, &capped_per_candidate).to_json()
     }
 
     fn find_solution(&mut self, nonce: i32, solver: Solver) -> String {
@@ -198,6 +260,39 @@ impl MessageHandler {
             let mut receiver = ReportCountSolutionReceiver::new(nonce, self);
             solver.find_solution_count(0, Some(&mut receiver), cancellation)
         };
+        Self::count_response(nonce, result)
+    }
+
+    async fn count_async<F, Fut>(
+        &mut self,
+        nonce: i32,
+        solver: Solver,
+        max_solutions: usize,
+        yield_now: &mut F,
+    ) -> String
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        const BOARDS_PER_CHUNK: usize = 10000;
+
+        let cancellation = self.cancellation.clone();
+        let mut counter = solver.solution_counter(max_solutions);
+        let result = loop {
+            if let Some(result) = counter.run(BOARDS_PER_CHUNK, None, &cancellation) {
+                break result;
+            }
+
+            if max_solutions == 0 {
+                let in_progress_response = CountResponse::new(nonce, counter.solution_count() as u64, true).to_json();
+                self.send_result(in_progress_response.as_str());
+            }
+            yield_now().await;
+        };
+        Self::count_response(nonce, result)
+    }
+
+    fn count_response(nonce: i32, result: SolutionCountResult) -> String {
         match result {
             SolutionCountResult::None => InvalidResponse::new(nonce, "No solutions found.").to_json(),
             SolutionCountResult::Error(error) => InvalidResponse::new(nonce, &error).to_json(),
@@ -222,17 +317,26 @@ impl MessageHandler {
     }
 
     fn solve_path(&mut self, nonce: i32, mut solver: Solver) -> String {
-        let result = solver.run_logical_solve();
+        let result = solver.run_logical_solve_with_cancellation(self.cancellation.clone());
+        if self.cancellation.check() {
+            return InvalidResponse::new(nonce, "cancelled").to_json();
+        }
         let cells: Vec<LogicalCell> = Self::logical_cells(&solver);
 
         match result {
-            LogicalSolveResult::None => LogicalResponse::new(nonce, &cells, "No logical steps found.", true).to_json(),
+            LogicalSolveResult::None => LogicalResponse::new(nonce, &cells, "No logical steps found.", true)
+                .with_progress(solver.board())
+                .to_json(),
             LogicalSolveResult::Changed(desc) | LogicalSolveResult::Solved(desc) => {
-                LogicalResponse::new(nonce, &cells, desc.to_string().as_str(), true).to_json()
+                LogicalResponse::new(nonce, &cells, desc.to_string().as_str(), true)
+                    .with_progress(solver.board())
+                    .to_json()
             }
             LogicalSolveResult::Invalid(mut desc) => {
                 desc.push("Board is invalid!".into());
-                LogicalResponse::new(nonce, &cells, desc.to_string().as_str(), false).to_json()
+                LogicalResponse::new(nonce, &cells, desc.to_string().as_str(), false)
+                    .with_progress(solver.board())
+                    .to_json()
             }
         }
     }
@@ -241,7 +345,7 @@ impl MessageHandler {
         let cells: Vec<LogicalCell> = Self::logical_cells(&solver);
 
         if solver.board().is_solved() {
-            return LogicalResponse::new(nonce, &cells, "Solved!", true).to_json();
+            return LogicalResponse::new(nonce, &cells, "Solved!", true).with_progress(solver.board()).to_json();
         }
 
         if let Some(original_center_marks) = solver.get_custom_info("OriginalCenterMarks") {
@@ -251,23 +355,38 @@ impl MessageHandler {
                 .map(|(_, mask)| if mask.is_solved() { String::new() } else { mask.into_iter().join(",") })
                 .join(";");
             if original_center_marks != new_center_marks {
-                return LogicalResponse::new(nonce, &cells, "Initial candidates.", false).to_json();
+                return LogicalResponse::new(nonce, &cells, "Initial candidates.", false)
+                    .with_progress(solver.board())
+                    .to_json();
             }
         }
 
-        let result = solver.run_single_logical_step();
+        let result = solver.run_single_logical_step_with_cancellation(self.cancellation.clone());
+        if self.cancellation.check() {
+            return InvalidResponse::new(nonce, "cancelled").to_json();
+        }
         let cells: Vec<LogicalCell> = Self::logical_cells(&solver);
         match result {
-            LogicalStepResult::None => LogicalResponse::new(nonce, &cells, "No logical steps found.", true).to_json(),
+            LogicalStepResult::None => LogicalResponse::new(nonce, &cells, "No logical steps found.", true)
+                .with_progress(solver.board())
+                .to_json(),
             LogicalStepResult::Changed(desc) => {
                 let desc = desc.unwrap_or_else(|| "ERROR: No logical step description!".into());
-                LogicalResponse::new(nonce, &cells, desc.to_string().as_str(), true).to_json()
+                LogicalResponse::new(nonce, &cells, desc.to_string().as_str(), true)
+                    .with_progress(solver.board())
+                    .with_highlights(desc.highlights())
+                    .to_json()
             }
             LogicalStepResult::Invalid(desc) => {
+                let desc = desc.unwrap_or_else(|| "ERROR: No logical step description!".into());
+                let highlights = desc.highlights().to_vec();
                 let mut desc_list = LogicalStepDescList::new();
-                desc_list.push(desc.unwrap_or_else(|| "ERROR: No logical step description!".into()));
+                desc_list.push(desc);
                 desc_list.push("Board is invalid!".into());
-                LogicalResponse::new(nonce, &cells, desc_list.to_string().as_str(), false).to_json()
+                LogicalResponse::new(nonce, &cells, desc_list.to_string().as_str(), false)
+                    .with_progress(solver.board())
+                    .with_highlights(&highlights)
+                    .to_json()
             }
         }
     }
@@ -303,7 +422,7 @@ impl<'a> ReportCountSolutionReceiver<'a> {
 }
 
 impl<'a> SolutionReceiver for ReportCountSolutionReceiver<'a> {
-    fn receive(&mut self, _result: Box<Board>) -> bool {
+    fn receive(&mut self, _result: Box<Board>, _statistics: &SearchStatistics) -> ReceiveAction {
         self.count += 1;
 
         #[cfg(not(target_arch = "wasm32"))]
@@ -315,7 +434,7 @@ impl<'a> SolutionReceiver for ReportCountSolutionReceiver<'a> {
             }
         }
 
-        true
+        ReceiveAction::Continue
     }
 
     #[cfg(target_arch = "wasm32")]
@@ -379,6 +498,95 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_step_highlights() {
+        let (mut handler, results) = create_test_handler();
+        let givens = "8...62..125.....7..197...5........9.....28..3.....36.54...1..6...74...3.5.2......";
+        let message = Message::new(123, "step", "auto", givens).to_json();
+        handler.handle_message(&message, Cancellation::default());
+        let result = results.lock().unwrap();
+        assert_eq!(result.len(), 1);
+
+        let response = LogicalResponse::from_json(result[0].as_str()).unwrap();
+        assert!(response.is_valid);
+        let placement = response.highlights.iter().find(|highlight| highlight.role == "placement").unwrap();
+        let solution = SolverBuilder::default().with_givens_string(givens).build().unwrap().find_first_solution();
+        let cell = CellUtility::new(9).cell_index(placement.cell as usize);
+        assert_eq!(solution.board().unwrap().cell(cell).value(), placement.value as usize);
+    }
+
+    #[test]
+    fn test_solve_path_progress() {
+        let (mut handler, results) = create_test_handler();
+        let givens = "5.6....29.9....13..4...376.........232.5......5..186.32..64.38..1.37529....821.7.";
+        let message = Message::new(123, "solvepath", "auto", givens).to_json();
+        handler.handle_message(&message, Cancellation::default());
+        let result = results.lock().unwrap();
+        assert_eq!(result.len(), 1);
+
+        let response = LogicalResponse::from_json(result[0].as_str()).unwrap();
+        let candidates: usize = response.cells.iter().map(|cell| cell.candidates.len().max(1)).sum();
+        let solved = response.cells.iter().filter(|cell| cell.value != 0).count();
+        assert_eq!(response.candidates_remaining, candidates);
+        assert_eq!(response.percent_solved, solved as f64 * 100.0 / 81.0);
+        assert!(response.percent_solved > 40.0);
+    }
+
+    #[test]
+    fn test_solve_6x6() {
+        let givens = ".........123..1..4..423..12.45.453..";
+        let expected_solution: Vec<i32> =
+            "123456456123231564564231312645645312".chars().map(|c| c as i32 - '0' as i32).collect();
+
+        let mut board = FPuzzlesBoard::new(6);
+        for (index, value) in givens.chars().enumerate() {
+            if let Some(value) = value.to_digit(10) {
+                board.grid[index / 6][index % 6].value = value as i32;
+                board.grid[index / 6][index % 6].given = true;
+            }
+        }
+
+        for data in [board.to_json(), givens.to_owned()] {
+            let (mut handler, results) = create_test_handler();
+            let message = Message::new(123, "solve", "auto", &data).to_json();
+            handler.handle_message(&message, Cancellation::default());
+            let result = results.lock().unwrap();
+            assert_eq!(result.len(), 1);
+
+            let response = SolvedResponse::from_json(result[0].as_str()).unwrap();
+            assert_eq!(response.solution, expected_solution);
+        }
+    }
+
+    #[test]
+    fn test_unsupported_size() {
+        let (mut handler, results) = create_test_handler();
+        let message = Message::new(5, "hello", "", "").to_json();
+        handler.handle_message(&message, Cancellation::default());
+        let response = CapabilitiesResponse::from_json(results.lock().unwrap()[0].as_str()).unwrap();
+        assert_eq!(response.nonce, 5);
+        assert_eq!(response.max_size, ValueMask::MAX_SIZE);
+        assert!(response.max_size_memory > 100 * 1024 * 1024);
+        assert!(response.commands.iter().any(|command| command == "truecandidates"));
+
+        for size in [ValueMask::MAX_SIZE + 1, 64] {
+            results.lock().unwrap().clear();
+            let board = FPuzzlesBoard::new(size as i32);
+            let message = Message::new(6, "solve", "fpuzzles", &board.to_lzstring_json()).to_json();
+            handler.handle_message(&message, Cancellation::default());
+            let response = UnsupportedSizeResponse::from_json(results.lock().unwrap()[0].as_str()).unwrap();
+            assert_eq!(response.response_type, "unsupportedsize");
+            assert_eq!(response.size, size);
+            assert_eq!(response.max_size, ValueMask::MAX_SIZE);
+        }
+
+        // The largest size f-puzzles draws is still solved
+        results.lock().unwrap().clear();
+        let message = Message::new(7, "solve", "fpuzzles", &FPuzzlesBoard::new(16).to_lzstring_json()).to_json();
+        handler.handle_message(&message, Cancellation::default());
+        assert!(SolvedResponse::from_json(results.lock().unwrap()[0].as_str()).is_ok());
+    }
+
     #[test]
     fn test_antikropki_count() {
         // Empty grid with negative constraint for kropki.
@@ -398,6 +606,57 @@ mod test {
         assert_eq!(response.count, 8448, "Count should be 8448 for solve message, but was {}", response.count);
     }
 
+    #[test]
+    fn test_antikropki_count_async() {
+        // Empty grid with negative constraint for kropki.
+        let lzstr = r#"N4IgzglgXgpiBcBOANCA5gJwgEwQbT2AF9ljSSzKLryBdZQmq8l54+x1p7rjtn/nQaCR3PgIm9hk0UM6zR4rssX0QAOwD26gMbawMHQFcALhABuceCYxGYqdTDQBDM5fwgMriJpBqvZr7weLREQA"#;
+
+        let message = Message::new(123, "count", "fpuzzles", lzstr).to_json();
+
+        let (mut handler, results) = create_test_handler();
+        let mut yield_count = 0;
+        let is_ready = {
+            let future = handler.handle_message_async(&message, Cancellation::default(), || {
+                yield_count += 1;
+                std::future::ready(())
+            });
+
+            // Every yield is immediately ready, so a single poll runs the whole count.
+            let mut future = std::pin::pin!(future);
+            let mut context = std::task::Context::from_waker(std::task::Waker::noop());
+            future.as_mut().poll(&mut context).is_ready()
+        };
+        assert!(is_ready);
+        assert!(yield_count > 1);
+
+        let result = results.lock().unwrap();
+        assert!(result.len() > 1);
+
+        let response = CountResponse::from_json(result.last().unwrap().as_str()).unwrap();
+        assert_eq!(response.nonce, 123);
+        assert!(!response.in_progress, "Count should be finished, but was in progress");
+        assert_eq!(response.count, 8448, "Count should be 8448 for solve message, but was {}", response.count);
+    }
+
+    #[test]
+    fn test_count_timeout() {
+        // An empty grid has far too many solutions to count, so only the timeout can end the count.
+        let mut board = FPuzzlesBoard::new(9);
+        board.truecandidatesoptions = vec!["timeout=10".to_owned()];
+        let message = Message::new(123, "count", "auto", &board.to_json()).to_json();
+
+        let (mut handler, results) = create_test_handler();
+        let cancellation = Cancellation::default();
+        handler.handle_message(&message, cancellation.clone());
+        assert!(!cancellation.check(), "The timeout should not cancel the caller's cancellation");
+
+        // A count which times out is reported the same as a cancelled count.
+        let result = results.lock().unwrap();
+        let response = InvalidResponse::from_json(result.last().unwrap().as_str()).unwrap();
+        assert_eq!(response.nonce, 123);
+        assert_eq!(response.message, "cancelled");
+    }
+
     #[test]
     fn test_xv_true_candidates() {
         // Empty grid other than an X between r1c12 and a V between r2c12.
@@ -489,4 +748,30 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn test_colored_true_candidates_capped() {
+        // Every candidate of an empty 4x4 grid is in more solutions than the maximum.
+        let mut board = FPuzzlesBoard::new(4);
+        board.truecandidatesoptions = vec!["colored".to_owned(), "maxcolored=2".to_owned()];
+        let message = Message::new(123, "truecandidates", "auto", &board.to_json()).to_json();
+
+        let (mut handler, results) = create_test_handler();
+        handler.handle_message(&message, Cancellation::default());
+        let response = TrueCandidatesResponse::from_json(results.lock().unwrap().last().unwrap().as_str()).unwrap();
+        assert_eq!(response.capped_per_candidate.len(), 4 * 4 * 4);
+        assert!(response.capped_per_candidate.iter().all(|&capped| capped));
+        assert!(response.solutions_per_candidate.iter().all(|&count| count >= 2));
+
+        // A unique puzzle has exactly one solution with each of its values.
+        let (lzstr, _) = FPUZZLES_CLASSICS_DATA[0];
+        let mut board = FPuzzlesBoard::from_lzstring_json(lzstr).unwrap();
+        board.truecandidatesoptions = vec!["colored".to_owned()];
+        let message = Message::new(124, "truecandidates", "auto", &board.to_json()).to_json();
+
+        handler.handle_message(&message, Cancellation::default());
+        let response = TrueCandidatesResponse::from_json(results.lock().unwrap().last().unwrap().as_str()).unwrap();
+        assert!(response.capped_per_candidate.iter().all(|&capped| !capped));
+        assert_eq!(response.solutions_per_candidate.iter().filter(|&&count| count == 1).count(), 81);
+    }
 }
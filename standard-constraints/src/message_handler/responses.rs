@@ -1,4 +1,5 @@
 use serde::*;
+use sudoku_solver_lib::prelude::*;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct CanceledResponse {
@@ -45,6 +46,74 @@ impl InvalidResponse {
     }
 }
 
+/// Sent instead of [`InvalidResponse`] when the grid is larger than the solver supports.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct UnsupportedSizeResponse {
+    pub nonce: i32,
+    #[serde(rename = "type")]
+    pub response_type: String,
+    pub message: String,
+    pub size: usize,
+    #[serde(rename = "maxSize")]
+    pub max_size: usize,
+}
+
+impl UnsupportedSizeResponse {
+    pub fn new(nonce: i32, size: usize, message: &str) -> Self {
+        Self {
+            nonce,
+            response_type: "unsupportedsize".to_owned(),
+            message: message.to_owned(),
+            size,
+            max_size: ValueMask::MAX_SIZE,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    #[allow(dead_code)]
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+/// What the handler supports, so that a front end can check before sending puzzles.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct CapabilitiesResponse {
+    pub nonce: i32,
+    #[serde(rename = "type")]
+    pub response_type: String,
+    #[serde(rename = "maxSize")]
+    pub max_size: usize,
+    /// The estimated bytes used by an empty grid of the largest size. See [`MemoryFootprint::estimate`].
+    #[serde(rename = "maxSizeMemory")]
+    pub max_size_memory: usize,
+    pub commands: Vec<String>,
+}
+
+impl CapabilitiesResponse {
+    pub fn new(nonce: i32, commands: &[&str]) -> Self {
+        Self {
+            nonce,
+            response_type: "capabilities".to_owned(),
+            max_size: ValueMask::MAX_SIZE,
+            max_size_memory: MemoryFootprint::estimate(ValueMask::MAX_SIZE).total(),
+            commands: commands.iter().map(|&command| command.to_owned()).collect(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    #[allow(dead_code)]
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct DebugLogResponse {
     #[serde(rename = "type")]
@@ -74,14 +143,18 @@ pub(crate) struct TrueCandidatesResponse {
     pub response_type: String,
     #[serde(rename = "solutionsPerCandidate")]
     pub solutions_per_candidate: Vec<i32>,
+    /// Whether the count of each candidate stopped at the maximum, so it should be shown as `8+`.
+    #[serde(rename = "cappedPerCandidate", default)]
+    pub capped_per_candidate: Vec<bool>,
 }
 
 impl TrueCandidatesResponse {
-    pub fn new(nonce: i32, solutions_per_candidate: &[i32]) -> Self {
+    pub fn new(nonce: i32, solutions_per_candidate: &[i32], capped_per_candidate: &[bool]) -> Self {
         Self {
             nonce,
             response_type: "truecandidates".to_owned(),
             solutions_per_candidate: solutions_per_candidate.to_owned(),
+            capped_per_candidate: capped_per_candidate.to_owned(),
         }
     }
 
@@ -149,6 +222,14 @@ pub(crate) struct LogicalCell {
     pub candidates: Vec<i32>,
 }
 
+/// A candidate which takes part in a logical step, with the role it plays.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct LogicalHighlight {
+    pub cell: i32,
+    pub value: i32,
+    pub role: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct LogicalResponse {
     pub nonce: i32,
@@ -158,6 +239,12 @@ pub(crate) struct LogicalResponse {
     pub message: String,
     #[serde(rename = "isValid")]
     pub is_valid: bool,
+    #[serde(default)]
+    pub highlights: Vec<LogicalHighlight>,
+    #[serde(rename = "candidatesRemaining", default)]
+    pub candidates_remaining: usize,
+    #[serde(rename = "percentSolved", default)]
+    pub percent_solved: f64,
 }
 
 impl LogicalResponse {
@@ -166,7 +253,38 @@ impl LogicalResponse {
         if !message.ends_with('\n') {
             message.push('\n');
         }
-        Self { nonce, response_type: "logical".to_owned(), cells: cells.to_owned(), message, is_valid }
+        Self {
+            nonce,
+            response_type: "logical".to_owned(),
+            cells: cells.to_owned(),
+            message,
+            is_valid,
+            highlights: Vec::new(),
+            candidates_remaining: 0,
+            percent_solved: 0.0,
+        }
+    }
+
+    pub fn with_highlights(mut self, highlights: &[CandidateHighlight]) -> Self {
+        self.highlights = highlights
+            .iter()
+            .map(|highlight| {
+                let (cell, value) = highlight.candidate.cell_index_and_value();
+                LogicalHighlight {
+                    cell: cell.index() as i32,
+                    value: value as i32,
+                    role: highlight.role.name().to_owned(),
+                }
+            })
+            .collect();
+        self
+    }
+
+    /// Reports how far along the solve of the board is.
+    pub fn with_progress(mut self, board: &Board) -> Self {
+        self.candidates_remaining = board.candidates_remaining();
+        self.percent_solved = board.percent_solved();
+        self
     }
 
     pub fn to_json(&self) -> String {
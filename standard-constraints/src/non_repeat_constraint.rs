@@ -1,12 +1,13 @@
 //! Contains the [`NonRepeatConstraint`] struct for representing a constraint where cells cannot repeat values.
 
+use std::sync::Arc;
 use sudoku_solver_lib::prelude::*;
 
 /// A [`Constraint`] implementation for representing a group of cells which cannot repeat digits.
 /// The number of cells passed cannot exceed the size of the grid, as that would be impossible.
 /// If the number of cells is equal to the size of the grid, this constraint is also considered
 /// to be a "house" for logical steps which use houses, like hidden singles and tuples.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct NonRepeatConstraint {
     specific_name: String,
     cells: Vec<CellIndex>,
@@ -41,6 +42,10 @@ impl Constraint for NonRepeatConstraint {
         self.specific_name.as_str()
     }
 
+    fn clone_constraint(&self) -> Option<Arc<dyn Constraint>> {
+        Some(Arc::new(self.clone()))
+    }
+
     fn get_weak_links(&self, size: usize) -> Vec<(CandidateIndex, CandidateIndex)> {
         if self.cells.len() > 1 && self.cells.len() <= size {
             get_weak_links_for_nonrepeat(self.cells.iter().copied())
@@ -49,8 +54,8 @@ impl Constraint for NonRepeatConstraint {
         }
     }
 
-    fn get_houses(&self, size: usize) -> Vec<House> {
-        if self.cells.len() == size {
+    fn get_houses(&self, context: &HouseContext) -> Vec<House> {
+        if self.cells.len() == context.size() {
             vec![House::new(self.specific_name.as_str(), &self.cells)]
         } else {
             Vec::new()
@@ -2,6 +2,7 @@
 //! must have certain number combinations.
 
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 use crate::prelude::*;
 use itertools::Itertools;
@@ -12,12 +13,19 @@ use sudoku_solver_lib::prelude::*;
 ///
 /// This constraint is useful for representing constraints like consecutive, anti-ratio, XV along
 /// with their optional negative constraints.
+///
+/// The negative constraints apply to the whole grid unless they are scoped to a set of cells
+/// with [`OrthogonalPairsConstraint::with_negative_cells`].
 #[derive(Debug, Clone)]
 pub struct OrthogonalPairsConstraint {
     specific_name: String,
     markers: Vec<OrthogonalPairsMarker>,
     negative_constraints: Vec<String>,
     candidate_pairs: HashMap<String, Vec<ValueMask>>,
+    /// The cells which the negative constraints apply within, or `None` for the whole grid.
+    negative_cells: Option<HashSet<CellIndex>>,
+    /// Whether the markers' own rules are enforced, rather than only exempting their pairs from the negative constraints.
+    enforce_markers: bool,
 }
 
 impl OrthogonalPairsConstraint {
@@ -33,9 +41,64 @@ impl OrthogonalPairsConstraint {
             markers,
             negative_constraints: negative_constraints.iter().map(|&s| s.to_owned()).collect(),
             candidate_pairs,
+            negative_cells: None,
+            enforce_markers: true,
         }
     }
 
+    /// Only applies the negative constraints between pairs of cells which are both in the given cells,
+    /// such as the cells of a marked region.
+    ///
+    /// # Example
+    /// ```
+    /// # use standard_constraints::prelude::*;
+    /// # use sudoku_solver_lib::prelude::*;
+    /// # use std::sync::Arc;
+    /// let cu = CellUtility::new(9);
+    /// let row1 = (0..9).map(|col| cu.cell(0, col)).collect::<Vec<_>>();
+    /// let anti_xv = OrthogonalPairsConstraint::from_standard_markers(9, "XV", &[], &[StandardPairType::Sum(10)])
+    ///     .with_negative_cells(&row1);
+    /// let solver = SolverBuilder::default()
+    ///     .with_constraint(Arc::new(anti_xv))
+    ///     .with_given(cu.cell(0, 0), 1)
+    ///     .build()
+    ///     .unwrap();
+    /// assert!(!solver.board().cell(cu.cell(0, 1)).has(9));
+    /// assert!(solver.board().cell(cu.cell(1, 0)).has(9));
+    /// ```
+    #[must_use]
+    pub fn with_negative_cells(mut self, cells: &[CellIndex]) -> Self {
+        self.negative_cells = Some(cells.iter().copied().collect());
+        self
+    }
+
+    /// Only uses the markers to find the unmarked pairs for the negative constraints, without enforcing
+    /// the rules of the markers themselves. This lets the markers and the negative constraints be
+    /// separate constraints, so that either can be turned off.
+    ///
+    /// # Example
+    /// ```
+    /// # use standard_constraints::prelude::*;
+    /// # use sudoku_solver_lib::prelude::*;
+    /// # use std::sync::Arc;
+    /// let cu = CellUtility::new(9);
+    /// let marker = StandardOrthogonalPairsMarker::sum(10, cu.cell(0, 0), cu.cell(0, 1));
+    /// let anti_xv = OrthogonalPairsConstraint::from_standard_markers(9, "XV", &[marker], &[StandardPairType::Sum(10)])
+    ///     .without_marker_rules();
+    /// let solver = SolverBuilder::default()
+    ///     .with_constraint(Arc::new(anti_xv))
+    ///     .with_given(cu.cell(0, 0), 1)
+    ///     .build()
+    ///     .unwrap();
+    /// assert!(solver.board().cell(cu.cell(0, 1)).has(8));
+    /// assert!(!solver.board().cell(cu.cell(1, 0)).has(9));
+    /// ```
+    #[must_use]
+    pub fn without_marker_rules(mut self) -> Self {
+        self.enforce_markers = false;
+        self
+    }
+
     /// Creates a new [`OrthogonalPairsConstraint`] with the given parameters
     /// and using a function to generate the candidate pairs.
     pub fn from_generic_markers_with_func(
@@ -100,6 +163,10 @@ impl Constraint for OrthogonalPairsConstraint {
         &self.specific_name
     }
 
+    fn clone_constraint(&self) -> Option<Arc<dyn Constraint>> {
+        Some(Arc::new(self.clone()))
+    }
+
     fn get_weak_links(&self, size: usize) -> Vec<(CandidateIndex, CandidateIndex)> {
         let cu = CellUtility::new(size);
 
@@ -115,6 +182,10 @@ impl Constraint for OrthogonalPairsConstraint {
                 }
             }
 
+            if !self.enforce_markers {
+                continue;
+            }
+
             let candidate_pairs = self.candidate_pairs.get(marker.marker_type.as_str());
             if let Some(candidate_pairs) = candidate_pairs {
                 for value in 1..=size {
@@ -153,6 +224,11 @@ impl Constraint for OrthogonalPairsConstraint {
                     if cell0 > cell1 || cell_pairs_seen.contains(&(cell0, cell1)) {
                         continue;
                     }
+                    if let Some(negative_cells) = &self.negative_cells {
+                        if !negative_cells.contains(&cell0) || !negative_cells.contains(&cell1) {
+                            continue;
+                        }
+                    }
 
                     for value in 1..=size {
                         let mask = combined_candidate_pairs[value - 1].without(value);
@@ -176,6 +252,30 @@ impl Constraint for OrthogonalPairsConstraint {
 
         result
     }
+
+    fn step_logic(&self, board: &mut Board, is_brute_forcing: bool, _: &Cancellation) -> LogicalStepResult {
+        if is_brute_forcing || !self.enforce_markers {
+            return LogicalStepResult::None;
+        }
+
+        for marker in self.markers.iter() {
+            let Some(candidate_pairs) = self.candidate_pairs.get(marker.marker_type.as_str()) else {
+                continue;
+            };
+
+            // The weak links go both ways, so a pair is only allowed if each value allows the other.
+            let allows = |value0: usize, value1: usize| {
+                value0 != value1 && candidate_pairs[value0 - 1].has(value1) && candidate_pairs[value1 - 1].has(value0)
+            };
+            for (cell0, cell1) in [(marker.cell0, marker.cell1), (marker.cell1, marker.cell0)] {
+                let result = step_unpaired_values(board, cell0, cell1, "marker", allows);
+                if !result.is_none() {
+                    return result;
+                }
+            }
+        }
+        LogicalStepResult::None
+    }
 }
 
 /// Represents a pair of cells that are adjacent to each other and have a marker between them.
@@ -238,11 +338,7 @@ impl StandardOrthogonalPairsMarker {
 
 impl From<StandardOrthogonalPairsMarker> for OrthogonalPairsMarker {
     fn from(marker: StandardOrthogonalPairsMarker) -> Self {
-        match marker.marker_type {
-            StandardPairType::Sum(n) => Self::new(&format!("s{n}"), marker.cell0, marker.cell1),
-            StandardPairType::Diff(n) => Self::new(&format!("d{n}"), marker.cell0, marker.cell1),
-            StandardPairType::Ratio(n) => Self::new(&format!("r{n}"), marker.cell0, marker.cell1),
-        }
+        Self::new(&marker.marker_type.name(), marker.cell0, marker.cell1)
     }
 }
 
@@ -314,4 +410,57 @@ mod test {
         assert_eq!(solver.board().cell(cell1).count(), 4);
         assert_eq!(solver.board().cell(cell1), ValueMask::from_lower_equal(4));
     }
+
+    #[test]
+    fn test_step_cause() {
+        let size = 9;
+        let cu = CellUtility::new(size);
+        let cell0 = cu.cell(0, 0);
+        let cell1 = cu.cell(0, 1);
+        let marker = StandardOrthogonalPairsMarker::sum(5, cell0, cell1);
+        let xv_constraint = Arc::new(OrthogonalPairsConstraint::from_standard_markers(size, "XV", &[marker], &[]));
+        let solver = SolverBuilder::default()
+            .with_constraint(xv_constraint)
+            .with_cell_mask(cell0, ValueMask::from_values(&[1, 2]))
+            .build()
+            .unwrap();
+
+        let mut board = solver.board().clone();
+        let result = StepConstraints.run(&mut board, true, &Cancellation::default());
+        assert_eq!(
+            result.to_string(),
+            "XV: Values without a partner => -1r1c2;-2r1c2 because r1c1=1/2 on marker r1c1-r1c2"
+        );
+        assert_eq!(board.cell(cell1), ValueMask::from_values(&[3, 4]));
+    }
+
+    #[test]
+    fn test_negative_cells() {
+        // Anti-kropki only within box 1
+        let size = 9;
+        let cu = CellUtility::new(size);
+        let box1 = (0..3).flat_map(|row| (0..3).map(move |col| cu.cell(row, col))).collect::<Vec<_>>();
+        let kropki_constraint = OrthogonalPairsConstraint::from_standard_markers(
+            size,
+            "Kropki",
+            &[],
+            &[StandardPairType::Diff(1), StandardPairType::Ratio(2)],
+        )
+        .with_negative_cells(&box1);
+        let solver = SolverBuilder::default()
+            .with_constraint(Arc::new(kropki_constraint))
+            .with_givens(&[(cu.cell(0, 2), 4)])
+            .build()
+            .unwrap();
+        let board = solver.board();
+
+        // Inside the box, 4 can't be next to 2, 3, 5 or 8
+        for cell in [cu.cell(0, 1), cu.cell(1, 2)] {
+            assert!([2, 3, 5, 8].iter().all(|&value| !board.cell(cell).has(value)));
+        }
+
+        // Across the edge of the box, it can
+        assert!([2, 3, 5, 8].iter().all(|&value| board.cell(cu.cell(0, 3)).has(value)));
+        assert_eq!(solver.find_solution_count(2, None, None).count().unwrap(), 2);
+    }
 }
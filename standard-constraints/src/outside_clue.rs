@@ -0,0 +1,56 @@
+//! Contains the [`OutsideClue`] struct for clues written outside of the grid, such as X-Sums and skyscrapers.
+
+use sudoku_solver_lib::prelude::*;
+
+/// A clue outside of the grid, which applies to the row or column next to it, read starting
+/// from the cell nearest the clue.
+///
+/// The position of the clue uses 1-based rows and columns like cell names do, where row or
+/// column 0 is above or left of the grid and `size + 1` is below or right of the grid.
+///
+/// # Example
+/// ```
+/// # use standard_constraints::prelude::*;
+/// # use sudoku_solver_lib::prelude::*;
+/// let cu = CellUtility::new(9);
+/// let clue = OutsideClue::new(9, 3, 10).unwrap();
+/// assert_eq!(clue.name(), "r3c10");
+/// assert_eq!(clue.cells()[..2], [cu.cell(2, 8), cu.cell(2, 7)]);
+/// assert!(OutsideClue::new(9, 3, 3).is_none());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutsideClue {
+    name: String,
+    cells: Vec<CellIndex>,
+}
+
+impl OutsideClue {
+    /// Creates the clue at the position, or `None` if the position isn't directly next to
+    /// a row or column of the grid.
+    pub fn new(size: usize, row: usize, col: usize) -> Option<Self> {
+        let cu = CellUtility::new(size);
+        let inside = 1..=size;
+        let cells: Vec<CellIndex> = if inside.contains(&row) && col == 0 {
+            (0..size).map(|col| cu.cell(row - 1, col)).collect()
+        } else if inside.contains(&row) && col == size + 1 {
+            (0..size).rev().map(|col| cu.cell(row - 1, col)).collect()
+        } else if inside.contains(&col) && row == 0 {
+            (0..size).map(|row| cu.cell(row, col - 1)).collect()
+        } else if inside.contains(&col) && row == size + 1 {
+            (0..size).rev().map(|row| cu.cell(row, col - 1)).collect()
+        } else {
+            return None;
+        };
+        Some(Self { name: format!("r{row}c{col}"), cells })
+    }
+
+    /// The position of the clue, such as `r1c0` for the clue left of the first row.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The cells of the row or column, in order from the clue.
+    pub fn cells(&self) -> &[CellIndex] {
+        &self.cells
+    }
+}
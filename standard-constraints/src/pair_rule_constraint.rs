@@ -0,0 +1,276 @@
+//! Contains the [`PairRuleConstraint`] struct for representing a custom rule between pairs of cells,
+//! and the [`PairRuleRegistry`] of rules which can be chosen by name.
+
+use std::sync::Arc;
+
+use crate::prelude::*;
+use sudoku_solver_lib::prelude::*;
+
+/// Whether the first cell of a pair can be the first value while the second cell is the second value.
+pub type PairRule = Arc<dyn Fn(usize, usize) -> bool + Send + Sync>;
+
+/// A [`Constraint`] implementation for a custom rule between pairs of cells, such as
+/// "adjacent cells must not sum to a prime".
+///
+/// The rule becomes weak links between the values of each pair which it doesn't allow. Rules can be
+/// closures, or come from text with a [`PairRuleRegistry`], which is how puzzle data can add new rules.
+///
+/// # Example
+/// ```
+/// # use standard_constraints::prelude::*;
+/// # use sudoku_solver_lib::prelude::*;
+/// # use std::sync::Arc;
+/// let cu = CellUtility::new(9);
+/// let rule = PairRuleRegistry::new().create("!isprime(a + b)").unwrap();
+/// let constraint = PairRuleConstraint::orthogonally_adjacent("No Prime Sums", &[], rule, 9);
+/// let solver = SolverBuilder::default()
+///     .with_constraint(Arc::new(constraint))
+///     .with_given(cu.cell(0, 0), 1)
+///     .build()
+///     .unwrap();
+/// assert!(!solver.board().cell(cu.cell(0, 1)).has(2));
+/// assert!(solver.board().cell(cu.cell(0, 1)).has(3));
+/// ```
+#[derive(Clone)]
+pub struct PairRuleConstraint {
+    specific_name: String,
+    pairs: Vec<(CellIndex, CellIndex)>,
+    rule: PairRule,
+}
+
+impl PairRuleConstraint {
+    /// Creates a new [`PairRuleConstraint`] where each pair of cells must follow the rule.
+    pub fn new(specific_name: &str, pairs: Vec<(CellIndex, CellIndex)>, rule: PairRule) -> Self {
+        Self { specific_name: specific_name.to_owned(), pairs, rule }
+    }
+
+    /// Creates a new [`PairRuleConstraint`] between every pair of orthogonally adjacent cells
+    /// which are both in the given cells, or in the whole grid if no cells are given.
+    ///
+    /// The rule must allow the values either way around.
+    pub fn orthogonally_adjacent(specific_name: &str, cells: &[CellIndex], rule: PairRule, size: usize) -> Self {
+        let cu = CellUtility::new(size);
+        let cells = if cells.is_empty() { cu.all_cells().collect() } else { cells.to_vec() };
+        let pairs = cells
+            .iter()
+            .flat_map(|&cell0| cell0.orthogonally_adjacent_cells().map(move |cell1| (cell0, cell1)))
+            .filter(|&(cell0, cell1)| cell0 < cell1 && cells.contains(&cell1))
+            .collect();
+        Self::new(specific_name, pairs, rule)
+    }
+
+    /// The pairs of cells which must follow the rule.
+    pub fn pairs(&self) -> &[(CellIndex, CellIndex)] {
+        &self.pairs
+    }
+}
+
+impl std::fmt::Debug for PairRuleConstraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PairRuleConstraint")
+            .field("specific_name", &self.specific_name)
+            .field("pairs", &self.pairs)
+            .finish()
+    }
+}
+
+impl Constraint for PairRuleConstraint {
+    fn name(&self) -> &str {
+        &self.specific_name
+    }
+
+    fn clone_constraint(&self) -> Option<Arc<dyn Constraint>> {
+        Some(Arc::new(self.clone()))
+    }
+
+    fn get_weak_links(&self, size: usize) -> Vec<(CandidateIndex, CandidateIndex)> {
+        let mut result = Vec::new();
+        for &(cell0, cell1) in self.pairs.iter() {
+            for value0 in 1..=size {
+                for value1 in 1..=size {
+                    if !(self.rule)(value0, value1) {
+                        result.push((cell0.candidate(value0), cell1.candidate(value1)));
+                    }
+                }
+            }
+
+            // A value with no partner at all can't go in the cell.
+            for value in 1..=size {
+                if !(1..=size).any(|other| (self.rule)(value, other)) {
+                    result.push((cell0.candidate(value), cell0.candidate(value)));
+                }
+                if !(1..=size).any(|other| (self.rule)(other, value)) {
+                    result.push((cell1.candidate(value), cell1.candidate(value)));
+                }
+            }
+        }
+        result
+    }
+
+    fn step_logic(&self, board: &mut Board, is_brute_forcing: bool, _: &Cancellation) -> LogicalStepResult {
+        if is_brute_forcing {
+            return LogicalStepResult::None;
+        }
+
+        for &(cell0, cell1) in self.pairs.iter() {
+            let result =
+                step_unpaired_values(board, cell0, cell1, "pair", |value0, value1| (self.rule)(value0, value1));
+            if !result.is_none() {
+                return result;
+            }
+            let result =
+                step_unpaired_values(board, cell1, cell0, "pair", |value1, value0| (self.rule)(value0, value1));
+            if !result.is_none() {
+                return result;
+            }
+        }
+        LogicalStepResult::None
+    }
+}
+
+/// Eliminates the values of the second cell which the rule doesn't allow next to any remaining value
+/// of the first cell. The step names the values of the first cell as its cause, on the `kind` of clue
+/// joining the cells, such as "marker".
+///
+/// The rule is given a value of the first cell and then a value of the second cell.
+///
+/// # Example
+/// ```
+/// # use standard_constraints::prelude::*;
+/// # use sudoku_solver_lib::prelude::*;
+/// let cu = CellUtility::new(9);
+/// let mut board = Board::default();
+/// board.keep_mask(cu.cell(0, 0), ValueMask::from_values(&[4, 6]));
+/// let result = step_unpaired_values(&mut board, cu.cell(0, 0), cu.cell(0, 1), "V clue", |a, b| a + b == 5);
+/// assert_eq!(result.to_string(), "Values without a partner => -2r1c2;-3r1c2;-4r1c2;-5r1c2;-6r1c2;-7r1c2;-8r1c2;-9r1c2 because r1c1=4/6 on V clue r1c1-r1c2");
+/// ```
+pub fn step_unpaired_values(
+    board: &mut Board,
+    cell0: CellIndex,
+    cell1: CellIndex,
+    kind: &str,
+    rule: impl Fn(usize, usize) -> bool,
+) -> LogicalStepResult {
+    let mask0 = board.cell(cell0);
+    let elims: EliminationList = board
+        .cell(cell1)
+        .into_iter()
+        .filter(|&value1| !mask0.into_iter().any(|value0| rule(value0, value1)))
+        .map(|value1| cell1.candidate(value1))
+        .collect();
+    if elims.is_empty() {
+        return LogicalStepResult::None;
+    }
+
+    let cause = StepCause::from_cell(board, cell0).with_source(&format!("{kind} {cell0}-{cell1}"));
+    let desc = LogicalStepDesc::from_elims("Values without a partner", &elims).with_cause(cause);
+    if !board.clear_candidates(elims.iter()) {
+        return LogicalStepResult::Invalid(Some(desc));
+    }
+    LogicalStepResult::Changed(Some(desc))
+}
+
+/// Pair rules which can be chosen by name, such as from puzzle data or the command line.
+///
+/// Text which isn't the name of a registered rule is parsed as a [`PairRuleExpression`], so simple
+/// rules don't need registering. Names are matched ignoring case.
+///
+/// # Example
+/// ```
+/// # use standard_constraints::prelude::*;
+/// let registry = PairRuleRegistry::new().with_rule("Coprime", |a, b| (2..=a.min(b)).all(|d| a % d != 0 || b % d != 0));
+/// assert!(registry.create("coprime").unwrap()(4, 9));
+/// assert!(!registry.create("coprime").unwrap()(4, 6));
+/// assert!(registry.create("a + b == 10").unwrap()(4, 6));
+/// assert!(registry.create("prime").is_err());
+/// ```
+#[derive(Clone, Default)]
+pub struct PairRuleRegistry {
+    rules: Vec<(String, PairRule)>,
+}
+
+impl PairRuleRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a rule to the registry, replacing any rule with the same name.
+    #[must_use]
+    pub fn with_rule(mut self, name: &str, rule: impl Fn(usize, usize) -> bool + Send + Sync + 'static) -> Self {
+        self.rules.retain(|(existing, _)| !existing.eq_ignore_ascii_case(name));
+        self.rules.push((name.to_owned(), Arc::new(rule)));
+        self
+    }
+
+    /// The names of the registered rules.
+    pub fn names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.rules.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Finds the rule with the given name, or parses the text as a [`PairRuleExpression`].
+    pub fn create(&self, rule: &str) -> Result<PairRule, String> {
+        if let Some((_, rule)) = self.rules.iter().find(|(name, _)| name.eq_ignore_ascii_case(rule.trim())) {
+            return Ok(rule.clone());
+        }
+
+        let expression = PairRuleExpression::parse(rule)?;
+        Ok(Arc::new(move |a, b| expression.allows(a, b)))
+    }
+}
+
+impl std::fmt::Debug for PairRuleRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_no_prime_sums() {
+        let size = 9;
+        let cu = CellUtility::new(size);
+        let rule = PairRuleRegistry::new().create("!isprime(a + b)").unwrap();
+        let row1 = (0..size).map(|col| cu.cell(0, col)).collect::<Vec<_>>();
+        let constraint = PairRuleConstraint::orthogonally_adjacent("No Prime Sums", &row1, rule, size);
+        assert_eq!(constraint.pairs().len(), size - 1);
+
+        let solver = SolverBuilder::default()
+            .with_constraint(Arc::new(constraint))
+            .with_given(cu.cell(0, 4), 2)
+            .build()
+            .unwrap();
+        let board = solver.board();
+        for cell in [cu.cell(0, 3), cu.cell(0, 5)] {
+            assert_eq!(board.cell(cell), ValueMask::from_values(&[4, 6, 7, 8]));
+        }
+        // Only the cells of the first row are restricted
+        assert!(board.cell(cu.cell(1, 4)).has(1));
+
+        let solution = solver.find_first_solution().board().unwrap();
+        for col in 1..size {
+            let sum = solution.cell(cu.cell(0, col - 1)).value() + solution.cell(cu.cell(0, col)).value();
+            assert!((2..sum).any(|d| sum % d == 0), "{sum} is prime");
+        }
+    }
+
+    #[test]
+    fn test_asymmetric_rule() {
+        // The second cell must be greater than the first
+        let size = 4;
+        let cu = CellUtility::new(size);
+        let rule: PairRule = Arc::new(|a, b| a < b);
+        let pairs = vec![(cu.cell(0, 0), cu.cell(1, 1)), (cu.cell(1, 1), cu.cell(2, 2))];
+        let solver = SolverBuilder::new(size)
+            .with_constraint(Arc::new(PairRuleConstraint::new("Less", pairs, rule)))
+            .build()
+            .unwrap();
+        let board = solver.board();
+        assert_eq!(board.cell(cu.cell(0, 0)), ValueMask::from_values(&[1, 2, 3]));
+        assert_eq!(board.cell(cu.cell(1, 1)), ValueMask::from_values(&[2, 3]));
+        assert_eq!(board.cell(cu.cell(2, 2)), ValueMask::from_values(&[2, 3, 4]));
+    }
+}
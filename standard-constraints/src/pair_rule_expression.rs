@@ -0,0 +1,423 @@
+//! Contains [`PairRuleExpression`] for parsing the rules of a
+//! [`PairRuleConstraint`](crate::pair_rule_constraint::PairRuleConstraint) from text.
+
+use sudoku_solver_lib::prelude::*;
+
+/// A small expression language for rules between the values of two cells, so that new rules can be
+/// written in puzzle data without recompiling.
+///
+/// The values of the cells are `a` and `b`. An expression allows the pair when it is true, which is
+/// any value other than zero. Everything is an integer, with the usual operators and precedence:
+/// - Arithmetic: `+`, `-`, `*`, `/`, `%`. Dividing by zero gives zero.
+/// - Comparison: `==`, `!=`, `<`, `<=`, `>`, `>=`, which give 1 or 0.
+/// - Logic: `&&`, `||` and `!`.
+/// - Functions: `abs(x)`, `min(x, y)`, `max(x, y)` and `isprime(x)`. `isprime` is false past
+///   the largest product of two values, which is as far as a rule between two cells needs.
+///
+/// Rules often come from puzzle data, so expressions longer than [`PairRuleExpression::MAX_LENGTH`]
+/// characters or nested deeper than [`PairRuleExpression::MAX_DEPTH`] are rejected.
+///
+/// # Example
+/// ```
+/// # use standard_constraints::prelude::*;
+/// let rule = PairRuleExpression::parse("!isprime(a + b)").unwrap();
+/// assert!(rule.allows(1, 3));
+/// assert!(!rule.allows(2, 3));
+///
+/// let rule = PairRuleExpression::parse("abs(a - b) >= 4").unwrap();
+/// assert!(rule.allows(9, 5));
+/// assert!(!rule.allows(5, 2));
+///
+/// assert!(PairRuleExpression::parse("a +").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PairRuleExpression {
+    text: String,
+    root: Expr,
+}
+
+impl PairRuleExpression {
+    /// The most characters an expression can have.
+    pub const MAX_LENGTH: usize = 1000;
+
+    /// The most that parentheses, function calls and unary operators can be nested.
+    pub const MAX_DEPTH: usize = 32;
+
+    /// Parses an expression, returning an error which describes the first problem found.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        // The text isn't repeated in this error, as it is too long to be useful.
+        if text.len() > Self::MAX_LENGTH {
+            return Err(format!("Pair rule is longer than the maximum of {} characters", Self::MAX_LENGTH));
+        }
+
+        let root = Self::parse_root(text).map_err(|error| format!("{error} in pair rule: {text}"))?;
+        Ok(Self { text: text.to_owned(), root })
+    }
+
+    fn parse_root(text: &str) -> Result<Expr, String> {
+        let tokens = tokenize(text)?;
+        let mut parser = Parser { tokens, position: 0, depth: 0 };
+        let root = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            return Err(format!("Unexpected {token}"));
+        }
+        Ok(root)
+    }
+
+    /// The text the expression was parsed from.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Whether the expression allows the first cell to be `a` and the second cell to be `b`.
+    pub fn allows(&self, a: usize, b: usize) -> bool {
+        self.root.eval(a as i64, b as i64) != 0
+    }
+}
+
+impl std::fmt::Display for PairRuleExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Expr {
+    Number(i64),
+    A,
+    B,
+    Not(Box<Expr>),
+    Negate(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Call(Function, Vec<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BinaryOp {
+    Or,
+    And,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Function {
+    Abs,
+    Min,
+    Max,
+    IsPrime,
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "abs" => Some(Self::Abs),
+            "min" => Some(Self::Min),
+            "max" => Some(Self::Max),
+            "isprime" => Some(Self::IsPrime),
+            _ => None,
+        }
+    }
+
+    fn arg_count(self) -> usize {
+        match self {
+            Self::Abs | Self::IsPrime => 1,
+            Self::Min | Self::Max => 2,
+        }
+    }
+}
+
+impl Expr {
+    fn eval(&self, a: i64, b: i64) -> i64 {
+        match self {
+            Expr::Number(n) => *n,
+            Expr::A => a,
+            Expr::B => b,
+            Expr::Not(expr) => (expr.eval(a, b) == 0) as i64,
+            Expr::Negate(expr) => expr.eval(a, b).wrapping_neg(),
+            Expr::Binary(op, lhs, rhs) => {
+                let lhs = lhs.eval(a, b);
+                // Logic short circuits, so only evaluate the right side when needed.
+                match op {
+                    BinaryOp::Or => return (lhs != 0 || rhs.eval(a, b) != 0) as i64,
+                    BinaryOp::And => return (lhs != 0 && rhs.eval(a, b) != 0) as i64,
+                    _ => {}
+                }
+                let rhs = rhs.eval(a, b);
+                match op {
+                    BinaryOp::Or | BinaryOp::And => unreachable!(),
+                    BinaryOp::Equal => (lhs == rhs) as i64,
+                    BinaryOp::NotEqual => (lhs != rhs) as i64,
+                    BinaryOp::Less => (lhs < rhs) as i64,
+                    BinaryOp::LessEqual => (lhs <= rhs) as i64,
+                    BinaryOp::Greater => (lhs > rhs) as i64,
+                    BinaryOp::GreaterEqual => (lhs >= rhs) as i64,
+                    BinaryOp::Add => lhs.wrapping_add(rhs),
+                    BinaryOp::Subtract => lhs.wrapping_sub(rhs),
+                    BinaryOp::Multiply => lhs.wrapping_mul(rhs),
+                    BinaryOp::Divide => lhs.checked_div(rhs).unwrap_or(0),
+                    BinaryOp::Remainder => lhs.checked_rem(rhs).unwrap_or(0),
+                }
+            }
+            Expr::Call(function, args) => {
+                let args = args.iter().map(|arg| arg.eval(a, b)).collect::<Vec<_>>();
+                match function {
+                    Function::Abs => args[0].wrapping_abs(),
+                    Function::Min => args[0].min(args[1]),
+                    Function::Max => args[0].max(args[1]),
+                    Function::IsPrime => usize::try_from(args[0]).is_ok_and(|n| n <= MAX_PRIME && is_prime(n)) as i64,
+                }
+            }
+        }
+    }
+}
+
+/// The largest product of two values, which is the most that `isprime` checks.
+const MAX_PRIME: usize = ValueMask::MAX_SIZE * ValueMask::MAX_SIZE;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Number(i64),
+    Identifier(String),
+    Symbol(&'static str),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Number(n) => write!(f, "'{n}'"),
+            Token::Identifier(name) => write!(f, "'{name}'"),
+            Token::Symbol(symbol) => write!(f, "'{symbol}'"),
+        }
+    }
+}
+
+/// The symbols of the language, with the longer symbols first so they are matched before their prefixes.
+const SYMBOLS: [&str; 18] =
+    ["==", "!=", "<=", ">=", "&&", "||", "<", ">", "!", "+", "-", "*", "/", "%", "(", ")", ",", "="];
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if c.is_whitespace() {
+            rest = &rest[c.len_utf8()..];
+        } else if c.is_ascii_digit() {
+            let end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+            let number = rest[..end].parse().map_err(|_| "Number too large".to_owned())?;
+            tokens.push(Token::Number(number));
+            rest = &rest[end..];
+        } else if c.is_ascii_alphabetic() {
+            let end = rest.find(|c: char| !c.is_ascii_alphanumeric() && c != '_').unwrap_or(rest.len());
+            tokens.push(Token::Identifier(rest[..end].to_ascii_lowercase()));
+            rest = &rest[end..];
+        } else if let Some(symbol) = SYMBOLS.iter().find(|symbol| rest.starts_with(**symbol)) {
+            if *symbol == "=" {
+                return Err("Use '==' to compare".to_owned());
+            }
+            tokens.push(Token::Symbol(symbol));
+            rest = &rest[symbol.len()..];
+        } else {
+            return Err(format!("Unexpected '{c}'"));
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    /// How deeply the expression being parsed is nested.
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Result<Token, String> {
+        let token = self.tokens.get(self.position).cloned().ok_or("Unexpected end")?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn take_symbol(&mut self, symbols: &[&'static str]) -> Option<&'static str> {
+        match self.peek() {
+            Some(Token::Symbol(symbol)) if symbols.contains(symbol) => {
+                let symbol = *symbol;
+                self.position += 1;
+                Some(symbol)
+            }
+            _ => None,
+        }
+    }
+
+    fn expect_symbol(&mut self, symbol: &'static str) -> Result<(), String> {
+        match self.next()? {
+            Token::Symbol(found) if found == symbol => Ok(()),
+            token => Err(format!("Expected '{symbol}' but found {token}")),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.parse_and()?;
+        while self.take_symbol(&["||"]).is_some() {
+            lhs = Expr::Binary(BinaryOp::Or, Box::new(lhs), Box::new(self.parse_and()?));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.parse_comparison()?;
+        while self.take_symbol(&["&&"]).is_some() {
+            lhs = Expr::Binary(BinaryOp::And, Box::new(lhs), Box::new(self.parse_comparison()?));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr, String> {
+        let lhs = self.parse_sum()?;
+        let op = match self.take_symbol(&["==", "!=", "<", "<=", ">", ">="]) {
+            Some("==") => BinaryOp::Equal,
+            Some("!=") => BinaryOp::NotEqual,
+            Some("<") => BinaryOp::Less,
+            Some("<=") => BinaryOp::LessEqual,
+            Some(">") => BinaryOp::Greater,
+            Some(">=") => BinaryOp::GreaterEqual,
+            _ => return Ok(lhs),
+        };
+        Ok(Expr::Binary(op, Box::new(lhs), Box::new(self.parse_sum()?)))
+    }
+
+    fn parse_sum(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.parse_product()?;
+        while let Some(symbol) = self.take_symbol(&["+", "-"]) {
+            let op = if symbol == "+" { BinaryOp::Add } else { BinaryOp::Subtract };
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.parse_product()?));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_product(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.parse_unary()?;
+        while let Some(symbol) = self.take_symbol(&["*", "/", "%"]) {
+            let op = match symbol {
+                "*" => BinaryOp::Multiply,
+                "/" => BinaryOp::Divide,
+                _ => BinaryOp::Remainder,
+            };
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.parse_unary()?));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, String> {
+        // Every kind of nesting passes through here, so this is where the depth is limited.
+        if self.depth == PairRuleExpression::MAX_DEPTH {
+            return Err(format!("Nested deeper than the maximum of {}", PairRuleExpression::MAX_DEPTH));
+        }
+        self.depth += 1;
+        let expr = match self.take_symbol(&["!", "-"]) {
+            Some("!") => self.parse_unary().map(|expr| Expr::Not(Box::new(expr))),
+            Some(_) => self.parse_unary().map(|expr| Expr::Negate(Box::new(expr))),
+            None => self.parse_primary(),
+        };
+        self.depth -= 1;
+        expr
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, String> {
+        match self.next()? {
+            Token::Number(n) => Ok(Expr::Number(n)),
+            Token::Identifier(name) if name == "a" => Ok(Expr::A),
+            Token::Identifier(name) if name == "b" => Ok(Expr::B),
+            Token::Identifier(name) => {
+                let function = Function::from_name(&name).ok_or_else(|| format!("Unknown name '{name}'"))?;
+                self.expect_symbol("(")?;
+                let mut args = vec![self.parse_or()?];
+                while self.take_symbol(&[","]).is_some() {
+                    args.push(self.parse_or()?);
+                }
+                self.expect_symbol(")")?;
+                if args.len() != function.arg_count() {
+                    return Err(format!(
+                        "'{name}' takes {} arguments but was given {}",
+                        function.arg_count(),
+                        args.len()
+                    ));
+                }
+                Ok(Expr::Call(function, args))
+            }
+            Token::Symbol("(") => {
+                let expr = self.parse_or()?;
+                self.expect_symbol(")")?;
+                Ok(expr)
+            }
+            token => Err(format!("Unexpected {token}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_precedence() {
+        let rule = PairRuleExpression::parse("a + b * 2 == 7 || a % 3 == 0 && !(b > 5)").unwrap();
+        assert!(rule.allows(1, 3));
+        assert!(rule.allows(3, 5));
+        assert!(!rule.allows(3, 6));
+        assert!(!rule.allows(2, 2));
+
+        let rule = PairRuleExpression::parse("-(-a) - max(b, 4) / 0 > min(a, b)").unwrap();
+        assert!(rule.allows(2, 1));
+        assert!(!rule.allows(2, 3));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(PairRuleExpression::parse("a = b").unwrap_err(), "Use '==' to compare in pair rule: a = b");
+        assert_eq!(PairRuleExpression::parse("c > 1").unwrap_err(), "Unknown name 'c' in pair rule: c > 1");
+        assert_eq!(
+            PairRuleExpression::parse("abs(a, b)").unwrap_err(),
+            "'abs' takes 1 arguments but was given 2 in pair rule: abs(a, b)"
+        );
+        assert_eq!(PairRuleExpression::parse("(a").unwrap_err(), "Unexpected end in pair rule: (a");
+        assert_eq!(PairRuleExpression::parse("(a b").unwrap_err(), "Expected ')' but found 'b' in pair rule: (a b");
+        assert_eq!(PairRuleExpression::parse("a b").unwrap_err(), "Unexpected 'b' in pair rule: a b");
+        assert_eq!(PairRuleExpression::parse("a # b").unwrap_err(), "Unexpected '#' in pair rule: a # b");
+    }
+
+    #[test]
+    fn test_limits() {
+        let nested = "(".repeat(200000) + "a" + &")".repeat(200000);
+        assert_eq!(
+            PairRuleExpression::parse(&nested).unwrap_err(),
+            "Pair rule is longer than the maximum of 1000 characters"
+        );
+
+        let depth = PairRuleExpression::MAX_DEPTH;
+        let nested = "(".repeat(depth) + "a" + &")".repeat(depth);
+        assert!(PairRuleExpression::parse(&nested).unwrap_err().starts_with("Nested deeper than the maximum of 32"));
+        let nested = "-".repeat(depth) + "a";
+        assert!(PairRuleExpression::parse(&nested).is_err());
+        let nested = "(".repeat(depth - 1) + "a" + &")".repeat(depth - 1);
+        assert!(PairRuleExpression::parse(&nested).unwrap().allows(1, 2));
+
+        // Huge arguments of isprime are never prime, rather than slow to check
+        let rule = PairRuleExpression::parse("isprime(9223372036854775783) || isprime(a * b)").unwrap();
+        assert!(!rule.allows(3, 3));
+        assert!(rule.allows(1, 7));
+    }
+}
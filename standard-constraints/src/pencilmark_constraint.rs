@@ -1,9 +1,10 @@
 //! Contains the [`PencilmarkConstraint`] struct for restricting a cell to specific pencilmarks.
 
+use std::sync::Arc;
 use sudoku_solver_lib::prelude::*;
 
 /// A [`Constraint`] implementation for restricting a cell to specific pencilmarks.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct PencilmarkConstraint {
     specific_name: String,
     cell: CellIndex,
@@ -71,6 +72,10 @@ impl Constraint for PencilmarkConstraint {
         self.specific_name.as_str()
     }
 
+    fn clone_constraint(&self) -> Option<Arc<dyn Constraint>> {
+        Some(Arc::new(self.clone()))
+    }
+
     fn get_weak_links(&self, size: usize) -> Vec<(CandidateIndex, CandidateIndex)> {
         let mut result = Vec::new();
         let clear_mask = self.values.inverted(size);
@@ -81,4 +86,8 @@ impl Constraint for PencilmarkConstraint {
 
         result
     }
+
+    fn relevant_cells(&self) -> Vec<CellIndex> {
+        vec![self.cell]
+    }
 }
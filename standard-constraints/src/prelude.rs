@@ -1,8 +1,23 @@
 pub use crate::arrow_sum_constraint::*;
+pub use crate::between_line_constraint::*;
+pub use crate::chaos_construction_constraint::*;
 pub use crate::chess_constraint::*;
 pub use crate::fpuzzles_parser::prelude::*;
 pub use crate::fpuzzles_parser::*;
+pub use crate::killer_cage_constraint::*;
+pub use crate::line_constraint::*;
 pub use crate::non_repeat_constraint::*;
 pub use crate::orthogonal_pairs_constraint::*;
+pub use crate::outside_clue::*;
+pub use crate::pair_rule_constraint::*;
+pub use crate::pair_rule_expression::*;
 pub use crate::pencilmark_constraint::*;
+pub use crate::puzzle_collection::*;
+pub use crate::puzzle_input::*;
+#[cfg(any(test, feature = "self-test"))]
+pub use crate::regression_suite::*;
+pub use crate::skyscraper_constraint::*;
+pub use crate::solver_builder_ext::*;
+pub use crate::solver_builder_presets::*;
 pub use crate::standard_pair_type::*;
+pub use crate::x_sum_constraint::*;
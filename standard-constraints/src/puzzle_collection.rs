@@ -0,0 +1,229 @@
+//! Contains [`PuzzleRecord`] and [`CollectionFormat`] for reading and writing collections of puzzles.
+
+use std::path::Path;
+
+use itertools::Itertools;
+
+use crate::prelude::*;
+use sudoku_solver_lib::prelude::*;
+
+/// The file formats of puzzle collections.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollectionFormat {
+    /// One puzzle per line, in any format [`PuzzleInputKind::detect`] accepts, followed by optional metadata columns.
+    ///
+    /// Columns are separated by tabs. A line without tabs which starts with a givens string may instead
+    /// separate its columns with spaces. Empty lines and lines starting with `#` are ignored.
+    Lines,
+    /// SadMan Sudoku collections: one 9x9 givens string per line, with `0` for empty cells.
+    Sdm,
+    /// A single SadMan Sudoku puzzle: one row of the 9x9 grid per line, with `.` for empty cells.
+    /// Lines starting with `#` are metadata, such as `#AAuthor` or `#DDescription`.
+    Sdk,
+}
+
+impl CollectionFormat {
+    /// Chooses the format from the extension of a file, using [`CollectionFormat::Lines`]
+    /// for anything other than `.sdm` and `.sdk`.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()).map(str::to_lowercase).as_deref() {
+            Some("sdm") => Self::Sdm,
+            Some("sdk") => Self::Sdk,
+            _ => Self::Lines,
+        }
+    }
+}
+
+/// A puzzle in a collection, along with any metadata stored with it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PuzzleRecord {
+    /// The puzzle text, which can be parsed by [`PuzzleInputParser`].
+    pub puzzle: String,
+    /// The metadata columns of [`CollectionFormat::Lines`], or the `#` lines of [`CollectionFormat::Sdk`]
+    /// without their `#`.
+    pub metadata: Vec<String>,
+}
+
+impl PuzzleRecord {
+    /// Creates a record with no metadata.
+    pub fn new(puzzle: &str) -> Self {
+        Self { puzzle: puzzle.to_owned(), metadata: Vec::new() }
+    }
+
+    /// Adds a metadata column to the end of the record.
+    #[must_use]
+    pub fn with_metadata(mut self, metadata: &str) -> Self {
+        self.metadata.push(metadata.to_owned());
+        self
+    }
+
+    /// Parses the puzzle of the record.
+    pub fn parse(&self, parser: &PuzzleInputParser) -> Result<SolverBuilder, String> {
+        parser.parse(&self.puzzle)
+    }
+}
+
+/// Reads the puzzles of a collection.
+///
+/// # Example
+/// ```
+/// # use standard_constraints::prelude::*;
+/// let givens = ".............23.4.....452....1.3.....3...4...6..7....8..6.....9.5....62.7.9...1..";
+/// let records = read_puzzles(&format!("# Puzzles\n{givens}\thard\n"), CollectionFormat::Lines).unwrap();
+/// assert_eq!(records, vec![PuzzleRecord::new(givens).with_metadata("hard")]);
+/// ```
+pub fn read_puzzles(text: &str, format: CollectionFormat) -> Result<Vec<PuzzleRecord>, String> {
+    match format {
+        CollectionFormat::Lines => Ok(text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(read_line_record)
+            .collect()),
+        CollectionFormat::Sdm => text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .enumerate()
+            .map(|(index, line)| {
+                if line.len() != 81 || !line.chars().all(|c| c.is_ascii_digit() || c == '.') {
+                    return Err(format!("Line {} is not a 9x9 givens string", index + 1));
+                }
+                Ok(PuzzleRecord::new(line))
+            })
+            .collect(),
+        CollectionFormat::Sdk => {
+            let mut metadata = Vec::new();
+            let mut puzzle = String::new();
+            for line in text.lines().map(str::trim).filter(|line| !line.is_empty() && *line != "[Puzzle]") {
+                if let Some(comment) = line.strip_prefix('#') {
+                    metadata.push(comment.to_owned());
+                } else if line.len() == 9 && line.chars().all(|c| c.is_ascii_digit() || c == '.') {
+                    puzzle.push_str(line);
+                } else {
+                    return Err(format!("Unexpected line in SDK puzzle: {line}"));
+                }
+            }
+            if puzzle.len() != 81 {
+                return Err(format!("SDK puzzle has {} cells, expected 81", puzzle.len()));
+            }
+            Ok(vec![PuzzleRecord { puzzle, metadata }])
+        }
+    }
+}
+
+fn read_line_record(line: &str) -> PuzzleRecord {
+    let columns = if line.contains('\t') {
+        line.split('\t').map(str::trim).collect::<Vec<_>>()
+    } else {
+        let first = line.split_whitespace().next().unwrap_or_default();
+        if matches!(PuzzleInputKind::detect(first), Some(PuzzleInputKind::Givens(_))) {
+            line.split_whitespace().collect()
+        } else {
+            vec![line]
+        }
+    };
+    PuzzleRecord {
+        puzzle: columns[0].to_owned(),
+        metadata: columns[1..].iter().map(|column| column.to_string()).collect(),
+    }
+}
+
+/// Writes the puzzles as a collection which [`read_puzzles`] reads back.
+///
+/// [`CollectionFormat::Sdm`] and [`CollectionFormat::Sdk`] only hold 9x9 givens strings, and
+/// [`CollectionFormat::Sdk`] only holds a single puzzle. [`CollectionFormat::Sdm`] has no metadata.
+pub fn write_puzzles(records: &[PuzzleRecord], format: CollectionFormat) -> Result<String, String> {
+    let givens_9x9 = |record: &PuzzleRecord| -> Result<String, String> {
+        if PuzzleInputKind::detect(&record.puzzle) == Some(PuzzleInputKind::Givens(9)) {
+            Ok(record.puzzle.trim().to_owned())
+        } else {
+            Err(format!("Only 9x9 givens strings can be written as {format:?}: {}", record.puzzle))
+        }
+    };
+
+    let mut text = String::new();
+    match format {
+        CollectionFormat::Lines => {
+            for record in records {
+                if record.puzzle.contains(['\t', '\n']) || record.metadata.iter().any(|m| m.contains(['\t', '\n'])) {
+                    return Err(format!("Puzzles and metadata can't contain tabs or new lines: {}", record.puzzle));
+                }
+                text.push_str(&std::iter::once(&record.puzzle).chain(record.metadata.iter()).join("\t"));
+                text.push('\n');
+            }
+        }
+        CollectionFormat::Sdm => {
+            for record in records {
+                text.push_str(&givens_9x9(record)?.replace('.', "0"));
+                text.push('\n');
+            }
+        }
+        CollectionFormat::Sdk => {
+            let [record] = records else {
+                return Err(format!("An SDK file holds one puzzle, not {}", records.len()));
+            };
+            let givens = givens_9x9(record)?.replace('0', ".");
+            for metadata in record.metadata.iter() {
+                text.push_str(&format!("#{metadata}\n"));
+            }
+            for row in givens.as_bytes().chunks(9) {
+                text.push_str(std::str::from_utf8(row).unwrap_or_default());
+                text.push('\n');
+            }
+        }
+    }
+    Ok(text)
+}
+
+/// Reads the puzzles of a collection file, with the format chosen by [`CollectionFormat::from_path`].
+pub fn read_puzzle_file(path: &Path) -> Result<Vec<PuzzleRecord>, String> {
+    let text = std::fs::read_to_string(path).map_err(|error| format!("Failed to read {}: {error}", path.display()))?;
+    read_puzzles(&text, CollectionFormat::from_path(path))
+}
+
+/// Writes the puzzles to a collection file, with the format chosen by [`CollectionFormat::from_path`].
+pub fn write_puzzle_file(path: &Path, records: &[PuzzleRecord]) -> Result<(), String> {
+    let text = write_puzzles(records, CollectionFormat::from_path(path))?;
+    std::fs::write(path, text).map_err(|error| format!("Failed to write {}: {error}", path.display()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const GIVENS: &str = ".............23.4.....452....1.3.....3...4...6..7....8..6.....9.5....62.7.9...1..";
+
+    #[test]
+    fn test_lines() {
+        let text =
+            format!("# A comment\n\n{GIVENS} 1234 easy\n{{\"size\": 9}}\tJSON\nhttps://f-puzzles.com/?load=N4Ig\n");
+        let records = read_puzzles(&text, CollectionFormat::Lines).unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0], PuzzleRecord::new(GIVENS).with_metadata("1234").with_metadata("easy"));
+        assert_eq!(records[1], PuzzleRecord::new("{\"size\": 9}").with_metadata("JSON"));
+        assert_eq!(records[2], PuzzleRecord::new("https://f-puzzles.com/?load=N4Ig"));
+
+        let written = write_puzzles(&records, CollectionFormat::Lines).unwrap();
+        assert_eq!(read_puzzles(&written, CollectionFormat::Lines).unwrap(), records);
+        assert!(records[0].parse(&PuzzleInputParser::new()).unwrap().build().is_ok());
+    }
+
+    #[test]
+    fn test_sdm_and_sdk() {
+        let records = vec![PuzzleRecord::new(GIVENS).with_metadata("AAuthor")];
+        let sdm = write_puzzles(&records, CollectionFormat::Sdm).unwrap();
+        assert_eq!(sdm, format!("{}\n", GIVENS.replace('.', "0")));
+        let read = read_puzzles(&sdm, CollectionFormat::Sdm).unwrap();
+        assert_eq!(read, vec![PuzzleRecord::new(&GIVENS.replace('.', "0"))]);
+
+        let sdk = write_puzzles(&records, CollectionFormat::Sdk).unwrap();
+        assert!(sdk.starts_with("#AAuthor\n.........\n"));
+        assert_eq!(read_puzzles(&sdk, CollectionFormat::Sdk).unwrap(), records);
+
+        assert!(write_puzzles(&[records[0].clone(), records[0].clone()], CollectionFormat::Sdk).is_err());
+        assert!(read_puzzles("123", CollectionFormat::Sdm).is_err());
+        assert_eq!(CollectionFormat::from_path(Path::new("puzzles.SDM")), CollectionFormat::Sdm);
+        assert_eq!(CollectionFormat::from_path(Path::new("puzzles.txt")), CollectionFormat::Lines);
+    }
+}
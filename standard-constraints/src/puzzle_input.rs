@@ -0,0 +1,224 @@
+//! Contains the [`PuzzleInputParser`] struct for turning any supported puzzle text into a [`SolverBuilder`].
+
+use crate::prelude::*;
+use sudoku_solver_lib::prelude::*;
+
+/// The kinds of puzzle text which [`PuzzleInputKind::detect`] can recognize.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PuzzleInputKind {
+    /// A givens string, as accepted by [`SolverBuilder::with_givens_string`], for a grid of the given size.
+    Givens(usize),
+    /// f-puzzles data compressed with lz-string, as used by the `load` parameter of an f-puzzles link.
+    FPuzzles,
+    /// An f-puzzles or SudokuPad link containing f-puzzles data.
+    FPuzzlesUrl,
+    /// Uncompressed f-puzzles JSON.
+    FPuzzlesJson,
+    /// SudokuPad's own compressed format, which starts with `scl`.
+    Scl,
+}
+
+impl PuzzleInputKind {
+    /// Detects the kind of the puzzle text, ignoring surrounding whitespace.
+    ///
+    /// Returns [`None`] if the text is empty or can't be any of the kinds.
+    ///
+    /// # Example
+    /// ```
+    /// # use standard_constraints::prelude::*;
+    /// let givens = ".............23.4.....452....1.3.....3...4...6..7....8..6.....9.5....62.7.9...1..";
+    /// assert_eq!(PuzzleInputKind::detect(givens), Some(PuzzleInputKind::Givens(9)));
+    /// assert_eq!(PuzzleInputKind::detect("https://www.f-puzzles.com/?load=N4Ig"), Some(PuzzleInputKind::FPuzzlesUrl));
+    /// assert_eq!(PuzzleInputKind::detect("{\"size\":9}"), Some(PuzzleInputKind::FPuzzlesJson));
+    /// assert_eq!(PuzzleInputKind::detect("N4IgzglgXgpiBcBOANCALhNAbO"), Some(PuzzleInputKind::FPuzzles));
+    /// ```
+    pub fn detect(input: &str) -> Option<Self> {
+        let input = input.trim();
+        if input.is_empty() {
+            return None;
+        }
+
+        if input.starts_with('{') {
+            return Some(Self::FPuzzlesJson);
+        }
+
+        if input.contains("://") {
+            // The f-puzzles data itself could contain "/scl", so look for it first
+            let is_scl = PuzzleInputParser::fpuzzles_from_url(input).is_none() && Self::scl_from_url(input).is_some();
+            return Some(if is_scl { Self::Scl } else { Self::FPuzzlesUrl });
+        }
+
+        if input.starts_with("scl") {
+            return Some(Self::Scl);
+        }
+
+        if let Some(size) = Self::givens_size(input) {
+            return Some(Self::Givens(size));
+        }
+
+        let is_lzstring = input.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '=' | '-' | '$'));
+        is_lzstring.then_some(Self::FPuzzles)
+    }
+
+    /// Returns the grid size if the text is a givens string of digits and `.` for some size,
+    /// or of digits and letters for a grid larger than 9. See [`GivensEncoding`].
+    fn givens_size(input: &str) -> Option<usize> {
+        (1..=ValueMask::MAX_SIZE).find(|&size: &usize| match GivensEncoding::detect(input, size) {
+            Some(GivensEncoding::Numeric) => input.chars().all(|c| c.is_ascii_digit() || c == '.'),
+            Some(GivensEncoding::Alphanumeric) => {
+                input.chars().all(|c| c == '.' || c.to_digit(36).is_some_and(|value| value as usize <= size))
+            }
+            None => false,
+        })
+    }
+
+    /// Returns the SudokuPad data of a link, if it contains any.
+    fn scl_from_url(url: &str) -> Option<&str> {
+        let start = url.find("/scl").map(|index| index + 1).or_else(|| url.find("=scl").map(|index| index + 1))?;
+        Some(&url[start..])
+    }
+}
+
+/// Turns puzzle text of any [`PuzzleInputKind`] into a [`SolverBuilder`].
+///
+/// This is shared by the front ends so that they all accept the same inputs.
+///
+/// # Example
+/// ```
+/// # use standard_constraints::prelude::*;
+/// let givens = ".............23.4.....452....1.3.....3...4...6..7....8..6.....9.5....62.7.9...1..";
+/// let solver = PuzzleInputParser::new().parse(givens).unwrap().build().unwrap();
+/// assert_eq!(solver.board().size(), 9);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct PuzzleInputParser {
+    fpuzzles_parser: FPuzzlesParser,
+    treat_pencilmarks_as_given: bool,
+}
+
+impl PuzzleInputParser {
+    /// Creates a new [`PuzzleInputParser`] which uses a default [`FPuzzlesParser`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use this parser for f-puzzles data. Its logical steps are also used for givens strings.
+    #[must_use]
+    pub fn with_fpuzzles_parser(mut self, fpuzzles_parser: FPuzzlesParser) -> Self {
+        self.fpuzzles_parser = fpuzzles_parser;
+        self
+    }
+
+    /// Whether to treat the center pencilmarks of f-puzzles data as given.
+    /// See [`FPuzzlesParser::parse_board`].
+    #[must_use]
+    pub fn with_pencilmarks_as_given(mut self, treat_pencilmarks_as_given: bool) -> Self {
+        self.treat_pencilmarks_as_given = treat_pencilmarks_as_given;
+        self
+    }
+
+    /// Detects the kind of the puzzle text and parses it.
+    pub fn parse(&self, input: &str) -> Result<SolverBuilder, String> {
+        let kind = PuzzleInputKind::detect(input).ok_or_else(|| "Unrecognized puzzle format".to_owned())?;
+        self.parse_as(input, kind)
+    }
+
+    /// Parses the puzzle text as the given kind.
+    pub fn parse_as(&self, input: &str, kind: PuzzleInputKind) -> Result<SolverBuilder, String> {
+        let input = input.trim();
+        match kind {
+            PuzzleInputKind::Givens(size) => {
+                let builder = SolverBuilder::new(size).with_givens_string(input);
+                let logical_steps = self.fpuzzles_parser.logical_steps();
+                if logical_steps.is_empty() {
+                    Ok(builder)
+                } else {
+                    Ok(builder.with_logical_steps(logical_steps.to_vec()))
+                }
+            }
+            PuzzleInputKind::FPuzzles => self.parse_fpuzzles(&FPuzzlesBoard::from_lzstring_json(input)?),
+            PuzzleInputKind::FPuzzlesUrl => {
+                let data = Self::fpuzzles_from_url(input).ok_or_else(|| "No f-puzzles data in link".to_owned())?;
+                self.parse_fpuzzles(&FPuzzlesBoard::from_lzstring_json(&data)?)
+            }
+            PuzzleInputKind::FPuzzlesJson => {
+                let board =
+                    FPuzzlesBoard::from_json(input).map_err(|error| format!("Failed to parse JSON: {error}"))?;
+                self.parse_fpuzzles(&board)
+            }
+            PuzzleInputKind::Scl => Err("SudokuPad scl puzzles are not supported".to_owned()),
+        }
+    }
+
+    fn parse_fpuzzles(&self, board: &FPuzzlesBoard) -> Result<SolverBuilder, String> {
+        Ok(self.fpuzzles_parser.parse_board_builder(board, self.treat_pencilmarks_as_given))
+    }
+
+    /// Finds the f-puzzles data in a link, which is either the `load` parameter of an f-puzzles link or
+    /// follows `fpuzzles` in a SudokuPad link.
+    fn fpuzzles_from_url(url: &str) -> Option<String> {
+        let start = if let Some(index) = url.find("load=") {
+            index + "load=".len()
+        } else {
+            url.rfind("fpuzzles").map(|index| index + "fpuzzles".len())?
+        };
+        let data = url[start..].split(['&', '#']).next()?;
+        Self::percent_decode(data)
+    }
+
+    /// Decodes the `%XX` escapes which browsers add to lz-string data in links.
+    fn percent_decode(data: &str) -> Option<String> {
+        let mut decoded = Vec::with_capacity(data.len());
+        let mut bytes = data.bytes();
+        while let Some(byte) = bytes.next() {
+            if byte == b'%' {
+                let hex = [bytes.next()?, bytes.next()?];
+                decoded.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+            } else {
+                decoded.push(byte);
+            }
+        }
+        String::from_utf8(decoded).ok()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fpuzzles_parser::fpuzzles_test_data::FPUZZLES_CLASSICS_DATA;
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(PuzzleInputKind::detect(""), None);
+        assert_eq!(PuzzleInputKind::detect("  \n"), None);
+        assert_eq!(PuzzleInputKind::detect(&"0".repeat(81)), Some(PuzzleInputKind::Givens(9)));
+        assert_eq!(PuzzleInputKind::detect(&"0".repeat(512)), Some(PuzzleInputKind::Givens(16)));
+        assert_eq!(PuzzleInputKind::detect(&"G.".repeat(128)), Some(PuzzleInputKind::Givens(16)));
+        assert_eq!(PuzzleInputKind::detect(&"H1".repeat(128)), Some(PuzzleInputKind::FPuzzles));
+        assert_eq!(PuzzleInputKind::detect("https://sudokupad.app/scl123"), Some(PuzzleInputKind::Scl));
+        assert_eq!(PuzzleInputKind::detect("sclN4Ig"), Some(PuzzleInputKind::Scl));
+        assert_eq!(PuzzleInputKind::detect("not a puzzle"), None);
+    }
+
+    #[test]
+    fn test_parse_fpuzzles_kinds() {
+        let (lzstring, expected_solution) = FPUZZLES_CLASSICS_DATA[0];
+        let parser = PuzzleInputParser::new();
+
+        let json = FPuzzlesBoard::from_lzstring_json(lzstring).unwrap().to_json();
+        let encoded = lzstring.replace('+', "%2B").replace('/', "%2F").replace('=', "%3D");
+        let inputs = [
+            lzstring.to_owned(),
+            format!("https://www.f-puzzles.com/?load={encoded}"),
+            format!("https://sudokupad.app/fpuzzles{lzstring}"),
+            json,
+        ];
+        for input in inputs {
+            let solver = parser.parse(&input).unwrap().build().unwrap();
+            let solution = solver.find_first_solution();
+            assert_eq!(solution.board().unwrap().to_string(), expected_solution);
+        }
+
+        assert!(parser.parse("https://sudokupad.app/scl123").is_err());
+    }
+}
@@ -0,0 +1,209 @@
+//! Contains [`RegressionSuite`] for checking the solver against puzzles with known solutions.
+
+use crate::fpuzzles_parser::fpuzzles_test_data::{FPUZZLES_CLASSICS_DATA, FPUZZLES_TEST_DATA};
+use crate::prelude::*;
+use sudoku_solver_lib::prelude::*;
+
+/// The titles of the variant puzzles of the test data which are bundled into [`RegressionSuite::bundled`],
+/// along with the constraints they exercise.
+const BUNDLED_VARIANTS: &[(&str, &str)] = &[
+    ("Self-contained", "Killer, Knight"),
+    ("300 Subs!", "Thermo, Kropki"),
+    ("XV Kropki X", "Diagonals, Kropki, XV"),
+    ("Hay Fever", "Thermo, Between Line, Kropki"),
+    ("Dotween", "Between Line, Kropki"),
+    ("Return of the Revenge of the Hash: The Movie: The Game", "Irregular, Thermo"),
+    ("Behemoth", "16x16, Irregular, Killer"),
+    ("Alien", "Whispers, Thermo"),
+    ("Tiny Scar", "Whispers, X-Sums"),
+    ("Renban Skyscrapers (CTC 400k)", "Renban, Skyscrapers"),
+    ("Entropic Lines 101", "Entropic Lines"),
+];
+
+/// A puzzle in f-puzzles format along with its only solution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegressionCase {
+    /// The name reported for the case.
+    pub name: String,
+    /// The puzzle, as an f-puzzles lzstring.
+    pub puzzle: String,
+    /// The solution, as the values of each cell in order.
+    pub expected_solution: String,
+}
+
+impl RegressionCase {
+    /// Creates a case from the f-puzzles lzstring of the puzzle and the values of its solution.
+    pub fn new(name: &str, puzzle: &str, expected_solution: &str) -> Self {
+        Self { name: name.to_owned(), puzzle: puzzle.to_owned(), expected_solution: expected_solution.to_owned() }
+    }
+}
+
+/// The outcome of running a single [`RegressionCase`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegressionResult {
+    /// The name of the case.
+    pub name: String,
+    /// A description of each check which did not produce the expected output.
+    pub failures: Vec<String>,
+}
+
+impl RegressionResult {
+    /// Whether every check produced the expected output.
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Runs puzzles with known solutions through the brute force solve, the solution count and the logical solve,
+/// reporting any result which differs from the stored solution.
+///
+/// [`RegressionSuite::bundled`] contains the classic puzzles and a puzzle for each supported variant,
+/// which is useful for verifying that a build behaves correctly on the platform it runs on.
+#[derive(Debug, Clone)]
+pub struct RegressionSuite {
+    parser: FPuzzlesParser,
+    cases: Vec<RegressionCase>,
+}
+
+impl RegressionSuite {
+    /// Creates a suite of the given cases, parsed with the default [`FPuzzlesParser`].
+    pub fn new(cases: Vec<RegressionCase>) -> Self {
+        Self { parser: FPuzzlesParser::new(), cases }
+    }
+
+    /// Creates a suite of the bundled classic and variant puzzles.
+    pub fn bundled() -> Self {
+        let classics = FPUZZLES_CLASSICS_DATA.iter().enumerate().map(|(index, (puzzle, solution))| {
+            RegressionCase::new(&format!("Classic {}", index + 1), puzzle, solution)
+        });
+        let titles = FPUZZLES_TEST_DATA
+            .iter()
+            .map(|(puzzle, _)| FPuzzlesBoard::from_lzstring_json(puzzle).map(|board| board.title).unwrap_or_default())
+            .collect::<Vec<_>>();
+        let variants = BUNDLED_VARIANTS.iter().map(|&(title, constraints)| {
+            let index = titles
+                .iter()
+                .position(|puzzle_title| puzzle_title == title)
+                .unwrap_or_else(|| panic!("No bundled puzzle is titled {title}"));
+            let (puzzle, solution) = FPUZZLES_TEST_DATA[index];
+            RegressionCase::new(&format!("{title} ({constraints})"), puzzle, solution)
+        });
+        Self::new(classics.chain(variants).collect())
+    }
+
+    /// Sets the parser used to create the solver of each case.
+    #[must_use]
+    pub fn with_parser(mut self, parser: FPuzzlesParser) -> Self {
+        self.parser = parser;
+        self
+    }
+
+    /// The cases of the suite, in the order they run.
+    pub fn cases(&self) -> &[RegressionCase] {
+        &self.cases
+    }
+
+    /// Runs every case, returning the results in the order of the cases.
+    pub fn run(&self) -> Vec<RegressionResult> {
+        self.cases.iter().map(|case| self.run_case(case)).collect()
+    }
+
+    /// Runs a single case, which does not need to be part of the suite.
+    pub fn run_case(&self, case: &RegressionCase) -> RegressionResult {
+        let failures = match self.check_case(case) {
+            Ok(failures) => failures,
+            Err(error) => vec![format!("Parse: {error}")],
+        };
+        RegressionResult { name: case.name.clone(), failures }
+    }
+
+    fn check_case(&self, case: &RegressionCase) -> Result<Vec<String>, String> {
+        let board = FPuzzlesBoard::from_lzstring_json(&case.puzzle)?;
+        let solver = self.parser.parse_board(&board, false)?;
+        let mut failures = Vec::new();
+
+        // The cases have a unique solution, so the fastest search finds the same one as a lexicographic search.
+        let solution = solver.find_solution_in_order(SolutionOrder::Fastest);
+        let solution_board = match solution {
+            SingleSolutionResult::Solved(solution_board) if solution_board.to_string() == case.expected_solution => {
+                Some(solution_board)
+            }
+            SingleSolutionResult::Solved(solution_board) => {
+                failures.push(format!("Solve: expected {}, found {}", case.expected_solution, solution_board));
+                None
+            }
+            SingleSolutionResult::None => {
+                failures.push("Solve: found no solution".to_owned());
+                None
+            }
+            SingleSolutionResult::Error(error) => {
+                failures.push(format!("Solve: {error}"));
+                None
+            }
+        };
+
+        let count = solver.find_solution_count(2, None, None);
+        if count.count() != Some(1) || !count.is_exact_count() {
+            failures.push(format!("Count: expected exactly 1, found {count:?}"));
+        }
+
+        let mut logical_solver = solver;
+        let logical_result = logical_solver.run_logical_solve();
+        if logical_result.is_invalid() {
+            failures.push("Logical solve: found the board invalid".to_owned());
+        } else if let Some(solution_board) = solution_board {
+            let board = logical_solver.board();
+            let wrong_cells = board
+                .all_cells()
+                .filter(|&cell| board.cell(cell).is_single() && board.cell(cell) != solution_board.cell(cell))
+                .collect::<Vec<_>>();
+            if !wrong_cells.is_empty() {
+                failures.push(format!("Logical solve: {} cells do not match the solution", wrong_cells.len()));
+            }
+        }
+
+        Ok(failures)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bundled() {
+        let suite = RegressionSuite::bundled();
+        assert_eq!(suite.cases().len(), FPUZZLES_CLASSICS_DATA.len() + BUNDLED_VARIANTS.len());
+
+        let classic = &suite.cases()[0];
+        let result = suite.run_case(classic);
+        assert_eq!(result.name, "Classic 1");
+        assert!(result.passed(), "{:?}", result.failures);
+
+        let behemoth = suite.cases().iter().find(|case| case.name.starts_with("Behemoth")).unwrap();
+        assert_eq!(behemoth.name, "Behemoth (16x16, Irregular, Killer)");
+        assert_eq!(FPuzzlesBoard::from_lzstring_json(&behemoth.puzzle).unwrap().size, 16);
+
+        let variants = RegressionSuite::new(suite.cases()[FPUZZLES_CLASSICS_DATA.len()..].to_vec());
+        for result in variants.run() {
+            assert!(result.passed(), "{}: {:?}", result.name, result.failures);
+        }
+    }
+
+    #[test]
+    fn test_wrong_solution() {
+        let (puzzle, solution) = FPUZZLES_CLASSICS_DATA[0];
+        let wrong_solution = solution.chars().rev().collect::<String>();
+        let suite = RegressionSuite::new(vec![
+            RegressionCase::new("Wrong", puzzle, &wrong_solution),
+            RegressionCase::new("Unparsable", "not a puzzle", solution),
+        ]);
+
+        let results = suite.run();
+        assert_eq!(results.len(), 2);
+        assert!(!results[0].passed());
+        assert!(results[0].failures[0].starts_with("Solve:"));
+        assert!(!results[1].passed());
+        assert!(results[1].failures[0].starts_with("Parse:"));
+    }
+}
@@ -0,0 +1,234 @@
+//! Contains the [`SkyscraperConstraint`] struct for representing a skyscraper clue.
+
+use itertools::Itertools;
+
+use crate::prelude::*;
+use std::sync::Arc;
+use sudoku_solver_lib::prelude::*;
+
+/// A [`Constraint`] implementation for representing a skyscraper clue, which is how many cells
+/// can be seen from the clue when each value is the height of a building in its cell.
+/// A building is seen when it is taller than every building in front of it.
+///
+/// The cells are a whole row or column, so they can't repeat values.
+///
+/// # Example
+/// ```
+/// # use standard_constraints::prelude::*;
+/// # use sudoku_solver_lib::prelude::*;
+/// # use std::sync::Arc;
+/// let cu = CellUtility::new(9);
+/// let skyscraper = SkyscraperConstraint::new(&OutsideClue::new(9, 0, 1).unwrap(), 1);
+/// let solver = SolverBuilder::default().with_constraint(Arc::new(skyscraper)).build().unwrap();
+/// assert_eq!(solver.board().cell(cu.cell(0, 0)), ValueMask::from_value(9));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SkyscraperConstraint {
+    specific_name: String,
+    clue: OutsideClue,
+    count: usize,
+}
+
+impl SkyscraperConstraint {
+    /// Creates a new [`SkyscraperConstraint`] for the row or column of the clue.
+    pub fn new(clue: &OutsideClue, count: usize) -> Self {
+        let specific_name = format!("Skyscraper {count} at {}", clue.name());
+        Self { specific_name, clue: clue.clone(), count }
+    }
+
+    /// The clue, whose cells are the row or column it applies to.
+    pub fn clue(&self) -> &OutsideClue {
+        &self.clue
+    }
+
+    /// The number of buildings seen from the clue, where taller buildings hide shorter ones behind them.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The tallest building and the number of buildings seen after the value of the cell at the index,
+    /// or `None` if that can't happen in a row or column with every value once.
+    ///
+    /// The value can't repeat the tallest building, the clue limits how many are seen, and the tallest
+    /// building after some cells is at least as tall as the number of cells.
+    fn next_state(&self, index: usize, (tallest, seen): (usize, usize), value: usize) -> Option<(usize, usize)> {
+        let next = match value.cmp(&tallest) {
+            std::cmp::Ordering::Greater if seen < self.count => (value, seen + 1),
+            std::cmp::Ordering::Less => (tallest, seen),
+            _ => return None,
+        };
+        (next.0 > index).then_some(next)
+    }
+
+    /// Finds the values each cell can have in at least one filling which sees the right number of buildings.
+    ///
+    /// Fillings are tracked only by the tallest building so far and the number seen, so values can
+    /// repeat between cells which don't change either. This is much cheaper than tracking every
+    /// used value, which needs a state for each set of values.
+    fn possible_values(&self, board: &Board) -> Vec<ValueMask> {
+        let size = board.size();
+        let len = self.clue.cells().len();
+        let masks = self.clue.cells().iter().map(|&cell| board.cell(cell).unsolved()).collect::<Vec<_>>();
+        let states = (size + 1) * (self.count + 1);
+        let state_index = |(tallest, seen): (usize, usize)| tallest * (self.count + 1) + seen;
+        let can_finish = |finishes: &[Vec<bool>], index: usize, state: (usize, usize), value: usize| {
+            self.next_state(index, state, value).is_some_and(|next| finishes[index + 1][state_index(next)])
+        };
+
+        // Whether each state can be reached before each cell, and whether the rest of the cells can be filled from it.
+        let mut reachable = vec![vec![false; states]; len + 1];
+        let mut finishes = vec![vec![false; states]; len + 1];
+        reachable[0][state_index((0, 0))] = true;
+        for (index, &mask) in masks.iter().enumerate() {
+            for state in (0..=size).cartesian_product(0..=self.count) {
+                if !reachable[index][state_index(state)] {
+                    continue;
+                }
+                for value in mask {
+                    if let Some(next) = self.next_state(index, state, value) {
+                        reachable[index + 1][state_index(next)] = true;
+                    }
+                }
+            }
+        }
+        finishes[len][state_index((size, self.count))] = true;
+        for (index, &mask) in masks.iter().enumerate().rev() {
+            for state in (0..=size).cartesian_product(0..=self.count) {
+                finishes[index][state_index(state)] =
+                    mask.into_iter().any(|value| can_finish(&finishes, index, state, value));
+            }
+        }
+
+        let mut possible = vec![ValueMask::new(); len];
+        for (index, &mask) in masks.iter().enumerate() {
+            for state in (0..=size).cartesian_product(0..=self.count) {
+                if !reachable[index][state_index(state)] {
+                    continue;
+                }
+                for value in mask & !possible[index] {
+                    if can_finish(&finishes, index, state, value) {
+                        possible[index] = possible[index].with(value);
+                    }
+                }
+            }
+        }
+        possible
+    }
+
+    /// Removes the candidates which aren't part of any filling which sees the right number of buildings.
+    fn eliminate_impossible(&self, board: &mut Board) -> (LogicalStepResult, EliminationList) {
+        let possible = self.possible_values(board);
+        let elims = EliminationList::from_possible(self.clue.cells(), &possible, board);
+        (elims.execute(board), elims)
+    }
+}
+
+impl Constraint for SkyscraperConstraint {
+    fn name(&self) -> &str {
+        &self.specific_name
+    }
+
+    fn clone_constraint(&self) -> Option<Arc<dyn Constraint>> {
+        Some(Arc::new(self.clone()))
+    }
+
+    fn init_board(&mut self, board: &mut Board) -> LogicalStepResult {
+        self.eliminate_impossible(board).0
+    }
+
+    fn enforce(&self, board: &Board, _cell: CellIndex, _val: usize) -> LogicalStepResult {
+        let masks = self.clue.cells().iter().map(|&cell| board.cell(cell)).collect::<Vec<_>>();
+        if !masks.iter().all(|mask| mask.is_single()) {
+            return LogicalStepResult::None;
+        }
+
+        let mut tallest = 0;
+        let mut seen = 0;
+        for mask in masks {
+            if mask.value() > tallest {
+                tallest = mask.value();
+                seen += 1;
+            }
+        }
+        if seen != self.count {
+            return LogicalStepResult::Invalid(None);
+        }
+        LogicalStepResult::None
+    }
+
+    fn step_logic(&self, board: &mut Board, is_brute_forcing: bool, _cancellation: &Cancellation) -> LogicalStepResult {
+        let (result, elims) = self.eliminate_impossible(board);
+        if is_brute_forcing {
+            return result;
+        }
+        match result {
+            LogicalStepResult::Invalid(_) => {
+                LogicalStepResult::Invalid(Some("No filling sees the right number of buildings.".into()))
+            }
+            LogicalStepResult::Changed(_) => LogicalStepResult::Changed(Some(LogicalStepDesc::from_elims(
+                "Would not see the right number of buildings",
+                &elims,
+            ))),
+            LogicalStepResult::None => LogicalStepResult::None,
+        }
+    }
+
+    fn relevant_cells(&self) -> Vec<CellIndex> {
+        self.clue.cells().to_vec()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[test]
+    fn test_skyscraper() {
+        let cu = CellUtility::new(9);
+        let skyscraper = SkyscraperConstraint::new(&OutsideClue::new(9, 1, 0).unwrap(), 9);
+        assert_eq!(skyscraper.name(), "Skyscraper 9 at r1c0");
+        let solver = SolverBuilder::default().with_constraint(Arc::new(skyscraper)).build().unwrap();
+        assert_eq!(solver.board().to_string()[..9], *"123456789");
+
+        // Seeing two buildings means the first is tallest unless the 9 is right behind it.
+        let skyscraper = SkyscraperConstraint::new(&OutsideClue::new(9, 0, 1).unwrap(), 2);
+        let mut solver = SolverBuilder::default().with_constraint(Arc::new(skyscraper)).build().unwrap();
+        assert!(!solver.board().cell(cu.cell(0, 0)).has(9));
+        assert!(solver.set_solved(cu.cell(0, 0), 1));
+        let result = solver.run_single_logical_step();
+        assert!(
+            result.to_string().starts_with("Skyscraper 2 at r0c1: Would not see the right number of buildings"),
+            "{result}"
+        );
+        assert_eq!(solver.board().cell(cu.cell(1, 0)), ValueMask::from_value(9));
+
+        // The count is checked once the column is full.
+        let skyscraper = SkyscraperConstraint::new(&OutsideClue::new(4, 5, 1).unwrap(), 3);
+        let mut solver = SolverBuilder::new(4).with_constraint(Arc::new(skyscraper)).build().unwrap();
+        let cu = CellUtility::new(4);
+        assert!(solver.set_solved(cu.cell(3, 0), 2));
+        assert!(solver.set_solved(cu.cell(2, 0), 1));
+        assert!(solver.set_solved_many(&[(cu.cell(1, 0), 4), (cu.cell(0, 0), 3)]).is_err());
+    }
+
+    #[test]
+    fn test_first_cell_limits_count() {
+        let cu = CellUtility::new(9);
+
+        // Seeing four buildings needs three taller ones behind the first.
+        let skyscraper = SkyscraperConstraint::new(&OutsideClue::new(9, 0, 1).unwrap(), 4);
+        let mut solver = SolverBuilder::default().with_constraint(Arc::new(skyscraper)).build().unwrap();
+        assert_eq!(solver.board().cell(cu.cell(0, 0)), ValueMask::from_between_inclusive(1, 6, 9));
+
+        // After a 6, the 7, 8 and 9 must all be seen, so neither 8 nor 9 can come next.
+        assert!(solver.set_solved(cu.cell(0, 0), 6));
+        assert_eq!(solver.board().cell(cu.cell(1, 0)), ValueMask::from_values(&[1, 2, 3, 4, 5, 7]));
+
+        // A 9 first hides everything behind it.
+        let skyscraper = SkyscraperConstraint::new(&OutsideClue::new(9, 0, 1).unwrap(), 2);
+        let mut solver = SolverBuilder::default().with_constraint(Arc::new(skyscraper)).build().unwrap();
+        assert!(!solver.set_solved(cu.cell(0, 0), 9));
+    }
+}
@@ -0,0 +1,190 @@
+//! Contains the [`SolverBuilderExt`] trait for adding standard constraints to a [`SolverBuilder`].
+
+use crate::prelude::*;
+use std::sync::Arc;
+use sudoku_solver_lib::prelude::*;
+
+/// Builder methods for adding the standard constraints without wrapping each of them in an [`Arc`].
+///
+/// Each constraint is added in the same group as [`FPuzzlesParser`] uses, so that it
+/// can be disabled with [`SolverBuilder::with_group_disabled`].
+///
+/// # Example
+/// ```
+/// # use standard_constraints::prelude::*;
+/// # use sudoku_solver_lib::prelude::*;
+/// let cu = CellUtility::new(9);
+/// let solver = SolverBuilder::default()
+///     .with_killer_cage(vec![cu.cell(0, 0), cu.cell(0, 1)], Some(17))
+///     .with_thermo(vec![cu.cell(8, 0), cu.cell(8, 1), cu.cell(8, 2)])
+///     .with_antiknight()
+///     .build()
+///     .unwrap();
+/// assert_eq!(solver.board().cell(cu.cell(0, 0)), ValueMask::from_values(&[8, 9]));
+/// assert_eq!(solver.board().constraints().len(), 3);
+/// ```
+pub trait SolverBuilderExt {
+    /// Adds a killer cage whose cells can't repeat values and must sum to the sum, if there is one.
+    /// See [`KillerCageConstraint`].
+    #[must_use]
+    fn with_killer_cage(self, cells: Vec<CellIndex>, sum: Option<usize>) -> Self;
+
+    /// Adds a thermometer whose values strictly increase from the bulb, which is the first cell.
+    #[must_use]
+    fn with_thermo(self, cells: Vec<CellIndex>) -> Self;
+
+    /// Adds a renban line, whose values are a set of consecutive values in any order.
+    #[must_use]
+    fn with_renban(self, cells: Vec<CellIndex>) -> Self;
+
+    /// Adds a whispers line, where adjacent values differ by at least the difference.
+    #[must_use]
+    fn with_whispers(self, cells: Vec<CellIndex>, difference: usize) -> Self;
+
+    /// Adds an entropic line, where every three cells in a row have one low, one middle and one high value.
+    #[must_use]
+    fn with_entropic_line(self, cells: Vec<CellIndex>) -> Self;
+
+    /// Adds a between line, whose cells have values strictly between the values of the first and last cells.
+    #[must_use]
+    fn with_between_line(self, cells: Vec<CellIndex>) -> Self;
+
+    /// Adds a region of cells which can't repeat values, in addition to the rows, columns and regions.
+    #[must_use]
+    fn with_extra_region(self, cells: Vec<CellIndex>) -> Self;
+
+    /// Stops values from repeating on the diagonal from the bottom left to the top right.
+    #[must_use]
+    fn with_positive_diagonal(self) -> Self;
+
+    /// Stops values from repeating on the diagonal from the top left to the bottom right.
+    #[must_use]
+    fn with_negative_diagonal(self) -> Self;
+
+    /// Stops cells a knight's move apart from containing the same value.
+    #[must_use]
+    fn with_antiknight(self) -> Self;
+
+    /// Stops cells a king's move apart from containing the same value.
+    #[must_use]
+    fn with_antiking(self) -> Self;
+}
+
+impl SolverBuilderExt for SolverBuilder {
+    fn with_killer_cage(self, cells: Vec<CellIndex>, sum: Option<usize>) -> Self {
+        with_cells_constraint(self, cells, 1, "A killer cage", "killercage", |cells| {
+            Arc::new(KillerCageConstraint::new(cells, sum))
+        })
+    }
+
+    fn with_thermo(self, cells: Vec<CellIndex>) -> Self {
+        with_cells_constraint(self, cells, 1, "A thermometer", "thermometer", |cells| {
+            Arc::new(LineConstraint::thermometer(cells))
+        })
+    }
+
+    fn with_renban(self, cells: Vec<CellIndex>) -> Self {
+        with_cells_constraint(self, cells, 1, "A renban line", "renban", |cells| {
+            Arc::new(LineConstraint::renban(cells))
+        })
+    }
+
+    fn with_whispers(self, cells: Vec<CellIndex>, difference: usize) -> Self {
+        with_cells_constraint(self, cells, 1, "A whispers line", "whispers", |cells| {
+            Arc::new(LineConstraint::whispers(cells, difference))
+        })
+    }
+
+    fn with_entropic_line(self, cells: Vec<CellIndex>) -> Self {
+        with_cells_constraint(self, cells, 1, "An entropic line", "entropicline", |cells| {
+            Arc::new(LineConstraint::entropic(cells))
+        })
+    }
+
+    fn with_between_line(self, cells: Vec<CellIndex>) -> Self {
+        with_cells_constraint(self, cells, 2, "A between line", "betweenline", |cells| {
+            Arc::new(BetweenLineConstraint::new(cells))
+        })
+    }
+
+    fn with_extra_region(self, cells: Vec<CellIndex>) -> Self {
+        with_cells_constraint(self, cells, 1, "An extra region", "extraregion", |cells| {
+            let name = format!("Extra Region at {}", cells[0]);
+            Arc::new(NonRepeatConstraint::new(&name, cells))
+        })
+    }
+
+    fn with_positive_diagonal(self) -> Self {
+        let size = self.size();
+        self.with_constraint_in_group(Arc::new(NonRepeatConstraint::from_diagonalp(size)), "diagonal+")
+    }
+
+    fn with_negative_diagonal(self) -> Self {
+        let size = self.size();
+        self.with_constraint_in_group(Arc::new(NonRepeatConstraint::from_diagonaln(size)), "diagonal-")
+    }
+
+    fn with_antiknight(self) -> Self {
+        self.with_constraint_in_group(Arc::new(ChessConstraint::anti_knight()), "antiknight")
+    }
+
+    fn with_antiking(self) -> Self {
+        self.with_constraint_in_group(Arc::new(ChessConstraint::anti_king()), "antiking")
+    }
+}
+
+/// Adds the constraint made from the cells, or records an error for [`SolverBuilder::build`] to return
+/// if there are too few cells for the constraint to be made.
+fn with_cells_constraint(
+    builder: SolverBuilder,
+    cells: Vec<CellIndex>,
+    min_cells: usize,
+    description: &str,
+    group: &str,
+    constraint: impl FnOnce(Vec<CellIndex>) -> Arc<dyn Constraint>,
+) -> SolverBuilder {
+    if cells.len() < min_cells {
+        let plural = if min_cells == 1 { "" } else { "s" };
+        return builder.with_error(&format!("{description} needs at least {min_cells} cell{plural}"));
+    }
+    builder.with_constraint_in_group(constraint(cells), group)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_builder_ext() {
+        let cu = CellUtility::new(9);
+        let solver = SolverBuilder::default()
+            .with_renban(vec![cu.cell(0, 0), cu.cell(0, 1), cu.cell(0, 2)])
+            .with_whispers(vec![cu.cell(4, 4), cu.cell(4, 5)], 5)
+            .with_between_line(vec![cu.cell(8, 0), cu.cell(8, 1), cu.cell(8, 2)])
+            .with_entropic_line(vec![cu.cell(6, 6), cu.cell(6, 7), cu.cell(6, 8)])
+            .with_extra_region(vec![cu.cell(1, 0), cu.cell(5, 5)])
+            .with_positive_diagonal()
+            .with_negative_diagonal()
+            .with_antiking()
+            .with_group_disabled("antiking")
+            .build()
+            .unwrap();
+        assert_eq!(solver.board().constraints().len(), 7);
+        assert!(!solver.board().cell(cu.cell(4, 4)).has(5));
+        assert!(!solver.board().cell(cu.cell(8, 1)).has(9));
+        assert!(solver.board().has_weak_link(cu.cell(6, 6).candidate(1), cu.cell(6, 7).candidate(2)));
+        assert!(solver.board().is_exclusive(cu.cell(1, 0), cu.cell(5, 5)));
+        assert_eq!(solver.board().houses().len(), 29);
+    }
+
+    #[test]
+    fn test_too_few_cells() {
+        let cu = CellUtility::new(9);
+        let error = SolverBuilder::default().with_killer_cage(Vec::new(), Some(10)).build().err().unwrap();
+        assert_eq!(error, "A killer cage needs at least 1 cell");
+        let error = SolverBuilder::default().with_extra_region(Vec::new()).build().err().unwrap();
+        assert_eq!(error, "An extra region needs at least 1 cell");
+        let error = SolverBuilder::default().with_between_line(vec![cu.cell(0, 0)]).build().err().unwrap();
+        assert_eq!(error, "A between line needs at least 2 cells");
+    }
+}
@@ -0,0 +1,58 @@
+//! Contains the [`SolverBuilderPresets`] trait for creating a [`SolverBuilder`] for common variants.
+
+use crate::prelude::*;
+use sudoku_solver_lib::prelude::*;
+
+/// Presets for common variants of sudoku, so that they don't need their constraints set up by hand.
+///
+/// The constraints are added in the same groups as [`FPuzzlesParser`] uses, so that they
+/// can be disabled with [`SolverBuilder::with_group_disabled`].
+///
+/// # Example
+/// ```
+/// # use standard_constraints::prelude::*;
+/// # use sudoku_solver_lib::prelude::*;
+/// let solver = SolverBuilder::sudoku_with_diagonals(9).build().unwrap();
+/// assert_eq!(solver.board().houses().len(), 29);
+/// ```
+pub trait SolverBuilderPresets {
+    /// A sudoku where values also cannot repeat on either of the two main diagonals, also known as Sudoku X.
+    fn sudoku_with_diagonals(size: usize) -> Self;
+
+    /// A sudoku where cells a knight's move apart cannot contain the same value.
+    fn anti_knight_sudoku(size: usize) -> Self;
+
+    /// A sudoku where cells a king's move apart cannot contain the same value.
+    fn anti_king_sudoku(size: usize) -> Self;
+}
+
+impl SolverBuilderPresets for SolverBuilder {
+    fn sudoku_with_diagonals(size: usize) -> Self {
+        SolverBuilder::new(size).with_positive_diagonal().with_negative_diagonal()
+    }
+
+    fn anti_knight_sudoku(size: usize) -> Self {
+        SolverBuilder::new(size).with_antiknight()
+    }
+
+    fn anti_king_sudoku(size: usize) -> Self {
+        SolverBuilder::new(size).with_antiking()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_presets() {
+        let givens = "......78............9.........................1.5.........4.....3....5.1....98...";
+        let solver = SolverBuilder::sudoku_with_diagonals(9).with_givens_string(givens).build().unwrap();
+        assert_eq!(solver.find_solution_count(10000, None, None).count(), Some(2));
+
+        let solver = SolverBuilder::anti_knight_sudoku(9).build().unwrap();
+        assert_eq!(solver.board().constraints()[0].name(), "Anti-Knight");
+        let solver = SolverBuilder::anti_king_sudoku(9).with_group_disabled("antiking").build().unwrap();
+        assert!(solver.board().constraints().is_empty());
+    }
+}
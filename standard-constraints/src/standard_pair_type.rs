@@ -1,5 +1,6 @@
 //! Contains the [`StandardPairType`] enum for representing the different types of standard pair constraints.
 
+use itertools::Itertools;
 use sudoku_solver_lib::prelude::*;
 
 /// Represents the different types of standard pair constraints.
@@ -11,6 +12,16 @@ pub enum StandardPairType {
     Diff(usize),
     /// The ratio of the two cells must equal the given value.
     Ratio(usize),
+    /// The sum of the two cells must be prime.
+    SumPrime,
+    /// The sum of the two cells must be odd.
+    SumOdd,
+    /// The sum of the two cells must be even.
+    SumEven,
+    /// The sum of the two cells must be one of the values of the mask.
+    SumInSet(ValueMask),
+    /// The difference of the two cells must be at least the given value.
+    DifferenceAtLeast(usize),
 }
 
 impl StandardPairType {
@@ -20,54 +31,103 @@ impl StandardPairType {
             Self::Sum(n) => format!("s{n}"),
             Self::Diff(n) => format!("d{n}"),
             Self::Ratio(n) => format!("r{n}"),
+            Self::SumPrime => "sprime".to_owned(),
+            Self::SumOdd => "sodd".to_owned(),
+            Self::SumEven => "seven".to_owned(),
+            Self::SumInSet(mask) => format!("sin{}", mask.into_iter().map(|value| value.to_string()).join(",")),
+            Self::DifferenceAtLeast(n) => format!("dmin{n}"),
+        }
+    }
+
+    /// Whether the two values are allowed next to each other by this constraint type.
+    pub fn allows(&self, value0: usize, value1: usize) -> bool {
+        let sum = value0 + value1;
+        match *self {
+            Self::Sum(n) => sum == n,
+            Self::Diff(n) => value0.abs_diff(value1) == n,
+            Self::Ratio(n) => value0 * n == value1 || value1 * n == value0,
+            Self::SumPrime => is_prime(sum),
+            Self::SumOdd => !sum.is_multiple_of(2),
+            Self::SumEven => sum.is_multiple_of(2),
+            Self::SumInSet(mask) => sum <= ValueMask::MAX_SIZE && mask.has(sum),
+            Self::DifferenceAtLeast(n) => value0.abs_diff(value1) >= n,
         }
     }
 
     /// Returns the candidate pairs for thie constraint type
     pub fn candidate_pairs(&self, size: usize) -> Vec<ValueMask> {
-        match self {
-            Self::Sum(n) => {
-                let n = *n;
-                let mut pairs = Vec::new();
-                for i in 1..=size {
-                    let mut mask = ValueMask::new();
-                    for j in 1..=size {
-                        if i + j == n {
-                            mask = mask.with(j);
-                        }
-                    }
-                    pairs.push(mask);
-                }
-                pairs
-            }
-            Self::Diff(n) => {
-                let n = *n;
-                let mut pairs = Vec::new();
-                for i in 1..=size {
-                    let mut mask = ValueMask::new();
-                    for j in 1..=size {
-                        if i + n == j || j + n == i {
-                            mask = mask.with(j);
-                        }
-                    }
-                    pairs.push(mask);
-                }
-                pairs
-            }
-            Self::Ratio(n) => {
-                let n = *n;
-                let mut pairs = Vec::new();
-                for i in 1..=size {
-                    let mut mask = ValueMask::new();
-                    for j in 1..=size {
-                        if i * n == j || j * n == i {
-                            mask = mask.with(j);
-                        }
-                    }
-                    pairs.push(mask);
-                }
-                pairs
-            }
-        }
+        (1..=size)
+            .map(|i| (1..=size).filter(|&j| self.allows(i, j)).fold(ValueMask::new(), |mask, j| mask.with(j)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_candidate_pairs() {
+        assert_eq!(StandardPairType::Sum(10).candidate_pairs(9)[3], ValueMask::from_value(6));
+        assert_eq!(StandardPairType::Diff(1).candidate_pairs(9)[3], ValueMask::from_values(&[3, 5]));
+        assert_eq!(StandardPairType::Ratio(2).candidate_pairs(9)[3], ValueMask::from_values(&[2, 8]));
+        assert_eq!(StandardPairType::SumPrime.candidate_pairs(9)[3], ValueMask::from_values(&[1, 3, 7, 9]));
+        assert_eq!(StandardPairType::SumOdd.candidate_pairs(9)[3], ValueMask::from_values(&[1, 3, 5, 7, 9]));
+        assert_eq!(StandardPairType::SumEven.candidate_pairs(9)[3], ValueMask::from_values(&[2, 4, 6, 8]));
+        let sums = StandardPairType::SumInSet(ValueMask::from_values(&[6, 12]));
+        assert_eq!(sums.candidate_pairs(9)[3], ValueMask::from_values(&[2, 8]));
+        assert_eq!(StandardPairType::DifferenceAtLeast(5).candidate_pairs(9)[3], ValueMask::from_value(9));
+
+        assert_eq!(sums.name(), "sin6,12");
+        assert_eq!(StandardPairType::DifferenceAtLeast(5).name(), "dmin5");
+    }
+
+    #[test]
+    fn test_sum_types() {
+        assert!(StandardPairType::SumPrime.allows(1, 1));
+        assert!(StandardPairType::SumPrime.allows(8, 9));
+        assert!(!StandardPairType::SumPrime.allows(4, 5));
+        assert_eq!(StandardPairType::SumPrime.candidate_pairs(9)[8], ValueMask::from_values(&[2, 4, 8]));
+
+        assert!(StandardPairType::SumOdd.allows(2, 3));
+        assert!(!StandardPairType::SumOdd.allows(2, 4));
+        assert_eq!(StandardPairType::SumOdd.candidate_pairs(9)[0], ValueMask::from_values(&[2, 4, 6, 8]));
+
+        assert!(StandardPairType::SumEven.allows(9, 9));
+        assert!(!StandardPairType::SumEven.allows(1, 2));
+        assert_eq!(StandardPairType::SumEven.candidate_pairs(9)[0], ValueMask::from_values(&[1, 3, 5, 7, 9]));
+
+        // Sums can be larger than the board, but sums larger than a mask are never in the set
+        let sums = StandardPairType::SumInSet(ValueMask::from_values(&[17, 31]));
+        assert!(sums.allows(8, 9));
+        assert!(sums.allows(15, 16));
+        assert!(!sums.allows(9, 9));
+        assert!(!sums.allows(20, 25));
+        assert_eq!(sums.candidate_pairs(9)[8], ValueMask::from_value(8));
+        assert_eq!(sums.candidate_pairs(25)[19], ValueMask::from_value(11));
+    }
+
+    #[test]
+    fn test_markers_and_negative_constraints() {
+        let size = 9;
+        let cu = CellUtility::new(size);
+        let cell0 = cu.cell(0, 0);
+        let cell1 = cu.cell(0, 1);
+
+        // As a marker, the pair must follow the rule
+        let marker = StandardOrthogonalPairsMarker::new(StandardPairType::DifferenceAtLeast(5), cell0, cell1);
+        let constraint = OrthogonalPairsConstraint::from_standard_markers(size, "German Whispers", &[marker], &[]);
+        let solver = SolverBuilder::default().with_constraint(Arc::new(constraint)).build().unwrap();
+        assert_eq!(solver.board().cell(cell0), ValueMask::from_values(&[1, 2, 3, 4, 6, 7, 8, 9]));
+
+        // As a negative constraint, no other adjacent pair can
+        let constraint =
+            OrthogonalPairsConstraint::from_standard_markers(size, "No Prime Sums", &[], &[StandardPairType::SumPrime]);
+        let solver =
+            SolverBuilder::default().with_constraint(Arc::new(constraint)).with_given(cell0, 1).build().unwrap();
+        assert_eq!(solver.board().cell(cell1), ValueMask::from_values(&[3, 5, 7, 8, 9]));
     }
 }
@@ -0,0 +1,202 @@
+//! Contains the [`XSumConstraint`] struct for representing an X-Sum clue.
+
+use crate::prelude::*;
+use std::sync::Arc;
+use sudoku_solver_lib::prelude::*;
+
+/// A [`Constraint`] implementation for representing an X-Sum clue, where the value of the first cell
+/// from the clue is how many cells from the clue sum to the clue's value, including the first cell.
+///
+/// The cells are a whole row or column, so they can't repeat values.
+///
+/// # Example
+/// ```
+/// # use standard_constraints::prelude::*;
+/// # use sudoku_solver_lib::prelude::*;
+/// # use std::sync::Arc;
+/// let cu = CellUtility::new(9);
+/// let x_sum = XSumConstraint::new(&OutsideClue::new(9, 1, 0).unwrap(), 45);
+/// let solver = SolverBuilder::default().with_constraint(Arc::new(x_sum)).build().unwrap();
+/// assert_eq!(solver.board().cell(cu.cell(0, 0)), ValueMask::from_value(9));
+/// ```
+#[derive(Debug, Clone)]
+pub struct XSumConstraint {
+    specific_name: String,
+    clue: OutsideClue,
+    sum: usize,
+}
+
+impl XSumConstraint {
+    /// Creates a new [`XSumConstraint`] for the row or column of the clue.
+    pub fn new(clue: &OutsideClue, sum: usize) -> Self {
+        let specific_name = format!("X-Sum {sum} at {}", clue.name());
+        Self { specific_name, clue: clue.clone(), sum }
+    }
+
+    /// The clue, whose cells are the row or column it applies to.
+    pub fn clue(&self) -> &OutsideClue {
+        &self.clue
+    }
+
+    /// The sum of the clue, which the first N cells from the clue add up to, where N is the value of the first cell.
+    pub fn sum(&self) -> usize {
+        self.sum
+    }
+
+    /// Finds the values each cell can have in at least one valid filling of the summed cells
+    /// for some value of the first cell.
+    fn possible_values(&self, board: &Board) -> Vec<ValueMask> {
+        let size = board.size();
+        let masks = self.clue.cells().iter().map(|&cell| board.cell(cell).unsolved()).collect::<Vec<_>>();
+        let mut possible = vec![ValueMask::new(); self.clue.cells().len()];
+        for count in masks[0].into_iter().filter(|&count| count <= masks.len()) {
+            let mut prefix_masks = masks[..count].to_vec();
+            prefix_masks[0] = ValueMask::from_value(count);
+
+            let mut prefix_possible = vec![ValueMask::new(); count];
+            for combination in sum_combinations(size, count, self.sum) {
+                if !combination.has(count) {
+                    continue;
+                }
+                let combination_masks = prefix_masks.iter().map(|&mask| mask & combination).collect::<Vec<_>>();
+                let combination_possible =
+                    possible_filling_values(&combination_masks, |values, value| !values.contains(&value), |_| true);
+                for (possible, combination_possible) in prefix_possible.iter_mut().zip(combination_possible) {
+                    *possible = *possible | combination_possible;
+                }
+            }
+
+            if prefix_possible[0].is_empty() {
+                continue;
+            }
+            // The cells after the summed cells can be anything.
+            for (index, possible) in possible.iter_mut().enumerate() {
+                *possible = *possible | prefix_possible.get(index).copied().unwrap_or(masks[index]);
+            }
+        }
+        possible
+    }
+
+    /// Removes the candidates which aren't part of any valid filling of the summed cells.
+    fn eliminate_impossible(&self, board: &mut Board) -> (LogicalStepResult, EliminationList) {
+        let possible = self.possible_values(board);
+        let elims = EliminationList::from_possible(self.clue.cells(), &possible, board);
+        (elims.execute(board), elims)
+    }
+}
+
+impl Constraint for XSumConstraint {
+    fn name(&self) -> &str {
+        &self.specific_name
+    }
+
+    fn clone_constraint(&self) -> Option<Arc<dyn Constraint>> {
+        Some(Arc::new(self.clone()))
+    }
+
+    fn init_board(&mut self, board: &mut Board) -> LogicalStepResult {
+        self.eliminate_impossible(board).0
+    }
+
+    fn enforce(&self, board: &Board, _cell: CellIndex, _val: usize) -> LogicalStepResult {
+        let first = board.cell(self.clue.cells()[0]);
+        if !first.is_single() {
+            return LogicalStepResult::None;
+        }
+
+        let masks = self.clue.cells().iter().take(first.value()).map(|&cell| board.cell(cell)).collect::<Vec<_>>();
+        if masks.iter().all(|mask| mask.is_single()) && masks.iter().map(|mask| mask.value()).sum::<usize>() != self.sum
+        {
+            return LogicalStepResult::Invalid(None);
+        }
+        LogicalStepResult::None
+    }
+
+    fn step_logic(&self, board: &mut Board, is_brute_forcing: bool, _cancellation: &Cancellation) -> LogicalStepResult {
+        let (result, elims) = self.eliminate_impossible(board);
+        if is_brute_forcing {
+            return result;
+        }
+        match result {
+            LogicalStepResult::Invalid(_) => {
+                LogicalStepResult::Invalid(Some("No value of the first cell can reach the sum.".into()))
+            }
+            LogicalStepResult::Changed(_) => {
+                LogicalStepResult::Changed(Some(LogicalStepDesc::from_elims("Not part of any valid sum", &elims)))
+            }
+            LogicalStepResult::None => LogicalStepResult::None,
+        }
+    }
+
+    fn relevant_cells(&self) -> Vec<CellIndex> {
+        self.clue.cells().to_vec()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[test]
+    fn test_x_sum() {
+        let cu = CellUtility::new(9);
+        let clue = OutsideClue::new(9, 1, 0).unwrap();
+        let x_sum = XSumConstraint::new(&clue, 6);
+        assert_eq!(x_sum.name(), "X-Sum 6 at r1c0");
+        let solver = SolverBuilder::default().with_constraint(Arc::new(x_sum)).build().unwrap();
+
+        // Only 2 + 4, 3 + 1 + 2 and 3 + 2 + 1 sum to 6.
+        assert_eq!(solver.board().cell(cu.cell(0, 0)), ValueMask::from_values(&[2, 3]));
+        assert_eq!(solver.board().cell(cu.cell(0, 1)), ValueMask::from_values(&[1, 2, 4]));
+        assert_eq!(solver.board().cell(cu.cell(0, 2)), ValueMask::from_all_values(9));
+
+        // The first cell decides how many cells are summed.
+        let mut solver = solver;
+        assert!(solver.set_solved(cu.cell(0, 0), 3));
+        let result = solver.run_single_logical_step();
+        assert!(result.to_string().starts_with("X-Sum 6 at r1c0: Not part of any valid sum"), "{result}");
+        assert_eq!(solver.board().cell(cu.cell(0, 2)), ValueMask::from_values(&[1, 2]));
+
+        let x_sum = XSumConstraint::new(&OutsideClue::new(9, 10, 1).unwrap(), 6);
+        let mut solver = SolverBuilder::default().with_constraint(Arc::new(x_sum)).build().unwrap();
+        assert!(solver.set_solved(cu.cell(8, 0), 2));
+        assert!(!solver.clone().set_solved(cu.cell(7, 0), 3));
+        assert!(solver.set_solved(cu.cell(7, 0), 4));
+    }
+
+    #[test]
+    fn test_first_cell_limits_sum() {
+        let cu = CellUtility::new(9);
+        let make_solver = || {
+            let x_sum = XSumConstraint::new(&OutsideClue::new(9, 1, 0).unwrap(), 10);
+            SolverBuilder::default().with_constraint(Arc::new(x_sum)).build().unwrap()
+        };
+
+        // Only 2 + 8, 3 + 1 + 6, 3 + 2 + 5 and 4 + 1 + 2 + 3 sum to 10.
+        let mut solver = make_solver();
+        assert_eq!(solver.board().cell(cu.cell(0, 0)), ValueMask::from_values(&[2, 3, 4]));
+        assert_eq!(solver.board().cell(cu.cell(0, 1)), ValueMask::from_values(&[1, 2, 3, 5, 6, 8]));
+        assert_eq!(solver.board().cell(cu.cell(0, 4)), ValueMask::from_all_values(9));
+
+        // Two summed cells must be 2 and 8.
+        assert!(solver.set_solved(cu.cell(0, 0), 2));
+        assert!(solver.run_single_logical_step().is_changed());
+        assert_eq!(solver.board().cell(cu.cell(0, 1)), ValueMask::from_value(8));
+
+        // Four summed cells must be 1, 2 and 3 after the 4.
+        let mut solver = make_solver();
+        assert!(solver.set_solved(cu.cell(0, 0), 4));
+        assert!(solver.run_single_logical_step().is_changed());
+        for col in 1..4 {
+            assert_eq!(solver.board().cell(cu.cell(0, col)), ValueMask::from_values(&[1, 2, 3]));
+        }
+
+        // Only two summed cells can include an 8.
+        let mut solver = make_solver();
+        assert!(solver.set_solved(cu.cell(0, 1), 8));
+        assert!(solver.run_single_logical_step().is_changed());
+        assert_eq!(solver.board().cell(cu.cell(0, 0)), ValueMask::from_value(2));
+    }
+}
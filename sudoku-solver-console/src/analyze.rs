@@ -0,0 +1,51 @@
+//! Implements the `analyze` command for reporting statistics about a file of puzzles.
+
+use crate::config::Config;
+use standard_constraints::prelude::*;
+use std::path::PathBuf;
+use sudoku_solver_lib::prelude::*;
+
+#[derive(Debug, clap::Args)]
+pub struct AnalyzeArgs {
+    /// File containing one puzzle per line, as a givens string, f-puzzles string, f-puzzles link or f-puzzles JSON,
+    /// optionally followed by tab separated metadata. Empty lines and lines starting with # are ignored.
+    /// Files ending in .sdm or .sdk are read as SadMan Sudoku files
+    file: PathBuf,
+}
+
+pub fn analyze(args: &AnalyzeArgs, config: &Config) -> Result<(), String> {
+    let records = read_puzzle_file(&args.file)?;
+    if records.is_empty() {
+        return Err(format!("No puzzles found in {}.", args.file.display()));
+    }
+
+    let parser = PuzzleInputParser::new().with_fpuzzles_parser(config.parser()?);
+    let mut analysis = PuzzleSetAnalysis::default();
+    let mut symmetry_counts = [0; Symmetry::ALL.len()];
+    let mut num_asymmetric = 0;
+    for (index, record) in records.iter().enumerate() {
+        let solver = parser
+            .parse(&record.puzzle)
+            .and_then(|builder| builder.build())
+            .map_err(|error| format!("Puzzle {}: {error}", index + 1))?;
+        let puzzle_analysis =
+            PuzzleAnalysis::new(&solver, None).map_err(|error| format!("Puzzle {}: {error}", index + 1))?;
+        analysis.add(&puzzle_analysis);
+
+        let symmetries = Symmetry::detect(solver.board());
+        for (count, symmetry) in symmetry_counts.iter_mut().zip(Symmetry::ALL) {
+            *count += usize::from(symmetries.contains(&symmetry));
+        }
+        num_asymmetric += usize::from(symmetries.is_empty());
+    }
+
+    println!("{analysis}");
+    println!();
+    println!("Symmetry of the givens:");
+    for (count, symmetry) in symmetry_counts.iter().zip(Symmetry::ALL) {
+        println!("{symmetry}: {count}");
+    }
+    println!("None: {num_asymmetric}");
+
+    Ok(())
+}
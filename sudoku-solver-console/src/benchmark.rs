@@ -0,0 +1,287 @@
+//! Implements the `benchmark` command for timing the solver against a list of puzzles.
+
+use crate::config::Config;
+use clap::ValueEnum;
+use itertools::Itertools;
+use standard_constraints::prelude::*;
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+use sudoku_solver_lib::prelude::*;
+
+#[derive(Debug, clap::Args)]
+pub struct BenchmarkArgs {
+    /// File containing one puzzle per line, as a givens string, f-puzzles string, f-puzzles link or f-puzzles JSON,
+    /// optionally followed by tab separated metadata. Empty lines and lines starting with # are ignored.
+    /// Files ending in .sdm or .sdk are read as SadMan Sudoku files
+    file: PathBuf,
+
+    /// What to measure for each puzzle
+    #[clap(long, value_enum, default_value_t = Workload::Solve)]
+    workload: Workload,
+
+    /// Stop counting after this many solutions (0 for no limit). Only used by the count workload
+    #[clap(long, default_value_t = 0)]
+    max_solutions: usize,
+
+    /// How the count workload stores the boards it has yet to explore
+    #[clap(long, value_enum, default_value_t = BoardStack::Arena)]
+    board_stack: BoardStack,
+
+    /// How brute force chooses between cells with the fewest candidates
+    #[clap(long, value_enum, default_value_t = TieBreak::WeakLinks)]
+    tie_break: TieBreak,
+
+    /// Number of untimed passes over the puzzles before measuring
+    #[clap(long, default_value_t = 1)]
+    warmup: usize,
+
+    /// Number of timed passes over the puzzles
+    #[clap(long, default_value_t = 1)]
+    iterations: usize,
+
+    /// Number of puzzles to run in parallel [default: 1]
+    #[clap(long)]
+    threads: Option<usize>,
+
+    /// Only print the summary, not the timing of each puzzle
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    summary_only: bool,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Workload {
+    /// Count the solutions using brute force
+    Count,
+    /// Find a single solution using brute force, always the same one so that runs can be compared
+    Solve,
+    /// Run a full logical solve
+    Logical,
+    /// Place only naked and hidden singles
+    Singles,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum BoardStack {
+    /// Reuse one board per search depth
+    Arena,
+    /// Copy every branch into its own board
+    Boxed,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum TieBreak {
+    /// Prefer the cell whose candidates have the most weak links
+    WeakLinks,
+    /// Take the first cell found
+    First,
+}
+
+struct PuzzleTiming {
+    index: usize,
+    outcome: String,
+    /// The boards the search explored, for the count workload.
+    boards_explored: Option<usize>,
+    duration: Duration,
+}
+
+struct Statistics {
+    mean: Duration,
+    median: Duration,
+    p95: Duration,
+    min: Duration,
+    max: Duration,
+}
+
+impl Statistics {
+    fn new(durations: &[Duration]) -> Option<Self> {
+        if durations.is_empty() {
+            return None;
+        }
+
+        let sorted = durations.iter().copied().sorted().collect_vec();
+        let total: Duration = sorted.iter().sum();
+        Some(Self {
+            mean: total / sorted.len() as u32,
+            median: Self::percentile(&sorted, 50.0),
+            p95: Self::percentile(&sorted, 95.0),
+            min: sorted[0],
+            max: sorted[sorted.len() - 1],
+        })
+    }
+
+    /// Nearest-rank percentile of an already sorted list.
+    fn percentile(sorted: &[Duration], percentile: f64) -> Duration {
+        let rank = (percentile / 100.0 * sorted.len() as f64).ceil() as usize;
+        sorted[rank.clamp(1, sorted.len()) - 1]
+    }
+}
+
+pub fn benchmark(args: &BenchmarkArgs, config: &Config) -> Result<(), String> {
+    let records = read_puzzle_file(&args.file)?;
+    let puzzles = records.iter().map(|record| record.puzzle.as_str()).collect_vec();
+    for (index, puzzle) in puzzles.iter().enumerate() {
+        parse_puzzle(puzzle, config).map_err(|error| format!("Puzzle {}: {error}", index + 1))?;
+    }
+    if puzzles.is_empty() {
+        return Err(format!("No puzzles found in {}.", args.file.display()));
+    }
+
+    let threads = args.threads.or(config.threads).unwrap_or(1).max(1);
+    for _ in 0..args.warmup {
+        run_pass(&puzzles, args, config, threads);
+    }
+
+    let start_time = Instant::now();
+    let mut timings = Vec::with_capacity(puzzles.len() * args.iterations);
+    for _ in 0..args.iterations {
+        timings.extend(run_pass(&puzzles, args, config, threads));
+    }
+    let elapsed = start_time.elapsed();
+
+    if !args.summary_only {
+        for timing in timings.iter() {
+            match timing.boards_explored {
+                Some(boards_explored) => println!(
+                    "Puzzle {}: {} in {} ({boards_explored} boards)",
+                    timing.index + 1,
+                    timing.outcome,
+                    format_duration(timing.duration)
+                ),
+                None => {
+                    println!("Puzzle {}: {} in {}", timing.index + 1, timing.outcome, format_duration(timing.duration))
+                }
+            }
+        }
+        println!();
+    }
+
+    let mut outcomes: BTreeMap<&str, usize> = BTreeMap::new();
+    for timing in timings.iter() {
+        *outcomes.entry(timing.outcome.as_str()).or_default() += 1;
+    }
+
+    println!("Puzzles: {} x {} iterations on {} threads", puzzles.len(), args.iterations, threads);
+    for (outcome, count) in outcomes {
+        println!("  {outcome}: {count}");
+    }
+    if matches!(args.workload, Workload::Count) {
+        let boards_explored: usize = timings.iter().filter_map(|timing| timing.boards_explored).sum();
+        println!("Boards explored: {boards_explored}");
+    }
+
+    let durations = timings.iter().map(|timing| timing.duration).collect_vec();
+    if let Some(statistics) = Statistics::new(&durations) {
+        println!("Mean:   {}", format_duration(statistics.mean));
+        println!("Median: {}", format_duration(statistics.median));
+        println!("P95:    {}", format_duration(statistics.p95));
+        println!("Min:    {}", format_duration(statistics.min));
+        println!("Max:    {}", format_duration(statistics.max));
+    }
+    println!("Total:  {}", format_duration(elapsed));
+
+    Ok(())
+}
+
+fn parse_puzzle(line: &str, config: &Config) -> Result<Solver, String> {
+    PuzzleInputParser::new().with_fpuzzles_parser(config.parser()?).parse(line)?.build()
+}
+
+/// Runs every puzzle once, spreading them over the given number of threads.
+///
+/// Each puzzle is parsed by the thread which runs it, outside of the timed section.
+fn run_pass(puzzles: &[&str], args: &BenchmarkArgs, config: &Config, threads: usize) -> Vec<PuzzleTiming> {
+    let next_index = AtomicUsize::new(0);
+    let mut timings: Vec<PuzzleTiming> = std::thread::scope(|scope| {
+        let workers = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut timings = Vec::new();
+                    loop {
+                        let index = next_index.fetch_add(1, Ordering::Relaxed);
+                        if index >= puzzles.len() {
+                            break;
+                        }
+
+                        let mut solver = match parse_puzzle(puzzles[index], config) {
+                            Ok(solver) => solver,
+                            Err(error) => {
+                                let outcome = format!("error: {error}");
+                                timings.push(PuzzleTiming {
+                                    index,
+                                    outcome,
+                                    boards_explored: None,
+                                    duration: Duration::ZERO,
+                                });
+                                continue;
+                            }
+                        };
+                        solver.set_board_arena(matches!(args.board_stack, BoardStack::Arena));
+                        solver.set_weak_link_tie_break(matches!(args.tie_break, TieBreak::WeakLinks));
+
+                        let start_time = Instant::now();
+                        let (outcome, boards_explored) = run_workload(&solver, args);
+                        timings.push(PuzzleTiming { index, outcome, boards_explored, duration: start_time.elapsed() });
+                    }
+                    timings
+                })
+            })
+            .collect_vec();
+        workers.into_iter().flat_map(|worker| worker.join().unwrap()).collect()
+    });
+
+    timings.sort_by_key(|timing| timing.index);
+    timings
+}
+
+/// Runs the workload on the puzzle, returning its outcome and, for the count workload, the boards explored.
+fn run_workload(solver: &Solver, args: &BenchmarkArgs) -> (String, Option<usize>) {
+    let outcome = match args.workload {
+        Workload::Count => {
+            let mut counter = solver.solution_counter(args.max_solutions);
+            let result = counter.run(usize::MAX, None, &Cancellation::default());
+            let outcome = match result {
+                Some(SolutionCountResult::None) => "no solutions".to_owned(),
+                Some(SolutionCountResult::ExactCount(1)) => "1 solution".to_owned(),
+                Some(SolutionCountResult::ExactCount(count)) => format!("{count} solutions"),
+                Some(SolutionCountResult::AtLeastCount(count)) => format!("at least {count} solutions"),
+                Some(SolutionCountResult::Error(error)) => format!("error: {error}"),
+                None => "unfinished".to_owned(),
+            };
+            return (outcome, Some(counter.boards_explored()));
+        }
+        Workload::Solve => match solver.find_fastest_solution() {
+            SingleSolutionResult::None => "no solution".to_owned(),
+            SingleSolutionResult::Solved(_) => "solved".to_owned(),
+            SingleSolutionResult::Error(error) => format!("error: {error}"),
+        },
+        Workload::Logical => {
+            let mut solver = solver.clone();
+            match solver.run_logical_solve() {
+                LogicalSolveResult::None => "no logical steps".to_owned(),
+                LogicalSolveResult::Changed(_) => "stuck".to_owned(),
+                LogicalSolveResult::Solved(_) => "solved".to_owned(),
+                LogicalSolveResult::Invalid(_) => "invalid".to_owned(),
+            }
+        }
+        Workload::Singles => {
+            let mut solver = solver.clone();
+            let result = solver.run_singles_only();
+            if result.is_complete() {
+                "solved by singles".to_owned()
+            } else if result.is_invalid() {
+                "invalid".to_owned()
+            } else {
+                "needs more than singles".to_owned()
+            }
+        }
+    };
+    (outcome, None)
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.3}ms", duration.as_secs_f64() * 1000.0)
+}
@@ -0,0 +1,70 @@
+//! Loads the optional TOML config file.
+//!
+//! Values in the config file replace the built-in defaults, and command line flags override both.
+
+use crate::generate::Difficulty;
+use serde::Deserialize;
+use standard_constraints::prelude::*;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use sudoku_solver_lib::prelude::*;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Names of the logical steps to use instead of the standard logic, such as "Hidden Single".
+    pub logic: Vec<String>,
+    /// The hardest puzzles the generate command should produce.
+    pub max_difficulty: Option<Difficulty>,
+    /// The number of threads used by the benchmark command.
+    pub threads: Option<usize>,
+    pub listener: ListenerSection,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ListenerSection {
+    pub hosts: Vec<String>,
+    pub port: Option<u16>,
+    pub tls_cert: Option<PathBuf>,
+    pub tls_key: Option<PathBuf>,
+}
+
+impl Config {
+    /// The config file used when no path is given: `sudoku-solver/config.toml` in the user's config directory.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("sudoku-solver").join("config.toml"))
+    }
+
+    /// Loads the config from the given path, or from [`Self::default_path`] if there is one.
+    ///
+    /// A missing file at the default path is not an error and results in the default config.
+    pub fn load(path: Option<&Path>) -> Result<Self, String> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => match Self::default_path() {
+                Some(path) if path.exists() => path,
+                _ => return Ok(Self::default()),
+            },
+        };
+
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|error| format!("Failed to read config file {}: {error}", path.display()))?;
+        let config: Self = toml::from_str(&contents)
+            .map_err(|error| format!("Failed to parse config file {}: {error}", path.display()))?;
+        config.logical_steps()?;
+        Ok(config)
+    }
+
+    /// The logical steps named by the config. Empty if the standard logic should be used.
+    pub fn logical_steps(&self) -> Result<Vec<Arc<dyn LogicalStep>>, String> {
+        logical_step_registry().create_all(&self.logic)
+    }
+
+    /// An f-puzzles parser which uses the logical steps named by the config.
+    pub fn parser(&self) -> Result<FPuzzlesParser, String> {
+        Ok(FPuzzlesParser::new().with_logical_steps(self.logical_steps()?))
+    }
+}
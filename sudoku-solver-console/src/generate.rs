@@ -0,0 +1,162 @@
+//! Implements the `generate` command for creating new puzzles with a unique solution.
+
+use crate::config::Config;
+use crate::output::*;
+use clap::ValueEnum;
+use serde::Deserialize;
+use standard_constraints::prelude::*;
+use std::{path::PathBuf, time::Instant};
+use sudoku_solver_lib::prelude::*;
+
+#[derive(Debug, clap::Args)]
+pub struct GenerateArgs {
+    /// The size of the grid
+    #[clap(long, default_value_t = 9)]
+    size: usize,
+
+    /// Comma separated list of constraints: antiknight, antiking, diagonal+, diagonal-,
+    /// disjointgroups, nonconsecutive, kropki-negative, xv-negative
+    #[clap(long, value_delimiter = ',')]
+    constraints: Vec<String>,
+
+    /// Only generate puzzles of this difficulty
+    #[clap(long, value_enum)]
+    difficulty: Option<Difficulty>,
+
+    /// Only generate puzzles of at most this difficulty
+    #[clap(long, value_enum)]
+    max_difficulty: Option<Difficulty>,
+
+    /// Only generate puzzles whose givens have this symmetry
+    #[clap(long, value_enum)]
+    symmetry: Option<SymmetryArg>,
+
+    /// The number of puzzles to generate
+    #[clap(long, default_value_t = 1)]
+    count: usize,
+
+    /// How each generated puzzle is printed
+    #[clap(long, value_enum, default_value_t = OutputFormat::Givens)]
+    output: OutputFormat,
+
+    /// Also save the puzzles to this file as givens strings, with their difficulty as metadata.
+    /// Files ending in .sdm or .sdk are written as SadMan Sudoku files
+    #[clap(long)]
+    save: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum SymmetryArg {
+    /// Unchanged by a half turn
+    Rotational,
+    /// Unchanged by a quarter turn
+    QuarterTurn,
+    /// Mirrored top to bottom
+    Horizontal,
+    /// Mirrored left to right
+    Vertical,
+    /// Mirrored across the main diagonal
+    Diagonal,
+    /// Mirrored across the anti diagonal
+    AntiDiagonal,
+}
+
+impl From<SymmetryArg> for Symmetry {
+    fn from(symmetry: SymmetryArg) -> Self {
+        match symmetry {
+            SymmetryArg::Rotational => Symmetry::Rotational180,
+            SymmetryArg::QuarterTurn => Symmetry::Rotational90,
+            SymmetryArg::Horizontal => Symmetry::MirrorHorizontal,
+            SymmetryArg::Vertical => Symmetry::MirrorVertical,
+            SymmetryArg::Diagonal => Symmetry::DiagonalMain,
+            SymmetryArg::AntiDiagonal => Symmetry::DiagonalAnti,
+        }
+    }
+}
+
+impl From<Difficulty> for PuzzleDifficulty {
+    fn from(difficulty: Difficulty) -> Self {
+        match difficulty {
+            Difficulty::Easy => PuzzleDifficulty::Easy,
+            Difficulty::Medium => PuzzleDifficulty::Medium,
+            Difficulty::Hard => PuzzleDifficulty::Hard,
+        }
+    }
+}
+
+pub fn generate(args: &GenerateArgs, config: &Config) -> Result<(), String> {
+    let mut board = FPuzzlesBoard::new(args.size as i32);
+    for constraint in args.constraints.iter() {
+        apply_constraint(&mut board, constraint.trim())?;
+    }
+
+    let parser = config.parser()?;
+    let solver = parser.parse_board(&board, false)?;
+    let mut generator = PuzzleGenerator::new(solver);
+    if let Some(difficulty) = args.difficulty {
+        generator = generator.with_difficulty(difficulty.into());
+    }
+    if let Some(max_difficulty) = args.max_difficulty.or(config.max_difficulty) {
+        generator = generator.with_max_difficulty(max_difficulty.into());
+    }
+    if let Some(symmetry) = args.symmetry {
+        generator = generator.with_symmetry(symmetry.into());
+    }
+
+    let mut records = Vec::new();
+    for index in 0..args.count {
+        let start_time = Instant::now();
+        let puzzle = generator.generate(None)?;
+        tracing::info!(
+            index,
+            difficulty = %puzzle.difficulty(),
+            givens = puzzle.givens().len(),
+            elapsed_ms = start_time.elapsed().as_secs_f64() * 1000.0,
+            "Generated puzzle"
+        );
+
+        let mut puzzle_board = board.clone();
+        for &(cell, value) in puzzle.givens() {
+            let entry = &mut puzzle_board.grid[cell.row()][cell.column()];
+            entry.value = value as i32;
+            entry.given = true;
+        }
+        let puzzle_solver = parser.parse_board(&puzzle_board, false)?;
+        println!("{}", format_board(puzzle_solver.board(), args.output, &puzzle_board));
+
+        let givens = format_board(puzzle_solver.board(), OutputFormat::Givens, &puzzle_board);
+        records.push(PuzzleRecord::new(&givens).with_metadata(&puzzle.difficulty().to_string()));
+    }
+
+    if let Some(path) = args.save.as_ref() {
+        write_puzzle_file(path, &records)?;
+    }
+
+    Ok(())
+}
+
+fn apply_constraint(board: &mut FPuzzlesBoard, name: &str) -> Result<(), String> {
+    match name.to_lowercase().as_str() {
+        "antiknight" => board.antiknight = true,
+        "antiking" => board.antiking = true,
+        "diagonal+" => board.diagonal_p = true,
+        "diagonal-" => board.diagonal_n = true,
+        "disjointgroups" => board.disjointgroups = true,
+        "nonconsecutive" => board.nonconsecutive = true,
+        "kropki-negative" => {
+            board.nonconsecutive = true;
+            board.negative.push("ratio".to_owned());
+        }
+        "xv-negative" => board.negative.push("xv".to_owned()),
+        _ => return Err(format!("Unknown constraint: {name}")),
+    }
+    Ok(())
+}
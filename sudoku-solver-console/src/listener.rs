@@ -2,22 +2,119 @@ mod client;
 mod handlers;
 mod ws;
 
-use std::{collections::HashMap, convert::Infallible, sync::Arc};
+use futures::future::{self, join_all, BoxFuture, FutureExt};
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    net::{SocketAddr, ToSocketAddrs},
+    path::PathBuf,
+    sync::Arc,
+};
 use tokio::sync::Mutex;
 use warp::{Filter, Rejection};
 
 type Clients = Arc<Mutex<HashMap<String, client::Client>>>;
 type Result<T> = std::result::Result<T, Rejection>;
 
-pub async fn listen() {
-    println!("Listening...");
+/// Where and how the websocket listener accepts connections.
+#[derive(Debug, Clone)]
+pub struct ListenerConfig {
+    pub hosts: Vec<String>,
+    pub port: u16,
+    pub tls_cert: Option<PathBuf>,
+    pub tls_key: Option<PathBuf>,
+    /// Names of the logical steps to use instead of the standard logic.
+    pub logic: Vec<String>,
+}
+
+/// The PEM contents of the TLS certificate and private key.
+struct TlsIdentity {
+    cert: Vec<u8>,
+    key: Vec<u8>,
+}
+
+impl ListenerConfig {
+    /// Resolves every configured host into the socket addresses to bind.
+    fn addresses(&self) -> std::result::Result<Vec<SocketAddr>, String> {
+        let mut addresses = Vec::new();
+        for host in self.hosts.iter() {
+            let host = host.trim().trim_start_matches('[').trim_end_matches(']');
+            let resolved =
+                (host, self.port).to_socket_addrs().map_err(|error| format!("Invalid host {host}: {error}"))?;
+            for address in resolved {
+                if !addresses.contains(&address) {
+                    addresses.push(address);
+                }
+            }
+        }
+
+        if addresses.is_empty() {
+            return Err("No addresses to listen on.".to_owned());
+        }
+        Ok(addresses)
+    }
+
+    /// Loads the certificate and key, if TLS is configured.
+    fn tls(&self) -> std::result::Result<Option<TlsIdentity>, String> {
+        match (&self.tls_cert, &self.tls_key) {
+            (None, None) => Ok(None),
+            (Some(cert), Some(key)) => {
+                let cert = std::fs::read(cert)
+                    .map_err(|error| format!("Failed to read TLS certificate {}: {error}", cert.display()))?;
+                let key =
+                    std::fs::read(key).map_err(|error| format!("Failed to read TLS key {}: {error}", key.display()))?;
+                Ok(Some(TlsIdentity { cert, key }))
+            }
+            _ => Err("Both --tls-cert and --tls-key must be provided to enable TLS.".to_owned()),
+        }
+    }
+}
+
+pub async fn listen(config: &ListenerConfig) -> std::result::Result<(), String> {
+    let addresses = config.addresses()?;
+    let tls = config.tls()?;
 
     let clients: Clients = Arc::new(Mutex::new(HashMap::new()));
 
-    let ws_route = warp::path::end().and(warp::ws()).and(with_clients(clients.clone())).and_then(handlers::ws_handler);
+    let logic = Arc::new(config.logic.clone());
+
+    let ws_route = warp::path::end()
+        .and(warp::ws())
+        .and(with_clients(clients.clone()))
+        .and(warp::any().map(move || logic.clone()))
+        .and_then(handlers::ws_handler);
 
     let routes = ws_route.with(warp::cors().allow_any_origin());
-    warp::serve(routes).run(([127, 0, 0, 1], 4545)).await;
+
+    let scheme = if tls.is_some() { "wss" } else { "ws" };
+    let mut servers = Vec::new();
+    for address in addresses {
+        let server = warp::serve(routes.clone());
+        let server: BoxFuture<()> = match &tls {
+            Some(TlsIdentity { cert, key }) => {
+                // TLS servers can only fail to bind without panicking through the graceful shutdown variant
+                let (address, server) = server
+                    .tls()
+                    .cert(cert)
+                    .key(key)
+                    .try_bind_with_graceful_shutdown(address, future::pending())
+                    .map_err(|error| format!("Failed to listen on {address}: {error}"))?;
+                println!("Listening on {scheme}://{address}");
+                server.boxed()
+            }
+            None => {
+                let (address, server) = server
+                    .try_bind_ephemeral(address)
+                    .map_err(|error| format!("Failed to listen on {address}: {error}"))?;
+                println!("Listening on {scheme}://{address}");
+                server.boxed()
+            }
+        };
+        servers.push(server);
+    }
+
+    join_all(servers).await;
+    Ok(())
 }
 
 fn with_clients(clients: Clients) -> impl Filter<Extract = (Clients,), Error = Infallible> + Clone {
@@ -1,6 +1,7 @@
 use super::{ws, Clients, Result};
+use std::sync::Arc;
 use warp::Reply;
 
-pub async fn ws_handler(ws: warp::ws::Ws, clients: Clients) -> Result<impl Reply> {
-    Ok(ws.on_upgrade(move |socket| ws::client_connection(socket, clients)))
+pub async fn ws_handler(ws: warp::ws::Ws, clients: Clients, logic: Arc<Vec<String>>) -> Result<impl Reply> {
+    Ok(ws.on_upgrade(move |socket| ws::client_connection(socket, clients, logic)))
 }
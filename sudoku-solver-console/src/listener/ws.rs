@@ -5,6 +5,7 @@ use std::sync::{
 
 use super::client::Client;
 use super::Clients;
+use crate::config::Config;
 use futures::{FutureExt, StreamExt};
 use standard_constraints::message_handler::*;
 use sudoku_solver_lib::prelude::*;
@@ -13,7 +14,7 @@ use tokio_stream::wrappers::ReceiverStream;
 use uuid::Uuid;
 use warp::ws::{Message, WebSocket};
 
-pub async fn client_connection(ws: WebSocket, clients: Clients) {
+pub async fn client_connection(ws: WebSocket, clients: Clients, logic: Arc<Vec<String>>) {
     let (client_ws_sender, mut client_ws_rcv) = ws.split();
     let (client_sender, client_rcv) = mpsc::channel(5);
 
@@ -33,7 +34,7 @@ pub async fn client_connection(ws: WebSocket, clients: Clients) {
 
     println!("Client {uuid} connected");
 
-    let mut handler = ThreadedHandler::new(client_sender.clone()).await;
+    let mut handler = ThreadedHandler::new(client_sender.clone(), logic.clone()).await;
 
     while let Some(result) = client_ws_rcv.next().await {
         let msg = match result {
@@ -46,7 +47,7 @@ pub async fn client_connection(ws: WebSocket, clients: Clients) {
 
         if !handler.make_ready().await {
             handler.close();
-            handler = ThreadedHandler::new(client_sender.clone()).await;
+            handler = ThreadedHandler::new(client_sender.clone(), logic.clone()).await;
         }
 
         if handler.send(msg.into()).await.is_err() {
@@ -103,12 +104,17 @@ struct ThreadedHandler {
 }
 
 impl ThreadedHandler {
-    async fn new(client_sender: Sender<Result<Message, warp::Error>>) -> Self {
+    async fn new(client_sender: Sender<Result<Message, warp::Error>>, logic: Arc<Vec<String>>) -> Self {
         let (handler_sender, mut handler_recv) = mpsc::channel::<CancellableMessage>(5);
 
         let _ = std::thread::spawn({
             move || {
-                let mut message_handler = MessageHandler::new(Box::new(SendResultForWS::new(client_sender)));
+                // Logical steps cannot be sent between threads, so they are created from their names here.
+                // The names were validated when the config was loaded.
+                let config = Config { logic: logic.to_vec(), ..Default::default() };
+                let parser = config.parser().unwrap_or_default();
+                let mut message_handler =
+                    MessageHandler::new(Box::new(SendResultForWS::new(client_sender))).with_parser(parser);
 
                 // This is the thread for handling messages from the client.
                 // We handle multiple messages before we give up
@@ -116,11 +122,11 @@ impl ThreadedHandler {
                     let cancel_token = message.cancel_token;
                     let completed_token = message.completed_token;
                     let message = match message.messsage.to_str() {
-                        Ok(v) => v.to_string(),
+                        Ok(v) => v,
                         Err(_) => break,
                     };
 
-                    message_handler.handle_message(&message, cancel_token);
+                    message_handler.handle_message(message, cancel_token);
                     completed_token.store(true, Ordering::SeqCst);
                 }
             }
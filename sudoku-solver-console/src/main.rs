@@ -1,7 +1,20 @@
+mod analyze;
+mod benchmark;
+mod config;
+mod generate;
 mod listener;
+mod output;
+mod report;
+mod selftest;
+mod solve;
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use colored::Colorize;
+use config::Config;
+use std::path::PathBuf;
+use sudoku_solver_lib::prelude::*;
+use tracing::Level;
+use tracing_subscriber::{filter::Targets, prelude::*};
 
 #[derive(Debug, Parser)]
 #[clap(name = "Sudoku Solver")]
@@ -15,11 +28,89 @@ struct Args {
     /// Listen for websocket connections
     #[clap(short, long, action = clap::ArgAction::SetTrue)]
     listen: bool,
+
+    /// Address to listen on. Repeat or comma separate to listen on multiple interfaces [default: 127.0.0.1]
+    #[clap(long, value_delimiter = ',')]
+    host: Vec<String>,
+
+    /// Port to listen on [default: 4545]
+    #[clap(long)]
+    port: Option<u16>,
+
+    /// Certificate file (PEM) for serving the listener over TLS
+    #[clap(long, requires = "tls-key")]
+    tls_cert: Option<PathBuf>,
+
+    /// Private key file (PEM) for serving the listener over TLS
+    #[clap(long, requires = "tls-cert")]
+    tls_key: Option<PathBuf>,
+
+    /// Config file to load instead of sudoku-solver/config.toml in the user's config directory
+    #[clap(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Log request timing and brute force statistics to stderr. Repeat for more detail
+    #[clap(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Log each logical step as it is applied
+    #[clap(long, global = true, action = clap::ArgAction::SetTrue)]
+    trace_steps: bool,
+
+    /// Solve the bundled regression puzzles and report any which do not match their stored solutions
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    selftest: bool,
+
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Report the difficulty, techniques and symmetry of a file of puzzles
+    Analyze(analyze::AnalyzeArgs),
+    /// Time the solver against a file of puzzles
+    Benchmark(benchmark::BenchmarkArgs),
+    /// Generate new puzzles with a unique solution
+    Generate(generate::GenerateArgs),
+    /// Print the logical solve path of a puzzle, optionally writing it to a report
+    Solve(solve::SolveArgs),
 }
 
 #[tokio::main]
 async fn main() {
     let args = Args::parse();
+    init_logging(args.verbose, args.trace_steps);
+
+    let config = match Config::load(args.config.as_deref()) {
+        Ok(config) => config,
+        Err(error) => {
+            eprintln!("{}", error.red());
+            std::process::exit(1);
+        }
+    };
+
+    if args.selftest {
+        if let Err(error) = selftest::selftest(&config) {
+            eprintln!("{}", error.red());
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(command) = args.command {
+        let result = match command {
+            Command::Analyze(analyze_args) => analyze::analyze(&analyze_args, &config),
+            Command::Benchmark(benchmark_args) => benchmark::benchmark(&benchmark_args, &config),
+            Command::Generate(generate_args) => generate::generate(&generate_args, &config),
+            Command::Solve(solve_args) => solve::solve(&solve_args, &config),
+        };
+        if let Err(error) = result {
+            eprintln!("{}", error.red());
+            std::process::exit(1);
+        }
+        return;
+    }
 
     const AUTHOR: &str = env!("CARGO_PKG_AUTHORS");
     const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -34,8 +125,43 @@ async fn main() {
     println!();
 
     if args.listen {
-        listener::listen().await;
+        let hosts = if !args.host.is_empty() {
+            args.host
+        } else if !config.listener.hosts.is_empty() {
+            config.listener.hosts
+        } else {
+            vec!["127.0.0.1".to_owned()]
+        };
+        let listener_config = listener::ListenerConfig {
+            hosts,
+            port: args.port.or(config.listener.port).unwrap_or(4545),
+            tls_cert: args.tls_cert.or(config.listener.tls_cert),
+            tls_key: args.tls_key.or(config.listener.tls_key),
+            logic: config.logic,
+        };
+        if let Err(error) = listener::listen(&listener_config).await {
+            eprintln!("{}", error.red());
+            std::process::exit(1);
+        }
     } else {
         println!("No arguments provided. Use --help for more information.");
     }
 }
+
+fn init_logging(verbose: u8, trace_steps: bool) {
+    let level = match verbose {
+        0 => Level::WARN,
+        1 => Level::INFO,
+        _ => Level::DEBUG,
+    };
+
+    let mut filter = Targets::new().with_default(level);
+    if trace_steps {
+        filter = filter.with_target(STEPS_TRACE_TARGET, Level::TRACE);
+    }
+
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+        .with(filter)
+        .init();
+}
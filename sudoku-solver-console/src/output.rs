@@ -0,0 +1,112 @@
+//! Shared output formats for commands which print a board.
+
+use clap::ValueEnum;
+use itertools::Itertools;
+use standard_constraints::prelude::*;
+use sudoku_solver_lib::prelude::*;
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum OutputFormat {
+    /// One character per cell, with . for unsolved cells (zero padded numbers for grids larger than 9)
+    Givens,
+    /// One character per cell, with . for unsolved cells and letters for values above 9, such as A-G on 16x16 grids
+    Alphanumeric,
+    /// Every candidate of every cell, with . for eliminated candidates
+    Candidates,
+    /// A human readable grid
+    Pretty,
+    /// An f-puzzles link with the solved cells filled in
+    Fpuzzles,
+}
+
+/// Formats the board in the requested format.
+///
+/// The f-puzzles board provides the constraints for the [`OutputFormat::Fpuzzles`] format.
+pub fn format_board(board: &Board, format: OutputFormat, fpuzzles_board: &FPuzzlesBoard) -> String {
+    match format {
+        OutputFormat::Givens => givens_string(board, GivensEncoding::Numeric),
+        OutputFormat::Alphanumeric => givens_string(board, GivensEncoding::Alphanumeric),
+        OutputFormat::Candidates => candidates_string(board),
+        OutputFormat::Pretty => pretty_string(board),
+        OutputFormat::Fpuzzles => fpuzzles_link(board, fpuzzles_board),
+    }
+}
+
+fn format_value(value: usize, size: usize) -> String {
+    if size <= 9 {
+        if value == 0 {
+            ".".to_owned()
+        } else {
+            value.to_string()
+        }
+    } else {
+        let num_digits = size.to_string().len();
+        format!("{value:0num_digits$}")
+    }
+}
+
+fn solved_value(mask: ValueMask) -> usize {
+    if mask.is_solved() {
+        mask.value()
+    } else {
+        0
+    }
+}
+
+fn givens_string(board: &Board, encoding: GivensEncoding) -> String {
+    let values = board.all_cell_masks().map(|(_, mask)| solved_value(mask)).collect::<Vec<_>>();
+    encoding.encode(&values, board.size())
+}
+
+fn candidates_string(board: &Board) -> String {
+    let size = board.size();
+    board
+        .all_cell_masks()
+        .flat_map(|(_, mask)| (1..=size).map(move |value| format_value(if mask.has(value) { value } else { 0 }, size)))
+        .join("")
+}
+
+/// Formats the solved cells of the board as a grid, with lines between regions.
+pub fn pretty_string(board: &Board) -> String {
+    let size = board.size();
+    let cu = board.cell_utility();
+
+    let mut regions = vec![usize::MAX; board.num_cells()];
+    for (region, house) in board.houses().iter().filter(|house| house.kind() == HouseKind::Region).enumerate() {
+        for cell in house.cells() {
+            regions[cell.index()] = region;
+        }
+    }
+    let region = |row: usize, col: usize| regions[cu.cell(row, col).index()];
+
+    let cell_width = if size <= 9 { 1 } else { size.to_string().len() };
+    let mut lines = Vec::new();
+    for row in 0..size {
+        let mut line = String::new();
+        for col in 0..size {
+            if col > 0 {
+                line.push_str(if region(row, col - 1) != region(row, col) { " | " } else { " " });
+            }
+            let value = solved_value(board.cell(cu.cell(row, col)));
+            line.push_str(&format!("{:>cell_width$}", format_value(value, size)));
+        }
+
+        if row > 0 && (0..size).all(|col| region(row - 1, col) != region(row, col)) {
+            lines.push(line.chars().map(|c| if c == '|' { '+' } else { '-' }).collect());
+        }
+        lines.push(line);
+    }
+
+    lines.join("\n")
+}
+
+fn fpuzzles_link(board: &Board, fpuzzles_board: &FPuzzlesBoard) -> String {
+    let mut fpuzzles_board = fpuzzles_board.clone();
+    for (cell, mask) in board.all_cell_masks() {
+        let entry = &mut fpuzzles_board.grid[cell.row()][cell.column()];
+        if entry.value == 0 && mask.is_solved() {
+            entry.value = mask.value() as i32;
+        }
+    }
+    format!("https://www.f-puzzles.com/?load={}", fpuzzles_board.to_lzstring_json())
+}
@@ -0,0 +1,132 @@
+//! Renders a logical solve path into a shareable markdown or HTML document.
+
+use std::path::Path;
+
+/// A logical step of a [`SolveReport`].
+pub struct ReportStep {
+    /// The description of the step, with any sub-steps on their own lines.
+    pub description: String,
+    /// The board after the step, as a pretty printed grid.
+    pub board: String,
+}
+
+/// The logical solve path of a puzzle, with the board after every step.
+pub struct SolveReport {
+    /// The puzzle as it was given.
+    pub puzzle: String,
+    /// The board before the first step, as a pretty printed grid.
+    pub initial_board: String,
+    /// The logical steps in the order they were applied.
+    pub steps: Vec<ReportStep>,
+    /// How the solve ended, such as "Solved!".
+    pub outcome: String,
+}
+
+impl SolveReport {
+    /// Renders the report as markdown.
+    pub fn to_markdown(&self) -> String {
+        let mut text = String::new();
+        text.push_str("# Solve Path\n\n");
+        text.push_str(&format!("Puzzle:\n\n{}\n\n", fenced(&self.puzzle)));
+        text.push_str(&format!("{}\n\n", fenced(&self.initial_board)));
+        for (index, step) in self.steps.iter().enumerate() {
+            text.push_str(&format!("## Step {}\n\n", index + 1));
+            for line in step.description.lines() {
+                text.push_str(&format!("    {line}\n"));
+            }
+            text.push_str(&format!("\n{}\n\n", fenced(&step.board)));
+        }
+        text.push_str(&format!("**{}**\n", self.outcome));
+        text
+    }
+
+    /// Renders the report as a standalone HTML page.
+    pub fn to_html(&self) -> String {
+        let mut text = String::new();
+        text.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Solve Path</title>\n");
+        text.push_str("<style>pre { font-size: 1.2em; line-height: 1.2; } .step { white-space: pre-wrap; }</style>\n");
+        text.push_str("</head>\n<body>\n<h1>Solve Path</h1>\n");
+        text.push_str(&format!("<p>Puzzle: <code>{}</code></p>\n", escape_html(&self.puzzle)));
+        text.push_str(&format!("<pre>{}</pre>\n", escape_html(&self.initial_board)));
+        for (index, step) in self.steps.iter().enumerate() {
+            text.push_str(&format!("<h2>Step {}</h2>\n", index + 1));
+            text.push_str(&format!("<p class=\"step\">{}</p>\n", escape_html(&step.description)));
+            text.push_str(&format!("<pre>{}</pre>\n", escape_html(&step.board)));
+        }
+        text.push_str(&format!("<p><strong>{}</strong></p>\n", escape_html(&self.outcome)));
+        text.push_str("</body>\n</html>\n");
+        text
+    }
+
+    /// Writes the report to the file, as HTML if it ends in .html or .htm and as markdown otherwise.
+    pub fn write(&self, path: &Path) -> Result<(), String> {
+        let is_html = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("html") || extension.eq_ignore_ascii_case("htm"));
+        let text = if is_html { self.to_html() } else { self.to_markdown() };
+        std::fs::write(path, text).map_err(|error| format!("Failed to write {}: {error}", path.display()))
+    }
+}
+
+/// Wraps the text in a markdown code block, with a fence longer than any run of backticks in the text.
+fn fenced(text: &str) -> String {
+    let longest_run = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    format!("{fence}\n{text}\n{fence}")
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn test_report() -> SolveReport {
+        SolveReport {
+            puzzle: "N4Ig`zg<b>".to_owned(),
+            initial_board: "1 . .\n```".to_owned(),
+            steps: vec![ReportStep {
+                description: "Naked Single: r1c2 = 2 & <3>".to_owned(),
+                board: "1 2 .".to_owned(),
+            }],
+            outcome: "Solved!".to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        let markdown = test_report().to_markdown();
+        assert!(markdown.contains("Puzzle:\n\n```\nN4Ig`zg<b>\n```\n"));
+        assert!(markdown.contains("````\n1 . .\n```\n````\n"));
+        assert!(markdown.contains("## Step 1\n\n    Naked Single: r1c2 = 2 & <3>\n"));
+        assert!(markdown.ends_with("**Solved!**\n"));
+    }
+
+    #[test]
+    fn test_to_html() {
+        let html = test_report().to_html();
+        assert!(html.contains("<code>N4Ig`zg&lt;b&gt;</code>"));
+        assert!(html.contains("<p class=\"step\">Naked Single: r1c2 = 2 &amp; &lt;3&gt;</p>"));
+        assert!(!html.contains("<b>"));
+    }
+
+    #[test]
+    fn test_write() {
+        let report = test_report();
+        let directory = std::env::temp_dir().join(format!("sudoku-solver-report-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        for (file_name, expected) in
+            [("report.HTML", report.to_html()), ("report.htm", report.to_html()), ("report.md", report.to_markdown())]
+        {
+            let path = directory.join(file_name);
+            report.write(&path).unwrap();
+            assert_eq!(std::fs::read_to_string(&path).unwrap(), expected, "{file_name}");
+        }
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        assert!(report.write(&directory.join("missing").join("report.md")).is_err());
+    }
+}
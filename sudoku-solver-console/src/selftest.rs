@@ -0,0 +1,28 @@
+//! Implements the `--selftest` flag for checking that the solver behaves correctly on this platform.
+
+use crate::config::Config;
+use colored::Colorize;
+use standard_constraints::prelude::*;
+
+pub fn selftest(config: &Config) -> Result<(), String> {
+    let suite = RegressionSuite::bundled().with_parser(config.parser()?);
+    let results = suite.run();
+    for result in &results {
+        if result.passed() {
+            println!("{} {}", "PASS".green(), result.name);
+        } else {
+            println!("{} {}", "FAIL".red(), result.name);
+            for failure in &result.failures {
+                println!("  {failure}");
+            }
+        }
+    }
+
+    let num_failed = results.iter().filter(|result| !result.passed()).count();
+    println!();
+    if num_failed > 0 {
+        return Err(format!("{num_failed} of {} regression puzzles failed", results.len()));
+    }
+    println!("All {} regression puzzles passed", results.len());
+    Ok(())
+}
@@ -0,0 +1,56 @@
+//! Implements the `solve` command for printing the logical solve path of a puzzle.
+
+use crate::config::Config;
+use crate::output::*;
+use crate::report::*;
+use standard_constraints::prelude::*;
+use std::path::PathBuf;
+
+#[derive(Debug, clap::Args)]
+pub struct SolveArgs {
+    /// The puzzle, as a givens string, f-puzzles string, f-puzzles link or f-puzzles JSON
+    puzzle: String,
+
+    /// Also write the solve path to this file, with the board after every step.
+    /// Files ending in .html or .htm are written as HTML, and other files as markdown
+    #[clap(long)]
+    report: Option<PathBuf>,
+}
+
+pub fn solve(args: &SolveArgs, config: &Config) -> Result<(), String> {
+    let parser = PuzzleInputParser::new().with_fpuzzles_parser(config.parser()?);
+    let mut solver = parser.parse(&args.puzzle)?.build()?;
+    tracing::info!(memory = %solver.memory_footprint(), "Solver built");
+    let initial_board = pretty_string(solver.board());
+
+    let mut steps = Vec::new();
+    let outcome = loop {
+        if solver.board().is_solved() {
+            break "Solved!";
+        }
+
+        let step_result = solver.run_single_logical_step();
+        if step_result.is_none() {
+            break "No more logical steps were found.";
+        }
+
+        let description = step_result.description().map(|desc| desc.to_string()).unwrap_or_default();
+        println!("{description}");
+        steps.push(ReportStep { description, board: pretty_string(solver.board()) });
+
+        if step_result.is_invalid() {
+            break "The puzzle has no solution.";
+        }
+    };
+
+    println!("{outcome}");
+    println!();
+    println!("{}", pretty_string(solver.board()));
+
+    if let Some(path) = args.report.as_ref() {
+        let report = SolveReport { puzzle: args.puzzle.clone(), initial_board, steps, outcome: outcome.to_owned() };
+        report.write(path)?;
+    }
+
+    Ok(())
+}
@@ -3,9 +3,15 @@
 use bitvec::bitvec;
 use bitvec::vec::BitVec;
 use itertools::Itertools;
+use smallvec::SmallVec;
 
+#[cfg(feature = "profiling")]
+use crate::constraint_profile::ConstraintTimers;
 use crate::prelude::*;
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, OnceLock},
+};
 
 /// Represents the state of the sudoku board.
 ///
@@ -17,13 +23,78 @@ use std::{collections::HashMap, sync::Arc};
 /// Unless [`Board::deep_clone`] is used, the board metadata is not copied,
 /// and instead is shared among boards when cloned. This makes cloning faster,
 /// and is generally safe because board metadata can't be changed after initialization.
-#[derive(Clone)]
 pub struct Board {
     board: Vec<ValueMask>,
     solved_count: usize,
+    /// The number of candidates of every cell, which is updated as candidates are removed.
+    candidate_count: usize,
+    solution_hash: u64,
+    /// Bit set of the cells, followed by the houses, whose candidates have changed since they were
+    /// last taken with [`Board::take_changed_cell`] or [`Board::take_changed_house`].
+    changed: Vec<u64>,
+    /// Bit set of the constraints with a relevant cell whose candidates have changed since
+    /// they were last taken with [`Board::take_changed_constraint`].
+    changed_constraints: Vec<u64>,
+    revision: u64,
+    /// Told about every change to the candidates. Clones don't keep it.
+    observer: Option<Arc<dyn BoardObserver>>,
+    /// Results of [`Constraint::cells_must_contain_by_running_logic`] for the current revision.
+    /// Clones start with an empty cache.
+    must_contain_cache: Mutex<Vec<MustContainEntry>>,
+    /// The regions each cell can be in, when the regions are unknown.
+    region_membership: Option<RegionMembership>,
     data: Arc<BoardData>,
 }
 
+/// The most results of [`Constraint::cells_must_contain_by_running_logic`] a board remembers
+/// for one revision. The oldest result is forgotten to make room for a new one.
+const MUST_CONTAIN_CACHE_LIMIT: usize = 64;
+
+/// A memoized result of [`Constraint::cells_must_contain_by_running_logic`].
+struct MustContainEntry {
+    revision: u64,
+    /// The index of the queried constraint in [`Board::constraints`].
+    constraint_index: usize,
+    value: usize,
+    cells: Vec<CellIndex>,
+    result: Vec<CellIndex>,
+}
+
+impl Clone for Board {
+    fn clone(&self) -> Self {
+        Board {
+            board: self.board.clone(),
+            solved_count: self.solved_count,
+            candidate_count: self.candidate_count,
+            solution_hash: self.solution_hash,
+            changed: self.changed.clone(),
+            changed_constraints: self.changed_constraints.clone(),
+            revision: self.revision,
+            observer: None,
+            must_contain_cache: Mutex::default(),
+            region_membership: self.region_membership.clone(),
+            data: self.data.clone(),
+        }
+    }
+
+    /// Copies the board while reusing the existing cell storage, which avoids an
+    /// allocation when the boards are the same size.
+    fn clone_from(&mut self, source: &Self) {
+        self.board.clone_from(&source.board);
+        self.solved_count = source.solved_count;
+        self.candidate_count = source.candidate_count;
+        self.solution_hash = source.solution_hash;
+        self.changed.clone_from(&source.changed);
+        self.changed_constraints.clone_from(&source.changed_constraints);
+        self.revision = source.revision;
+        if let Ok(cache) = self.must_contain_cache.get_mut() {
+            cache.clear();
+        }
+        self.region_membership.clone_from(&source.region_membership);
+        self.data.clone_from(&source.data);
+    }
+}
+
 /// Contains meta-data about the board.
 ///
 /// This data is immutable after initialization and contains information
@@ -34,13 +105,32 @@ pub struct BoardData {
     num_cells: usize,
     num_candidates: usize,
     all_values_mask: ValueMask,
+    /// The region of each cell.
+    regions: Vec<usize>,
     houses: Vec<Arc<House>>,
-    houses_by_cell: Vec<Vec<Arc<House>>>,
+    houses_by_cell: Vec<SmallVec<[Arc<House>; 4]>>,
+    /// For each cell, a bit set of the houses which contain it.
+    house_bits_by_cell: Vec<u64>,
+    /// For each cell, a bit set of the constraints for which it is relevant.
+    constraint_bits_by_cell: Vec<u64>,
+    /// For each cell, the indexes of the constraints to enforce when it is set.
+    enforced_constraints_by_cell: Vec<SmallVec<[usize; 4]>>,
     powerful_cells: Vec<CellIndex>,
     weak_links: Vec<CandidateLinks>,
     total_weak_links: usize,
-    exclusive_cells: Vec<BitVec>,
+    /// For each candidate, the number of candidates it is weakly linked to.
+    weak_link_degrees: Vec<usize>,
+    /// How the weak links of each constraint were used, in the same order as the constraints.
+    weak_link_stats: Vec<WeakLinkStats>,
+    /// For each cell, the cells which can't share a value with it.
+    /// Each row is computed the first time it is queried, since most boards never need them.
+    exclusive_cells: Vec<OnceLock<BitVec>>,
     constraints: Vec<Arc<dyn Constraint>>,
+    /// The time spent in each constraint, shared by every clone of the board.
+    #[cfg(feature = "profiling")]
+    constraint_timers: Arc<ConstraintTimers>,
+    /// The kinds of houses which logical steps search.
+    step_house_kinds: Vec<HouseKind>,
 }
 
 impl Board {
@@ -48,8 +138,20 @@ impl Board {
         let mut data = BoardData::new(size, regions, constraints);
         let elims = data.init_weak_links();
 
-        let mut board =
-            Board { board: vec![data.all_values_mask; data.num_cells], solved_count: 0, data: Arc::new(data) };
+        let mut board = Board {
+            board: vec![data.all_values_mask; data.num_cells],
+            solved_count: 0,
+            candidate_count: data.num_candidates,
+            solution_hash: 0,
+            changed: vec![0; data.num_cells.div_ceil(64) + data.houses.len().div_ceil(64)],
+            changed_constraints: vec![0; data.constraints.len().div_ceil(64)],
+            revision: 0,
+            observer: None,
+            must_contain_cache: Mutex::default(),
+            region_membership: None,
+            data: Arc::new(data),
+        };
+        board.mark_all_changed();
 
         board.clear_candidates(elims.iter());
 
@@ -60,6 +162,14 @@ impl Board {
         Board {
             board: self.board.clone(),
             solved_count: self.solved_count,
+            candidate_count: self.candidate_count,
+            solution_hash: self.solution_hash,
+            changed: self.changed.clone(),
+            changed_constraints: self.changed_constraints.clone(),
+            revision: self.revision,
+            observer: None,
+            must_contain_cache: Mutex::default(),
+            region_membership: self.region_membership.clone(),
             data: Arc::new(BoardData::clone(&self.data)),
         }
     }
@@ -108,6 +218,158 @@ impl Board {
         self.solved_count
     }
 
+    /// The number of cells which haven't been solved yet.
+    pub fn empty_cells(&self) -> usize {
+        self.data.num_cells - self.solved_count
+    }
+
+    /// The number of candidates left in every cell, counting each solved cell as one.
+    ///
+    /// This is kept up to date as candidates are removed, so it doesn't need to scan the board.
+    pub fn candidates_remaining(&self) -> usize {
+        self.candidate_count
+    }
+
+    /// The percentage of the cells which are solved, from `0.0` to `100.0`.
+    pub fn percent_solved(&self) -> f64 {
+        if self.data.num_cells == 0 {
+            0.0
+        } else {
+            self.solved_count as f64 * 100.0 / self.data.num_cells as f64
+        }
+    }
+
+    /// A Zobrist hash of the values of the solved cells, which is updated as cells are solved.
+    ///
+    /// Boards with the same solved values have the same hash regardless of the order in
+    /// which the cells were solved, so solutions can be told apart by their hash without
+    /// keeping the whole board around.
+    pub fn solution_hash(&self) -> u64 {
+        self.solution_hash
+    }
+
+    /// The approximate number of bytes a clone of the board takes, not counting the data it shares.
+    pub fn memory_size(&self) -> usize {
+        std::mem::size_of::<Board>()
+            + self.board.capacity() * std::mem::size_of::<ValueMask>()
+            + (self.changed.capacity() + self.changed_constraints.capacity()) * std::mem::size_of::<u64>()
+    }
+
+    /// An estimate of the bytes used by the data of the board and by the board itself.
+    /// See [`MemoryFootprint`].
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        let data = &self.data;
+        let computed_exclusive_cells: usize =
+            data.exclusive_cells.iter().filter_map(|row| row.get()).map(|row| bit_set_size(row.len())).sum();
+        MemoryFootprint {
+            weak_links: data.weak_links.iter().map(CandidateLinks::memory_size).sum::<usize>()
+                + data.weak_link_degrees.len() * std::mem::size_of::<usize>(),
+            exclusive_cells: data.exclusive_cells.len() * std::mem::size_of::<OnceLock<BitVec>>()
+                + computed_exclusive_cells,
+            houses: data
+                .houses
+                .iter()
+                .map(|house| house_memory_size(house.cells().len()) + house.name().len())
+                .sum::<usize>()
+                + data.houses_by_cell.len() * std::mem::size_of::<HousesOfCell>(),
+            cells: data.num_cells * cell_memory_size(),
+            board: self.memory_size(),
+        }
+    }
+
+    /// A counter which is incremented every time the candidates of a cell change.
+    ///
+    /// A board with the same revision as when something was computed from it is unchanged
+    /// since then. Clones start with the revision of the board they were cloned from.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Sets the [`BoardObserver`] which is told about every change to the candidates
+    /// of this board, replacing any previous observer.
+    ///
+    /// Clones of this board do not keep the observer.
+    pub fn set_observer(&mut self, observer: Arc<dyn BoardObserver>) {
+        self.observer = Some(observer);
+    }
+
+    /// Removes the observer, returning it if there was one.
+    pub fn take_observer(&mut self) -> Option<Arc<dyn BoardObserver>> {
+        self.observer.take()
+    }
+
+    /// The regions each cell can be in, if the regions of the board are unknown.
+    /// See [`SolverBuilder::with_unknown_regions`].
+    pub fn region_membership(&self) -> Option<&RegionMembership> {
+        self.region_membership.as_ref()
+    }
+
+    /// Changes the regions each cell can be in, if the regions of the board are unknown.
+    ///
+    /// This counts as a change to the board for its [`Board::revision`]. Regions are not candidates,
+    /// so every cell, house and constraint is marked as changed for the logic to look at them again.
+    pub fn region_membership_mut(&mut self) -> Option<&mut RegionMembership> {
+        if self.region_membership.is_some() {
+            self.revision += 1;
+            self.mark_all_changed();
+        }
+        self.region_membership.as_mut()
+    }
+
+    /// Sets whether the regions of the board are unknown, and which regions each cell can be in if so.
+    pub fn set_region_membership(&mut self, region_membership: Option<RegionMembership>) {
+        self.revision += 1;
+        self.mark_all_changed();
+        self.region_membership = region_membership;
+    }
+
+    /// Returns the memoized result for a query made at the current revision, or computes and
+    /// remembers it. Entries from earlier revisions are discarded, and at most
+    /// [`MUST_CONTAIN_CACHE_LIMIT`] entries are kept.
+    ///
+    /// Used by [`Constraint::cells_must_contain_by_running_logic`].
+    pub(crate) fn memoize_cells_must_contain(
+        &self,
+        constraint_index: usize,
+        value: usize,
+        cells: &[CellIndex],
+        compute: impl FnOnce() -> Vec<CellIndex>,
+    ) -> Vec<CellIndex> {
+        if let Ok(mut cache) = self.must_contain_cache.lock() {
+            cache.retain(|entry| entry.revision == self.revision);
+            let entry = cache.iter().find(|entry| {
+                entry.constraint_index == constraint_index && entry.value == value && entry.cells == cells
+            });
+            if let Some(entry) = entry {
+                return entry.result.clone();
+            }
+        }
+
+        // The lock isn't held while computing, since the computation may query other boards.
+        let result = compute();
+        if let Ok(mut cache) = self.must_contain_cache.lock() {
+            if cache.len() >= MUST_CONTAIN_CACHE_LIMIT {
+                cache.remove(0);
+            }
+            cache.push(MustContainEntry {
+                revision: self.revision,
+                constraint_index,
+                value,
+                cells: cells.to_vec(),
+                result: result.clone(),
+            });
+        }
+        result
+    }
+
+    /// The random-looking key for a candidate, from the SplitMix64 mixing function.
+    fn zobrist_key(candidate: CandidateIndex) -> u64 {
+        let mut key = (candidate.index() as u64).wrapping_add(0x9E37_79B9_7F4A_7C15);
+        key = (key ^ (key >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        key = (key ^ (key >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        key ^ (key >> 31)
+    }
+
     pub fn is_solved(&self) -> bool {
         self.solved_count == self.data.num_cells
     }
@@ -132,6 +394,13 @@ impl Board {
         self.data.all_values_mask
     }
 
+    /// The region of each cell, indexed by [`CellIndex::index`].
+    ///
+    /// A board with no regions has every cell in region 0.
+    pub fn regions(&self) -> &[usize] {
+        &self.data.regions
+    }
+
     pub fn houses(&self) -> &[Arc<House>] {
         &self.data.houses
     }
@@ -140,6 +409,21 @@ impl Board {
         &self.data.houses_by_cell[cell.index()]
     }
 
+    /// How many candidates remain in each cell and house. See [`CandidateDensity`].
+    pub fn candidate_density(&self) -> CandidateDensity {
+        CandidateDensity::new(self)
+    }
+
+    /// Whether logical steps should search the house. See [`SolverBuilder::with_step_house_kinds`].
+    pub fn is_step_house(&self, house: &House) -> bool {
+        self.data.is_step_house(house)
+    }
+
+    /// Limit the houses which logical steps search to the given kinds.
+    pub fn set_step_house_kinds(&mut self, kinds: &[HouseKind]) {
+        Arc::make_mut(&mut self.data).step_house_kinds = kinds.to_vec();
+    }
+
     pub fn total_weak_links(&self) -> usize {
         self.data.total_weak_links
     }
@@ -148,10 +432,51 @@ impl Board {
         &self.data.weak_links
     }
 
+    /// The number of candidates the candidate is weakly linked to, whether or not they are still on the board.
+    pub fn weak_link_degree(&self, candidate: CandidateIndex) -> usize {
+        self.data.weak_link_degrees[candidate.index()]
+    }
+
+    /// Whether the two candidates can't both be true.
+    pub fn has_weak_link(&self, candidate0: CandidateIndex, candidate1: CandidateIndex) -> bool {
+        self.data.has_weak_link(candidate0, candidate1)
+    }
+
+    /// Exports the weak and strong links between the remaining candidates, for use by external
+    /// chaining and SAT engines. See [`LinkGraph`].
+    pub fn export_link_graph(&self) -> LinkGraph {
+        LinkGraph::new(self)
+    }
+
+    /// How the weak links of each constraint were used, in the same order as [`Board::constraints`].
+    pub fn weak_link_stats(&self) -> &[WeakLinkStats] {
+        &self.data.weak_link_stats
+    }
+
     pub fn constraints(&self) -> &[Arc<dyn Constraint>] {
         &self.data.constraints
     }
 
+    /// The time spent in each constraint by this board and every board cloned from it or the
+    /// board it was cloned from, in the same order as [`Board::constraints`].
+    #[cfg(feature = "profiling")]
+    pub fn constraint_profile(&self) -> Vec<ConstraintProfile> {
+        self.data.constraint_timers.profiles(self.data.constraints.iter().map(|constraint| constraint.name()))
+    }
+
+    /// Restarts the times of [`Board::constraint_profile`] from zero.
+    #[cfg(feature = "profiling")]
+    pub fn reset_constraint_profile(&self) {
+        self.data.constraint_timers.reset();
+    }
+
+    /// Whether any of the other constraints states the fact about the cell. See [`Constraint::has_cell_fact`].
+    ///
+    /// While a constraint runs [`Constraint::init_board`], it is not one of the constraints asked.
+    pub fn cell_has_fact(&self, cell: CellIndex, fact: CellFact) -> bool {
+        self.data.constraints.iter().any(|constraint| constraint.has_cell_fact(cell, fact))
+    }
+
     pub fn cell(&self, cell: CellIndex) -> ValueMask {
         self.board[cell.index()]
     }
@@ -175,7 +500,7 @@ impl Board {
 
     pub fn clear_value(&mut self, cell: CellIndex, val: usize) -> bool {
         let cell = cell.index();
-        self.board[cell] = self.board[cell].without(val);
+        self.update_cell(cell, self.board[cell].without(val));
         !self.board[cell].is_empty()
     }
 
@@ -196,17 +521,58 @@ impl Board {
 
     pub fn clear_mask(&mut self, cell: CellIndex, mask: ValueMask) -> bool {
         let cell = cell.index();
-        self.board[cell] = self.board[cell] & (!mask).solved();
+        self.update_cell(cell, self.board[cell] & (!mask).solved());
         !self.board[cell].is_empty()
     }
 
     pub fn keep_mask(&mut self, cell: CellIndex, mask: ValueMask) -> bool {
         let cell = cell.index();
-        self.board[cell] = self.board[cell] & mask.solved();
+        self.update_cell(cell, self.board[cell] & mask.solved());
         !self.board[cell].is_empty()
     }
 
     pub fn set_solved(&mut self, cell: CellIndex, value: usize) -> bool {
+        self.place(cell, value) && self.enforce_placement(cell, value)
+    }
+
+    /// Sets several cells to values, enforcing the constraints only once every placement and its
+    /// weak links have been applied.
+    ///
+    /// Cells which are already solved to their value are skipped. On failure, returns the first
+    /// placement which could not be made or whose constraints were broken, and the board is left
+    /// in an unknown state.
+    ///
+    /// # Example
+    /// ```
+    /// # use sudoku_solver_lib::prelude::*;
+    /// let mut board = Board::default();
+    /// let cu = board.cell_utility();
+    /// assert!(board.set_solved_many(&[(cu.cell(0, 0), 1), (cu.cell(4, 4), 2)]).is_ok());
+    /// assert_eq!(board.set_solved_many(&[(cu.cell(0, 0), 1), (cu.cell(0, 8), 1)]), Err((cu.cell(0, 8), 1)));
+    /// ```
+    pub fn set_solved_many(&mut self, placements: &[(CellIndex, usize)]) -> Result<(), (CellIndex, usize)> {
+        let mut placed = Vec::with_capacity(placements.len());
+        for &(cell, value) in placements {
+            let mask = self.cell(cell);
+            if mask.is_solved() && mask.is_single() && mask.value() == value {
+                continue;
+            }
+            if !self.place(cell, value) {
+                return Err((cell, value));
+            }
+            placed.push((cell, value));
+        }
+
+        for &(cell, value) in &placed {
+            if !self.enforce_placement(cell, value) {
+                return Err((cell, value));
+            }
+        }
+        Ok(())
+    }
+
+    /// Solves the cell and applies the weak links of the value, without enforcing the constraints.
+    fn place(&mut self, cell: CellIndex, value: usize) -> bool {
         // Is this value possible?
         if !self.cell(cell).has(value) {
             return false;
@@ -218,41 +584,168 @@ impl Board {
         }
 
         // Mark as solved
-        self.board[cell.index()] = self.board[cell.index()].with_only(value).solved();
+        let cu = CellUtility::new(self.size());
+        let set_candidate_index = cu.candidate(cell, value);
+        self.update_cell(cell.index(), self.board[cell.index()].with_only(value).solved());
         self.solved_count += 1;
+        self.solution_hash ^= Self::zobrist_key(set_candidate_index);
 
         // Clone the BoardData Arc to avoid borrowing issues
         let board_data = self.data.clone();
 
-        // Apply all weak links
-        let cu = CellUtility::new(self.size());
-        let set_candidate_index = cu.candidate(cell, value);
-        for candidate_index in board_data.weak_links[set_candidate_index.index()].links() {
-            if !self.clear_candidate(candidate_index) {
+        // Apply all weak links, one cell at a time
+        let weak_links = &board_data.weak_links[set_candidate_index.index()];
+        if self.size() == 9 {
+            self.clear_masks(weak_links.cell_masks_of_size::<9>())
+        } else {
+            self.clear_masks(weak_links.cell_masks())
+        }
+    }
+
+    /// Enforces the constraints for which the cell is relevant.
+    fn enforce_placement(&mut self, cell: CellIndex, value: usize) -> bool {
+        let board_data = self.data.clone();
+        for &constraint_index in board_data.enforced_constraints_by_cell[cell.index()].iter() {
+            let constraint = &board_data.constraints[constraint_index];
+            if board_data.time_enforce(constraint_index, || constraint.enforce(self, cell, value)).is_invalid() {
                 return false;
             }
         }
 
-        // Enforce all constraints
-        for constraint in board_data.constraints.iter() {
-            if constraint.enforce(self, cell, value).is_invalid() {
+        true
+    }
+
+    /// Clears the masks from their cells, stopping as soon as a cell has no candidates left.
+    fn clear_masks(&mut self, cell_masks: impl Iterator<Item = (CellIndex, ValueMask)>) -> bool {
+        for (cell, mask) in cell_masks {
+            if !self.clear_mask(cell, mask) {
                 return false;
             }
         }
-
         true
     }
 
+    /// Sets a cell to a solved value without checking candidates, weak links or constraints.
+    ///
+    /// Used to load a complete grid which is then checked as a whole, and to replay recorded placements.
+    pub(crate) fn fill_unchecked(&mut self, cell: CellIndex, value: usize) {
+        let old_mask = self.board[cell.index()];
+        if !old_mask.is_solved() {
+            self.solved_count += 1;
+        } else if old_mask.is_single() {
+            self.solution_hash ^= Self::zobrist_key(cell.candidate(old_mask.value()));
+        }
+        self.solution_hash ^= Self::zobrist_key(cell.candidate(value));
+        self.update_cell(cell.index(), ValueMask::from_value(value).solved());
+    }
+
     pub fn set_mask(&mut self, cell: usize, mask: ValueMask) -> bool {
         assert!(!mask.is_solved());
         if mask.is_empty() {
             return false;
         }
 
-        self.board[cell] = mask;
+        self.update_cell(cell, mask);
         true
     }
 
+    /// Sets the mask of a cell, marking the cell and its houses as changed if the mask is different.
+    fn update_cell(&mut self, cell: usize, mask: ValueMask) {
+        if self.board[cell] == mask {
+            return;
+        }
+
+        let old_mask = self.board[cell];
+        self.board[cell] = mask;
+        self.candidate_count = self.candidate_count + mask.count() - old_mask.count();
+        self.revision += 1;
+        if let Some(observer) = &self.observer {
+            let cell = self.cell_utility().cell_index(cell);
+            observer.cell_changed(&BoardChange { cell, old_mask, new_mask: mask, revision: self.revision });
+        }
+        self.changed[cell / 64] |= 1 << (cell % 64);
+        let house_offset = self.data.num_cells.div_ceil(64);
+        let house_words = self.changed.len() - house_offset;
+        let house_bits = &self.data.house_bits_by_cell[cell * house_words..(cell + 1) * house_words];
+        for (changed, bits) in self.changed[house_offset..].iter_mut().zip(house_bits) {
+            *changed |= bits;
+        }
+        let constraint_words = self.changed_constraints.len();
+        let constraint_bits =
+            &self.data.constraint_bits_by_cell[cell * constraint_words..(cell + 1) * constraint_words];
+        for (changed, bits) in self.changed_constraints.iter_mut().zip(constraint_bits) {
+            *changed |= bits;
+        }
+    }
+
+    /// Marks every cell, house and constraint as changed, so that the next brute force pass checks all of them.
+    pub fn mark_all_changed(&mut self) {
+        let house_offset = self.data.num_cells.div_ceil(64);
+        self.changed.fill(0);
+        for cell in 0..self.data.num_cells {
+            self.changed[cell / 64] |= 1 << (cell % 64);
+        }
+        for house in 0..self.data.houses.len() {
+            self.changed[house_offset + house / 64] |= 1 << (house % 64);
+        }
+        self.changed_constraints.fill(0);
+        for constraint in 0..self.data.constraints.len() {
+            self.changed_constraints[constraint / 64] |= 1 << (constraint % 64);
+        }
+    }
+
+    /// Marks a cell as changed so that it is returned by [`Board::take_changed_cell`] again.
+    pub fn mark_cell_changed(&mut self, cell: CellIndex) {
+        let cell = cell.index();
+        self.changed[cell / 64] |= 1 << (cell % 64);
+    }
+
+    /// Marks a house, by its index in [`Board::houses`], as changed so that it is
+    /// returned by [`Board::take_changed_house`] again.
+    pub fn mark_house_changed(&mut self, house: usize) {
+        let house_offset = self.data.num_cells.div_ceil(64);
+        self.changed[house_offset + house / 64] |= 1 << (house % 64);
+    }
+
+    /// Removes and returns a cell whose candidates have changed since it was last taken.
+    ///
+    /// This lets brute force steps check only the cells which could have a new result
+    /// instead of scanning the whole board. Each cell is tracked once no matter how many
+    /// steps use it, so only a single step should take cells.
+    pub fn take_changed_cell(&mut self) -> Option<CellIndex> {
+        let house_offset = self.data.num_cells.div_ceil(64);
+        Self::take_first_bit(&mut self.changed[..house_offset]).map(|cell| self.cell_utility().cell_index(cell))
+    }
+
+    /// Removes and returns the index in [`Board::houses`] of a house containing a cell whose
+    /// candidates have changed since the house was last taken.
+    ///
+    /// Like [`Board::take_changed_cell`], only a single step should take houses.
+    pub fn take_changed_house(&mut self) -> Option<usize> {
+        let house_offset = self.data.num_cells.div_ceil(64);
+        Self::take_first_bit(&mut self.changed[house_offset..])
+    }
+
+    /// Returns whether a relevant cell of the constraint, by its index in [`Board::constraints`],
+    /// has changed since the constraint was last taken, and clears the flag.
+    ///
+    /// A constraint without [`Constraint::relevant_cells`] is changed by any cell.
+    /// Only [`StepConstraints`] should take constraints.
+    pub fn take_changed_constraint(&mut self, constraint: usize) -> bool {
+        let word = &mut self.changed_constraints[constraint / 64];
+        let bit = 1 << (constraint % 64);
+        let changed = *word & bit != 0;
+        *word &= !bit;
+        changed
+    }
+
+    fn take_first_bit(words: &mut [u64]) -> Option<usize> {
+        let (index, word) = words.iter_mut().enumerate().find(|(_, word)| **word != 0)?;
+        let bit = word.trailing_zeros() as usize;
+        *word &= *word - 1;
+        Some(index * 64 + bit)
+    }
+
     pub fn is_exclusive(&self, cell1: CellIndex, cell2: CellIndex) -> bool {
         self.data.is_exclusive(cell1, cell2)
     }
@@ -289,6 +782,29 @@ impl Board {
         }
         true
     }
+
+    /// The pairs of values which can't be in the two cells at the same time, because their
+    /// candidates are weakly linked. Each pair is `(value in cell_a, value in cell_b)`, in order.
+    ///
+    /// Every value of the board is checked, not just the values which are still candidates.
+    ///
+    /// # Example
+    /// ```
+    /// # use sudoku_solver_lib::prelude::*;
+    /// let board = Board::default();
+    /// let cu = board.cell_utility();
+    /// let links = board.links_between(cu.cell(0, 0), cu.cell(0, 1));
+    /// assert_eq!(links, (1..=9).map(|value| (value, value)).collect::<Vec<_>>());
+    /// assert!(board.links_between(cu.cell(0, 0), cu.cell(4, 4)).is_empty());
+    /// ```
+    pub fn links_between(&self, cell_a: CellIndex, cell_b: CellIndex) -> Vec<(usize, usize)> {
+        let values = 1..=self.size();
+        values
+            .clone()
+            .cartesian_product(values)
+            .filter(|&(value_a, value_b)| self.data.has_weak_link(cell_a.candidate(value_a), cell_b.candidate(value_b)))
+            .collect()
+    }
 }
 
 impl BoardData {
@@ -296,10 +812,15 @@ impl BoardData {
         let all_values_mask = ValueMask::from_all_values(size);
         let num_cells = size * size;
         let num_candidates = size * num_cells;
-        let houses = Self::create_houses(size, regions, &constraints);
+        let regions = if regions.len() == num_cells { regions.to_vec() } else { default_regions(size) };
+        let houses = Self::create_houses(size, &regions, &constraints);
         let houses_by_cell = Self::create_houses_by_cell(size, &houses);
+        let house_bits_by_cell = Self::create_house_bits_by_cell(size, &houses);
+        let relevant_cells = constraints.iter().map(|c| c.relevant_cells()).collect_vec();
+        let constraint_bits_by_cell = Self::create_constraint_bits_by_cell(size, &relevant_cells);
+        let enforced_constraints_by_cell = Self::create_enforced_constraints_by_cell(size, &relevant_cells);
         let weak_links = vec![CandidateLinks::new(size); num_candidates];
-        let exclusive_cells = vec![bitvec![0; num_cells]; num_cells];
+        let exclusive_cells = vec![OnceLock::new(); num_cells];
         let powerful_cells = constraints.iter().flat_map(|c| c.powerful_cells()).unique().collect();
 
         BoardData {
@@ -307,13 +828,22 @@ impl BoardData {
             num_cells,
             num_candidates,
             all_values_mask,
+            regions,
             houses,
             houses_by_cell,
+            house_bits_by_cell,
+            constraint_bits_by_cell,
+            enforced_constraints_by_cell,
             powerful_cells,
             weak_links,
             total_weak_links: 0,
+            weak_link_degrees: vec![0; num_candidates],
+            weak_link_stats: Vec::new(),
             exclusive_cells,
+            #[cfg(feature = "profiling")]
+            constraint_timers: Arc::new(ConstraintTimers::new(constraints.len())),
             constraints,
+            step_house_kinds: HouseKind::ALL.to_vec(),
         }
     }
 
@@ -333,11 +863,20 @@ impl BoardData {
         self.all_values_mask
     }
 
+    pub fn regions(&self) -> &[usize] {
+        &self.regions
+    }
+
     pub fn houses(&self) -> &[Arc<House>] {
         &self.houses
     }
 
-    pub fn houses_by_cell(&self) -> &[Vec<Arc<House>>] {
+    /// Whether logical steps should search the house. See [`SolverBuilder::with_step_house_kinds`].
+    pub fn is_step_house(&self, house: &House) -> bool {
+        self.step_house_kinds.contains(&house.kind())
+    }
+
+    pub fn houses_by_cell(&self) -> &[SmallVec<[Arc<House>; 4]>] {
         &self.houses_by_cell
     }
 
@@ -353,6 +892,10 @@ impl BoardData {
         self.total_weak_links
     }
 
+    pub fn weak_link_degree(&self, candidate: CandidateIndex) -> usize {
+        self.weak_link_degrees[candidate.index()]
+    }
+
     pub fn powerful_cells(&self) -> &[CellIndex] {
         &self.powerful_cells
     }
@@ -361,6 +904,28 @@ impl BoardData {
         &self.constraints
     }
 
+    /// Adds the time the function takes to the constraint's enforce time, when profiling.
+    #[cfg(feature = "profiling")]
+    pub(crate) fn time_enforce<T>(&self, constraint_index: usize, f: impl FnOnce() -> T) -> T {
+        self.constraint_timers.time_enforce(constraint_index, f)
+    }
+
+    #[cfg(not(feature = "profiling"))]
+    pub(crate) fn time_enforce<T>(&self, _constraint_index: usize, f: impl FnOnce() -> T) -> T {
+        f()
+    }
+
+    /// Adds the time the function takes to the constraint's step logic time, when profiling.
+    #[cfg(feature = "profiling")]
+    pub(crate) fn time_step_logic<T>(&self, constraint_index: usize, f: impl FnOnce() -> T) -> T {
+        self.constraint_timers.time_step_logic(constraint_index, f)
+    }
+
+    #[cfg(not(feature = "profiling"))]
+    pub(crate) fn time_step_logic<T>(&self, _constraint_index: usize, f: impl FnOnce() -> T) -> T {
+        f()
+    }
+
     fn take_constraint(&mut self, idx: usize) -> Arc<dyn Constraint> {
         self.constraints.remove(idx)
     }
@@ -374,13 +939,11 @@ impl BoardData {
     }
 
     pub fn is_exclusive(&self, cell1: CellIndex, cell2: CellIndex) -> bool {
-        self.exclusive_cells[cell1.index()][cell2.index()]
+        self.exclusive_cells[cell1.index()].get_or_init(|| self.create_exclusive_cells(cell1))[cell2.index()]
     }
 
     fn create_houses(size: usize, regions: &[usize], constraints: &[Arc<dyn Constraint>]) -> Vec<Arc<House>> {
         let cu = CellUtility::new(size);
-        let num_cells = size * size;
-        let regions = if regions.len() == num_cells { regions.to_vec() } else { default_regions(size) };
 
         let mut houses: Vec<Arc<House>> = Vec::new();
 
@@ -392,7 +955,7 @@ impl BoardData {
                 let cell = cu.cell(row, col);
                 house.push(cell);
             }
-            houses.push(Arc::new(House::new(&name, &house)));
+            houses.push(Arc::new(House::new(&name, &house).with_kind(HouseKind::Row)));
         }
 
         // Create a house for each column
@@ -403,7 +966,7 @@ impl BoardData {
                 let cell = cu.cell(row, col);
                 house.push(cell);
             }
-            houses.push(Arc::new(House::new(&name, &house)));
+            houses.push(Arc::new(House::new(&name, &house).with_kind(HouseKind::Column)));
         }
 
         // Create a house for each region
@@ -418,7 +981,7 @@ impl BoardData {
         for (region, house) in house_for_region.iter() {
             if house.len() == size {
                 let name = format!("Region {}", region + 1);
-                let house = House::new(&name, house);
+                let house = House::new(&name, house).with_kind(HouseKind::Region);
                 if !houses.iter().any(|h| h.cells() == house.cells()) {
                     houses.push(Arc::new(house));
                 }
@@ -427,7 +990,7 @@ impl BoardData {
 
         // Add any non-duplicate regions created by constraints
         for constraint in constraints.iter() {
-            let constraint_houses = constraint.get_houses(size);
+            let constraint_houses = constraint.get_houses(&HouseContext::new(size, regions, constraints, &houses));
             for house in constraint_houses {
                 if !houses.iter().any(|h| h.cells() == house.cells()) {
                     houses.push(Arc::new(house));
@@ -438,12 +1001,9 @@ impl BoardData {
         houses
     }
 
-    fn create_houses_by_cell(size: usize, houses: &[Arc<House>]) -> Vec<Vec<Arc<House>>> {
+    fn create_houses_by_cell(size: usize, houses: &[Arc<House>]) -> Vec<SmallVec<[Arc<House>; 4]>> {
         let num_cells = size * size;
-        let mut houses_by_cell = Vec::new();
-        for _ in 0..num_cells {
-            houses_by_cell.push(Vec::new());
-        }
+        let mut houses_by_cell = vec![SmallVec::new(); num_cells];
         for house in houses {
             for cell in house.cells().iter() {
                 houses_by_cell[cell.index()].push(house.clone());
@@ -452,22 +1012,77 @@ impl BoardData {
         houses_by_cell
     }
 
-    fn add_weak_link(&mut self, candidate1: CandidateIndex, candidate2: CandidateIndex) {
+    fn create_house_bits_by_cell(size: usize, houses: &[Arc<House>]) -> Vec<u64> {
+        let house_words = houses.len().div_ceil(64);
+        let mut house_bits_by_cell = vec![0; size * size * house_words];
+        for (house_index, house) in houses.iter().enumerate() {
+            for cell in house.cells().iter() {
+                house_bits_by_cell[cell.index() * house_words + house_index / 64] |= 1 << (house_index % 64);
+            }
+        }
+        house_bits_by_cell
+    }
+
+    /// Constraints without relevant cells are relevant to every cell.
+    fn create_constraint_bits_by_cell(size: usize, relevant_cells: &[Vec<CellIndex>]) -> Vec<u64> {
+        let num_cells = size * size;
+        let constraint_words = relevant_cells.len().div_ceil(64);
+        let mut constraint_bits_by_cell = vec![0; num_cells * constraint_words];
+        for (constraint_index, cells) in relevant_cells.iter().enumerate() {
+            let bit = 1 << (constraint_index % 64);
+            if cells.is_empty() {
+                for cell in 0..num_cells {
+                    constraint_bits_by_cell[cell * constraint_words + constraint_index / 64] |= bit;
+                }
+            } else {
+                for cell in cells {
+                    constraint_bits_by_cell[cell.index() * constraint_words + constraint_index / 64] |= bit;
+                }
+            }
+        }
+        constraint_bits_by_cell
+    }
+
+    fn create_enforced_constraints_by_cell(
+        size: usize,
+        relevant_cells: &[Vec<CellIndex>],
+    ) -> Vec<SmallVec<[usize; 4]>> {
+        let num_cells = size * size;
+        let mut enforced_constraints_by_cell = vec![SmallVec::new(); num_cells];
+        for (constraint_index, cells) in relevant_cells.iter().enumerate() {
+            if cells.is_empty() {
+                for constraints in enforced_constraints_by_cell.iter_mut() {
+                    constraints.push(constraint_index);
+                }
+            } else {
+                for cell in cells.iter().unique() {
+                    enforced_constraints_by_cell[cell.index()].push(constraint_index);
+                }
+            }
+        }
+        enforced_constraints_by_cell
+    }
+
+    /// Links the two candidates, returning false if they were already linked.
+    fn add_weak_link(&mut self, candidate1: CandidateIndex, candidate2: CandidateIndex) -> bool {
+        let mut added = false;
         if self.weak_links[candidate1.index()].set(candidate2, true) {
             self.total_weak_links += 1;
+            self.weak_link_degrees[candidate1.index()] += 1;
+            added = true;
         }
 
         if self.weak_links[candidate2.index()].set(candidate1, true) {
             self.total_weak_links += 1;
+            self.weak_link_degrees[candidate2.index()] += 1;
+            added = true;
         }
+        added
     }
 
     fn init_weak_links(&mut self) -> EliminationList {
         self.init_sudoku_weak_links();
-        let elminiation_list = self.init_constraint_weak_links();
-        self.init_exclusive_cells();
-
-        elminiation_list
+        self.init_constraint_weak_links()
     }
 
     fn init_sudoku_weak_links(&mut self) {
@@ -482,12 +1097,12 @@ impl BoardData {
                 let candidate2 = cu.candidate(cell1, val2);
                 self.add_weak_link(candidate1, candidate2);
             }
+        }
 
-            // Add a weak link to every other candidate with the same value that shares a house
-            for house in self.houses_by_cell[cell1.index()].clone() {
-                for (cand0, cand1) in cu.candidate_pairs(house.cells()) {
-                    self.add_weak_link(cand0, cand1);
-                }
+        // Add a weak link between every pair of candidates with the same value that share a house
+        for house in self.houses.clone() {
+            for (cand0, cand1) in cu.candidate_pairs(house.cells()) {
+                self.add_weak_link(cand0, cand1);
             }
         }
     }
@@ -496,34 +1111,45 @@ impl BoardData {
         let mut elims: EliminationList = EliminationList::new();
         for constraint in self.constraints.clone() {
             let weak_links = constraint.get_weak_links(self.size);
+            let mut stats = WeakLinkStats {
+                constraint: constraint.name().to_owned(),
+                links: weak_links.len(),
+                added: 0,
+                duplicates: 0,
+                self_eliminations: 0,
+            };
             for (candidate0, candidate1) in weak_links {
-                if candidate0 != candidate1 {
-                    self.add_weak_link(candidate0, candidate1);
-                } else {
+                if candidate0 == candidate1 {
                     elims.add(candidate0);
+                    stats.self_eliminations += 1;
+                } else if self.add_weak_link(candidate0, candidate1) {
+                    stats.added += 1;
+                } else {
+                    stats.duplicates += 1;
                 }
             }
+            self.weak_link_stats.push(stats);
         }
         elims
     }
 
-    fn init_exclusive_cells(&mut self) {
+    /// Finds the cells which have a weak link to the given cell for every value.
+    fn create_exclusive_cells(&self, cell1: CellIndex) -> BitVec {
         let cu = CellUtility::new(self.size);
-        for (cell1, cell2) in (0..self.num_cells).tuple_combinations() {
-            let cell1 = cu.cell_index(cell1);
-            let cell2 = cu.cell_index(cell2);
-            let mut exclusive = true;
-            for val in 1..=self.size {
+        let mut exclusive_cells = bitvec![0; self.num_cells];
+        for cell2 in cu.all_cells() {
+            if cell1 == cell2 {
+                continue;
+            }
+
+            let exclusive = (1..=self.size).all(|val| {
                 let candidate1 = cu.candidate(cell1, val);
                 let candidate2 = cu.candidate(cell2, val);
-                if !self.weak_links[candidate1.index()].is_linked(candidate2) {
-                    exclusive = false;
-                    break;
-                }
-            }
-            self.exclusive_cells[cell1.index()].set(cell2.index(), exclusive);
-            self.exclusive_cells[cell2.index()].set(cell1.index(), exclusive);
+                self.weak_links[candidate1.index()].is_linked(candidate2)
+            });
+            exclusive_cells.set(cell2.index(), exclusive);
         }
+        exclusive_cells
     }
 }
 
@@ -576,6 +1202,7 @@ mod test {
         assert_eq!(board.num_candidates(), 729);
         assert_eq!(board.houses().len(), 27);
         assert_eq!(board.total_weak_links(), ((board.size() - 1) * 4 - 4) * board.num_candidates());
+        assert_eq!(board.weak_link_degree(board.cell_utility().candidate(CellIndex::new(40, 9), 5)), 28);
     }
 
     #[test]
@@ -587,4 +1214,260 @@ mod test {
         assert_eq!(board.houses().len(), 48);
         assert_eq!(board.total_weak_links(), ((board.size() - 1) * 4 - 6) * board.num_candidates());
     }
+
+    #[test]
+    fn test_is_exclusive() {
+        let board = Board::default();
+        let cu = board.cell_utility();
+
+        assert!(board.is_exclusive(cu.cell(0, 0), cu.cell(0, 8)));
+        assert!(board.is_exclusive(cu.cell(0, 8), cu.cell(0, 0)));
+        assert!(board.is_exclusive(cu.cell(0, 0), cu.cell(2, 2)));
+        assert!(!board.is_exclusive(cu.cell(0, 0), cu.cell(4, 4)));
+        assert!(!board.is_exclusive(cu.cell(0, 0), cu.cell(0, 0)));
+        assert!(board.is_grouped(&[cu.cell(3, 0), cu.cell(4, 1), cu.cell(5, 2)]));
+        assert!(!board.is_grouped(&[cu.cell(3, 0), cu.cell(3, 4), cu.cell(4, 1)]));
+    }
+
+    /// Stops 1 in r4c1 from seeing 2 or 3 in r9c9.
+    #[derive(Debug)]
+    struct LinkedCells;
+
+    impl Constraint for LinkedCells {
+        fn name(&self) -> &str {
+            "Linked Cells"
+        }
+
+        fn get_weak_links(&self, size: usize) -> Vec<(CandidateIndex, CandidateIndex)> {
+            let cu = CellUtility::new(size);
+            let candidate = cu.cell(3, 0).candidate(1);
+            vec![(candidate, cu.cell(8, 8).candidate(2)), (candidate, cu.cell(8, 8).candidate(3))]
+        }
+    }
+
+    #[test]
+    fn test_links_between() {
+        let board = Board::default();
+        let cu = board.cell_utility();
+        let (cell_a, cell_b) = (cu.cell(3, 0), cu.cell(8, 8));
+        assert!(board.links_between(cell_a, cell_b).is_empty());
+        assert_eq!(board.links_between(cell_a, cu.cell(3, 8)).len(), 9);
+
+        let board = Board::new(9, &[], vec![Arc::new(LinkedCells)]);
+        assert_eq!(board.links_between(cell_a, cell_b), vec![(1, 2), (1, 3)]);
+        assert_eq!(board.links_between(cell_b, cell_a), vec![(2, 1), (3, 1)]);
+    }
+
+    /// Returns each pair twice, a pair already linked by the row, and removes 5 from r1c1.
+    #[derive(Debug)]
+    struct RepeatedLinks;
+
+    impl Constraint for RepeatedLinks {
+        fn name(&self) -> &str {
+            "Repeated Links"
+        }
+
+        fn get_weak_links(&self, size: usize) -> Vec<(CandidateIndex, CandidateIndex)> {
+            let cu = CellUtility::new(size);
+            let (a, b) = (cu.cell(3, 0).candidate(1), cu.cell(8, 8).candidate(2));
+            let row_pair = (cu.cell(0, 0).candidate(1), cu.cell(0, 1).candidate(1));
+            let five = cu.cell(0, 0).candidate(5);
+            vec![(a, b), (b, a), (a, b), row_pair, (five, five)]
+        }
+    }
+
+    #[test]
+    fn test_weak_link_stats() {
+        let board = Board::new(9, &[], vec![Arc::new(LinkedCells), Arc::new(RepeatedLinks)]);
+        let stats = board.weak_link_stats();
+        assert_eq!(
+            stats[0],
+            WeakLinkStats {
+                constraint: "Linked Cells".to_owned(),
+                links: 2,
+                added: 2,
+                duplicates: 0,
+                self_eliminations: 0
+            }
+        );
+        assert_eq!(
+            stats[1],
+            WeakLinkStats {
+                constraint: "Repeated Links".to_owned(),
+                links: 5,
+                added: 0,
+                duplicates: 4,
+                self_eliminations: 1
+            }
+        );
+        assert_eq!(
+            stats[1].to_string(),
+            "Repeated Links: 5 weak links, 0 added, 4 duplicates ignored, 1 self-eliminations"
+        );
+        assert_eq!(board.total_weak_links(), Board::default().total_weak_links() + 4);
+    }
+
+    #[test]
+    fn test_set_solved_many() {
+        let cu = CellUtility::new(9);
+        let placements = [(cu.cell(0, 0), 1), (cu.cell(4, 4), 2), (cu.cell(8, 8), 3), (cu.cell(0, 0), 1)];
+
+        let mut board = Board::new(9, &[], vec![Arc::new(LinkedCells)]);
+        let mut expected = board.clone();
+        assert!(board.set_solved_many(&placements).is_ok());
+        for &(cell, value) in &placements[..3] {
+            assert!(expected.set_solved(cell, value));
+        }
+        assert!(board.all_cell_masks().eq(expected.all_cell_masks()));
+        assert_eq!(board.solution_hash(), expected.solution_hash());
+
+        // Already solved to the same value is skipped, but a different value fails
+        assert!(board.set_solved_many(&[(cu.cell(4, 4), 2)]).is_ok());
+        assert_eq!(board.clone().set_solved_many(&[(cu.cell(4, 4), 3)]), Err((cu.cell(4, 4), 3)));
+
+        // 1 in r4c1 removes 3 from r9c9 through the constraint's weak links
+        let mut board = Board::new(9, &[], vec![Arc::new(LinkedCells)]);
+        assert_eq!(board.set_solved_many(&[(cu.cell(3, 0), 1), (cu.cell(8, 8), 3)]), Err((cu.cell(8, 8), 3)));
+    }
+
+    #[test]
+    fn test_solution_hash() {
+        let board = Board::default();
+        let cu = board.cell_utility();
+        assert_eq!(board.solution_hash(), 0);
+
+        let mut board1 = board.clone();
+        assert!(board1.set_solved(cu.cell(0, 0), 1));
+        assert!(board1.set_solved(cu.cell(4, 4), 2));
+
+        let mut board2 = board.clone();
+        assert!(board2.set_solved(cu.cell(4, 4), 2));
+        assert!(board2.set_solved(cu.cell(0, 0), 1));
+        assert_eq!(board1.solution_hash(), board2.solution_hash());
+
+        let mut board3 = board.clone();
+        assert!(board3.set_solved(cu.cell(0, 0), 2));
+        assert!(board3.set_solved(cu.cell(4, 4), 1));
+        assert_ne!(board1.solution_hash(), board3.solution_hash());
+    }
+
+    #[test]
+    fn test_changed_cells() {
+        let mut board = Board::default();
+        let cu = board.cell_utility();
+        while board.take_changed_cell().is_some() {}
+        while board.take_changed_house().is_some() {}
+
+        // Clearing a candidate that is already gone is not a change
+        assert!(board.clear_value(cu.cell(2, 3), 4));
+        assert!(board.clear_value(cu.cell(2, 3), 4));
+        assert_eq!(board.take_changed_cell(), Some(cu.cell(2, 3)));
+        assert_eq!(board.take_changed_cell(), None);
+
+        // The row, column and box of the cell have changed
+        let mut changed_houses = Vec::new();
+        while let Some(house) = board.take_changed_house() {
+            changed_houses.push(board.houses()[house].name().to_owned());
+        }
+        changed_houses.sort();
+        assert_eq!(changed_houses, vec!["Column 4", "Region 2", "Row 3"]);
+    }
+
+    #[test]
+    fn test_revision() {
+        let mut board = Board::default();
+        let cu = board.cell_utility();
+        let revision = board.revision();
+
+        assert!(board.clear_value(cu.cell(0, 0), 1));
+        assert_eq!(board.revision(), revision + 1);
+
+        // Clearing a candidate that is already gone is not a change
+        assert!(board.clear_value(cu.cell(0, 0), 1));
+        assert_eq!(board.revision(), revision + 1);
+
+        let clone = board.clone();
+        assert_eq!(clone.revision(), board.revision());
+    }
+
+    #[test]
+    fn test_candidates_remaining() {
+        let mut board = Board::default();
+        let cu = board.cell_utility();
+        assert_eq!(board.candidates_remaining(), 729);
+        assert_eq!(board.empty_cells(), 81);
+        assert_eq!(board.percent_solved(), 0.0);
+
+        assert!(board.clear_value(cu.cell(8, 8), 5));
+        assert_eq!(board.candidates_remaining(), 728);
+
+        // The solved cell keeps one candidate, and its 20 neighbours each lose one
+        assert!(board.set_solved(cu.cell(0, 0), 1));
+        assert_eq!(board.candidates_remaining(), 728 - 8 - 20);
+        assert_eq!(board.empty_cells(), 80);
+        assert_eq!(board.percent_solved(), 100.0 / 81.0);
+
+        let counted: usize = board.all_cell_masks().map(|(_, mask)| mask.count()).sum();
+        assert_eq!(board.candidates_remaining(), counted);
+        assert_eq!(board.clone().candidates_remaining(), counted);
+    }
+
+    #[derive(Default)]
+    struct ChangeLog {
+        changes: Mutex<Vec<BoardChange>>,
+    }
+
+    impl BoardObserver for ChangeLog {
+        fn cell_changed(&self, change: &BoardChange) {
+            self.changes.lock().unwrap().push(*change);
+        }
+    }
+
+    #[test]
+    fn test_observer() {
+        let mut board = Board::default();
+        let cu = board.cell_utility();
+        let log = Arc::new(ChangeLog::default());
+        board.set_observer(log.clone());
+
+        assert!(board.clear_value(cu.cell(0, 0), 1));
+        assert!(board.clear_value(cu.cell(0, 0), 1));
+        {
+            let changes = log.changes.lock().unwrap();
+            assert_eq!(changes.len(), 1);
+            assert_eq!(changes[0].cell, cu.cell(0, 0));
+            assert_eq!(changes[0].old_mask, board.all_values_mask());
+            assert_eq!(changes[0].new_mask, board.all_values_mask().without(1));
+            assert_eq!(changes[0].revision, board.revision());
+        }
+
+        // Clones are not observed
+        let mut clone = board.clone();
+        assert!(clone.clear_value(cu.cell(0, 0), 2));
+        assert_eq!(log.changes.lock().unwrap().len(), 1);
+
+        // Solving a cell reports the cell and every candidate removed by its weak links
+        assert!(board.set_solved(cu.cell(8, 8), 9));
+        assert_eq!(log.changes.lock().unwrap().len(), 1 + 1 + 20);
+
+        assert!(board.take_observer().is_some());
+        assert!(board.clear_value(cu.cell(0, 0), 2));
+        assert_eq!(log.changes.lock().unwrap().len(), 22);
+    }
+
+    #[test]
+    fn test_must_contain_cache_limit() {
+        let board = Board::default();
+        let cells = [board.cell_utility().cell(0, 0)];
+        for constraint_index in 0..MUST_CONTAIN_CACHE_LIMIT + 1 {
+            board.memoize_cells_must_contain(constraint_index, 1, &cells, Vec::new);
+        }
+        assert_eq!(board.must_contain_cache.lock().unwrap().len(), MUST_CONTAIN_CACHE_LIMIT);
+
+        // The oldest entry was forgotten, so it is computed again.
+        let result = board.memoize_cells_must_contain(0, 1, &cells, || cells.to_vec());
+        assert_eq!(result, cells);
+        let result = board.memoize_cells_must_contain(MUST_CONTAIN_CACHE_LIMIT, 1, &cells, || cells.to_vec());
+        assert!(result.is_empty());
+    }
 }
@@ -0,0 +1,30 @@
+//! Contains the [`BoardObserver`] trait for being told about changes to a [`Board`]
+//! and the [`BoardChange`] which describes each change.
+
+use crate::prelude::*;
+
+/// A change to the candidates of a single cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoardChange {
+    /// The cell which changed.
+    pub cell: CellIndex,
+    /// The candidates of the cell before the change.
+    pub old_mask: ValueMask,
+    /// The candidates of the cell after the change.
+    pub new_mask: ValueMask,
+    /// The [`Board::revision`] of the board after the change.
+    pub revision: u64,
+}
+
+/// A trait for observing the changes made to a [`Board`].
+///
+/// An observer is attached with [`Board::set_observer`]. It is told about every change to the
+/// candidates of a cell, which allows caches built from the board to invalidate only what
+/// the change affects instead of being rebuilt from scratch.
+///
+/// Clones of the board do not keep the observer, so the many boards cloned while brute
+/// forcing are not observed.
+pub trait BoardObserver: Send + Sync {
+    /// Called after the candidates of a cell have changed.
+    fn cell_changed(&self, change: &BoardChange);
+}
@@ -0,0 +1,259 @@
+//! Contains the [`BoardRecorder`] for recording the changes made to a [`Board`]
+//! and the [`ReplayScript`] for replaying them onto another board.
+
+use crate::prelude::*;
+use std::sync::{Arc, Mutex};
+
+/// What a recorded change did to its cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayAction {
+    /// The cell was solved with the value.
+    Place(usize),
+    /// The values were removed from the candidates of the cell.
+    Eliminate(ValueMask),
+}
+
+/// A single change of a [`ReplayScript`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedChange {
+    /// The cell which changed.
+    pub cell: CellIndex,
+    /// What the change did to the cell.
+    pub action: ReplayAction,
+    /// The name of the logical step which made the change, if it was made by one.
+    pub source: Option<String>,
+}
+
+impl RecordedChange {
+    fn from_board_change(change: &BoardChange, source: Option<String>) -> Self {
+        let action = if change.new_mask.is_solved() && !change.old_mask.is_solved() {
+            ReplayAction::Place(change.new_mask.value())
+        } else {
+            ReplayAction::Eliminate((change.old_mask & !change.new_mask).unsolved())
+        };
+        Self { cell: change.cell, action, source }
+    }
+}
+
+impl std::fmt::Display for RecordedChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.action {
+            ReplayAction::Place(value) => write!(f, "{}={value}", self.cell)?,
+            ReplayAction::Eliminate(values) => write!(f, "{}-{values}", self.cell)?,
+        }
+        if let Some(source) = &self.source {
+            write!(f, " ({source})")?;
+        }
+        Ok(())
+    }
+}
+
+/// The placements and eliminations made to a board, in the order they were made.
+///
+/// The script is written one change per line, such as `r1c1=5 (Naked Single)` for a placement
+/// or `r2c3-1,4 (Hidden Single)` for an elimination, and can be parsed back with [`ReplayScript::parse`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReplayScript {
+    changes: Vec<RecordedChange>,
+}
+
+impl ReplayScript {
+    /// The changes of the script.
+    pub fn changes(&self) -> &[RecordedChange] {
+        &self.changes
+    }
+
+    /// Parses a script written by the [`std::fmt::Display`] implementation.
+    pub fn parse(text: &str, size: usize) -> Result<Self, String> {
+        let cu = CellUtility::new(size);
+        let mut changes = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let error = || format!("Line {} is not a recorded change: {line}", index + 1);
+            let (change, source) = match line.split_once(' ') {
+                Some((change, source)) => {
+                    let source = source.trim().strip_prefix('(').and_then(|source| source.strip_suffix(')'));
+                    (change, Some(source.ok_or_else(error)?.to_owned()))
+                }
+                None => (line, None),
+            };
+            let Some(split) = change.find(['=', '-']) else {
+                return Err(error());
+            };
+            let cell = match cu.parse_cell_groups(&change[..split]) {
+                Ok(groups) if groups.len() == 1 && groups[0].len() == 1 => groups[0][0],
+                _ => return Err(error()),
+            };
+            let values: Vec<usize> = change[split + 1..]
+                .split(',')
+                .map(|value| value.parse().ok().filter(|value| (1..=size).contains(value)))
+                .collect::<Option<_>>()
+                .ok_or_else(error)?;
+            let action = match (&change[split..split + 1], values.as_slice()) {
+                ("=", &[value]) => ReplayAction::Place(value),
+                ("-", values) => ReplayAction::Eliminate(ValueMask::from_values(values)),
+                _ => return Err(error()),
+            };
+            changes.push(RecordedChange { cell, action, source });
+        }
+        Ok(Self { changes })
+    }
+
+    /// Applies the changes to a board in the same state as the recorded board was when recording started.
+    ///
+    /// Placements do not apply their weak links or constraints, as the eliminations they
+    /// made were recorded as changes of their own. Fails at the first change which places
+    /// a value which isn't a candidate or eliminates a candidate which is already gone,
+    /// as the board has then gone differently than when it was recorded.
+    pub fn replay(&self, board: &mut Board) -> Result<(), String> {
+        for (index, change) in self.changes.iter().enumerate() {
+            let mask = board.cell(change.cell);
+            match change.action {
+                ReplayAction::Place(value) => {
+                    if mask.is_solved() || !mask.has(value) {
+                        return Err(format!("Change {}: {} cannot be {value}", index + 1, change.cell));
+                    }
+                    board.fill_unchecked(change.cell, value);
+                }
+                ReplayAction::Eliminate(values) => {
+                    if (mask & values) != values {
+                        return Err(format!("Change {}: {} does not have {values}", index + 1, change.cell));
+                    }
+                    board.clear_mask(change.cell, values);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for ReplayScript {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for change in self.changes.iter() {
+            writeln!(f, "{change}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A [`BoardObserver`] which records every change made to a board into a [`ReplayScript`].
+///
+/// Use [`BoardRecorder::record`] to record the changes of a piece of work along with their
+/// source, or attach a recorder to a [`Solver`] with [`Solver::set_recorder`] to record its logical steps.
+///
+/// # Example
+/// ```
+/// # use sudoku_solver_lib::prelude::*;
+/// # use std::sync::Arc;
+/// let mut solver = SolverBuilder::default()
+///     .with_givens_string("8...62..125.....7..197...5........9.....28..3.....36.54...1..6...74...3.5.2......")
+///     .build()
+///     .unwrap();
+/// let mut start = solver.board().clone();
+///
+/// let recorder = Arc::new(BoardRecorder::default());
+/// solver.set_recorder(Some(recorder.clone()));
+/// assert!(solver.run_logical_solve().is_solved());
+///
+/// let script = recorder.script();
+/// script.replay(&mut start).unwrap();
+/// assert_eq!(start.to_string(), solver.board().to_string());
+/// ```
+#[derive(Default)]
+pub struct BoardRecorder {
+    changes: Mutex<Vec<RecordedChange>>,
+    source: Mutex<Option<String>>,
+    /// The observer the board had before recording started, which is still told about changes.
+    previous_observer: Mutex<Option<Arc<dyn BoardObserver>>>,
+}
+
+impl BoardRecorder {
+    /// Runs the function on the board, recording the changes it makes as coming from the source.
+    ///
+    /// The recorder is the observer of the board while the function runs, passing each change on
+    /// to any previous observer of the board, which is restored afterwards.
+    pub fn record<T>(self: &Arc<Self>, board: &mut Board, source: Option<&str>, f: impl FnOnce(&mut Board) -> T) -> T {
+        let previous_observer = board.take_observer();
+        *self.source.lock().unwrap() = source.map(str::to_owned);
+        *self.previous_observer.lock().unwrap() = previous_observer.clone();
+        board.set_observer(self.clone());
+
+        let result = f(board);
+
+        board.take_observer();
+        *self.source.lock().unwrap() = None;
+        *self.previous_observer.lock().unwrap() = None;
+        if let Some(previous_observer) = previous_observer {
+            board.set_observer(previous_observer);
+        }
+        result
+    }
+
+    /// The changes recorded so far.
+    pub fn script(&self) -> ReplayScript {
+        ReplayScript { changes: self.changes.lock().unwrap().clone() }
+    }
+
+    /// Forgets the changes recorded so far.
+    pub fn clear(&self) {
+        self.changes.lock().unwrap().clear();
+    }
+}
+
+impl BoardObserver for BoardRecorder {
+    fn cell_changed(&self, change: &BoardChange) {
+        let source = self.source.lock().unwrap().clone();
+        self.changes.lock().unwrap().push(RecordedChange::from_board_change(change, source));
+        if let Some(previous_observer) = self.previous_observer.lock().unwrap().as_ref() {
+            previous_observer.cell_changed(change);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_record_and_replay() {
+        let mut board = Board::default();
+        let cu = board.cell_utility();
+        let start = board.clone();
+
+        let recorder = Arc::new(BoardRecorder::default());
+        recorder.record(&mut board, Some("Test"), |board| {
+            assert!(board.clear_value(cu.cell(0, 1), 5));
+            assert!(board.set_solved(cu.cell(0, 0), 1));
+        });
+        let recorded_board = board.clone();
+
+        // Changes outside of record are not recorded
+        assert!(board.clear_value(cu.cell(8, 8), 9));
+
+        let script = recorder.script();
+        assert_eq!(script.changes().len(), 1 + 1 + 20);
+        assert_eq!(script.changes()[0].to_string(), "r1c2-5 (Test)");
+        assert_eq!(script.changes()[1].to_string(), "r1c1=1 (Test)");
+        assert_eq!(ReplayScript::parse(&script.to_string(), 9).unwrap(), script);
+
+        let mut replayed = start.clone();
+        script.replay(&mut replayed).unwrap();
+        assert!(replayed.all_cell_masks().eq(recorded_board.all_cell_masks()));
+        assert_eq!(replayed.solved_count(), 1);
+        assert_eq!(replayed.solution_hash(), recorded_board.solution_hash());
+
+        // The board has gone differently
+        let mut other = start.clone();
+        assert!(other.set_solved(cu.cell(0, 0), 2));
+        assert!(script.replay(&mut other).is_err());
+
+        assert!(ReplayScript::parse("r1c1=10", 9).is_err());
+        assert!(ReplayScript::parse("r1c1=1,2", 9).is_err());
+        assert!(ReplayScript::parse("r1c1-1 Test", 9).is_err());
+        assert_eq!(ReplayScript::parse("r1c1-1,2", 9).unwrap().changes()[0].source, None);
+    }
+}
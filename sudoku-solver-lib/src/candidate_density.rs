@@ -0,0 +1,126 @@
+//! Contains [`CandidateDensity`] for describing how constrained each area of a board is.
+
+use crate::prelude::*;
+
+/// How many candidates remain in a house. See [`CandidateDensity`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HouseDensity {
+    /// The name of the house, such as `Row 1`.
+    pub name: String,
+    /// The kind of the house.
+    pub kind: HouseKind,
+    /// The candidates remaining in the cells of the house, counting each solved cell as one.
+    pub candidates: usize,
+    /// The cells of the house which aren't solved yet.
+    pub unsolved_cells: usize,
+    /// For each value, starting with 1, the number of cells of the house it can still go in.
+    pub value_positions: Vec<usize>,
+}
+
+impl HouseDensity {
+    fn new(board: &Board, house: &House) -> Self {
+        let masks = house.cells().iter().map(|&cell| board.cell(cell)).collect::<Vec<_>>();
+        Self {
+            name: house.name().to_owned(),
+            kind: house.kind(),
+            candidates: masks.iter().map(|mask| mask.count()).sum(),
+            unsolved_cells: masks.iter().filter(|mask| !mask.is_solved()).count(),
+            value_positions: (1..=board.size())
+                .map(|value| masks.iter().filter(|mask| mask.has(value)).count())
+                .collect(),
+        }
+    }
+
+    /// The fraction of the candidates of the house which remain, from `1.0` for a house with every
+    /// candidate left to `1.0 / size` for a solved house.
+    pub fn fraction_remaining(&self) -> f64 {
+        let size = self.value_positions.len();
+        let total = size * size;
+        if total == 0 {
+            0.0
+        } else {
+            self.candidates as f64 / total as f64
+        }
+    }
+}
+
+/// How many candidates remain in each cell and house of a board, for interfaces which show
+/// how constrained the different areas of the grid are, such as a heatmap.
+///
+/// # Example
+/// ```
+/// # use sudoku_solver_lib::prelude::*;
+/// let solver = SolverBuilder::default().with_givens_string(&("123456789".to_owned() + &".".repeat(72))).build().unwrap();
+/// let density = solver.board().candidate_density();
+/// let cu = solver.cell_utility();
+/// assert_eq!(density.cell_count(cu.cell(0, 0)), 1);
+/// assert_eq!(density.cell_count(cu.cell(1, 0)), 6);
+///
+/// let row = density.houses().iter().find(|house| house.name == "Row 1").unwrap();
+/// assert_eq!(row.unsolved_cells, 0);
+/// assert_eq!(row.value_positions, vec![1; 9]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CandidateDensity {
+    cell_counts: Vec<usize>,
+    houses: Vec<HouseDensity>,
+}
+
+impl CandidateDensity {
+    /// Measures the candidates of the board. This is the same as [`Board::candidate_density`].
+    pub fn new(board: &Board) -> Self {
+        Self {
+            cell_counts: board.all_cell_masks().map(|(_, mask)| mask.count()).collect(),
+            houses: board.houses().iter().map(|house| HouseDensity::new(board, house)).collect(),
+        }
+    }
+
+    /// The number of candidates of each cell, indexed by [`CellIndex::index`].
+    pub fn cell_counts(&self) -> &[usize] {
+        &self.cell_counts
+    }
+
+    /// The number of candidates of the cell.
+    pub fn cell_count(&self, cell: CellIndex) -> usize {
+        self.cell_counts[cell.index()]
+    }
+
+    /// The candidates of each house, in the same order as [`Board::houses`].
+    pub fn houses(&self) -> &[HouseDensity] {
+        &self.houses
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_candidate_density() {
+        let mut board = Board::default();
+        let cu = board.cell_utility();
+
+        let density = board.candidate_density();
+        assert!(density.cell_counts().iter().all(|&count| count == 9));
+        assert_eq!(density.houses().len(), 27);
+        assert!(density.houses().iter().all(|house| house.fraction_remaining() == 1.0));
+
+        assert!(board.set_solved(cu.cell(0, 0), 1));
+        let density = board.candidate_density();
+        assert_eq!(density.cell_count(cu.cell(0, 0)), 1);
+        assert_eq!(density.cell_count(cu.cell(0, 8)), 8);
+        assert_eq!(density.cell_count(cu.cell(8, 8)), 9);
+
+        let row = &density.houses()[0];
+        assert_eq!(row.kind, HouseKind::Row);
+        assert_eq!(row.candidates, 1 + 8 * 8);
+        assert_eq!(row.unsolved_cells, 8);
+        assert_eq!(row.value_positions[0], 1);
+        assert_eq!(row.value_positions[1], 8);
+
+        // The last row is untouched except for r9c1, which sees the 1
+        let last_row = &density.houses()[8];
+        assert_eq!(last_row.candidates, 9 * 9 - 1);
+        assert_eq!(last_row.value_positions[0], 8);
+    }
+}
@@ -35,6 +35,11 @@ impl CandidateIndex {
         self.size
     }
 
+    /// Gets the [`CompactCandidateIndex`] of this candidate, which doesn't store the size.
+    pub fn compact(&self) -> CompactCandidateIndex {
+        CompactCandidateIndex::from(*self)
+    }
+
     /// Gets the cell index of the candidate.
     pub fn cell_index(&self) -> CellIndex {
         CellIndex::new(self.index / self.size, self.size)
@@ -84,6 +89,27 @@ impl std::hash::Hash for CandidateIndex {
     }
 }
 
+/// The index of a candidate without the size of the board, for storing many candidates compactly.
+///
+/// Like [`CompactCellIndex`], this has no methods which need the size. Convert it back to a
+/// [`CandidateIndex`] with [`CellUtility::candidate_from_compact`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CompactCandidateIndex(u32);
+
+impl CompactCandidateIndex {
+    /// Gets the index of the candidate.
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl From<CandidateIndex> for CompactCandidateIndex {
+    fn from(candidate: CandidateIndex) -> Self {
+        debug_assert!(candidate.index() <= u32::MAX as usize);
+        Self(candidate.index() as u32)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -97,4 +123,20 @@ mod tests {
         assert_eq!(CandidateIndex::from_cv(CellIndex::new(40, 9), 5).index(), 364);
         assert_eq!(CandidateIndex::from_cv(CellIndex::new(80, 9), 9).index(), 728);
     }
+
+    #[test]
+    fn test_compact_candidate_index() {
+        let cu = CellUtility::new(16);
+        for candidate in cu.all_candidates() {
+            assert_eq!(cu.candidate_from_compact(candidate.compact()), candidate);
+        }
+        for cell in cu.all_cells() {
+            assert_eq!(cu.cell_from_compact(cell.compact()), cell);
+        }
+
+        // The compact indexes are a quarter and an eighth of the size on 64-bit targets.
+        assert_eq!(std::mem::size_of::<CompactCandidateIndex>(), 4);
+        assert_eq!(std::mem::size_of::<CompactCellIndex>(), 2);
+        assert_eq!(std::mem::size_of::<CandidateIndex>(), 2 * std::mem::size_of::<usize>());
+    }
 }
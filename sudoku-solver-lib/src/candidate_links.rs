@@ -11,7 +11,7 @@ use bitvec::prelude::*;
 /// Internally, a BitVec is used to store the links. The index of the
 /// BitVec is the index of the candidate. The value of the BitVec is
 /// whether the candidate is linked to the candidate with the index of
-/// the BitVec.
+/// the BitVec. Since no indexes are stored, it has no use for [`CompactCandidateIndex`].
 #[derive(Clone, Debug)]
 pub struct CandidateLinks {
     links: BitVec,
@@ -26,6 +26,16 @@ impl CandidateLinks {
         Self { links: bitvec!(0; num_candidates), size }
     }
 
+    /// The approximate number of bytes the links of a board of the given size take.
+    pub fn memory_size_for(size: usize) -> usize {
+        std::mem::size_of::<Self>() + bit_set_size(size * size * size)
+    }
+
+    /// The approximate number of bytes the links take.
+    pub fn memory_size(&self) -> usize {
+        std::mem::size_of::<Self>() + std::mem::size_of_val(self.links.as_raw_slice())
+    }
+
     /// Returns true if the candidate is linked
     pub fn is_linked(&self, candidate: CandidateIndex) -> bool {
         self.links[candidate.index()]
@@ -63,7 +73,75 @@ impl CandidateLinks {
     pub fn links(&self) -> impl Iterator<Item = CandidateIndex> + '_ {
         let cu = CellUtility::new(self.size);
 
-        self.links.iter().enumerate().filter_map(move |(i, b)| if *b { Some(cu.candidate_index(i)) } else { None })
+        self.ones().map(move |i| cu.candidate_index(i))
+    }
+
+    /// Returns an iterator over the cells with linked candidates, along with the mask of linked values in each cell.
+    ///
+    /// Candidates of the same cell are stored next to each other, so this lets callers apply
+    /// all of the links to a cell at once instead of one candidate at a time.
+    pub fn cell_masks(&self) -> impl Iterator<Item = (CellIndex, ValueMask)> + '_ {
+        self.cell_masks_with_size(self.size)
+    }
+
+    /// The same as [`Self::cell_masks`], but with the board size known at compile time.
+    ///
+    /// Finding the cell of each candidate divides by the size, which is much cheaper when
+    /// the size is a constant. Panics if `SIZE` is not the size of the board.
+    pub fn cell_masks_of_size<const SIZE: usize>(&self) -> impl Iterator<Item = (CellIndex, ValueMask)> + '_ {
+        assert_eq!(self.size, SIZE);
+        self.cell_masks_with_size(ConstSize::<SIZE>)
+    }
+
+    fn cell_masks_with_size(&self, size: impl GridSize) -> impl Iterator<Item = (CellIndex, ValueMask)> + '_ {
+        let mut ones = self.ones().peekable();
+        std::iter::from_fn(move || {
+            let size = size.get();
+            let first = ones.next()?;
+            let cell = first / size;
+            let mut mask = ValueMask::from_value(first % size + 1);
+            while let Some(next) = ones.next_if(|next| next / size == cell) {
+                mask = mask.with(next % size + 1);
+            }
+            Some((CellIndex::new(cell, size), mask))
+        })
+    }
+
+    /// The indexes of the linked candidates.
+    ///
+    /// This walks the raw words of the bit vector, which is a lot faster than [`BitSlice::iter_ones`].
+    fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.links.as_raw_slice().iter().enumerate().flat_map(|(word_index, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(word_index * usize::BITS as usize + bit)
+            })
+        })
+    }
+}
+
+/// A board size which is either known at compile time or only at runtime.
+trait GridSize: Copy + 'static {
+    fn get(self) -> usize;
+}
+
+impl GridSize for usize {
+    fn get(self) -> usize {
+        self
+    }
+}
+
+#[derive(Clone, Copy)]
+struct ConstSize<const SIZE: usize>;
+
+impl<const SIZE: usize> GridSize for ConstSize<SIZE> {
+    fn get(self) -> usize {
+        SIZE
     }
 }
 
@@ -76,3 +154,31 @@ impl std::fmt::Display for CandidateLinks {
         write!(f, " }}")
     }
 }
+
+#[cfg(test)]
+mod test {
+    use itertools::assert_equal;
+
+    use super::*;
+
+    #[test]
+    fn test_cell_masks() {
+        let cu = CellUtility::new(9);
+        let mut links = CandidateLinks::new(9);
+        links.set(cu.candidate(cu.cell(0, 1), 3), true);
+        links.set(cu.candidate(cu.cell(0, 1), 9), true);
+        links.set(cu.candidate(cu.cell(0, 2), 1), true);
+        links.set(cu.candidate(cu.cell(8, 8), 5), true);
+
+        let cell_masks: Vec<_> = links.cell_masks().collect();
+        assert_equal(links.cell_masks_of_size::<9>(), cell_masks.iter().copied());
+        assert_eq!(
+            cell_masks,
+            vec![
+                (cu.cell(0, 1), ValueMask::from_values(&[3, 9])),
+                (cu.cell(0, 2), ValueMask::from_value(1)),
+                (cu.cell(8, 8), ValueMask::from_value(5)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,137 @@
+//! Contains [`CellFact`] for the facts which constraints can state about cells for other constraints.
+
+/// A fact about a cell which changes how other constraints treat its value.
+///
+/// A constraint states facts with [`Constraint::has_cell_fact`](crate::constraint::Constraint::has_cell_fact)
+/// and other constraints ask for them with [`Board::cell_has_fact`](crate::board::Board::cell_has_fact),
+/// so that variants can be combined without the constraints knowing each other's types.
+/// For example, a killer cage asks whether each of its cells is doubled, whether the doublers
+/// come from a doublers constraint, a chaos construction or anything else.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CellFact {
+    /// The value of the cell counts twice towards sums, as with a doubler.
+    Doubled,
+    /// The value of the cell counts negatively towards sums, as with a negator.
+    Negated,
+}
+
+impl CellFact {
+    /// Every fact.
+    pub const ALL: [CellFact; 2] = [Self::Doubled, Self::Negated];
+
+    /// Returns the name of the fact for display purposes.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Doubled => "doubled",
+            Self::Negated => "negated",
+        }
+    }
+}
+
+impl std::fmt::Display for CellFact {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use std::sync::Arc;
+
+    /// States that its cells are doubled.
+    #[derive(Debug)]
+    struct Doublers {
+        cells: Vec<CellIndex>,
+    }
+
+    impl Constraint for Doublers {
+        fn name(&self) -> &str {
+            "Doublers"
+        }
+
+        fn has_cell_fact(&self, cell: CellIndex, fact: CellFact) -> bool {
+            fact == CellFact::Doubled && self.cells.contains(&cell)
+        }
+    }
+
+    /// A killer cage without its logic, which sums its cells as other constraints say they count.
+    #[derive(Debug)]
+    struct Cage {
+        cells: Vec<CellIndex>,
+        sum: i32,
+        weights: Vec<i32>,
+    }
+
+    impl Cage {
+        fn new(cells: Vec<CellIndex>, sum: i32) -> Self {
+            let weights = vec![1; cells.len()];
+            Self { cells, sum, weights }
+        }
+    }
+
+    impl Constraint for Cage {
+        fn name(&self) -> &str {
+            "Cage"
+        }
+
+        fn init_board(&mut self, board: &mut Board) -> LogicalStepResult {
+            for (weight, &cell) in self.weights.iter_mut().zip(self.cells.iter()) {
+                let doubled = if board.cell_has_fact(cell, CellFact::Doubled) { 2 } else { 1 };
+                let negated = if board.cell_has_fact(cell, CellFact::Negated) { -1 } else { 1 };
+                *weight = doubled * negated;
+            }
+            LogicalStepResult::None
+        }
+
+        fn enforce(&self, board: &Board, _cell: CellIndex, _val: usize) -> LogicalStepResult {
+            let masks = self.cells.iter().map(|&cell| board.cell(cell)).collect::<Vec<_>>();
+            if !masks.iter().all(|mask| mask.is_solved()) {
+                return LogicalStepResult::None;
+            }
+            let sum: i32 =
+                masks.iter().zip(self.weights.iter()).map(|(mask, weight)| mask.value() as i32 * weight).sum();
+            if sum == self.sum {
+                LogicalStepResult::None
+            } else {
+                LogicalStepResult::Invalid(None)
+            }
+        }
+
+        fn relevant_cells(&self) -> Vec<CellIndex> {
+            self.cells.clone()
+        }
+    }
+
+    fn board_with_cage(doublers: &[CellIndex], cage: &[CellIndex], sum: i32) -> Board {
+        let constraints: Vec<Arc<dyn Constraint>> =
+            vec![Arc::new(Cage::new(cage.to_vec(), sum)), Arc::new(Doublers { cells: doublers.to_vec() })];
+        let mut board = Board::new(9, &[], constraints);
+        board.init_constraints().unwrap();
+        board
+    }
+
+    #[test]
+    fn test_cage_with_doubler() {
+        let cu = CellUtility::new(9);
+        let cage = [cu.cell(0, 0), cu.cell(0, 1)];
+
+        // 2 doubled is 4, and 4 + 1 is 5
+        let mut board = board_with_cage(&[cu.cell(0, 0)], &cage, 5);
+        assert!(board.cell_has_fact(cu.cell(0, 0), CellFact::Doubled));
+        assert!(!board.cell_has_fact(cu.cell(0, 0), CellFact::Negated));
+        assert!(!board.cell_has_fact(cu.cell(0, 1), CellFact::Doubled));
+        assert!(board.set_solved(cu.cell(0, 0), 2));
+        assert!(board.set_solved(cu.cell(0, 1), 1));
+
+        // Without the doubler, 2 + 1 is only 3
+        let mut board = board_with_cage(&[], &cage, 5);
+        assert!(board.set_solved(cu.cell(0, 0), 2));
+        assert!(!board.set_solved(cu.cell(0, 1), 1));
+
+        // The doubler of the other cell counts instead
+        let mut board = board_with_cage(&[cu.cell(0, 1)], &cage, 5);
+        assert!(board.set_solved(cu.cell(0, 0), 2));
+        assert!(!board.set_solved(cu.cell(0, 1), 1));
+    }
+}
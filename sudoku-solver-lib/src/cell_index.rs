@@ -2,6 +2,11 @@
 
 use crate::prelude::*;
 
+// The offsets of neighbouring cells, ordered so that the cells they produce are in index order.
+const ORTHOGONAL_OFFSETS: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+const DIAGONAL_OFFSETS: [(isize, isize); 4] = [(-1, -1), (-1, 1), (1, -1), (1, 1)];
+const ADJACENT_OFFSETS: [(isize, isize); 8] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+
 /// Represents the location of a specific cell on a board.
 ///
 /// A 9x9 grid has 81 cells and are indexed 0 to 80.
@@ -105,14 +110,19 @@ impl CellIndex {
         (self.row(), self.column())
     }
 
+    /// Gets the [`CompactCellIndex`] of this cell, which doesn't store the size.
+    pub fn compact(self) -> CompactCellIndex {
+        CompactCellIndex::from(self)
+    }
+
     /// Gets the [`CandidateIndex`] of a value in this cell
     pub fn candidate(self, value: usize) -> CandidateIndex {
         CandidateIndex::from_cv(self, value)
     }
 
     /// Gets the [`CandidateIndex`] of all values in this cell
-    pub fn all_candidates(self) -> Vec<CandidateIndex> {
-        (0..self.size).map(|value| self.candidate(value)).collect()
+    pub fn all_candidates(self) -> impl Iterator<Item = CandidateIndex> {
+        (1..=self.size).map(move |value| self.candidate(value))
     }
 
     /// Gets the lexicographically next [`CellIndex`], if any.
@@ -263,133 +273,81 @@ impl CellIndex {
         self.is_orthogonally_adjacent(other) || self.is_diagonally_adjacent(other)
     }
 
-    /// Returns a vector of all cells that are orthogonally adjacent to this cell.
+    /// Returns all cells, in order, that are orthogonally adjacent to this cell.
     ///
     /// # Example
     /// ```
     /// # use sudoku_solver_lib::cell_index::CellIndex;
     /// let cell = CellIndex::from_rc(0, 0, 9);
-    /// let adjacent_cells = cell.orthogonally_adjacent_cells();
+    /// let adjacent_cells: Vec<_> = cell.orthogonally_adjacent_cells().collect();
     /// assert_eq!(adjacent_cells.len(), 2);
     /// assert_eq!(adjacent_cells, vec![CellIndex::from_rc(0, 1, 9), CellIndex::from_rc(1, 0, 9)]);
     ///
     /// let cell = CellIndex::from_rc(0, 1, 9);
-    /// let adjacent_cells = cell.orthogonally_adjacent_cells();
+    /// let adjacent_cells: Vec<_> = cell.orthogonally_adjacent_cells().collect();
     /// assert_eq!(adjacent_cells.len(), 3);
     /// assert_eq!(adjacent_cells, vec![CellIndex::from_rc(0, 0, 9), CellIndex::from_rc(0, 2, 9), CellIndex::from_rc(1, 1, 9)]);
     ///
     /// let cell = CellIndex::from_rc(1, 1, 9);
-    /// let adjacent_cells = cell.orthogonally_adjacent_cells();
+    /// let adjacent_cells: Vec<_> = cell.orthogonally_adjacent_cells().collect();
     /// assert_eq!(adjacent_cells.len(), 4);
     /// assert_eq!(adjacent_cells, vec![CellIndex::from_rc(0, 1, 9), CellIndex::from_rc(1, 0, 9), CellIndex::from_rc(1, 2, 9), CellIndex::from_rc(2, 1, 9)]);
     /// ```
-    pub fn orthogonally_adjacent_cells(self) -> Vec<Self> {
-        let (row, column) = self.rc();
-        let mut adjacent_cells = Vec::new();
-        if row > 0 {
-            adjacent_cells.push(Self::from_rc(row - 1, column, self.size));
-        }
-        if row < self.size - 1 {
-            adjacent_cells.push(Self::from_rc(row + 1, column, self.size));
-        }
-        if column > 0 {
-            adjacent_cells.push(Self::from_rc(row, column - 1, self.size));
-        }
-        if column < self.size - 1 {
-            adjacent_cells.push(Self::from_rc(row, column + 1, self.size));
-        }
-        adjacent_cells.sort();
-        adjacent_cells
+    pub fn orthogonally_adjacent_cells(self) -> impl Iterator<Item = Self> {
+        self.offset_cells(&ORTHOGONAL_OFFSETS)
     }
 
-    /// Returns a vector of all cells that are diagonally adjacent to this cell.
+    /// Returns all cells, in order, that are diagonally adjacent to this cell.
     ///
     /// # Example
     /// ```
     /// # use sudoku_solver_lib::cell_index::CellIndex;
     /// let cell = CellIndex::from_rc(0, 0, 9);
-    /// let adjacent_cells = cell.diagonally_adjacent_cells();
+    /// let adjacent_cells: Vec<_> = cell.diagonally_adjacent_cells().collect();
     /// assert_eq!(adjacent_cells.len(), 1);
     /// assert_eq!(adjacent_cells, vec![CellIndex::from_rc(1, 1, 9)]);
     ///
     /// let cell = CellIndex::from_rc(0, 1, 9);
-    /// let adjacent_cells = cell.diagonally_adjacent_cells();
+    /// let adjacent_cells: Vec<_> = cell.diagonally_adjacent_cells().collect();
     /// assert_eq!(adjacent_cells.len(), 2);
     /// assert_eq!(adjacent_cells, vec![CellIndex::from_rc(1, 0, 9), CellIndex::from_rc(1, 2, 9)]);
     ///
     /// let cell = CellIndex::from_rc(1, 1, 9);
-    /// let adjacent_cells = cell.diagonally_adjacent_cells();
+    /// let adjacent_cells: Vec<_> = cell.diagonally_adjacent_cells().collect();
     /// assert_eq!(adjacent_cells.len(), 4);
     /// assert_eq!(adjacent_cells, vec![CellIndex::from_rc(0, 0, 9), CellIndex::from_rc(0, 2, 9), CellIndex::from_rc(2, 0, 9), CellIndex::from_rc(2, 2, 9)]);
     /// ```
-    pub fn diagonally_adjacent_cells(self) -> Vec<Self> {
-        let (row, column) = self.rc();
-        let mut adjacent_cells = Vec::new();
-        if row > 0 && column > 0 {
-            adjacent_cells.push(Self::from_rc(row - 1, column - 1, self.size));
-        }
-        if row > 0 && column < self.size - 1 {
-            adjacent_cells.push(Self::from_rc(row - 1, column + 1, self.size));
-        }
-        if row < self.size - 1 && column > 0 {
-            adjacent_cells.push(Self::from_rc(row + 1, column - 1, self.size));
-        }
-        if row < self.size - 1 && column < self.size - 1 {
-            adjacent_cells.push(Self::from_rc(row + 1, column + 1, self.size));
-        }
-        adjacent_cells.sort();
-        adjacent_cells
+    pub fn diagonally_adjacent_cells(self) -> impl Iterator<Item = Self> {
+        self.offset_cells(&DIAGONAL_OFFSETS)
     }
 
-    /// Returns a vector of all cells that are adjacent to this cell.
+    /// Returns all cells, in order, that are adjacent to this cell.
     ///
     /// # Example
     /// ```
     /// # use sudoku_solver_lib::cell_index::CellIndex;
     /// let cell = CellIndex::from_rc(0, 0, 9);
-    /// let adjacent_cells = cell.adjacent_cells();
+    /// let adjacent_cells: Vec<_> = cell.adjacent_cells().collect();
     /// assert_eq!(adjacent_cells.len(), 3);
     /// assert_eq!(adjacent_cells, vec![CellIndex::from_rc(0, 1, 9), CellIndex::from_rc(1, 0, 9), CellIndex::from_rc(1, 1, 9)]);
     ///
     /// let cell = CellIndex::from_rc(0, 1, 9);
-    /// let adjacent_cells = cell.adjacent_cells();
+    /// let adjacent_cells: Vec<_> = cell.adjacent_cells().collect();
     /// assert_eq!(adjacent_cells.len(), 5);
     /// assert_eq!(adjacent_cells, vec![CellIndex::from_rc(0, 0, 9), CellIndex::from_rc(0, 2, 9), CellIndex::from_rc(1, 0, 9), CellIndex::from_rc(1, 1, 9), CellIndex::from_rc(1, 2, 9)]);
     ///
     /// let cell = CellIndex::from_rc(1, 1, 9);
-    /// let adjacent_cells = cell.adjacent_cells();
+    /// let adjacent_cells: Vec<_> = cell.adjacent_cells().collect();
     /// assert_eq!(adjacent_cells.len(), 8);
     /// assert_eq!(adjacent_cells, vec![CellIndex::from_rc(0, 0, 9), CellIndex::from_rc(0, 1, 9), CellIndex::from_rc(0, 2, 9), CellIndex::from_rc(1, 0, 9), CellIndex::from_rc(1, 2, 9), CellIndex::from_rc(2, 0, 9), CellIndex::from_rc(2, 1, 9), CellIndex::from_rc(2, 2, 9)]);
     /// ```
-    pub fn adjacent_cells(self) -> Vec<Self> {
-        let (row, column) = self.rc();
-        let mut adjacent_cells = Vec::new();
-        if row > 0 {
-            adjacent_cells.push(Self::from_rc(row - 1, column, self.size));
-        }
-        if row < self.size - 1 {
-            adjacent_cells.push(Self::from_rc(row + 1, column, self.size));
-        }
-        if column > 0 {
-            adjacent_cells.push(Self::from_rc(row, column - 1, self.size));
-        }
-        if column < self.size - 1 {
-            adjacent_cells.push(Self::from_rc(row, column + 1, self.size));
-        }
-        if row > 0 && column > 0 {
-            adjacent_cells.push(Self::from_rc(row - 1, column - 1, self.size));
-        }
-        if row > 0 && column < self.size - 1 {
-            adjacent_cells.push(Self::from_rc(row - 1, column + 1, self.size));
-        }
-        if row < self.size - 1 && column > 0 {
-            adjacent_cells.push(Self::from_rc(row + 1, column - 1, self.size));
-        }
-        if row < self.size - 1 && column < self.size - 1 {
-            adjacent_cells.push(Self::from_rc(row + 1, column + 1, self.size));
-        }
-        adjacent_cells.sort();
-        adjacent_cells
+    pub fn adjacent_cells(self) -> impl Iterator<Item = Self> {
+        self.offset_cells(&ADJACENT_OFFSETS)
+    }
+
+    /// The cells at each of the offsets which are inside the grid.
+    fn offset_cells(self, offsets: &'static [(isize, isize)]) -> impl Iterator<Item = Self> {
+        offsets.iter().filter_map(move |&(offset_row, offset_col)| self.offset(offset_row, offset_col))
     }
 }
 
@@ -426,8 +384,32 @@ impl std::hash::Hash for CellIndex {
     }
 }
 
+/// The index of a cell without the size of the board, for storing many cells compactly.
+///
+/// A [`CellIndex`] is twice the size of a `usize` because it carries the size of the board.
+/// This is only two bytes, so it has no methods which need the size. Convert it back to a
+/// [`CellIndex`] with [`CellUtility::cell_from_compact`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CompactCellIndex(u16);
+
+impl CompactCellIndex {
+    /// Gets the index of the cell.
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl From<CellIndex> for CompactCellIndex {
+    fn from(cell: CellIndex) -> Self {
+        debug_assert!(cell.index() <= u16::MAX as usize);
+        Self(cell.index() as u16)
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use itertools::{assert_equal, Itertools};
+
     use super::*;
 
     #[test]
@@ -454,85 +436,115 @@ mod test {
         assert_eq!(CellIndex::new(40, 9).rc(), (4, 4));
     }
 
+    #[test]
+    fn test_all_candidates() {
+        let cell = CellIndex::from_rc(1, 2, 9);
+        assert_equal(cell.all_candidates(), (1..=9).map(|value| CandidateIndex::from_cv(cell, value)));
+    }
+
     #[test]
     fn test_orthogonally_adjacent_cells() {
         let cu = crate::cell_utility::CellUtility::new(9);
-        assert_eq!(cu.cell(0, 0).orthogonally_adjacent_cells(), vec![cu.cell(0, 1), cu.cell(1, 0)]);
-        assert_eq!(cu.cell(0, 1).orthogonally_adjacent_cells(), vec![cu.cell(0, 0), cu.cell(0, 2), cu.cell(1, 1)]);
-        assert_eq!(cu.cell(0, 2).orthogonally_adjacent_cells(), vec![cu.cell(0, 1), cu.cell(0, 3), cu.cell(1, 2)]);
-        assert_eq!(cu.cell(0, 3).orthogonally_adjacent_cells(), vec![cu.cell(0, 2), cu.cell(0, 4), cu.cell(1, 3)]);
-        assert_eq!(cu.cell(0, 4).orthogonally_adjacent_cells(), vec![cu.cell(0, 3), cu.cell(0, 5), cu.cell(1, 4)]);
-        assert_eq!(cu.cell(0, 5).orthogonally_adjacent_cells(), vec![cu.cell(0, 4), cu.cell(0, 6), cu.cell(1, 5)]);
-        assert_eq!(cu.cell(0, 6).orthogonally_adjacent_cells(), vec![cu.cell(0, 5), cu.cell(0, 7), cu.cell(1, 6)]);
-        assert_eq!(cu.cell(0, 7).orthogonally_adjacent_cells(), vec![cu.cell(0, 6), cu.cell(0, 8), cu.cell(1, 7)]);
-        assert_eq!(cu.cell(0, 8).orthogonally_adjacent_cells(), vec![cu.cell(0, 7), cu.cell(1, 8)]);
-        assert_eq!(cu.cell(1, 0).orthogonally_adjacent_cells(), vec![cu.cell(0, 0), cu.cell(1, 1), cu.cell(2, 0)]);
+        assert_eq!(cu.cell(0, 0).orthogonally_adjacent_cells().collect_vec(), vec![cu.cell(0, 1), cu.cell(1, 0)]);
+        assert_eq!(
+            cu.cell(0, 1).orthogonally_adjacent_cells().collect_vec(),
+            vec![cu.cell(0, 0), cu.cell(0, 2), cu.cell(1, 1)]
+        );
+        assert_eq!(
+            cu.cell(0, 2).orthogonally_adjacent_cells().collect_vec(),
+            vec![cu.cell(0, 1), cu.cell(0, 3), cu.cell(1, 2)]
+        );
+        assert_eq!(
+            cu.cell(0, 3).orthogonally_adjacent_cells().collect_vec(),
+            vec![cu.cell(0, 2), cu.cell(0, 4), cu.cell(1, 3)]
+        );
+        assert_eq!(
+            cu.cell(0, 4).orthogonally_adjacent_cells().collect_vec(),
+            vec![cu.cell(0, 3), cu.cell(0, 5), cu.cell(1, 4)]
+        );
+        assert_eq!(
+            cu.cell(0, 5).orthogonally_adjacent_cells().collect_vec(),
+            vec![cu.cell(0, 4), cu.cell(0, 6), cu.cell(1, 5)]
+        );
+        assert_eq!(
+            cu.cell(0, 6).orthogonally_adjacent_cells().collect_vec(),
+            vec![cu.cell(0, 5), cu.cell(0, 7), cu.cell(1, 6)]
+        );
+        assert_eq!(
+            cu.cell(0, 7).orthogonally_adjacent_cells().collect_vec(),
+            vec![cu.cell(0, 6), cu.cell(0, 8), cu.cell(1, 7)]
+        );
+        assert_eq!(cu.cell(0, 8).orthogonally_adjacent_cells().collect_vec(), vec![cu.cell(0, 7), cu.cell(1, 8)]);
+        assert_eq!(
+            cu.cell(1, 0).orthogonally_adjacent_cells().collect_vec(),
+            vec![cu.cell(0, 0), cu.cell(1, 1), cu.cell(2, 0)]
+        );
         assert_eq!(
-            cu.cell(4, 4).orthogonally_adjacent_cells(),
+            cu.cell(4, 4).orthogonally_adjacent_cells().collect_vec(),
             vec![cu.cell(3, 4), cu.cell(4, 3), cu.cell(4, 5), cu.cell(5, 4)]
         );
-        assert_eq!(cu.cell(8, 8).orthogonally_adjacent_cells(), vec![cu.cell(7, 8), cu.cell(8, 7)]);
+        assert_eq!(cu.cell(8, 8).orthogonally_adjacent_cells().collect_vec(), vec![cu.cell(7, 8), cu.cell(8, 7)]);
     }
 
     #[test]
     fn test_diagonal_cells() {
         let cu = crate::cell_utility::CellUtility::new(9);
-        assert_eq!(cu.cell(0, 0).diagonally_adjacent_cells(), vec![cu.cell(1, 1)]);
-        assert_eq!(cu.cell(0, 1).diagonally_adjacent_cells(), vec![cu.cell(1, 0), cu.cell(1, 2)]);
-        assert_eq!(cu.cell(0, 2).diagonally_adjacent_cells(), vec![cu.cell(1, 1), cu.cell(1, 3)]);
-        assert_eq!(cu.cell(0, 3).diagonally_adjacent_cells(), vec![cu.cell(1, 2), cu.cell(1, 4)]);
-        assert_eq!(cu.cell(0, 4).diagonally_adjacent_cells(), vec![cu.cell(1, 3), cu.cell(1, 5)]);
-        assert_eq!(cu.cell(0, 5).diagonally_adjacent_cells(), vec![cu.cell(1, 4), cu.cell(1, 6)]);
-        assert_eq!(cu.cell(0, 6).diagonally_adjacent_cells(), vec![cu.cell(1, 5), cu.cell(1, 7)]);
-        assert_eq!(cu.cell(0, 7).diagonally_adjacent_cells(), vec![cu.cell(1, 6), cu.cell(1, 8)]);
-        assert_eq!(cu.cell(0, 8).diagonally_adjacent_cells(), vec![cu.cell(1, 7)]);
-        assert_eq!(cu.cell(1, 0).diagonally_adjacent_cells(), vec![cu.cell(0, 1), cu.cell(2, 1)]);
+        assert_eq!(cu.cell(0, 0).diagonally_adjacent_cells().collect_vec(), vec![cu.cell(1, 1)]);
+        assert_eq!(cu.cell(0, 1).diagonally_adjacent_cells().collect_vec(), vec![cu.cell(1, 0), cu.cell(1, 2)]);
+        assert_eq!(cu.cell(0, 2).diagonally_adjacent_cells().collect_vec(), vec![cu.cell(1, 1), cu.cell(1, 3)]);
+        assert_eq!(cu.cell(0, 3).diagonally_adjacent_cells().collect_vec(), vec![cu.cell(1, 2), cu.cell(1, 4)]);
+        assert_eq!(cu.cell(0, 4).diagonally_adjacent_cells().collect_vec(), vec![cu.cell(1, 3), cu.cell(1, 5)]);
+        assert_eq!(cu.cell(0, 5).diagonally_adjacent_cells().collect_vec(), vec![cu.cell(1, 4), cu.cell(1, 6)]);
+        assert_eq!(cu.cell(0, 6).diagonally_adjacent_cells().collect_vec(), vec![cu.cell(1, 5), cu.cell(1, 7)]);
+        assert_eq!(cu.cell(0, 7).diagonally_adjacent_cells().collect_vec(), vec![cu.cell(1, 6), cu.cell(1, 8)]);
+        assert_eq!(cu.cell(0, 8).diagonally_adjacent_cells().collect_vec(), vec![cu.cell(1, 7)]);
+        assert_eq!(cu.cell(1, 0).diagonally_adjacent_cells().collect_vec(), vec![cu.cell(0, 1), cu.cell(2, 1)]);
         assert_eq!(
-            cu.cell(4, 4).diagonally_adjacent_cells(),
+            cu.cell(4, 4).diagonally_adjacent_cells().collect_vec(),
             vec![cu.cell(3, 3), cu.cell(3, 5), cu.cell(5, 3), cu.cell(5, 5)]
         );
-        assert_eq!(cu.cell(8, 8).diagonally_adjacent_cells(), vec![cu.cell(7, 7)]);
+        assert_eq!(cu.cell(8, 8).diagonally_adjacent_cells().collect_vec(), vec![cu.cell(7, 7)]);
     }
 
     #[test]
     fn test_adjacent_cells() {
         let cu = crate::cell_utility::CellUtility::new(9);
-        assert_eq!(cu.cell(0, 0).adjacent_cells(), vec![cu.cell(0, 1), cu.cell(1, 0), cu.cell(1, 1),]);
+        assert_eq!(cu.cell(0, 0).adjacent_cells().collect_vec(), vec![cu.cell(0, 1), cu.cell(1, 0), cu.cell(1, 1),]);
         assert_eq!(
-            cu.cell(0, 1).adjacent_cells(),
+            cu.cell(0, 1).adjacent_cells().collect_vec(),
             vec![cu.cell(0, 0), cu.cell(0, 2), cu.cell(1, 0), cu.cell(1, 1), cu.cell(1, 2),]
         );
         assert_eq!(
-            cu.cell(0, 2).adjacent_cells(),
+            cu.cell(0, 2).adjacent_cells().collect_vec(),
             vec![cu.cell(0, 1), cu.cell(0, 3), cu.cell(1, 1), cu.cell(1, 2), cu.cell(1, 3),]
         );
         assert_eq!(
-            cu.cell(0, 3).adjacent_cells(),
+            cu.cell(0, 3).adjacent_cells().collect_vec(),
             vec![cu.cell(0, 2), cu.cell(0, 4), cu.cell(1, 2), cu.cell(1, 3), cu.cell(1, 4),]
         );
         assert_eq!(
-            cu.cell(0, 4).adjacent_cells(),
+            cu.cell(0, 4).adjacent_cells().collect_vec(),
             vec![cu.cell(0, 3), cu.cell(0, 5), cu.cell(1, 3), cu.cell(1, 4), cu.cell(1, 5),]
         );
         assert_eq!(
-            cu.cell(0, 5).adjacent_cells(),
+            cu.cell(0, 5).adjacent_cells().collect_vec(),
             vec![cu.cell(0, 4), cu.cell(0, 6), cu.cell(1, 4), cu.cell(1, 5), cu.cell(1, 6),]
         );
         assert_eq!(
-            cu.cell(0, 6).adjacent_cells(),
+            cu.cell(0, 6).adjacent_cells().collect_vec(),
             vec![cu.cell(0, 5), cu.cell(0, 7), cu.cell(1, 5), cu.cell(1, 6), cu.cell(1, 7),]
         );
         assert_eq!(
-            cu.cell(0, 7).adjacent_cells(),
+            cu.cell(0, 7).adjacent_cells().collect_vec(),
             vec![cu.cell(0, 6), cu.cell(0, 8), cu.cell(1, 6), cu.cell(1, 7), cu.cell(1, 8),]
         );
-        assert_eq!(cu.cell(0, 8).adjacent_cells(), vec![cu.cell(0, 7), cu.cell(1, 7), cu.cell(1, 8),]);
+        assert_eq!(cu.cell(0, 8).adjacent_cells().collect_vec(), vec![cu.cell(0, 7), cu.cell(1, 7), cu.cell(1, 8),]);
         assert_eq!(
-            cu.cell(1, 0).adjacent_cells(),
+            cu.cell(1, 0).adjacent_cells().collect_vec(),
             vec![cu.cell(0, 0), cu.cell(0, 1), cu.cell(1, 1), cu.cell(2, 0), cu.cell(2, 1),]
         );
         assert_eq!(
-            cu.cell(4, 4).adjacent_cells(),
+            cu.cell(4, 4).adjacent_cells().collect_vec(),
             vec![
                 cu.cell(3, 3),
                 cu.cell(3, 4),
@@ -544,6 +556,6 @@ mod test {
                 cu.cell(5, 5),
             ]
         );
-        assert_eq!(cu.cell(8, 8).adjacent_cells(), vec![cu.cell(7, 7), cu.cell(7, 8), cu.cell(8, 7),]);
+        assert_eq!(cu.cell(8, 8).adjacent_cells().collect_vec(), vec![cu.cell(7, 7), cu.cell(7, 8), cu.cell(8, 7),]);
     }
 }
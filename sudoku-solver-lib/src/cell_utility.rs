@@ -115,6 +115,32 @@ impl CellUtility {
         CandidateIndex::new(index, self.size)
     }
 
+    /// Creates a [`CellIndex`] from a [`CompactCellIndex`].
+    ///
+    /// # Example
+    /// ```
+    /// # use sudoku_solver_lib::cell_utility::CellUtility;
+    /// let cu = CellUtility::new(9);
+    /// let cell = cu.cell(4, 5);
+    /// assert_eq!(cu.cell_from_compact(cell.compact()), cell);
+    /// ```
+    pub fn cell_from_compact(self, cell: CompactCellIndex) -> CellIndex {
+        CellIndex::new(cell.index(), self.size)
+    }
+
+    /// Creates a [`CandidateIndex`] from a [`CompactCandidateIndex`].
+    ///
+    /// # Example
+    /// ```
+    /// # use sudoku_solver_lib::cell_utility::CellUtility;
+    /// let cu = CellUtility::new(16);
+    /// let candidate = cu.candidate(cu.cell(15, 15), 16);
+    /// assert_eq!(cu.candidate_from_compact(candidate.compact()), candidate);
+    /// ```
+    pub fn candidate_from_compact(self, candidate: CompactCandidateIndex) -> CandidateIndex {
+        CandidateIndex::new(candidate.index(), self.size)
+    }
+
     /// Creates an iterator over all cells in the board.
     ///
     /// # Example
@@ -1,6 +1,7 @@
 //! Contains the [`Constraint`] trait for defining the behavior of additional constraints.
 
 use crate::prelude::*;
+use std::sync::Arc;
 use std::vec::Vec;
 
 /// Constraints are used by variant sudokus to define extra rules
@@ -16,7 +17,10 @@ use std::vec::Vec;
 /// - [`Constraint::get_weak_links`] can call [`get_weak_links_for_nonrepeat`]
 /// to automatically generate weak links based on the constraint having cells
 /// which cannot repeat a value.
-pub trait Constraint: std::any::Any + core::fmt::Debug {
+///
+/// Constraints must be [`Send`] and [`Sync`] so that puzzles can be solved on other threads,
+/// such as by `solve_batch`.
+pub trait Constraint: std::any::Any + core::fmt::Debug + Send + Sync {
     /// A generic name for the constaint which is independent of how it was intialized.
     fn name(&self) -> &str;
 
@@ -80,12 +84,16 @@ pub trait Constraint: std::any::Any + core::fmt::Debug {
     ///
     /// Eliminations do not need to be tracked if the brute forcing boolean is set to true.
     ///
+    /// Very heavy logic should check the cancellation as it goes, and return [`LogicalStepResult::None`]
+    /// once it is cancelled, so that a cancelled solve doesn't wait for the step to finish.
+    /// Any changes already made to the board must still be valid deductions.
+    ///
     /// Return the following based on the situation. You must track this yourself and return an accurate [`LogicalStepResult`]:
     /// - [`LogicalStepResult::None`] if the board is unchanged.
     /// - [`LogicalStepResult::Changed`] if the board is changed.
     /// - [`LogicalStepResult::Invalid`] if this constraint can no longer be satisfied.
-    fn step_logic(&self, board: &mut Board, is_brute_forcing: bool) -> LogicalStepResult {
-        let (_, _) = (board, is_brute_forcing);
+    fn step_logic(&self, board: &mut Board, is_brute_forcing: bool, cancellation: &Cancellation) -> LogicalStepResult {
+        let (_, _, _) = (board, is_brute_forcing, cancellation);
         LogicalStepResult::None
     }
 
@@ -99,6 +107,36 @@ pub trait Constraint: std::any::Any + core::fmt::Debug {
         Vec::new()
     }
 
+    /// Return the cells which this constraint depends on.
+    ///
+    /// When this is not empty, [`Constraint::enforce`] is only called when one of these cells is
+    /// set, and [`Constraint::step_logic`] is skipped until the candidates of one of these cells
+    /// change. This saves a lot of time on puzzles with many small constraints, such as
+    /// dozens of cages or lines.
+    ///
+    /// Only return cells if the result of both methods depends on nothing but the candidates
+    /// of these cells. The default empty [`Vec`] means that the constraint could depend on
+    /// any cell, so it is always called.
+    ///
+    /// This is queried once when the board is created, before [`Constraint::init_board`].
+    fn relevant_cells(&self) -> Vec<CellIndex> {
+        Vec::new()
+    }
+
+    /// Return whether this constraint states the fact about the cell.
+    ///
+    /// Other constraints ask about facts with [`Board::cell_has_fact`] rather than looking
+    /// for constraints of a particular type. For example, a doublers constraint states
+    /// [`CellFact::Doubled`] for its doubled cells, and a killer cage on the same board
+    /// asks for it to count those values twice in its sum.
+    ///
+    /// The answer must not change once the board is created. Constraints usually ask
+    /// in [`Constraint::init_board`] and keep the answers, so they aren't asked again on every step.
+    fn has_cell_fact(&self, cell: CellIndex, fact: CellFact) -> bool {
+        let (_, _) = (cell, fact);
+        false
+    }
+
     /// Return a vector of cells which are powerful to check early in the solve.
     ///
     /// This is used purely for performance reasons. The brute force solver will prefer
@@ -107,46 +145,66 @@ pub trait Constraint: std::any::Any + core::fmt::Debug {
         Vec::new()
     }
 
+    /// Return a copy of this constraint which another solver can initialize.
+    ///
+    /// Each solver initializes its own constraints, so a constraint which is shared with another
+    /// [`SolverBuilder`] or solver, such as by cloning a builder, is copied when it is built.
+    /// Constraints which implement [`Clone`] can return `Some(Arc::new(self.clone()))`.
+    ///
+    /// The default returns `None`, so the constraint can only be built into one solver.
+    fn clone_constraint(&self) -> Option<Arc<dyn Constraint>> {
+        None
+    }
+
     /// Can be used by [`Constraint::cells_must_contain`] to automatically determine the
     /// answer based on running the [`Constraint::step_logic`] method.
     ///
     /// This is determined by cloning the board, and then removing the given value from all
     /// cells in the constraint and then running the [`Constraint::step_logic`] method to see
     /// if it returns [`LogicalStepResult::Invalid`].
-    fn cells_must_contain_by_running_logic(
-        &self,
-        board: &mut Board,
-        cells: &[CellIndex],
-        value: usize,
-    ) -> Vec<CellIndex> {
-        let mut result = Vec::new();
-
-        for &cell in cells {
-            let mask = board.cell(cell);
-            if mask.is_single() || !mask.has(value) {
-                continue;
-            }
+    ///
+    /// When the constraint is one of the [`Board::constraints`], the result is memoized on the
+    /// board until its [`Board::revision`] changes, so repeated queries within one logical step
+    /// don't run the logic again.
+    fn cells_must_contain_by_running_logic(&self, board: &Board, cells: &[CellIndex], value: usize) -> Vec<CellIndex> {
+        let compute = || {
+            let mut result = Vec::new();
 
-            result.push(cell);
-        }
+            for &cell in cells {
+                let mask = board.cell(cell);
+                if mask.is_single() || !mask.has(value) {
+                    continue;
+                }
 
-        if !result.is_empty() {
-            let mut board_clone = board.clone();
-            for &cell in &result {
-                board_clone.clear_value(cell, value);
+                result.push(cell);
             }
 
-            let mut logic_result = LogicalStepResult::Changed(None);
-            while logic_result.is_changed() {
-                logic_result = self.step_logic(&mut board_clone, true);
-            }
+            if !result.is_empty() {
+                let mut board_clone = board.clone();
+                for &cell in &result {
+                    board_clone.clear_value(cell, value);
+                }
+
+                let cancellation = Cancellation::default();
+                let mut logic_result = LogicalStepResult::Changed(None);
+                while logic_result.is_changed() {
+                    logic_result = self.step_logic(&mut board_clone, true, &cancellation);
+                }
 
-            if !logic_result.is_invalid() {
-                result.clear();
+                if !logic_result.is_invalid() {
+                    result.clear();
+                }
             }
-        }
 
-        result
+            result
+        };
+
+        let constraint_index =
+            board.constraints().iter().position(|constraint| std::ptr::addr_eq(Arc::as_ptr(constraint), self));
+        match constraint_index {
+            Some(constraint_index) => board.memoize_cells_must_contain(constraint_index, value, cells, compute),
+            None => compute(),
+        }
     }
 
     /// A weak link is a relationship between candidates A and B which may be in different
@@ -190,9 +248,79 @@ pub trait Constraint: std::any::Any + core::fmt::Debug {
     ///
     /// This method returns a [`Vec`] of [`House`] which are created by the constraint.
     ///
-    /// The size of the board is passed in so that the constraint can know the size of the house.
-    fn get_houses(&self, size: usize) -> Vec<House> {
-        let _ = size;
+    /// The context has the size of the board, so that the constraint can know the size of the house,
+    /// along with the regions, the other constraints and the houses created so far. For example, a
+    /// constraint can check whether its cells already form a region before adding a house for them.
+    fn get_houses(&self, context: &HouseContext) -> Vec<House> {
+        let _ = context;
         Vec::new()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// The first three cells of row 1 must contain a 9, and counts how often its logic runs.
+    #[derive(Debug, Default)]
+    struct MustHaveNine {
+        step_count: Arc<AtomicUsize>,
+    }
+
+    impl Constraint for MustHaveNine {
+        fn name(&self) -> &str {
+            "Must Have Nine"
+        }
+
+        fn step_logic(
+            &self,
+            board: &mut Board,
+            _is_brute_forcing: bool,
+            _cancellation: &Cancellation,
+        ) -> LogicalStepResult {
+            self.step_count.fetch_add(1, Ordering::Relaxed);
+            let cu = board.cell_utility();
+            if (0..3).any(|col| board.cell(cu.cell(0, col)).has(9)) {
+                LogicalStepResult::None
+            } else {
+                LogicalStepResult::Invalid(None)
+            }
+        }
+    }
+
+    #[test]
+    fn test_cells_must_contain_is_memoized() {
+        let step_count = Arc::new(AtomicUsize::new(0));
+        let constraints = (0..2).map(|_| MustHaveNine { step_count: step_count.clone() });
+        let solver = constraints
+            .fold(SolverBuilder::default(), |builder, constraint| builder.with_constraint(Arc::new(constraint)))
+            .build()
+            .unwrap();
+        let mut board = solver.board().clone();
+        let (first, second) = (board.constraints()[0].clone(), board.constraints()[1].clone());
+        let cu = board.cell_utility();
+        let cells = (0..3).map(|col| cu.cell(0, col)).collect::<Vec<_>>();
+        let step_count = || step_count.load(Ordering::Relaxed);
+        let initial_count = step_count();
+
+        assert_eq!(first.cells_must_contain_by_running_logic(&board, &cells, 9), cells);
+        assert_eq!(first.cells_must_contain_by_running_logic(&board, &cells, 9), cells);
+        assert_eq!(step_count(), initial_count + 1);
+
+        // Each constraint of the board has its own result
+        assert_eq!(second.cells_must_contain_by_running_logic(&board, &cells, 9), cells);
+        assert_eq!(step_count(), initial_count + 2);
+
+        // Changing the board invalidates the memoized result
+        assert!(board.clear_value(cells[0], 9));
+        assert_eq!(first.cells_must_contain_by_running_logic(&board, &cells, 9), &cells[1..]);
+        assert_eq!(step_count(), initial_count + 3);
+
+        // A constraint which isn't on the board runs its logic every time
+        let standalone = MustHaveNine::default();
+        standalone.cells_must_contain_by_running_logic(&board, &cells, 9);
+        standalone.cells_must_contain_by_running_logic(&board, &cells, 9);
+        assert_eq!(standalone.step_count.load(Ordering::Relaxed), 2);
+    }
+}
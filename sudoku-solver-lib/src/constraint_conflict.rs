@@ -0,0 +1,127 @@
+//! Contains [`ConstraintConflict`] and [`find_constraint_conflicts`] for detecting constraints
+//! which can't be satisfied together.
+
+use crate::prelude::*;
+
+/// A cell which can't have any value because of the weak links of the constraints.
+///
+/// This is found before any constraint is initialized, so it describes a contradiction
+/// in the rules themselves rather than in the givens.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConstraintConflict {
+    /// The cell with no possible values.
+    pub cell: CellIndex,
+    /// The names of the constraints which remove values from the cell.
+    pub constraints: Vec<String>,
+}
+
+impl std::fmt::Display for ConstraintConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.constraints.is_empty() {
+            write!(f, "{} has no possible values", self.cell)
+        } else {
+            write!(f, "{} has no possible values because {} conflict", self.cell, self.constraints.join(" and "))
+        }
+    }
+}
+
+/// Finds the cells of a newly created board which can't have any value.
+///
+/// A value is impossible in a cell if it is weakly linked to every candidate of another cell,
+/// such as when two constraints on the same pair of cells allow no pair of values between them.
+/// The conflict names every constraint which links the cell to those other cells.
+///
+/// This is checked by [`SolverBuilder::build`], so that contradictory rules are reported by
+/// name instead of as an invalid board.
+pub fn find_constraint_conflicts(board: &Board) -> Vec<ConstraintConflict> {
+    let mut conflicts = Vec::new();
+    for (cell, mask) in board.all_cell_masks() {
+        let mut forcing_cells = Vec::new();
+        let mut remaining = mask.unsolved();
+        for value in mask.unsolved() {
+            let links = &board.weak_links()[cell.candidate(value).index()];
+            let forcing_cell = links.cell_masks().find(|&(other, linked)| {
+                let other_mask = board.cell(other).unsolved();
+                other != cell && !other_mask.is_empty() && (other_mask & !linked).is_empty()
+            });
+            if let Some((other, _)) = forcing_cell {
+                remaining = remaining.without(value);
+                forcing_cells.push(other);
+            }
+        }
+
+        if remaining.is_empty() {
+            let constraints = board
+                .constraints()
+                .iter()
+                .filter(|constraint| {
+                    constraint.get_weak_links(board.size()).iter().any(|(candidate0, candidate1)| {
+                        let (cell0, cell1) = (candidate0.cell_index(), candidate1.cell_index());
+                        if candidate0 == candidate1 {
+                            return cell0 == cell;
+                        }
+                        (cell0 == cell && forcing_cells.contains(&cell1))
+                            || (cell1 == cell && forcing_cells.contains(&cell0))
+                    })
+                })
+                .map(|constraint| constraint.name().to_owned())
+                .collect();
+            conflicts.push(ConstraintConflict { cell, constraints });
+        }
+    }
+    conflicts
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::Arc;
+
+    /// Only allows the given differences between the first two cells of row 1.
+    #[derive(Debug)]
+    struct PairDifference {
+        name: String,
+        allowed: fn(usize) -> bool,
+    }
+
+    impl Constraint for PairDifference {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn get_weak_links(&self, size: usize) -> Vec<(CandidateIndex, CandidateIndex)> {
+            let cu = CellUtility::new(size);
+            let (cell0, cell1) = (cu.cell(0, 0), cu.cell(0, 1));
+            let mut weak_links = Vec::new();
+            for value0 in 1..=size {
+                for value1 in 1..=size {
+                    if !(self.allowed)(value0.abs_diff(value1)) {
+                        weak_links.push((cell0.candidate(value0), cell1.candidate(value1)));
+                    }
+                }
+            }
+            weak_links
+        }
+    }
+
+    #[test]
+    fn test_constraint_conflicts() {
+        let close = Arc::new(PairDifference { name: "Close".to_owned(), allowed: |diff| diff <= 1 });
+        let far = Arc::new(PairDifference { name: "Far".to_owned(), allowed: |diff| diff >= 5 });
+        let any = Arc::new(PairDifference { name: "Any".to_owned(), allowed: |_| true });
+
+        let board = Board::new(9, &[], vec![close.clone(), any.clone()]);
+        assert!(find_constraint_conflicts(&board).is_empty());
+
+        let board = Board::new(9, &[], vec![close, far, any]);
+        let conflicts = find_constraint_conflicts(&board);
+        assert_eq!(conflicts.len(), 2);
+        assert_eq!(conflicts[0].constraints, vec!["Close".to_owned(), "Far".to_owned()]);
+        assert_eq!(conflicts[0].to_string(), "r1c1 has no possible values because Close and Far conflict");
+
+        let far = Arc::new(PairDifference { name: "Far".to_owned(), allowed: |diff| diff >= 5 });
+        let close = Arc::new(PairDifference { name: "Close".to_owned(), allowed: |diff| diff <= 1 });
+        let result = SolverBuilder::default().with_constraint(far).with_constraint(close).build();
+        assert!(result.err().unwrap().starts_with("r1c1 has no possible values because Far and Close conflict"));
+    }
+}
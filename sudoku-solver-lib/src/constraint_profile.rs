@@ -0,0 +1,158 @@
+//! Contains [`ConstraintProfile`] for reporting the time spent in each constraint of a board.
+//!
+//! Only available with the `profiling` feature, since timing every call slows down the solver.
+
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+/// The time spent in a constraint's [`Constraint::enforce`](crate::constraint::Constraint::enforce)
+/// and [`Constraint::step_logic`](crate::constraint::Constraint::step_logic), summed over every
+/// board which shares the constraint, such as the boards of a brute force search.
+///
+/// See [`Board::constraint_profile`](crate::board::Board::constraint_profile).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstraintProfile {
+    /// The name of the constraint.
+    pub constraint: String,
+    /// The number of values the constraint enforced.
+    pub enforce_calls: usize,
+    /// The time spent enforcing values.
+    pub enforce_time: Duration,
+    /// The number of times the constraint's logic ran.
+    pub step_logic_calls: usize,
+    /// The time spent running the constraint's logic.
+    pub step_logic_time: Duration,
+}
+
+impl ConstraintProfile {
+    /// The time spent in the constraint.
+    pub fn total_time(&self) -> Duration {
+        self.enforce_time + self.step_logic_time
+    }
+}
+
+impl std::fmt::Display for ConstraintProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} enforce calls in {:.3}ms, {} step logic calls in {:.3}ms",
+            self.constraint,
+            self.enforce_calls,
+            self.enforce_time.as_secs_f64() * 1000.0,
+            self.step_logic_calls,
+            self.step_logic_time.as_secs_f64() * 1000.0
+        )
+    }
+}
+
+/// The running totals of one constraint. Boards on other threads may share them, so they are atomic.
+#[derive(Debug, Default)]
+struct ConstraintTimer {
+    enforce_calls: AtomicU64,
+    enforce_nanos: AtomicU64,
+    step_logic_calls: AtomicU64,
+    step_logic_nanos: AtomicU64,
+}
+
+/// The running totals of every constraint of a board, in the same order as the constraints.
+#[derive(Debug, Default)]
+pub(crate) struct ConstraintTimers {
+    timers: Vec<ConstraintTimer>,
+}
+
+impl ConstraintTimers {
+    pub(crate) fn new(constraint_count: usize) -> Self {
+        Self { timers: (0..constraint_count).map(|_| ConstraintTimer::default()).collect() }
+    }
+
+    pub(crate) fn time_enforce<T>(&self, constraint_index: usize, f: impl FnOnce() -> T) -> T {
+        let timer = &self.timers[constraint_index];
+        Self::time(&timer.enforce_calls, &timer.enforce_nanos, f)
+    }
+
+    pub(crate) fn time_step_logic<T>(&self, constraint_index: usize, f: impl FnOnce() -> T) -> T {
+        let timer = &self.timers[constraint_index];
+        Self::time(&timer.step_logic_calls, &timer.step_logic_nanos, f)
+    }
+
+    fn time<T>(calls: &AtomicU64, nanos: &AtomicU64, f: impl FnOnce() -> T) -> T {
+        let start_time = Instant::now();
+        let result = f();
+        nanos.fetch_add(start_time.elapsed().as_nanos() as u64, Ordering::Relaxed);
+        calls.fetch_add(1, Ordering::Relaxed);
+        result
+    }
+
+    /// The totals so far, given the names of the constraints.
+    pub(crate) fn profiles<'a>(&self, names: impl Iterator<Item = &'a str>) -> Vec<ConstraintProfile> {
+        names
+            .zip(self.timers.iter())
+            .map(|(name, timer)| ConstraintProfile {
+                constraint: name.to_owned(),
+                enforce_calls: timer.enforce_calls.load(Ordering::Relaxed) as usize,
+                enforce_time: Duration::from_nanos(timer.enforce_nanos.load(Ordering::Relaxed)),
+                step_logic_calls: timer.step_logic_calls.load(Ordering::Relaxed) as usize,
+                step_logic_time: Duration::from_nanos(timer.step_logic_nanos.load(Ordering::Relaxed)),
+            })
+            .collect()
+    }
+
+    pub(crate) fn reset(&self) {
+        for timer in self.timers.iter() {
+            timer.enforce_calls.store(0, Ordering::Relaxed);
+            timer.enforce_nanos.store(0, Ordering::Relaxed);
+            timer.step_logic_calls.store(0, Ordering::Relaxed);
+            timer.step_logic_nanos.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::prelude::*;
+
+    /// Rejects a 9 in the first cell, taking a little time for every value it checks.
+    #[derive(Debug)]
+    struct SlowConstraint;
+
+    impl Constraint for SlowConstraint {
+        fn name(&self) -> &str {
+            "Slow"
+        }
+
+        fn enforce(&self, board: &Board, cell: CellIndex, value: usize) -> LogicalStepResult {
+            std::thread::sleep(std::time::Duration::from_micros(1));
+            if cell == board.cell_utility().cell(0, 0) && value == 9 {
+                LogicalStepResult::Invalid(None)
+            } else {
+                LogicalStepResult::None
+            }
+        }
+    }
+
+    #[test]
+    fn test_constraint_profile() {
+        let solver = SolverBuilder::default()
+            .with_givens_string("1...2..4...7...3...6..1..5..7......4.4.5.9..6.....8.3.4..2.........5.....8...6.7.")
+            .with_constraint(Arc::new(SlowConstraint))
+            .build()
+            .unwrap();
+        solver.board().reset_constraint_profile();
+        assert!(solver.find_solution_count(10, None, None).is_at_least_count());
+
+        let profile = solver.board().constraint_profile();
+        assert_eq!(profile.len(), 1);
+        assert_eq!(profile[0].constraint, "Slow");
+        assert!(profile[0].enforce_calls > 0);
+        assert!(profile[0].enforce_time >= std::time::Duration::from_micros(profile[0].enforce_calls as u64));
+        assert!(profile[0].step_logic_calls > 0);
+        assert!(profile[0].to_string().starts_with(&format!("Slow: {} enforce calls in ", profile[0].enforce_calls)));
+
+        solver.board().reset_constraint_profile();
+        assert_eq!(solver.board().constraint_profile()[0].total_time(), std::time::Duration::ZERO);
+    }
+}
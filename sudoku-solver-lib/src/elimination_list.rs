@@ -10,15 +10,19 @@ use std::{collections::BTreeSet, fmt::Display};
 ///
 /// Use the [`Display`] to get a human-readable description of the list of
 /// eliminated candidates.
+///
+/// The candidates are stored as [`CompactCandidateIndex`] along with the size of the board,
+/// which is taken from the first candidate added.
 #[derive(Clone)]
 pub struct EliminationList {
-    candidates: BTreeSet<CandidateIndex>,
+    candidates: BTreeSet<CompactCandidateIndex>,
+    size: usize,
 }
 
 impl EliminationList {
     /// Create a new empty elimination list.
     pub fn new() -> EliminationList {
-        EliminationList { candidates: BTreeSet::new() }
+        EliminationList { candidates: BTreeSet::new(), size: 0 }
     }
 
     /// Get the number of candidates in the elimination list.
@@ -32,18 +36,40 @@ impl EliminationList {
     }
 
     /// Get the candidates in the elimination list.
-    pub fn candidates(&self) -> &BTreeSet<CandidateIndex> {
+    ///
+    /// # Example
+    /// ```
+    /// # use sudoku_solver_lib::prelude::*;
+    /// let cu = CellUtility::new(16);
+    /// let mut elims = EliminationList::new();
+    /// elims.add(cu.cell(15, 15).candidate(16));
+    /// elims.add(cu.cell(0, 0).candidate(1));
+    ///
+    /// let candidates = elims.candidates();
+    /// assert_eq!(candidates.first(), Some(&cu.cell(0, 0).candidate(1)));
+    /// assert_eq!(candidates.last(), Some(&cu.cell(15, 15).candidate(16)));
+    /// assert_eq!(elims.compact_candidates().len(), 2);
+    /// ```
+    pub fn candidates(&self) -> BTreeSet<CandidateIndex> {
+        self.iter().collect()
+    }
+
+    /// Get the candidates in the elimination list as they are stored, without the board size.
+    ///
+    /// Use [`CellUtility::candidate_from_compact`] to convert them back to [`CandidateIndex`].
+    pub fn compact_candidates(&self) -> &BTreeSet<CompactCandidateIndex> {
         &self.candidates
     }
 
     /// Returns true if the list contains the given candidate.
     pub fn contains(&self, candidate: CandidateIndex) -> bool {
-        self.candidates.contains(&candidate)
+        self.candidates.contains(&candidate.compact())
     }
 
     /// Returns an iterator over the candidates in the list.
     pub fn iter(&self) -> impl Iterator<Item = CandidateIndex> + '_ {
-        self.candidates.iter().copied()
+        let cu = CellUtility::new(self.size);
+        self.candidates.iter().map(move |&candidate| cu.candidate_from_compact(candidate))
     }
 
     /// Add a candidate to the elimination list.
@@ -67,7 +93,8 @@ impl EliminationList {
     /// assert_eq!(desc, "-3r4c5");
     /// ```
     pub fn add(&mut self, candidate: CandidateIndex) {
-        self.candidates.insert(candidate);
+        self.size = candidate.size();
+        self.candidates.insert(candidate.compact());
     }
 
     /// Add all candidates to the elimination list.
@@ -91,7 +118,9 @@ impl EliminationList {
     /// assert_eq!(desc, "-1r1c1;-3r4c56");
     /// ```
     pub fn add_all(&mut self, candidates: &[CandidateIndex]) {
-        self.candidates.extend(candidates.iter());
+        for &candidate in candidates {
+            self.add(candidate);
+        }
     }
 
     /// Add a candidate to the elimination list by cell index and value.
@@ -115,11 +144,34 @@ impl EliminationList {
         self.add(cell.candidate(value));
     }
 
+    /// Create an elimination list of the candidates of each cell which are not in its possible values.
+    ///
+    /// The cells and possible values are paired in order.
+    ///
+    /// # Example
+    /// ```
+    /// # use sudoku_solver_lib::prelude::*;
+    /// let board = Board::default();
+    /// let cu = board.cell_utility();
+    /// let cells = [cu.cell(0, 0), cu.cell(0, 1)];
+    /// let possible = [ValueMask::from_values(&[1, 2, 3, 4, 5, 6, 7, 8]), ValueMask::from_all_values(9)];
+    ///
+    /// let elims = EliminationList::from_possible(&cells, &possible, &board);
+    /// assert_eq!(elims.to_string(), "-9r1c1");
+    /// ```
+    pub fn from_possible(cells: &[CellIndex], possible: &[ValueMask], board: &Board) -> EliminationList {
+        cells
+            .iter()
+            .zip(possible.iter())
+            .flat_map(|(&cell, &possible)| (board.cell(cell) & !possible).into_iter().map(move |v| cell.candidate(v)))
+            .collect()
+    }
+
     /// Remove a candidate from the elimination list.
     /// Returns true if the candidate was removed, false if it was not in the list.
     /// If the candidate was not in the list, this function does nothing.
     pub fn remove(&mut self, candidate: CandidateIndex) -> bool {
-        self.candidates.remove(&candidate)
+        self.candidates.remove(&candidate.compact())
     }
 
     /// Execute the eliminations on a [`Board`].
@@ -163,7 +215,7 @@ impl EliminationList {
     /// ```
     pub fn execute(&self, board: &mut Board) -> LogicalStepResult {
         let mut result = LogicalStepResult::None;
-        for &candidate in self.candidates.iter() {
+        for candidate in self.iter() {
             if board.has_candidate(candidate) {
                 if board.clear_candidate(candidate) {
                     if result.is_none() {
@@ -187,13 +239,13 @@ impl Default for EliminationList {
 
 impl From<BTreeSet<CandidateIndex>> for EliminationList {
     fn from(candidates: BTreeSet<CandidateIndex>) -> Self {
-        Self { candidates }
+        candidates.into_iter().collect()
     }
 }
 
 impl From<EliminationList> for BTreeSet<CandidateIndex> {
     fn from(elims: EliminationList) -> Self {
-        elims.candidates
+        elims.iter().collect()
     }
 }
 
@@ -203,7 +255,11 @@ impl FromIterator<CandidateIndex> for EliminationList {
     where
         I: IntoIterator<Item = CandidateIndex>,
     {
-        EliminationList { candidates: iter.into_iter().collect() }
+        let mut elims = EliminationList::new();
+        for candidate in iter {
+            elims.add(candidate);
+        }
+        elims
     }
 }
 
@@ -218,10 +274,10 @@ impl Display for EliminationList {
         if self.candidates.is_empty() {
             write!(f, "No eliminations")
         } else {
-            let board_size = self.candidates.iter().next().unwrap().size();
+            let board_size = self.size;
             let cu = CellUtility::new(board_size);
             let mut elims_by_value: Vec<Vec<CellIndex>> = vec![vec![]; board_size];
-            for &candidate in self.candidates.iter() {
+            for candidate in self.iter() {
                 let (cell, value) = candidate.cell_index_and_value();
                 elims_by_value[value - 1].push(cell);
             }
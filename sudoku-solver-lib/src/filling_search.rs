@@ -0,0 +1,102 @@
+//! Contains [`possible_filling_values`] for finding which candidates of a group of cells can be part of a valid filling.
+
+use crate::prelude::*;
+
+/// Finds the values each cell can have in at least one valid filling of the cells.
+///
+/// A filling gives each cell one value of its mask, in order. `can_follow` says whether a value can
+/// follow the values of the earlier cells, and `is_valid` whether a filling of every cell is valid.
+///
+/// A filling is searched for each candidate which no filling found so far includes, so the result
+/// is usually found with far fewer searches than there are candidates.
+///
+/// # Example
+/// ```
+/// # use sudoku_solver_lib::prelude::*;
+/// // Two different values which sum to 5
+/// let masks = [ValueMask::from_values(&[1, 2, 3, 4]), ValueMask::from_values(&[2, 3, 4, 5])];
+/// let possible = possible_filling_values(
+///     &masks,
+///     |values, value| !values.contains(&value),
+///     |values| values.iter().sum::<usize>() == 5,
+/// );
+/// assert_eq!(possible, vec![ValueMask::from_values(&[1, 2, 3]), ValueMask::from_values(&[2, 3, 4])]);
+/// ```
+pub fn possible_filling_values(
+    masks: &[ValueMask],
+    mut can_follow: impl FnMut(&[usize], usize) -> bool,
+    mut is_valid: impl FnMut(&[usize]) -> bool,
+) -> Vec<ValueMask> {
+    let mut possible = vec![ValueMask::new(); masks.len()];
+    let mut values = Vec::with_capacity(masks.len());
+    for (index, &mask) in masks.iter().enumerate() {
+        for value in mask {
+            // Any filling found for an earlier candidate may already include this one.
+            if possible[index].has(value) {
+                continue;
+            }
+
+            values.clear();
+            if fill(masks, &mut values, (index, value), &mut can_follow, &mut is_valid) {
+                for (possible, &value) in possible.iter_mut().zip(values.iter()) {
+                    *possible = possible.with(value);
+                }
+            }
+        }
+    }
+    possible
+}
+
+/// Extends the values with a valid filling of the rest of the cells where the fixed
+/// cell has the fixed value, returning false if there is none.
+fn fill(
+    masks: &[ValueMask],
+    values: &mut Vec<usize>,
+    fixed: (usize, usize),
+    can_follow: &mut impl FnMut(&[usize], usize) -> bool,
+    is_valid: &mut impl FnMut(&[usize]) -> bool,
+) -> bool {
+    let index = values.len();
+    if index == masks.len() {
+        return is_valid(values);
+    }
+
+    let mask = if index == fixed.0 { masks[index] & ValueMask::from_value(fixed.1) } else { masks[index] };
+    for value in mask {
+        if !can_follow(values, value) {
+            continue;
+        }
+
+        values.push(value);
+        if fill(masks, values, fixed, can_follow, is_valid) {
+            return true;
+        }
+        values.pop();
+    }
+    false
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_possible_filling_values() {
+        // Increasing values from 1 to 4 where the last is 4
+        let masks = [ValueMask::from_all_values(4); 3];
+        let possible = possible_filling_values(
+            &masks,
+            |values, value| values.last().is_none_or(|&last| value > last),
+            |values| values[2] == 4,
+        );
+        assert_eq!(
+            possible,
+            vec![ValueMask::from_values(&[1, 2]), ValueMask::from_values(&[2, 3]), ValueMask::from_value(4)]
+        );
+
+        // Nothing is possible without a valid filling
+        let possible = possible_filling_values(&masks, |values, value| !values.contains(&value), |_| false);
+        assert!(possible.iter().all(|mask| mask.is_empty()));
+        assert!(possible_filling_values(&[], |_, _| true, |_| true).is_empty());
+    }
+}
@@ -0,0 +1,78 @@
+//! Contains [`GivenConflict`] and [`find_given_conflicts`] for detecting cells which are given more than one value.
+
+use crate::prelude::*;
+use itertools::Itertools;
+
+/// A cell which is given different values.
+///
+/// Givens are usually added from several sources, such as a givens string and the digits of an
+/// f-puzzles grid, so the same cell can be given twice by mistake.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GivenConflict {
+    /// The cell which is given more than one value.
+    pub cell: CellIndex,
+    /// The different values the cell is given, in the order they were given.
+    pub values: Vec<usize>,
+}
+
+impl std::fmt::Display for GivenConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} is given conflicting values {}", self.cell, self.values.iter().join(" and "))
+    }
+}
+
+/// Finds the cells which are given more than one value, in the order the cells were first given.
+///
+/// Giving a cell the same value more than once is not a conflict.
+///
+/// This is checked by [`SolverBuilder::build`], so that conflicting givens are reported by
+/// cell instead of as an invalid board.
+pub fn find_given_conflicts(givens: &[(CellIndex, usize)]) -> Vec<GivenConflict> {
+    givens
+        .iter()
+        .into_group_map_by(|(cell, _)| *cell)
+        .into_iter()
+        .map(|(cell, givens)| GivenConflict { cell, values: givens.iter().map(|(_, value)| *value).unique().collect() })
+        .filter(|conflict| conflict.values.len() > 1)
+        .sorted_by_key(|conflict| givens.iter().position(|(cell, _)| *cell == conflict.cell))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_find_given_conflicts() {
+        let cu = CellUtility::new(9);
+        let givens = [
+            (cu.cell(4, 4), 5),
+            (cu.cell(0, 0), 1),
+            (cu.cell(4, 4), 5),
+            (cu.cell(0, 1), 2),
+            (cu.cell(0, 0), 3),
+            (cu.cell(4, 4), 6),
+        ];
+
+        let conflicts = find_given_conflicts(&givens);
+        assert_eq!(
+            conflicts,
+            vec![
+                GivenConflict { cell: cu.cell(4, 4), values: vec![5, 6] },
+                GivenConflict { cell: cu.cell(0, 0), values: vec![1, 3] },
+            ]
+        );
+        assert_eq!(conflicts[0].to_string(), "r5c5 is given conflicting values 5 and 6");
+        assert!(find_given_conflicts(&givens[..4]).is_empty());
+
+        let error = SolverBuilder::default().with_givens(&givens).build().err().unwrap();
+        assert_eq!(error, "r5c5 is given conflicting values 5 and 6, r1c1 is given conflicting values 1 and 3");
+
+        // The same value given twice is fine
+        let solver = SolverBuilder::default()
+            .with_givens_string(&("1".to_owned() + &".".repeat(80)))
+            .with_given(cu.cell(0, 0), 1)
+            .build();
+        assert!(solver.is_ok());
+    }
+}
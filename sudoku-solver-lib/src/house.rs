@@ -1,6 +1,6 @@
 //! Contains [`House`] for representing the cells in a house along with its name.
 
-use crate::cell_index::CellIndex;
+use crate::prelude::*;
 
 /// A *house* is a group of N cells where N is the size of the board where
 /// digits cannot repeat within that group.
@@ -20,15 +20,58 @@ use crate::cell_index::CellIndex;
 pub struct House {
     name: String,
     cells: Vec<CellIndex>,
+    kind: HouseKind,
+}
+
+/// Where a [`House`] comes from, so that logical steps can be limited to some kinds of houses.
+/// See [`SolverBuilder::with_step_house_kinds`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HouseKind {
+    Row,
+    Column,
+    /// A region of the board, such as a 3x3 box.
+    Region,
+    /// A house added by a constraint, such as an extra region.
+    Constraint,
+}
+
+impl HouseKind {
+    /// Every kind of house.
+    pub const ALL: [HouseKind; 4] = [Self::Row, Self::Column, Self::Region, Self::Constraint];
+
+    /// Returns the name of the kind as used in step messages.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Row => "row",
+            Self::Column => "column",
+            Self::Region => "region",
+            Self::Constraint => "constraint",
+        }
+    }
+}
+
+impl std::fmt::Display for HouseKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
 }
 
 impl House {
     /// Create a new house with the given name and cells.
+    ///
+    /// The house is of kind [`HouseKind::Constraint`] unless changed with [`Self::with_kind`].
     pub fn new(name: &str, cells: &[CellIndex]) -> House {
         let mut cells = cells.to_vec();
         cells.sort();
 
-        House { name: name.to_string(), cells }
+        House { name: name.to_string(), cells, kind: HouseKind::Constraint }
+    }
+
+    /// Set the kind of the house.
+    #[must_use]
+    pub fn with_kind(mut self, kind: HouseKind) -> House {
+        self.kind = kind;
+        self
     }
 
     /// Get the name of the house.
@@ -36,6 +79,11 @@ impl House {
         &self.name
     }
 
+    /// Get the kind of the house.
+    pub fn kind(&self) -> HouseKind {
+        self.kind
+    }
+
     /// Get the cells that make up the house.
     pub fn cells(&self) -> &Vec<CellIndex> {
         &self.cells
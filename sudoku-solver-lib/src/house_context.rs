@@ -0,0 +1,94 @@
+//! Contains [`HouseContext`] which describes the board to constraints deciding which houses they form.
+
+use crate::prelude::*;
+use std::sync::Arc;
+
+/// What is known about the board while its houses are being created. See [`Constraint::get_houses`].
+///
+/// The givens are applied after the houses are created, so they are not known yet.
+pub struct HouseContext<'a> {
+    size: usize,
+    regions: &'a [usize],
+    constraints: &'a [Arc<dyn Constraint>],
+    houses: &'a [Arc<House>],
+}
+
+impl<'a> HouseContext<'a> {
+    pub(crate) fn new(
+        size: usize,
+        regions: &'a [usize],
+        constraints: &'a [Arc<dyn Constraint>],
+        houses: &'a [Arc<House>],
+    ) -> Self {
+        Self { size, regions, constraints, houses }
+    }
+
+    /// The size of the board, which is also the number of cells in a house.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// The region index of each cell, indexed by [`CellIndex::index`].
+    ///
+    /// Every cell is in region 0 when the board has no regions.
+    pub fn regions(&self) -> &[usize] {
+        self.regions
+    }
+
+    /// Every constraint of the board, including the one whose houses are being created.
+    pub fn constraints(&self) -> &[Arc<dyn Constraint>] {
+        self.constraints
+    }
+
+    /// The houses created so far, which are the rows, columns and regions
+    /// followed by the houses of the constraints before this one.
+    pub fn houses(&self) -> &[Arc<House>] {
+        self.houses
+    }
+
+    /// Whether the cells are exactly the cells of a house created so far.
+    pub fn is_house(&self, cells: &[CellIndex]) -> bool {
+        let mut cells = cells.to_vec();
+        cells.sort();
+        self.houses.iter().any(|house| *house.cells() == cells)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Forms a house from the cells of the second box, unless the board already has boxes.
+    #[derive(Debug)]
+    struct SecondBox;
+
+    impl Constraint for SecondBox {
+        fn name(&self) -> &str {
+            "Second Box"
+        }
+
+        fn get_houses(&self, context: &HouseContext) -> Vec<House> {
+            assert_eq!(context.constraints().len(), 1);
+            assert_eq!(context.regions().len(), context.size() * context.size());
+
+            let cu = CellUtility::new(context.size());
+            let cells = (0..3).flat_map(|row| (3..6).map(move |col| cu.cell(row, col))).collect::<Vec<_>>();
+            if context.is_house(&cells) {
+                Vec::new()
+            } else {
+                vec![House::new("Box 2", &cells)]
+            }
+        }
+    }
+
+    #[test]
+    fn test_house_context() {
+        let board = Board::new(9, &[], vec![Arc::new(SecondBox)]);
+        assert_eq!(board.houses().len(), 27);
+        assert!(board.houses().iter().all(|house| house.name() != "Box 2"));
+
+        let board = Board::new(9, &[0; 81], vec![Arc::new(SecondBox)]);
+        assert_eq!(board.houses().len(), 19);
+        assert_eq!(board.houses().last().unwrap().name(), "Box 2");
+    }
+}
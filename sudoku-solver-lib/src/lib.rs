@@ -11,16 +11,36 @@
 //! This library does not provide any implementations of this trait, and instead relies on the
 //! consumer of this library to provide the constraints for the puzzle to be solved.
 
+#![cfg_attr(feature = "simd", feature(portable_simd))]
+
 pub mod board;
+pub mod board_observer;
+pub mod board_recorder;
+pub mod candidate_density;
 pub mod candidate_index;
 pub mod candidate_links;
+pub mod cell_fact;
 pub mod cell_index;
 pub mod cell_utility;
 pub mod constraint;
+pub mod constraint_conflict;
+#[cfg(feature = "profiling")]
+pub mod constraint_profile;
 pub mod elimination_list;
+pub mod filling_search;
+pub mod given_conflict;
 pub mod house;
+pub mod house_context;
+pub mod link_graph;
 pub mod logical_step;
 pub mod math;
+pub mod memory_footprint;
 pub mod prelude;
+pub mod region_membership;
+pub mod solution_statistics;
 pub mod solver;
+pub mod symmetry;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
 pub mod value_mask;
+pub mod weak_link_stats;
@@ -0,0 +1,145 @@
+//! Contains [`LinkGraph`] for exporting the weak and strong links between the candidates of a board.
+
+use crate::prelude::*;
+
+/// The weak and strong links between the remaining candidates of a board, for chaining and SAT
+/// engines outside of this crate. See [`Board::export_link_graph`].
+///
+/// A weak link means the two candidates can't both be true. These come from the sudoku rules and
+/// from the [`Constraint::get_weak_links`] of every constraint, so they are aware of the variant rules.
+///
+/// A strong link means the two candidates can't both be false. These are found from the current
+/// candidates: the two candidates of a cell with only two left, and the two places for a value
+/// in a house where it only has two left.
+///
+/// Both are stored as adjacency lists keyed by [`CandidateIndex::index`], each sorted and holding only
+/// candidates which are still on the board. Links are symmetric, so each appears in both lists.
+///
+/// The [`std::fmt::Display`] form is a plain text edge list, one link per line with the lower
+/// candidate index first: `w 0 9` is a weak link between candidates 0 and 9, and `s 0 1` is a
+/// strong link. The first line is `links {size}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkGraph {
+    /// The size of the board.
+    pub size: usize,
+    /// The candidates weakly linked to each candidate.
+    pub weak_links: Vec<Vec<usize>>,
+    /// The candidates strongly linked to each candidate.
+    pub strong_links: Vec<Vec<usize>>,
+}
+
+impl LinkGraph {
+    pub(crate) fn new(board: &Board) -> Self {
+        let size = board.size();
+        let cu = board.cell_utility();
+        let num_candidates = cu.candidate_count();
+
+        let mut weak_links = vec![Vec::new(); num_candidates];
+        for candidate in cu.all_candidates().filter(|&candidate| board.has_candidate(candidate)) {
+            weak_links[candidate.index()] = board
+                .data()
+                .weak_links_for(candidate)
+                .links()
+                .filter(|&other| board.has_candidate(other))
+                .map(|other| other.index())
+                .collect();
+        }
+
+        let mut strong_links = vec![Vec::new(); num_candidates];
+        let mut add_strong_link = |candidate0: CandidateIndex, candidate1: CandidateIndex| {
+            let (index0, index1) = (candidate0.index(), candidate1.index());
+            if !strong_links[index0].contains(&index1) {
+                strong_links[index0].push(index1);
+                strong_links[index1].push(index0);
+            }
+        };
+
+        for (cell, mask) in board.all_cell_masks() {
+            if !mask.is_solved() && mask.count() == 2 {
+                add_strong_link(cell.candidate(mask.min()), cell.candidate(mask.max()));
+            }
+        }
+
+        for house in board.houses().iter().filter(|house| house.cells().len() == size) {
+            for value in 1..=size {
+                let mut cells = house.cells().iter().filter(|&&cell| {
+                    let mask = board.cell(cell);
+                    !mask.is_solved() && mask.has(value)
+                });
+                if let (Some(&cell0), Some(&cell1), None) = (cells.next(), cells.next(), cells.next()) {
+                    add_strong_link(cell0.candidate(value), cell1.candidate(value));
+                }
+            }
+        }
+
+        for links in strong_links.iter_mut() {
+            links.sort_unstable();
+        }
+
+        Self { size, weak_links, strong_links }
+    }
+
+    /// The number of weak links, counting each pair of candidates once.
+    pub fn weak_link_count(&self) -> usize {
+        self.weak_links.iter().map(Vec::len).sum::<usize>() / 2
+    }
+
+    /// The number of strong links, counting each pair of candidates once.
+    pub fn strong_link_count(&self) -> usize {
+        self.strong_links.iter().map(Vec::len).sum::<usize>() / 2
+    }
+}
+
+impl std::fmt::Display for LinkGraph {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "links {}", self.size)?;
+        for (kind, adjacency) in [("w", &self.weak_links), ("s", &self.strong_links)] {
+            for (index, links) in adjacency.iter().enumerate() {
+                for &other in links.iter().filter(|&&other| other > index) {
+                    writeln!(f, "{kind} {index} {other}")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_link_graph() {
+        let mut board = Board::default();
+        let cu = board.cell_utility();
+
+        // Only 1 and 2 remain in r1c1, and 1 can only go in r1c1 or r1c2 in row 1
+        let r1c1 = cu.cell(0, 0);
+        assert!(board.clear_candidates((3..=9).map(|value| cu.candidate(r1c1, value))));
+        assert!(board.clear_candidates((2..9).map(|col| cu.candidate(cu.cell(0, col), 1))));
+
+        let graph = board.export_link_graph();
+        let candidate = |cell: CellIndex, value: usize| cell.candidate(value).index();
+
+        // Eliminated candidates have no links
+        assert!(graph.weak_links[candidate(r1c1, 3)].is_empty());
+        assert!(!graph.weak_links[candidate(cu.cell(0, 2), 2)].contains(&candidate(cu.cell(0, 2), 1)));
+
+        // Weak links come from the cell and its houses
+        let weak = &graph.weak_links[candidate(r1c1, 1)];
+        assert!(weak.contains(&candidate(r1c1, 2)));
+        assert!(weak.contains(&candidate(cu.cell(0, 1), 1)));
+        assert!(weak.contains(&candidate(cu.cell(8, 0), 1)));
+        assert!(!weak.contains(&candidate(cu.cell(0, 2), 1)));
+
+        // The bivalue cell and the bilocal value in row 1 are strong links
+        assert_eq!(graph.strong_links[candidate(r1c1, 1)], vec![candidate(r1c1, 2), candidate(cu.cell(0, 1), 1)]);
+        assert_eq!(graph.strong_links[candidate(cu.cell(0, 1), 1)], vec![candidate(r1c1, 1)]);
+        assert_eq!(graph.strong_link_count(), 2);
+
+        let text = graph.to_string();
+        assert!(text.starts_with("links 9\n"));
+        assert!(text.contains(&format!("s {} {}\n", candidate(r1c1, 1), candidate(r1c1, 2))));
+        assert_eq!(text.lines().filter(|line| line.starts_with("w ")).count(), graph.weak_link_count());
+    }
+}
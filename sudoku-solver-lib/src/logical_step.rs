@@ -1,14 +1,21 @@
 //! Contains the [`LogicalStep`] trait for representing a logical step.
 
 pub mod all_naked_singles;
+pub mod candidate_highlight;
 pub mod hidden_single;
+pub mod logic_profile;
 pub mod logical_step_desc;
 pub mod logical_step_desc_list;
+pub mod logical_step_registry;
 pub mod logical_step_result;
 pub mod naked_single;
+pub mod naked_tuple;
+pub mod pointing_claiming;
 pub mod prelude;
 pub mod simple_cell_forcing;
+pub mod step_cause;
 pub mod step_constraints;
+pub mod step_message;
 
 use crate::prelude::*;
 
@@ -18,7 +25,9 @@ use crate::prelude::*;
 /// Each logical elimination concept has its own implementation of this trait.
 /// Generally, these logical steps do not interact with constraints other than
 /// through the weak links generated by those constraints.
-pub trait LogicalStep: std::any::Any + std::fmt::Debug {
+///
+/// Like [`Constraint`], logical steps must be [`Send`] and [`Sync`].
+pub trait LogicalStep: std::any::Any + std::fmt::Debug + Send + Sync {
     /// The name of the logical step for display purposes.
     fn name(&self) -> &'static str;
 
@@ -62,10 +71,13 @@ pub trait LogicalStep: std::any::Any + std::fmt::Debug {
 
     /// Runs one logical step on the board.
     ///
+    /// Steps which can take a long time should check the cancellation as they go, and return
+    /// [`LogicalStepResult::None`] once it is cancelled, leaving the board consistent.
+    ///
     /// # Return value
     /// Return the following based on the situation:
     /// - [`LogicalStepResult::None`] if the board is unchanged.
     /// - [`LogicalStepResult::Changed`] if the board is changed.
     /// - [`LogicalStepResult::Invalid`] if this constraint has made the solve impossible.
-    fn run(&self, board: &mut Board, generate_description: bool) -> LogicalStepResult;
+    fn run(&self, board: &mut Board, generate_description: bool, cancellation: &Cancellation) -> LogicalStepResult;
 }
@@ -4,6 +4,9 @@ use crate::prelude::*;
 ///
 /// Generally, this is used during brute force only, when there is no
 /// need for user-facing descriptions.
+///
+/// Only cells whose candidates changed since the last run are checked,
+/// using [`Board::take_changed_cell`].
 #[derive(Debug)]
 pub struct AllNakedSingles;
 
@@ -20,37 +23,27 @@ impl LogicalStep for AllNakedSingles {
         false
     }
 
-    fn run(&self, board: &mut Board, generate_description: bool) -> LogicalStepResult {
+    fn run(&self, board: &mut Board, generate_description: bool, _cancellation: &Cancellation) -> LogicalStepResult {
         assert!(!generate_description, "AllNakedSingles should not be used during logical solves");
 
         let mut result = LogicalStepResult::None;
-        loop {
-            if board.is_solved() {
-                break;
+        while let Some(cell) = board.take_changed_cell() {
+            let mask = board.cell(cell);
+            if mask.is_solved() {
+                continue;
             }
 
-            let mut changed = false;
-            for cell in board.all_cells() {
-                let mask = board.cell(cell);
-                if mask.is_solved() {
-                    continue;
-                }
-
-                if mask.is_single() {
-                    let value = mask.value();
-                    if board.set_solved(cell, value) {
-                        changed = true;
-                    } else {
-                        return LogicalStepResult::Invalid(None);
-                    }
-                } else if mask.is_empty() {
+            if mask.is_single() {
+                let value = mask.value();
+                if board.set_solved(cell, value) {
+                    result = LogicalStepResult::Changed(None);
+                } else {
+                    board.mark_cell_changed(cell);
                     return LogicalStepResult::Invalid(None);
                 }
-            }
-            if !changed {
-                break;
-            } else {
-                result = LogicalStepResult::Changed(None);
+            } else if mask.is_empty() {
+                board.mark_cell_changed(cell);
+                return LogicalStepResult::Invalid(None);
             }
         }
 
@@ -69,7 +62,7 @@ mod test {
         let all_naked_singles = AllNakedSingles;
 
         // There should be no naked singles on the initial board
-        assert!(all_naked_singles.run(&mut board, false).is_none());
+        assert!(all_naked_singles.run(&mut board, false, &Cancellation::default()).is_none());
 
         // Set up the board so that the entire thing solves with just naked singles
         let board_str = "5.6....29.9....13..4...376.........232.5......5..186.32..64.38..1.37529....821.7.";
@@ -80,7 +73,7 @@ mod test {
         });
 
         // The board should fully solve with naked singles
-        assert!(all_naked_singles.run(&mut board, false).is_changed());
+        assert!(all_naked_singles.run(&mut board, false, &Cancellation::default()).is_changed());
         assert!(board.is_solved());
         assert_eq!(
             board.to_string(),
@@ -0,0 +1,48 @@
+//! Contains [`CandidateHighlight`] for marking the candidates which take part in a logical step.
+
+use crate::prelude::*;
+
+/// The part a candidate plays in a logical step, so that user interfaces can color it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HighlightRole {
+    /// The candidates whose pattern makes the step work, such as the cells of a naked pair.
+    DefiningSet,
+    /// The extra candidates of a finned pattern.
+    Fin,
+    /// A candidate which the step removes.
+    Elimination,
+    /// A value which the step places.
+    Placement,
+}
+
+impl HighlightRole {
+    /// Returns the name of the role as used by the message handler.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::DefiningSet => "defining",
+            Self::Fin => "fin",
+            Self::Elimination => "elimination",
+            Self::Placement => "placement",
+        }
+    }
+}
+
+impl std::fmt::Display for HighlightRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// A candidate of a logical step along with the part it plays. See [`LogicalStepDesc::with_highlight`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CandidateHighlight {
+    pub candidate: CandidateIndex,
+    pub role: HighlightRole,
+}
+
+impl CandidateHighlight {
+    /// Creates a new instance.
+    pub fn new(candidate: CandidateIndex, role: HighlightRole) -> Self {
+        Self { candidate, role }
+    }
+}
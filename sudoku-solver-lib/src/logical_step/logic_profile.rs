@@ -0,0 +1,97 @@
+//! Contains [`LogicProfile`] for pinning the logical steps of a solver to a fixed set.
+
+use crate::prelude::*;
+use std::sync::Arc;
+
+/// A named, fixed set of logical steps.
+///
+/// The standard logic of [`SolverBuilder`] grows as new logical steps are added, which changes the
+/// solve paths it finds. Choosing a profile with [`SolverBuilder::with_logic_profile`] keeps the
+/// steps, and so the solve paths, the same across versions of this library.
+///
+/// # Example
+/// ```
+/// # use sudoku_solver_lib::prelude::*;
+/// let profile = LogicProfile::parse("v1-basic").unwrap();
+/// assert_eq!(profile, LogicProfile::V1Basic);
+/// assert!(!profile.step_names().contains(&"Naked Tuple"));
+/// assert!(LogicProfile::V2WithTuples.step_names().contains(&"Naked Tuple"));
+/// assert!(LogicProfile::parse("v0").is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogicProfile {
+    /// Singles, the logic of the constraints, and simple cell forcing.
+    V1Basic,
+    /// Everything in [`LogicProfile::V1Basic`], plus the opt-in naked tuples.
+    V2WithTuples,
+}
+
+impl LogicProfile {
+    /// All of the profiles, oldest first.
+    pub const ALL: [Self; 2] = [Self::V1Basic, Self::V2WithTuples];
+
+    /// The profile which matches the standard logic of [`SolverBuilder`].
+    pub const LATEST: Self = Self::V1Basic;
+
+    /// Returns the name of the profile as used by [`LogicProfile::parse`].
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::V1Basic => "v1-basic",
+            Self::V2WithTuples => "v2-with-tuples",
+        }
+    }
+
+    /// Finds a profile by its name, ignoring case.
+    pub fn parse(name: &str) -> Result<Self, String> {
+        Self::ALL
+            .into_iter()
+            .find(|profile| profile.name().eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| format!("Unknown logic profile: {name}"))
+    }
+
+    /// The names of the logical steps of the profile, in the order they run.
+    /// These are names in the [`logical_step_registry`].
+    pub fn step_names(self) -> &'static [&'static str] {
+        match self {
+            Self::V1Basic => {
+                &["All Naked Singles", "Hidden Single", "Naked Single", "Step Constraints", "Simple Cell Forcing"]
+            }
+            Self::V2WithTuples => &[
+                "All Naked Singles",
+                "Hidden Single",
+                "Naked Single",
+                "Step Constraints",
+                "Naked Tuple",
+                "Simple Cell Forcing",
+            ],
+        }
+    }
+
+    /// Creates the logical steps of the profile.
+    pub fn logical_steps(self) -> Vec<Arc<dyn LogicalStep>> {
+        logical_step_registry().create_all(self.step_names()).expect("Logic profiles only name registered steps")
+    }
+}
+
+impl std::fmt::Display for LogicProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_latest_matches_standard_logic() {
+        let registry = logical_step_registry();
+        assert_eq!(LogicProfile::LATEST.step_names(), registry.standard_names().collect::<Vec<_>>());
+
+        for profile in LogicProfile::ALL {
+            assert_eq!(LogicProfile::parse(profile.name()).unwrap(), profile);
+            let steps = profile.logical_steps();
+            assert_eq!(steps.iter().map(|step| step.name()).collect::<Vec<_>>(), profile.step_names());
+        }
+    }
+}
@@ -17,34 +17,144 @@ pub struct LogicalStepDesc {
     step: String,
     sub_steps: LogicalStepDescList,
     depth: usize,
+    /// The structured form of the description without its prefixes, if it has one.
+    message: Option<StepMessage>,
+    /// The prefixes which `step` starts with, outermost first.
+    prefixes: Vec<String>,
+    highlights: Vec<CandidateHighlight>,
+    /// Why the step could be made, shown after the step as "because ...".
+    causes: Vec<StepCause>,
 }
 
 impl LogicalStepDesc {
     /// Creates a new instance.
     pub fn new(step: &str, sub_steps: &LogicalStepDescList) -> Self {
-        Self { step: step.to_owned(), sub_steps: sub_steps.with_depth(1), depth: 0 }
+        Self { sub_steps: sub_steps.with_depth(1), ..Self::from_desc(step) }
     }
 
     /// Creates a new instance from a description string an no sub-steps.
     pub fn from_desc(desc: &str) -> Self {
-        Self { step: desc.to_owned(), sub_steps: LogicalStepDescList::new(), depth: 0 }
+        desc.to_owned().into()
     }
 
     /// Creates a new instance from a description and a list of eliminations.
+    ///
+    /// The eliminations are highlighted with [`HighlightRole::Elimination`].
     pub fn from_elims(desc: &str, elimination_list: &EliminationList) -> Self {
         let step = format!("{desc} => {elimination_list}");
-        Self::from_desc(&step)
+        Self::from_desc(&step).with_highlights(elimination_list.iter(), HighlightRole::Elimination)
+    }
+
+    /// Creates a new instance from a structured message, which a [`Localizer`] can render in other languages.
+    pub fn from_message(message: StepMessage) -> Self {
+        Self { message: Some(message.clone()), ..message.to_string().into() }
+    }
+
+    /// Creates a new instance from a structured message and a list of eliminations,
+    /// which the message template shows with `{elims}`.
+    ///
+    /// The eliminations are highlighted with [`HighlightRole::Elimination`].
+    pub fn from_message_elims(message: StepMessage, elimination_list: &EliminationList) -> Self {
+        let message = message.with_arg("elims", MessageArg::Eliminations(elimination_list.iter().collect()));
+        Self::from_message(message).with_highlights(elimination_list.iter(), HighlightRole::Elimination)
     }
 
     /// Creates a new instance where the description is prefixed with the provided
     /// string.
     pub fn with_prefix(&self, prefix: &str) -> Self {
         let step = format!("{}{}", prefix, self.step);
-        Self { step, sub_steps: self.sub_steps.clone(), depth: self.depth }
+        let prefixes = std::iter::once(prefix.to_owned()).chain(self.prefixes.iter().cloned()).collect();
+        Self { step, prefixes, ..self.clone() }
+    }
+
+    /// Highlights a candidate with the part it plays in the step.
+    #[must_use]
+    pub fn with_highlight(mut self, candidate: CandidateIndex, role: HighlightRole) -> Self {
+        self.highlights.push(CandidateHighlight::new(candidate, role));
+        self
+    }
+
+    /// Highlights every candidate with the same role.
+    #[must_use]
+    pub fn with_highlights(
+        mut self,
+        candidates: impl IntoIterator<Item = CandidateIndex>,
+        role: HighlightRole,
+    ) -> Self {
+        self.highlights.extend(candidates.into_iter().map(|candidate| CandidateHighlight::new(candidate, role)));
+        self
+    }
+
+    /// Adds the candidates which trigger the step. See [`StepCause`].
+    #[must_use]
+    pub fn with_cause(mut self, cause: StepCause) -> Self {
+        self.causes.push(cause);
+        self
+    }
+
+    /// The causes of the step, not including those of its sub-steps.
+    pub fn causes(&self) -> &[StepCause] {
+        &self.causes
+    }
+
+    /// The highlighted candidates of the step, not including those of its sub-steps.
+    pub fn highlights(&self) -> &[CandidateHighlight] {
+        &self.highlights
+    }
+
+    /// The structured message of the description, if it was created from one.
+    pub fn message(&self) -> Option<&StepMessage> {
+        self.message.as_ref()
+    }
+
+    /// Renders the description and its sub-steps with a [`Localizer`],
+    /// falling back to English for anything it doesn't know.
+    pub fn to_localized_string(&self, localizer: &dyn Localizer) -> String {
+        let mut text = String::new();
+        self.write(&mut text, Some(localizer)).unwrap();
+        text
     }
 
     pub(crate) fn with_depth(&self, depth: usize) -> LogicalStepDesc {
-        LogicalStepDesc { step: self.step.clone(), sub_steps: self.sub_steps.with_depth(depth + 1), depth }
+        LogicalStepDesc { sub_steps: self.sub_steps.with_depth(depth + 1), depth, ..self.clone() }
+    }
+
+    pub(crate) fn write(&self, f: &mut impl std::fmt::Write, localizer: Option<&dyn Localizer>) -> std::fmt::Result {
+        write!(f, "{}", self.indent_str())?;
+        match localizer {
+            Some(localizer) => {
+                // Prefixes are usually a step name followed by ": ", and only the name is translated.
+                for prefix in self.prefixes.iter() {
+                    let name = prefix.trim_end_matches([':', ' ']);
+                    let localized = localizer.localize_text(name);
+                    write!(f, "{}{}", localized.as_deref().unwrap_or(name), &prefix[name.len()..])?;
+                }
+                let prefix_len: usize = self.prefixes.iter().map(String::len).sum();
+                let body = &self.step[prefix_len..];
+                let localized = match &self.message {
+                    Some(message) => localizer.localize(message),
+                    None => localizer.localize_text(body),
+                };
+                write!(f, "{}", localized.as_deref().unwrap_or(body))?;
+            }
+            None => write!(f, "{}", self.step)?,
+        }
+
+        for (index, cause) in self.causes.iter().enumerate() {
+            if index == 0 {
+                let because = localizer.and_then(|localizer| localizer.localize_text("because"));
+                write!(f, " {} ", because.as_deref().unwrap_or("because"))?;
+            } else {
+                write!(f, "; ")?;
+            }
+            write!(f, "{cause}")?;
+        }
+
+        if !self.sub_steps.is_empty() {
+            writeln!(f)?;
+            self.sub_steps.write(f, localizer)?;
+        }
+        Ok(())
     }
 
     fn indent_str(&self) -> String {
@@ -62,24 +172,32 @@ impl LogicalStepDesc {
 
 impl From<&str> for LogicalStepDesc {
     fn from(step: &str) -> Self {
-        Self { step: step.to_owned(), sub_steps: LogicalStepDescList::new(), depth: 0 }
+        step.to_owned().into()
     }
 }
 
 impl From<String> for LogicalStepDesc {
     fn from(step: String) -> Self {
-        Self { step, sub_steps: LogicalStepDescList::new(), depth: 0 }
+        Self {
+            step,
+            sub_steps: LogicalStepDescList::new(),
+            depth: 0,
+            message: None,
+            prefixes: Vec::new(),
+            highlights: Vec::new(),
+            causes: Vec::new(),
+        }
+    }
+}
+
+impl From<StepMessage> for LogicalStepDesc {
+    fn from(message: StepMessage) -> Self {
+        Self::from_message(message)
     }
 }
 
 impl std::fmt::Display for LogicalStepDesc {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let indent = self.indent_str();
-        if self.sub_steps.is_empty() {
-            write!(f, "{}{}", indent, self.step)
-        } else {
-            writeln!(f, "{}{}", indent, self.step)?;
-            write!(f, "{}", self.sub_steps)
-        }
+        self.write(f, None)
     }
 }
@@ -125,6 +125,23 @@ impl LogicalStepDescList {
         self.steps.push(step);
     }
 
+    /// Renders every step with a [`Localizer`]. See [`LogicalStepDesc::to_localized_string`].
+    pub fn to_localized_string(&self, localizer: &dyn Localizer) -> String {
+        let mut text = String::new();
+        self.write(&mut text, Some(localizer)).unwrap();
+        text
+    }
+
+    pub(crate) fn write(&self, f: &mut impl std::fmt::Write, localizer: Option<&dyn Localizer>) -> std::fmt::Result {
+        for (index, step) in self.steps.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            step.write(f, localizer)?;
+        }
+        Ok(())
+    }
+
     pub(crate) fn with_depth(&self, depth: usize) -> LogicalStepDescList {
         let mut steps = Vec::new();
         for step in self.steps.iter() {
@@ -165,12 +182,6 @@ impl Extend<LogicalStepDesc> for LogicalStepDescList {
 
 impl std::fmt::Display for LogicalStepDescList {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        for step in self.steps.iter().take(self.steps.len() - 1) {
-            writeln!(f, "{step}")?;
-        }
-        if let Some(last_step) = self.steps.last() {
-            write!(f, "{last_step}")?;
-        }
-        Ok(())
+        self.write(f, None)
     }
 }
@@ -0,0 +1,177 @@
+//! Contains [`LogicalStepRegistry`] for creating logical steps from their names.
+
+use crate::prelude::*;
+use std::sync::Arc;
+
+/// Creates a new instance of a logical step.
+pub type LogicalStepConstructor = fn() -> Arc<dyn LogicalStep>;
+
+/// Maps the names of logical steps to their constructors, so that steps can be chosen by
+/// strings such as command line flags, config files, or the `disabledlogic` of f-puzzles data.
+///
+/// Names are matched ignoring case and whitespace, so `"Hidden Single"` and `"hiddensingle"`
+/// are the same step. The order in which steps are added is the order in which they run.
+///
+/// Opt-in steps are only created when they are named or enabled, so they aren't part of the
+/// standard logic.
+///
+/// # Example
+/// ```
+/// # use sudoku_solver_lib::prelude::*;
+/// let registry = logical_step_registry();
+/// assert_eq!(registry.create("hidden single").unwrap().name(), "Hidden Single");
+///
+/// let steps = registry.create_all_except(&["Simple Cell Forcing"]);
+/// assert!(steps.iter().all(|step| step.name() != "Simple Cell Forcing"));
+/// ```
+#[derive(Clone, Default)]
+pub struct LogicalStepRegistry {
+    entries: Vec<(&'static str, LogicalStepConstructor, bool)>,
+}
+
+impl LogicalStepRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a step to the registry, replacing any step with the same name.
+    #[must_use]
+    pub fn with_step(self, name: &'static str, constructor: LogicalStepConstructor) -> Self {
+        self.with_entry(name, constructor, false)
+    }
+
+    /// Adds an opt-in step to the registry, replacing any step with the same name.
+    #[must_use]
+    pub fn with_opt_in_step(self, name: &'static str, constructor: LogicalStepConstructor) -> Self {
+        self.with_entry(name, constructor, true)
+    }
+
+    fn with_entry(mut self, name: &'static str, constructor: LogicalStepConstructor, is_opt_in: bool) -> Self {
+        self.entries.retain(|(existing, _, _)| normalize_name(existing) != normalize_name(name));
+        self.entries.push((name, constructor, is_opt_in));
+        self
+    }
+
+    /// The names of the registered steps, in the order they run.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.entries.iter().map(|(name, _, _)| *name)
+    }
+
+    /// The names of the registered steps which are not opt-in, in the order they run.
+    pub fn standard_names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.entries.iter().filter(|(_, _, is_opt_in)| !is_opt_in).map(|(name, _, _)| *name)
+    }
+
+    /// Creates the step with the given name.
+    pub fn create(&self, name: &str) -> Option<Arc<dyn LogicalStep>> {
+        let name = normalize_name(name);
+        self.entries.iter().find(|(entry, _, _)| normalize_name(entry) == name).map(|(_, constructor, _)| constructor())
+    }
+
+    /// Creates the steps with the given names, in the given order.
+    ///
+    /// Returns an error naming the first unknown step.
+    pub fn create_all<S: AsRef<str>>(&self, names: &[S]) -> Result<Vec<Arc<dyn LogicalStep>>, String> {
+        names
+            .iter()
+            .map(|name| self.create(name.as_ref()).ok_or_else(|| format!("Unknown logical step: {}", name.as_ref())))
+            .collect()
+    }
+
+    /// Creates every registered step which is not opt-in, except those with the given names.
+    ///
+    /// Unknown names are ignored, since lists of disabled steps often come from other solvers.
+    pub fn create_all_except<S: AsRef<str>>(&self, disabled: &[S]) -> Vec<Arc<dyn LogicalStep>> {
+        self.create_all_with::<&str, S>(&[], disabled)
+    }
+
+    /// Creates every registered step which is not opt-in along with the enabled opt-in steps,
+    /// except those with the disabled names.
+    ///
+    /// Unknown names are ignored.
+    pub fn create_all_with<E: AsRef<str>, D: AsRef<str>>(
+        &self,
+        enabled: &[E],
+        disabled: &[D],
+    ) -> Vec<Arc<dyn LogicalStep>> {
+        let enabled = enabled.iter().map(|name| normalize_name(name.as_ref())).collect::<Vec<_>>();
+        let disabled = disabled.iter().map(|name| normalize_name(name.as_ref())).collect::<Vec<_>>();
+        self.entries
+            .iter()
+            .filter(|(name, _, is_opt_in)| !is_opt_in || enabled.contains(&normalize_name(name)))
+            .filter(|(name, _, _)| !disabled.contains(&normalize_name(name)))
+            .map(|(_, constructor, _)| constructor())
+            .collect()
+    }
+}
+
+impl std::fmt::Debug for LogicalStepRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+/// The registry of the logical steps in this library, in the order of the standard logic
+/// used by [`SolverBuilder`].
+pub fn logical_step_registry() -> LogicalStepRegistry {
+    LogicalStepRegistry::new()
+        .with_step("All Naked Singles", || Arc::new(AllNakedSingles))
+        .with_step("Hidden Single", || Arc::new(HiddenSingle))
+        .with_step("Naked Single", || Arc::new(NakedSingle))
+        .with_step("Step Constraints", || Arc::new(StepConstraints))
+        .with_opt_in_step("Naked Tuple", || Arc::new(NakedTuple))
+        .with_opt_in_step("Pointing Claiming", || Arc::new(PointingClaiming))
+        .with_step("Simple Cell Forcing", || Arc::new(SimpleCellForcing))
+}
+
+/// Normalizes a step name so that names differing only in case and whitespace are equal.
+pub(crate) fn normalize_name(name: &str) -> String {
+    name.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_lowercase()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_registry_names_match_steps() {
+        let registry = logical_step_registry();
+        for name in registry.names() {
+            assert_eq!(registry.create(name).unwrap().name(), name);
+        }
+    }
+
+    #[test]
+    fn test_create_all() {
+        let registry = logical_step_registry();
+        let steps = registry.create_all(&["nakedsingle", "Hidden Single"]).unwrap();
+        assert_eq!(steps.iter().map(|step| step.name()).collect::<Vec<_>>(), vec!["Naked Single", "Hidden Single"]);
+        assert_eq!(registry.create_all(&["X-Wing"]).unwrap_err(), "Unknown logical step: X-Wing");
+
+        let steps = registry.create_all_except(&["X-Wing", "hidden single"]);
+        assert_eq!(steps.len(), 4);
+    }
+
+    #[test]
+    fn test_opt_in() {
+        let registry = logical_step_registry();
+        assert!(registry.names().any(|name| name == "Naked Tuple"));
+        assert!(registry.standard_names().all(|name| name != "Naked Tuple"));
+        assert!(registry.create("nakedtuple").is_some());
+        assert!(registry.create_all_except::<&str>(&[]).iter().all(|step| step.name() != "Naked Tuple"));
+    }
+
+    #[test]
+    fn test_create_all_with() {
+        let registry = logical_step_registry();
+        assert!(registry.standard_names().all(|name| name != "Pointing Claiming"));
+        assert!(registry.create("pointingclaiming").is_some());
+
+        let steps = registry.create_all_with(&["Pointing Claiming"], &["Naked Single"]);
+        assert_eq!(
+            steps.iter().map(|step| step.name()).collect::<Vec<_>>(),
+            vec!["All Naked Singles", "Hidden Single", "Step Constraints", "Pointing Claiming", "Simple Cell Forcing"]
+        );
+    }
+}
@@ -33,6 +33,11 @@ impl LogicalStepResult {
         }
     }
 
+    /// The highlighted candidates of the step's description. See [`LogicalStepDesc::with_highlight`].
+    pub fn highlights(&self) -> &[CandidateHighlight] {
+        self.description().map(LogicalStepDesc::highlights).unwrap_or_default()
+    }
+
     pub fn with_prefix(&self, prefix: &str) -> Self {
         match self {
             LogicalStepResult::None => LogicalStepResult::None,
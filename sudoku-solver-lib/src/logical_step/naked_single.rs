@@ -11,7 +11,7 @@ impl LogicalStep for NakedSingle {
         "Naked Single"
     }
 
-    fn run(&self, board: &mut Board, generate_description: bool) -> LogicalStepResult {
+    fn run(&self, board: &mut Board, generate_description: bool, _cancellation: &Cancellation) -> LogicalStepResult {
         for cell in board.all_cells() {
             let mask = board.cell(cell);
             if mask.is_solved() {
@@ -21,18 +21,30 @@ impl LogicalStep for NakedSingle {
             if mask.is_single() {
                 let value = mask.value();
                 if board.set_solved(cell, value) {
-                    let desc = if generate_description { Some(format!("{cell}={value}").into()) } else { None };
+                    let desc = generate_description.then(|| {
+                        LogicalStepDesc::from_message(
+                            StepMessage::new("naked_single", "{cell}={value}")
+                                .with_arg("cell", MessageArg::Cell(cell))
+                                .with_arg("value", MessageArg::Value(value)),
+                        )
+                        .with_highlight(cell.candidate(value), HighlightRole::Placement)
+                    });
                     return LogicalStepResult::Changed(desc);
                 } else {
-                    let desc = if generate_description {
-                        Some(format!("{cell} cannot be set to {value}").into())
-                    } else {
-                        None
-                    };
+                    let desc = generate_description.then(|| {
+                        StepMessage::new("naked_single_invalid", "{cell} cannot be set to {value}")
+                            .with_arg("cell", MessageArg::Cell(cell))
+                            .with_arg("value", MessageArg::Value(value))
+                            .into()
+                    });
                     return LogicalStepResult::Invalid(desc);
                 }
             } else if mask.is_empty() {
-                let desc = if generate_description { Some(format!("{cell} has no candidates").into()) } else { None };
+                let desc = generate_description.then(|| {
+                    StepMessage::new("no_candidates", "{cell} has no candidates")
+                        .with_arg("cell", MessageArg::Cell(cell))
+                        .into()
+                });
                 return LogicalStepResult::Invalid(desc);
             }
         }
@@ -52,15 +64,16 @@ mod test {
         let naked_single = NakedSingle;
 
         // There should be no naked singles on the initial board
-        assert!(naked_single.run(&mut board, true).is_none());
+        assert!(naked_single.run(&mut board, true, &Cancellation::default()).is_none());
 
         // Clear all candidates except 9 from r1c1
         let cell = cu.cell(0, 0);
         board.clear_candidates((1..=8).map(|v| cu.candidate(cell, v)));
 
         // There should be a naked single in r1c1
-        let result = naked_single.run(&mut board, true);
+        let result = naked_single.run(&mut board, true, &Cancellation::default());
         assert!(result.is_changed());
         assert_eq!(result.to_string(), "r1c1=9");
+        assert_eq!(result.highlights(), [CandidateHighlight::new(cell.candidate(9), HighlightRole::Placement)]);
     }
 }
@@ -0,0 +1,143 @@
+use itertools::Itertools;
+
+use crate::prelude::*;
+
+/// A "Naked Tuple" is when a set of N unsolved cells within a house have only N candidates between them.
+/// Those candidates must go in those cells, so they can be eliminated from the rest of the house.
+///
+/// Pairs, triples and quads are searched, smallest first.
+/// Only the houses of the kinds set with [`SolverBuilder::with_step_house_kinds`] are searched.
+///
+/// This step is opt-in, see [`SolverBuilder::with_enabled_logical_steps`] and [`LogicProfile::V2WithTuples`].
+#[derive(Debug)]
+pub struct NakedTuple;
+
+impl NakedTuple {
+    /// The largest tuple which is searched for.
+    pub const MAX_TUPLE_SIZE: usize = 4;
+}
+
+impl LogicalStep for NakedTuple {
+    fn name(&self) -> &'static str {
+        "Naked Tuple"
+    }
+
+    fn run(&self, board: &mut Board, generate_description: bool, cancellation: &Cancellation) -> LogicalStepResult {
+        let board_data = board.data();
+
+        for tuple_size in 2..=Self::MAX_TUPLE_SIZE {
+            for house in board_data.houses().iter().filter(|house| board_data.is_step_house(house)) {
+                if cancellation.check() {
+                    return LogicalStepResult::None;
+                }
+
+                let unsolved_cells: Vec<CellIndex> = house
+                    .cells()
+                    .iter()
+                    .copied()
+                    .filter(|&cell| {
+                        let mask = board.cell(cell);
+                        !mask.is_solved() && mask.count() <= tuple_size
+                    })
+                    .collect();
+                if unsolved_cells.len() < tuple_size {
+                    continue;
+                }
+
+                for tuple_cells in unsolved_cells.into_iter().combinations(tuple_size) {
+                    let tuple_mask =
+                        tuple_cells.iter().fold(ValueMask::new(), |mask, &cell| mask | board.cell(cell).unsolved());
+                    if tuple_mask.count() != tuple_size {
+                        continue;
+                    }
+
+                    let mut elims = EliminationList::new();
+                    for &cell in house.cells() {
+                        if tuple_cells.contains(&cell) {
+                            continue;
+                        }
+                        let mask = board.cell(cell);
+                        if mask.is_solved() {
+                            continue;
+                        }
+                        for value in mask & tuple_mask {
+                            elims.add(cell.candidate(value));
+                        }
+                    }
+
+                    if elims.is_empty() {
+                        continue;
+                    }
+
+                    let desc = generate_description.then(|| {
+                        let tuple_name = match tuple_size {
+                            2 => "Pair",
+                            3 => "Triple",
+                            _ => "Quad",
+                        };
+                        let message =
+                            StepMessage::new("naked_tuple", "{tuple} {values} in {cells} ({house}) => {elims}")
+                                .with_arg("tuple", MessageArg::Text(tuple_name.to_owned()))
+                                .with_arg("values", MessageArg::Values(tuple_mask))
+                                .with_arg("cells", MessageArg::Cells(tuple_cells.clone()))
+                                .with_arg("house", MessageArg::House(house.name().to_owned()));
+                        LogicalStepDesc::from_message_elims(message, &elims).with_highlights(
+                            tuple_cells
+                                .iter()
+                                .flat_map(|&cell| board.cell(cell).into_iter().map(move |value| cell.candidate(value))),
+                            HighlightRole::DefiningSet,
+                        )
+                    });
+
+                    if !board.clear_candidates(elims.iter()) {
+                        return LogicalStepResult::Invalid(desc);
+                    }
+                    return LogicalStepResult::Changed(desc);
+                }
+            }
+        }
+
+        LogicalStepResult::None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{assert_step, test_support::parse_candidates};
+
+    #[test]
+    fn test_naked_pair() {
+        // Only 1 and 2 remain in r1c1 and r1c2
+        let mut board = Board::default();
+        board.clear_candidates(parse_candidates(9, "-3456789r1c12").unwrap().into_iter());
+
+        // The pair is removed from the rest of the row, and then the box
+        assert_step!(board, NakedTuple, eliminates: ["-12r1c3456789"], places: []);
+        assert_step!(board, NakedTuple, eliminates: ["-12r2c123,r3c123"], places: []);
+
+        // Nothing else is left to find
+        assert_step!(board, NakedTuple, eliminates: [], places: []);
+    }
+
+    #[test]
+    fn test_naked_triple() {
+        // r1c1, r4c1 and r7c1 only have 1, 2 and 3 between them
+        let mut board = Board::default();
+        board.clear_candidates(parse_candidates(9, "-3456789r1c1;-1456789r4c1;-2456789r7c1").unwrap().into_iter());
+        let result = NakedTuple.run(&mut board.clone(), true, &Cancellation::default());
+        let desc = result.description().unwrap();
+        assert_eq!(desc.to_string(), "Triple 1,2,3 in r147c1 (Column 1) => -1r235689c1;-2r235689c1;-3r235689c1");
+
+        let catalog = MessageCatalog::parse(
+            "naked_tuple = {elims}: {tuple} {values} in {cells} ({house})\nTriple = Tripel\nColumn 1 = Spalte 1",
+        )
+        .unwrap();
+        assert_eq!(
+            desc.to_localized_string(&catalog),
+            "-1r235689c1;-2r235689c1;-3r235689c1: Tripel 1,2,3 in r147c1 (Spalte 1)"
+        );
+
+        assert_step!(board, NakedTuple, eliminates: ["-123r235689c1"], places: []);
+    }
+}
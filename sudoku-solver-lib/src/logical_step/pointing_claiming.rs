@@ -0,0 +1,125 @@
+use std::sync::Arc;
+
+use crate::prelude::*;
+
+/// "Pointing" and "Claiming", also known as intersection removal, is when the candidates for a value
+/// within a house all lie in another house. The value must go in one of those cells, so it can be
+/// eliminated from the rest of the other house.
+///
+/// It is called pointing when the candidates of a region point along a row or column, and claiming when
+/// a row or column claims the value for a region. Other houses, such as extra regions, are searched too.
+/// Only the houses of the kinds set with [`SolverBuilder::with_step_house_kinds`] are searched.
+///
+/// This step is opt-in, see [`SolverBuilder::with_enabled_logical_steps`].
+#[derive(Debug)]
+pub struct PointingClaiming;
+
+impl LogicalStep for PointingClaiming {
+    fn name(&self) -> &'static str {
+        "Pointing Claiming"
+    }
+
+    fn run(&self, board: &mut Board, generate_description: bool, cancellation: &Cancellation) -> LogicalStepResult {
+        let board_data = board.data();
+
+        for house in board_data.houses().iter().filter(|house| board_data.is_step_house(house)) {
+            if cancellation.check() {
+                return LogicalStepResult::None;
+            }
+
+            for value in 1..=board.size() {
+                let value_cells: Vec<CellIndex> = house
+                    .cells()
+                    .iter()
+                    .copied()
+                    .filter(|&cell| {
+                        let mask = board.cell(cell);
+                        !mask.is_solved() && mask.has(value)
+                    })
+                    .collect();
+                if value_cells.len() < 2 {
+                    continue;
+                }
+
+                let other_houses = board_data.houses_by_cell()[value_cells[0].index()].iter().filter(|other| {
+                    !Arc::ptr_eq(other, house)
+                        && board_data.is_step_house(other)
+                        && value_cells.iter().all(|cell| other.cells().contains(cell))
+                });
+                for other in other_houses {
+                    let elims: EliminationList = other
+                        .cells()
+                        .iter()
+                        .copied()
+                        .filter(|cell| !house.cells().contains(cell))
+                        .filter(|&cell| {
+                            let mask = board.cell(cell);
+                            !mask.is_solved() && mask.has(value)
+                        })
+                        .map(|cell| cell.candidate(value))
+                        .collect();
+                    if elims.is_empty() {
+                        continue;
+                    }
+
+                    let desc = generate_description.then(|| {
+                        let kind_name = match house.kind() {
+                            HouseKind::Row | HouseKind::Column => "Claiming",
+                            HouseKind::Region | HouseKind::Constraint => "Pointing",
+                        };
+                        let message = StepMessage::new(
+                            "pointing_claiming",
+                            "{kind} {value} in {cells} ({house} to {other}) => {elims}",
+                        )
+                        .with_arg("kind", MessageArg::Text(kind_name.to_owned()))
+                        .with_arg("value", MessageArg::Value(value))
+                        .with_arg("cells", MessageArg::Cells(value_cells.clone()))
+                        .with_arg("house", MessageArg::House(house.name().to_owned()))
+                        .with_arg("other", MessageArg::House(other.name().to_owned()));
+                        LogicalStepDesc::from_message_elims(message, &elims).with_highlights(
+                            value_cells.iter().map(|&cell| cell.candidate(value)),
+                            HighlightRole::DefiningSet,
+                        )
+                    });
+
+                    if !board.clear_candidates(elims.iter()) {
+                        return LogicalStepResult::Invalid(desc);
+                    }
+                    return LogicalStepResult::Changed(desc);
+                }
+            }
+        }
+
+        LogicalStepResult::None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{assert_step, test_support::parse_candidates};
+
+    #[test]
+    fn test_pointing() {
+        // 1 is only in the first row of region 1
+        let mut board = Board::default();
+        board.clear_candidates(parse_candidates(9, "-1r23c123").unwrap().into_iter());
+        let result = PointingClaiming.run(&mut board.clone(), true, &Cancellation::default());
+        assert!(result.description().unwrap().to_string().starts_with("Pointing 1 in r1c123 (Region 1 to Row 1)"));
+
+        assert_step!(board, PointingClaiming, eliminates: ["-1r1c456789"], places: []);
+        assert_step!(board, PointingClaiming, eliminates: [], places: []);
+    }
+
+    #[test]
+    fn test_claiming() {
+        // 1 is only in the first region of column 1
+        let mut board = Board::default();
+        board.clear_candidates(parse_candidates(9, "-1r456789c1").unwrap().into_iter());
+        let result = PointingClaiming.run(&mut board.clone(), true, &Cancellation::default());
+        assert!(result.description().unwrap().to_string().starts_with("Claiming 1 in r123c1 (Column 1 to Region 1)"));
+
+        assert_step!(board, PointingClaiming, eliminates: ["-1r123c23"], places: []);
+        assert_step!(board, PointingClaiming, eliminates: [], places: []);
+    }
+}
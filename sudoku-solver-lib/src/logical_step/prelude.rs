@@ -1,8 +1,15 @@
 pub use super::all_naked_singles::*;
+pub use super::candidate_highlight::*;
 pub use super::hidden_single::*;
+pub use super::logic_profile::*;
 pub use super::logical_step_desc::*;
 pub use super::logical_step_desc_list::*;
+pub use super::logical_step_registry::*;
 pub use super::logical_step_result::*;
 pub use super::naked_single::*;
+pub use super::naked_tuple::*;
+pub use super::pointing_claiming::*;
 pub use super::simple_cell_forcing::*;
+pub use super::step_cause::*;
 pub use super::step_constraints::*;
+pub use super::step_message::*;
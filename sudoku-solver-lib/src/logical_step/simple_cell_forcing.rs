@@ -10,7 +10,7 @@ impl LogicalStep for SimpleCellForcing {
         "Simple Cell Forcing"
     }
 
-    fn run(&self, board: &mut Board, generate_description: bool) -> LogicalStepResult {
+    fn run(&self, board: &mut Board, generate_description: bool, _cancellation: &Cancellation) -> LogicalStepResult {
         let size = board.size();
         let cu = board.cell_utility();
         let bd = board.data();
@@ -46,7 +46,12 @@ impl LogicalStep for SimpleCellForcing {
 
             if !elims.is_empty() {
                 let desc = if generate_description {
-                    let desc = LogicalStepDesc::from_elims(&cell.to_string(), &elims);
+                    let desc = LogicalStepDesc::from_message_elims(
+                        StepMessage::new("simple_cell_forcing", "{cell} => {elims}")
+                            .with_arg("cell", MessageArg::Cell(cell)),
+                        &elims,
+                    )
+                    .with_highlights(mask.into_iter().map(|value| cell.candidate(value)), HighlightRole::DefiningSet);
                     Some(desc)
                 } else {
                     None
@@ -93,14 +98,14 @@ mod test {
         let simple_cell_forcing = SimpleCellForcing;
 
         // No cell forcing should be possible here
-        let result = simple_cell_forcing.run(&mut board, true);
+        let result = simple_cell_forcing.run(&mut board, true, &Cancellation::default());
         assert!(result.is_none());
 
         // Remove 9 as a candidate from r1c1
         assert!(board.clear_candidate(cu.candidate(cu.cell(0, 0), 9)));
 
         // Cell forcing should be possible here
-        let result = simple_cell_forcing.run(&mut board, true);
+        let result = simple_cell_forcing.run(&mut board, true, &Cancellation::default());
         assert!(result.is_changed());
 
         // Check that 1 has been eliminated from r1c2
@@ -109,5 +114,10 @@ mod test {
         // Check that the description is correct
         let desc = result.to_string();
         assert_eq!(desc, "r1c1 => -1r1c2");
+        assert!(result.description().unwrap().causes().is_empty());
+        let highlights = result.highlights();
+        assert_eq!(highlights[0], CandidateHighlight::new(cu.candidate(cu.cell(0, 1), 1), HighlightRole::Elimination));
+        assert_eq!(highlights.len(), 9);
+        assert!(highlights[1..].iter().all(|highlight| highlight.role == HighlightRole::DefiningSet));
     }
 }
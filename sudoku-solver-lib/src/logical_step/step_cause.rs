@@ -0,0 +1,82 @@
+//! Contains [`StepCause`] for explaining why a logical step could make its eliminations.
+
+use itertools::Itertools;
+
+use crate::prelude::*;
+
+/// The candidates which trigger a logical step, and optionally the rule which links them to the
+/// eliminations. See [`LogicalStepDesc::with_cause`].
+///
+/// The candidates are shown grouped by cell, so a cause of a single value reads `r1c1=4` and a
+/// cause of a cell which has a few values left reads `r1c1=4/5`.
+///
+/// # Example
+/// ```
+/// # use sudoku_solver_lib::prelude::*;
+/// let cu = CellUtility::new(9);
+/// let cause = StepCause::new([cu.cell(0, 0).candidate(4)]).with_source("V clue");
+/// assert_eq!(cause.to_string(), "r1c1=4 on V clue");
+///
+/// let elims: EliminationList = [cu.cell(0, 1).candidate(5)].into_iter().collect();
+/// let desc = LogicalStepDesc::from_elims("Sum of 5", &elims).with_cause(cause);
+/// assert_eq!(desc.to_string(), "Sum of 5 => -5r1c2 because r1c1=4 on V clue");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepCause {
+    candidates: Vec<CandidateIndex>,
+    source: Option<String>,
+}
+
+impl StepCause {
+    /// Creates a new instance from the triggering candidates.
+    pub fn new(candidates: impl IntoIterator<Item = CandidateIndex>) -> Self {
+        Self { candidates: candidates.into_iter().collect(), source: None }
+    }
+
+    /// Creates a new instance from all of the remaining candidates of a cell.
+    pub fn from_cell(board: &Board, cell: CellIndex) -> Self {
+        Self::new(board.cell(cell).into_iter().map(|value| cell.candidate(value)))
+    }
+
+    /// Names the rule, usually a constraint, which links the candidates to the eliminations.
+    #[must_use]
+    pub fn with_source(mut self, source: &str) -> Self {
+        self.source = Some(source.to_owned());
+        self
+    }
+
+    /// The triggering candidates.
+    pub fn candidates(&self) -> &[CandidateIndex] {
+        &self.candidates
+    }
+
+    /// The rule which links the candidates to the eliminations, if known.
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+}
+
+impl std::fmt::Display for StepCause {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut cells: Vec<(CellIndex, Vec<usize>)> = Vec::new();
+        for candidate in self.candidates.iter() {
+            let (cell, value) = candidate.cell_index_and_value();
+            match cells.iter_mut().find(|(existing, _)| *existing == cell) {
+                Some((_, values)) => values.push(value),
+                None => cells.push((cell, vec![value])),
+            }
+        }
+
+        for (index, (cell, values)) in cells.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{cell}={}", values.iter().join("/"))?;
+        }
+
+        if let Some(source) = &self.source {
+            write!(f, " on {source}")?;
+        }
+        Ok(())
+    }
+}
@@ -1,6 +1,9 @@
 use crate::prelude::*;
 
 /// Applies constraint-specific logic.
+///
+/// Constraints whose [`Constraint::relevant_cells`] haven't changed since they were
+/// last run are skipped.
 #[derive(Debug)]
 pub struct StepConstraints;
 
@@ -17,10 +20,26 @@ impl LogicalStep for StepConstraints {
         true
     }
 
-    fn run(&self, board: &mut Board, generate_description: bool) -> LogicalStepResult {
+    fn run(&self, board: &mut Board, generate_description: bool, cancellation: &Cancellation) -> LogicalStepResult {
         let board_data = board.data();
-        for constraint in board_data.constraints() {
-            let result = constraint.step_logic(board, !generate_description);
+        for (constraint_index, constraint) in board_data.constraints().iter().enumerate() {
+            if cancellation.check() {
+                return LogicalStepResult::None;
+            }
+
+            // Nothing has changed for the constraint since it last found nothing
+            if !board.take_changed_constraint(constraint_index) {
+                continue;
+            }
+
+            let result = board_data.time_step_logic(constraint_index, || {
+                constraint.step_logic(board, !generate_description, cancellation)
+            });
+            if result.is_none() && cancellation.check() {
+                // The constraint may have stopped before finding anything, so it must run again.
+                board.mark_all_changed();
+                return LogicalStepResult::None;
+            }
             if !result.is_none() {
                 return result.with_prefix(format!("{}: ", constraint.name()).as_str());
             }
@@ -32,7 +51,10 @@ impl LogicalStep for StepConstraints {
 
 #[cfg(test)]
 mod test {
-    use std::sync::Arc;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
 
     use super::*;
 
@@ -53,14 +75,25 @@ mod test {
             &self.specific_name
         }
 
-        fn step_logic(&self, board: &mut Board, _generate_description: bool) -> LogicalStepResult {
+        fn step_logic(
+            &self,
+            board: &mut Board,
+            _generate_description: bool,
+            _cancellation: &Cancellation,
+        ) -> LogicalStepResult {
             if board.has_candidate(self.candidate) {
                 if !board.clear_candidate(self.candidate) {
                     return LogicalStepResult::Invalid(Some(
-                        format!("{} remover failed to remove it.", self.candidate).into(),
+                        StepMessage::new("remove_candidate_failed", "{candidate} remover failed to remove it.")
+                            .with_arg("candidate", MessageArg::Candidate(self.candidate))
+                            .into(),
                     ));
                 }
-                LogicalStepResult::Changed(Some(format!("{} removed.", self.candidate).into()))
+                LogicalStepResult::Changed(Some(
+                    StepMessage::new("remove_candidate", "{candidate} removed.")
+                        .with_arg("candidate", MessageArg::Candidate(self.candidate))
+                        .into(),
+                ))
             } else {
                 LogicalStepResult::None
             }
@@ -88,21 +121,21 @@ mod test {
         assert!(board.has_candidate(candidate2));
 
         // Stepping the logic should remove just the first candidate
-        let result = step_constraints.run(&mut board, true);
+        let result = step_constraints.run(&mut board, true, &Cancellation::default());
         assert!(result.is_changed());
         assert_eq!(result.description().unwrap().to_string(), "Remove 1r1c1: 1r1c1 removed.");
         assert!(!board.has_candidate(candidate1));
         assert!(board.has_candidate(candidate2));
 
         // Stepping the logic should remove just the second candidate
-        let result = step_constraints.run(&mut board, true);
+        let result = step_constraints.run(&mut board, true, &Cancellation::default());
         assert!(result.is_changed());
         assert_eq!(result.description().unwrap().to_string(), "Remove 1r1c2: 1r1c2 removed.");
         assert!(!board.has_candidate(candidate1));
         assert!(!board.has_candidate(candidate2));
 
         // Stepping the logic should now do nothing
-        let result = step_constraints.run(&mut board, true);
+        let result = step_constraints.run(&mut board, true, &Cancellation::default());
         assert!(result.is_none());
 
         // Create a new board with the same constraints
@@ -120,8 +153,125 @@ mod test {
         assert!(board.clear_candidates((2..=size).map(|value| cu.candidate(cell, value))));
 
         // Stepping the logic should try to remove 1r1c1 and discover this makes the board invalid
-        let result = step_constraints.run(&mut board, true);
+        let result = step_constraints.run(&mut board, true, &Cancellation::default());
         assert!(result.is_invalid());
         assert_eq!(result.description().unwrap().to_string(), "Remove 1r1c1: 1r1c1 remover failed to remove it.");
     }
+
+    /// Counts how often each of its methods is called for a single relevant cell.
+    #[derive(Debug, Default)]
+    struct CountingConstraint {
+        cell: usize,
+        enforce_count: AtomicUsize,
+        step_count: AtomicUsize,
+    }
+
+    impl Constraint for CountingConstraint {
+        fn name(&self) -> &str {
+            "Counting"
+        }
+
+        fn enforce(&self, _board: &Board, _cell: CellIndex, _val: usize) -> LogicalStepResult {
+            self.enforce_count.fetch_add(1, Ordering::Relaxed);
+            LogicalStepResult::None
+        }
+
+        fn step_logic(
+            &self,
+            _board: &mut Board,
+            _is_brute_forcing: bool,
+            _cancellation: &Cancellation,
+        ) -> LogicalStepResult {
+            self.step_count.fetch_add(1, Ordering::Relaxed);
+            LogicalStepResult::None
+        }
+
+        fn relevant_cells(&self) -> Vec<CellIndex> {
+            vec![CellUtility::new(9).cell_index(self.cell)]
+        }
+    }
+
+    #[test]
+    fn test_relevant_cells() {
+        let cu = CellUtility::new(9);
+        let constraint = Arc::new(CountingConstraint { cell: 0, ..Default::default() });
+        let mut board = Board::new(9, &[], vec![constraint.clone()]);
+        let step_constraints = StepConstraints;
+
+        // The constraint is always run the first time
+        assert!(step_constraints.run(&mut board, true, &Cancellation::default()).is_none());
+        assert!(step_constraints.run(&mut board, true, &Cancellation::default()).is_none());
+        assert_eq!(constraint.step_count.load(Ordering::Relaxed), 1);
+
+        // Changing an unrelated cell doesn't run it again
+        assert!(board.clear_value(cu.cell(4, 4), 1));
+        assert!(step_constraints.run(&mut board, true, &Cancellation::default()).is_none());
+        assert_eq!(constraint.step_count.load(Ordering::Relaxed), 1);
+
+        // Changing the relevant cell does
+        assert!(board.clear_value(cu.cell(0, 0), 1));
+        assert!(step_constraints.run(&mut board, true, &Cancellation::default()).is_none());
+        assert_eq!(constraint.step_count.load(Ordering::Relaxed), 2);
+
+        // Only setting the relevant cell enforces the constraint
+        assert!(board.set_solved(cu.cell(8, 8), 9));
+        assert_eq!(constraint.enforce_count.load(Ordering::Relaxed), 0);
+        assert!(board.set_solved(cu.cell(0, 0), 2));
+        assert_eq!(constraint.enforce_count.load(Ordering::Relaxed), 1);
+    }
+
+    /// Cancels the search partway through its first call, as a heavy constraint would be interrupted.
+    #[derive(Debug, Default)]
+    struct InterruptedConstraint {
+        step_count: AtomicUsize,
+    }
+
+    impl Constraint for InterruptedConstraint {
+        fn name(&self) -> &str {
+            "Interrupted"
+        }
+
+        fn step_logic(
+            &self,
+            _board: &mut Board,
+            _is_brute_forcing: bool,
+            cancellation: &Cancellation,
+        ) -> LogicalStepResult {
+            if self.step_count.fetch_add(1, Ordering::Relaxed) == 0 {
+                cancellation.cancel();
+            }
+            LogicalStepResult::None
+        }
+
+        fn relevant_cells(&self) -> Vec<CellIndex> {
+            vec![CellUtility::new(9).cell(0, 0)]
+        }
+    }
+
+    #[test]
+    fn test_cancellation() {
+        let constraint = Arc::new(InterruptedConstraint::default());
+        let mut board = Board::new(9, &[], vec![constraint.clone()]);
+        let step_constraints = StepConstraints;
+
+        // An already cancelled search doesn't run any constraints
+        let cancellation = Cancellation::default();
+        cancellation.cancel();
+        assert!(step_constraints.run(&mut board, true, &cancellation).is_none());
+        assert_eq!(constraint.step_count.load(Ordering::Relaxed), 0);
+
+        // A constraint interrupted partway through is run again next time
+        cancellation.reset();
+        assert!(step_constraints.run(&mut board, true, &cancellation).is_none());
+        assert!(cancellation.check());
+        assert_eq!(constraint.step_count.load(Ordering::Relaxed), 1);
+
+        cancellation.reset();
+        assert!(step_constraints.run(&mut board, true, &cancellation).is_none());
+        assert_eq!(constraint.step_count.load(Ordering::Relaxed), 2);
+
+        // Once it completes without being cancelled, it isn't run again until something changes
+        assert!(step_constraints.run(&mut board, true, &cancellation).is_none());
+        assert_eq!(constraint.step_count.load(Ordering::Relaxed), 2);
+    }
 }
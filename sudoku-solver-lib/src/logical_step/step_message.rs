@@ -0,0 +1,245 @@
+//! Contains [`StepMessage`] and the [`Localizer`] trait for rendering logical step descriptions in other languages.
+
+use crate::prelude::*;
+use std::collections::HashMap;
+
+/// A parameter of a [`StepMessage`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MessageArg {
+    Cell(CellIndex),
+    /// A group of cells, shown with [`CellUtility::compact_name`].
+    Cells(Vec<CellIndex>),
+    Candidate(CandidateIndex),
+    /// Eliminated candidates, shown like an [`EliminationList`].
+    Eliminations(Vec<CandidateIndex>),
+    Value(usize),
+    Values(ValueMask),
+    /// The name of a house, such as `Row 1`.
+    House(String),
+    Text(String),
+}
+
+impl std::fmt::Display for MessageArg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cell(cell) => write!(f, "{cell}"),
+            Self::Cells(cells) => match cells.first() {
+                Some(cell) => write!(f, "{}", CellUtility::new(cell.size()).compact_name(cells)),
+                None => Ok(()),
+            },
+            Self::Candidate(candidate) => write!(f, "{candidate}"),
+            Self::Eliminations(candidates) => {
+                write!(f, "{}", candidates.iter().copied().collect::<EliminationList>())
+            }
+            Self::Value(value) => write!(f, "{value}"),
+            Self::Values(values) => write!(f, "{values}"),
+            Self::House(name) | Self::Text(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+/// A logical step description as a message key and named parameters, rather than finished text.
+///
+/// The English text comes from a template where each `{name}` is replaced by the parameter of that name.
+/// A [`Localizer`] can render the same message with its own template instead.
+///
+/// # Example
+/// ```
+/// # use sudoku_solver_lib::prelude::*;
+/// let cu = CellUtility::new(9);
+/// let message = StepMessage::new("naked_single", "{cell}={value}")
+///     .with_arg("cell", MessageArg::Cell(cu.cell(0, 0)))
+///     .with_arg("value", MessageArg::Value(5));
+/// assert_eq!(message.to_string(), "r1c1=5");
+///
+/// let catalog = MessageCatalog::new().with_template("naked_single", "{cell} ist {value}");
+/// assert_eq!(catalog.localize(&message).unwrap(), "r1c1 ist 5");
+/// ```
+///
+/// # Constraints
+/// Constraints can describe their steps the same way: return a description made with
+/// [`LogicalStepDesc::from_message`], or with [`LogicalStepDesc::from_message_elims`] when the step
+/// eliminates candidates. Use a key which starts with the name of the constraint, such as
+/// `killer_cage_sum`, so that it doesn't clash with the keys of other steps. The constraint name
+/// which [`StepConstraints`] puts in front of the message is translated with [`Localizer::localize_text`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepMessage {
+    key: &'static str,
+    template: &'static str,
+    args: Vec<(&'static str, MessageArg)>,
+}
+
+impl StepMessage {
+    /// Creates a message with the given key and English template.
+    pub fn new(key: &'static str, template: &'static str) -> Self {
+        Self { key, template, args: Vec::new() }
+    }
+
+    /// Adds a named parameter to the message.
+    #[must_use]
+    pub fn with_arg(mut self, name: &'static str, arg: MessageArg) -> Self {
+        self.args.push((name, arg));
+        self
+    }
+
+    /// The key which identifies the message in a [`MessageCatalog`].
+    pub fn key(&self) -> &'static str {
+        self.key
+    }
+
+    /// The English template of the message.
+    pub fn template(&self) -> &'static str {
+        self.template
+    }
+
+    /// The named parameters of the message.
+    pub fn args(&self) -> &[(&'static str, MessageArg)] {
+        &self.args
+    }
+
+    /// The parameter with the given name.
+    pub fn arg(&self, name: &str) -> Option<&MessageArg> {
+        self.args.iter().find(|(arg_name, _)| *arg_name == name).map(|(_, arg)| arg)
+    }
+
+    /// Renders the message with a template, using the localizer for each parameter.
+    ///
+    /// A `{name}` which isn't a parameter of the message is left as it is.
+    pub fn format(&self, template: &str, localizer: &dyn Localizer) -> String {
+        let mut text = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            text.push_str(&rest[..start]);
+            let placeholder = &rest[start..];
+            let arg = placeholder.find('}').and_then(|end| Some((end, self.arg(&placeholder[1..end])?)));
+            match arg {
+                Some((end, arg)) => {
+                    text.push_str(&localizer.localize_arg(arg));
+                    rest = &placeholder[end + 1..];
+                }
+                None => {
+                    text.push('{');
+                    rest = &placeholder[1..];
+                }
+            }
+        }
+        text.push_str(rest);
+        text
+    }
+}
+
+impl std::fmt::Display for StepMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.format(self.template, &MessageCatalog::new()))
+    }
+}
+
+/// Renders logical step descriptions in another language.
+///
+/// Anything the localizer doesn't know falls back to the English text.
+/// See [`LogicalStepDesc::to_localized_string`].
+pub trait Localizer {
+    /// The text of a message, or `None` to use the English text.
+    fn localize(&self, message: &StepMessage) -> Option<String>;
+
+    /// The translation of a fixed piece of text, such as the name of a logical step, or `None` to keep it.
+    fn localize_text(&self, _text: &str) -> Option<String> {
+        None
+    }
+
+    /// The text of a message parameter.
+    ///
+    /// The default implementation translates house names and text with [`Localizer::localize_text`].
+    fn localize_arg(&self, arg: &MessageArg) -> String {
+        match arg {
+            MessageArg::House(text) | MessageArg::Text(text) => {
+                self.localize_text(text).unwrap_or_else(|| text.clone())
+            }
+            _ => arg.to_string(),
+        }
+    }
+}
+
+/// A [`Localizer`] made of message templates and text translations.
+///
+/// A catalog can be parsed from lines of the form `key = template`. Keys of messages are
+/// the [`StepMessage::key`]s, and any other key is a fixed piece of text, such as `Naked Single`.
+/// Empty lines and lines starting with `#` are ignored.
+///
+/// # Example
+/// ```
+/// # use sudoku_solver_lib::prelude::*;
+/// let catalog = MessageCatalog::parse("# German\nnaked_single = {cell} ist {value}\nNaked Single = Nackter Single").unwrap();
+///
+/// let mut board = Board::default();
+/// let cell = board.cell_utility().cell(0, 0);
+/// board.clear_candidates((1..=8).map(|value| cell.candidate(value)));
+/// let result = NakedSingle.run(&mut board, true, &Cancellation::default()).with_prefix("Naked Single: ");
+/// assert_eq!(result.description().unwrap().to_localized_string(&catalog), "Nackter Single: r1c1 ist 9");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MessageCatalog {
+    templates: HashMap<String, String>,
+}
+
+impl MessageCatalog {
+    /// Creates an empty catalog, which renders everything in English.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a catalog from lines of the form `key = template`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut catalog = Self::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let Some((key, template)) = line.split_once('=') else {
+                return Err(format!("Line {} is not of the form key = template", index + 1));
+            };
+            catalog.templates.insert(key.trim().to_owned(), template.trim().to_owned());
+        }
+        Ok(catalog)
+    }
+
+    /// Adds the template of a message key, or the translation of a fixed piece of text.
+    #[must_use]
+    pub fn with_template(mut self, key: &str, template: &str) -> Self {
+        self.templates.insert(key.to_owned(), template.to_owned());
+        self
+    }
+}
+
+impl Localizer for MessageCatalog {
+    fn localize(&self, message: &StepMessage) -> Option<String> {
+        self.templates.get(message.key()).map(|template| message.format(template, self))
+    }
+
+    fn localize_text(&self, text: &str) -> Option<String> {
+        self.templates.get(text).cloned()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_step_message() {
+        let cu = CellUtility::new(9);
+        let message = StepMessage::new("hidden_single", "In {house}: {cell}={value}")
+            .with_arg("house", MessageArg::House("Row 1".to_owned()))
+            .with_arg("cell", MessageArg::Cell(cu.cell(0, 2)))
+            .with_arg("value", MessageArg::Value(4));
+        assert_eq!(message.to_string(), "In Row 1: r1c3=4");
+
+        let catalog =
+            MessageCatalog::parse("hidden_single = {cell}={value} ({house}) {unknown\nRow 1 = Zeile 1").unwrap();
+        assert_eq!(catalog.localize(&message).unwrap(), "r1c3=4 (Zeile 1) {unknown");
+        assert!(MessageCatalog::new().localize(&message).is_none());
+        assert!(MessageCatalog::parse("no template").is_err());
+    }
+}
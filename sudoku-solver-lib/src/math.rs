@@ -2,6 +2,10 @@
 
 use crate::prelude::*;
 use itertools::Itertools;
+use std::{
+    collections::HashMap,
+    sync::{Mutex, OnceLock},
+};
 
 /// Returns the binoomial coefficient of `n` choose `k`.
 ///
@@ -29,6 +33,25 @@ use itertools::Itertools;
 /// assert_eq!(binomial_coefficient(10, 10), 1);
 /// ```
 pub fn binomial_coefficient(n: usize, k: usize) -> usize {
+    // Small coefficients, which covers every grid size, come from a cached Pascal's triangle.
+    const CACHED_ROWS: usize = 32;
+    static PASCALS_TRIANGLE: OnceLock<Vec<Vec<usize>>> = OnceLock::new();
+    if n < CACHED_ROWS {
+        let triangle = PASCALS_TRIANGLE.get_or_init(|| {
+            let mut rows: Vec<Vec<usize>> = Vec::with_capacity(CACHED_ROWS);
+            for row in 0..CACHED_ROWS {
+                let prev = rows.last();
+                rows.push(
+                    (0..=row)
+                        .map(|i| if i == 0 || i == row { 1 } else { prev.unwrap()[i - 1] + prev.unwrap()[i] })
+                        .collect(),
+                );
+            }
+            rows
+        });
+        return triangle[n].get(k).copied().unwrap_or(0);
+    }
+
     if k > n {
         0
     } else if k == 0 || k == n {
@@ -42,6 +65,159 @@ pub fn binomial_coefficient(n: usize, k: usize) -> usize {
     }
 }
 
+/// Returns `n!`, or [`None`] if it doesn't fit in a [`u128`], which is when `n > 34`.
+///
+/// The factorials are computed once and cached.
+///
+/// # Example
+/// ```
+/// # use sudoku_solver_lib::math::factorial;
+/// assert_eq!(factorial(0), Some(1));
+/// assert_eq!(factorial(9), Some(362880));
+/// assert!(factorial(34).is_some());
+/// assert!(factorial(35).is_none());
+/// ```
+pub fn factorial(n: usize) -> Option<u128> {
+    static FACTORIALS: OnceLock<Vec<u128>> = OnceLock::new();
+    let factorials = FACTORIALS.get_or_init(|| {
+        let mut factorials = vec![1u128];
+        while let Some(next) = factorials.last().unwrap().checked_mul(factorials.len() as u128) {
+            factorials.push(next);
+        }
+        factorials
+    });
+    factorials.get(n).copied()
+}
+
+/// Returns whether `n` is prime, by trial division.
+///
+/// # Example
+/// ```
+/// # use sudoku_solver_lib::math::is_prime;
+/// let primes = (0..20).filter(|&n| is_prime(n)).collect::<Vec<_>>();
+/// assert_eq!(primes, vec![2, 3, 5, 7, 11, 13, 17, 19]);
+/// ```
+pub fn is_prime(n: usize) -> bool {
+    n >= 2 && (2..).take_while(|d| d * d <= n).all(|d| !n.is_multiple_of(d))
+}
+
+/// Iterates over every subset of exactly `k` values of the mask, in increasing order of their bits.
+///
+/// # Example
+/// ```
+/// # use sudoku_solver_lib::prelude::*;
+/// let mask = ValueMask::from_values(&[1, 3, 4]);
+/// let subsets = k_subsets(mask, 2).collect::<Vec<_>>();
+/// assert_eq!(subsets, vec![
+///     ValueMask::from_values(&[1, 3]),
+///     ValueMask::from_values(&[1, 4]),
+///     ValueMask::from_values(&[3, 4]),
+/// ]);
+/// ```
+pub fn k_subsets(mask: ValueMask, k: usize) -> impl Iterator<Item = ValueMask> {
+    let values = mask.unsolved().to_vec();
+    let n = values.len();
+
+    // Gosper's hack steps through the k-bit numbers below 2^n, each picking k of the values.
+    let mut next = if k > n { None } else { Some((1u64 << k) - 1) };
+    std::iter::from_fn(move || {
+        let current = next?;
+        if k == 0 {
+            next = None;
+        } else {
+            let lowest = current & current.wrapping_neg();
+            let ripple = current + lowest;
+            let following = (((ripple ^ current) >> 2) / lowest) | ripple;
+            next = (following < (1u64 << n)).then_some(following);
+        }
+
+        let mut subset = ValueMask::new();
+        for (i, &value) in values.iter().enumerate() {
+            if current & (1 << i) != 0 {
+                subset = subset.with(value);
+            }
+        }
+        Some(subset)
+    })
+}
+
+/// Counts the ways of placing a different value in each cell, given the candidates of each cell.
+///
+/// For a house, with one mask per cell, this is the number of ways to fill the house, which is the
+/// permanent of the matrix of candidates. It is computed exactly, in time and memory proportional to
+/// the number of subsets of the values, so [`None`] is returned when any candidate is larger than
+/// [`HOUSE_PERMUTATION_MAX_VALUE`]. Use [`house_permutation_estimate`] for larger houses.
+///
+/// # Example
+/// ```
+/// # use sudoku_solver_lib::prelude::*;
+/// let all = ValueMask::from_all_values(4);
+/// assert_eq!(house_permutation_count(&[all, all, all, all]), Some(24));
+///
+/// let pair = ValueMask::from_values(&[1, 2]);
+/// assert_eq!(house_permutation_count(&[pair, pair, all, all]), Some(4));
+/// assert_eq!(house_permutation_count(&[pair, pair, pair, all]), Some(0));
+///
+/// assert_eq!(house_permutation_count(&[ValueMask::from_value(31)]), None);
+/// ```
+pub fn house_permutation_count(masks: &[ValueMask]) -> Option<u128> {
+    // The number of ways to fill the first cells using exactly the values of each subset.
+    let all_values = masks.iter().fold(ValueMask::new(), |all, &mask| all | mask.unsolved());
+    let value_bits = all_values.max();
+    if value_bits > HOUSE_PERMUTATION_MAX_VALUE {
+        return None;
+    }
+
+    let mut ways = vec![0u128; 1 << value_bits];
+    ways[0] = 1;
+    for (used, &mask) in masks.iter().enumerate() {
+        let mut next_ways = vec![0u128; 1 << value_bits];
+        for (subset, &count) in ways.iter().enumerate() {
+            if count == 0 || subset.count_ones() as usize != used {
+                continue;
+            }
+            for value in mask.unsolved() {
+                let bit = 1 << (value - 1);
+                if subset & bit == 0 {
+                    next_ways[subset | bit] += count;
+                }
+            }
+        }
+        ways = next_ways;
+    }
+    Some(ways.iter().sum())
+}
+
+/// The largest value which [`house_permutation_count`] counts with, which keeps its tables of
+/// subsets to a few million entries.
+pub const HOUSE_PERMUTATION_MAX_VALUE: usize = 20;
+
+/// Estimates [`house_permutation_count`] without enumerating subsets, which works for any size.
+///
+/// This is the Bregman-Minc bound, the product over the cells of `(c!)^(1/c)` where `c` is the
+/// number of candidates in the cell. It is exact when the cells split into groups of `c` cells
+/// which share the same `c` candidates, and an overestimate otherwise.
+///
+/// # Example
+/// ```
+/// # use sudoku_solver_lib::prelude::*;
+/// let all = ValueMask::from_all_values(4);
+/// assert!((house_permutation_estimate(&[all, all, all, all]) - 24.0).abs() < 1e-9);
+/// ```
+pub fn house_permutation_estimate(masks: &[ValueMask]) -> f64 {
+    masks
+        .iter()
+        .map(|mask| {
+            let count = mask.count();
+            if count == 0 {
+                return 0.0;
+            }
+            let log_factorial = (1..=count).map(|i| (i as f64).ln()).sum::<f64>();
+            (log_factorial / count as f64).exp()
+        })
+        .product()
+}
+
 /// Returns the default regions assignments for a board of the given size.
 ///
 /// This is a flat list of which region index each cell belongs to.
@@ -127,6 +303,40 @@ pub fn get_weak_links_for_clone(
         .collect()
 }
 
+/// Returns every set of `num_cells` different values from `1..=size` which sum to `total`,
+/// as one mask of the values per set.
+///
+/// This is the subset-sum enumeration needed by killer cages, little killers, sandwiches and arrows.
+/// Results are memoized, so repeated calls for the same sum are cheap.
+///
+/// # Example
+/// ```
+/// # use sudoku_solver_lib::prelude::*;
+/// assert_eq!(sum_combinations(9, 2, 4), vec![ValueMask::from_values(&[1, 3])]);
+/// assert_eq!(sum_combinations(9, 3, 7), vec![ValueMask::from_values(&[1, 2, 4])]);
+/// assert_eq!(sum_combinations(9, 2, 10).len(), 4);
+/// assert!(sum_combinations(9, 2, 18).is_empty());
+/// ```
+pub fn sum_combinations(size: usize, num_cells: usize, total: usize) -> Vec<ValueMask> {
+    /// The combinations keyed by `(size, num_cells, total)`.
+    type SumCombinationsCache = Mutex<HashMap<(usize, usize, usize), Vec<ValueMask>>>;
+    static SUM_COMBINATIONS: OnceLock<SumCombinationsCache> = OnceLock::new();
+
+    let key = (size, num_cells, total);
+    let cache = SUM_COMBINATIONS.get_or_init(Mutex::default);
+    if let Some(combinations) = cache.lock().unwrap().get(&key) {
+        return combinations.clone();
+    }
+
+    let combinations = (1..=size)
+        .combinations(num_cells)
+        .filter(|values| values.iter().sum::<usize>() == total)
+        .map(|values| ValueMask::from_values(&values))
+        .collect_vec();
+    cache.lock().unwrap().insert(key, combinations.clone());
+    combinations
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -160,4 +370,55 @@ mod test {
             assert!(!links.contains(&(cell1.candidate(val), cell2.candidate(val))));
         }
     }
+
+    #[test]
+    fn test_sum_combinations() {
+        assert_eq!(sum_combinations(9, 9, 45), vec![ValueMask::from_all_values(9)]);
+        assert_eq!(sum_combinations(9, 1, 5), vec![ValueMask::from_value(5)]);
+        assert!(sum_combinations(9, 1, 10).is_empty());
+        assert!(sum_combinations(9, 0, 1).is_empty());
+        assert_eq!(sum_combinations(9, 0, 0), vec![ValueMask::new()]);
+
+        let combinations = sum_combinations(9, 4, 20);
+        assert_eq!(combinations.len(), 12);
+        assert!(combinations.iter().all(|mask| mask.count() == 4 && mask.into_iter().sum::<usize>() == 20));
+        assert_eq!(sum_combinations(9, 4, 20), combinations);
+    }
+
+    #[test]
+    fn test_binomial_coefficient_cache() {
+        for n in 0..40 {
+            let row_sum: usize = (0..=n).map(|k| binomial_coefficient(n, k)).sum();
+            assert_eq!(row_sum, 1 << n);
+            assert_eq!(binomial_coefficient(n, n + 1), 0);
+        }
+    }
+
+    #[test]
+    fn test_k_subsets() {
+        let mask = ValueMask::from_all_values(9);
+        for k in 0..=9 {
+            let subsets = k_subsets(mask, k).collect_vec();
+            assert_eq!(subsets.len(), binomial_coefficient(9, k));
+            assert!(subsets.iter().all(|subset| subset.count() == k));
+            assert_eq!(subsets.iter().unique().count(), subsets.len());
+        }
+        assert_eq!(k_subsets(mask, 10).count(), 0);
+        assert_eq!(k_subsets(ValueMask::new(), 0).collect_vec(), vec![ValueMask::new()]);
+    }
+
+    #[test]
+    fn test_house_permutation_count() {
+        let all = ValueMask::from_all_values(9);
+        assert_eq!(house_permutation_count(&[all; 9]), factorial(9));
+        assert_eq!(house_permutation_count(&[]), Some(1));
+
+        let masks = [ValueMask::from_values(&[1, 2]), ValueMask::from_values(&[2, 3]), ValueMask::from_values(&[1, 3])];
+        assert_eq!(house_permutation_count(&masks), Some(2));
+
+        // Too many values to count, rather than allocating tables for every subset
+        assert_eq!(house_permutation_count(&[ValueMask::from_all_values(21)]), None);
+        assert!(house_permutation_count(&[ValueMask::from_all_values(20)]).is_some());
+        assert!(house_permutation_estimate(&masks) >= 2.0);
+    }
 }
@@ -0,0 +1,127 @@
+//! Contains the [`MemoryFootprint`] struct for reporting how much memory a puzzle takes.
+
+use crate::prelude::*;
+use bitvec::vec::BitVec;
+use smallvec::SmallVec;
+use std::sync::{Arc, OnceLock};
+
+/// How [`BoardData`] stores the houses of each cell.
+pub(crate) type HousesOfCell = SmallVec<[Arc<House>; 4]>;
+
+/// An estimate of the bytes used by the data of a [`Board`], by what the bytes are used for.
+///
+/// The data is shared by every clone of a board, so this is paid once per puzzle, while the
+/// [`MemoryFootprint::board`] bytes are paid again for every board a brute force search keeps.
+/// The weak links grow with the cube of the number of candidates, so they dominate on large grids.
+///
+/// Use [`Solver::memory_footprint`] to measure a puzzle, or [`MemoryFootprint::estimate`] to
+/// estimate a grid size without building a solver for it.
+///
+/// # Example
+/// ```
+/// # use sudoku_solver_lib::prelude::*;
+/// let footprint = Solver::default().memory_footprint();
+/// assert!(footprint.weak_links > footprint.houses);
+/// assert!(MemoryFootprint::estimate(25).total() > footprint.total());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryFootprint {
+    /// The weak links between candidates, and the number of weak links of each candidate.
+    pub weak_links: usize,
+    /// The cells which can't share a value with each cell. These are only computed when
+    /// first needed, so a measured footprint only counts the cells computed so far.
+    pub exclusive_cells: usize,
+    /// The houses, and the houses of each cell.
+    pub houses: usize,
+    /// Everything else kept for each cell, such as its region and the constraints to enforce.
+    pub cells: usize,
+    /// A single board, which is copied for every board a brute force search keeps.
+    pub board: usize,
+}
+
+impl MemoryFootprint {
+    /// Estimates the footprint of a grid of the given size with the default rows, columns and regions,
+    /// and no constraints, if the exclusive cells of every cell were computed.
+    pub fn estimate(size: usize) -> Self {
+        let num_cells = size * size;
+        let num_candidates = num_cells * size;
+        let house_count = size * 3;
+        Self {
+            weak_links: num_candidates * (CandidateLinks::memory_size_for(size) + std::mem::size_of::<usize>()),
+            exclusive_cells: num_cells * (std::mem::size_of::<OnceLock<BitVec>>() + bit_set_size(num_cells)),
+            houses: house_count * house_memory_size(size) + num_cells * std::mem::size_of::<HousesOfCell>(),
+            cells: num_cells * cell_memory_size(),
+            board: std::mem::size_of::<Board>()
+                + num_cells * std::mem::size_of::<ValueMask>()
+                + (num_cells + house_count).div_ceil(64) * std::mem::size_of::<u64>(),
+        }
+    }
+
+    /// The total bytes of the footprint.
+    pub fn total(&self) -> usize {
+        self.weak_links + self.exclusive_cells + self.houses + self.cells + self.board
+    }
+}
+
+/// The bytes of a bit set with the given number of bits.
+pub(crate) fn bit_set_size(bits: usize) -> usize {
+    bits.div_ceil(usize::BITS as usize) * std::mem::size_of::<usize>()
+}
+
+/// The bytes of a house with the given number of cells, not counting its name.
+pub(crate) fn house_memory_size(cell_count: usize) -> usize {
+    std::mem::size_of::<Arc<House>>() + std::mem::size_of::<House>() + cell_count * std::mem::size_of::<CellIndex>()
+}
+
+/// The bytes of what [`BoardData`] keeps for each cell besides its houses and exclusive cells.
+pub(crate) fn cell_memory_size() -> usize {
+    // The region, the bit sets of houses and constraints, and the constraints to enforce
+    std::mem::size_of::<usize>() + 2 * std::mem::size_of::<u64>() + std::mem::size_of::<SmallVec<[usize; 4]>>()
+}
+
+impl std::fmt::Display for MemoryFootprint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kib = |bytes: usize| bytes.div_ceil(1024);
+        write!(
+            f,
+            "{} KiB (weak links {} KiB, exclusive cells {} KiB, houses {} KiB, cells {} KiB, board {} KiB)",
+            kib(self.total()),
+            kib(self.weak_links),
+            kib(self.exclusive_cells),
+            kib(self.houses),
+            kib(self.cells),
+            kib(self.board)
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_estimate_matches_solver() {
+        for size in [4, 9, 16] {
+            let solver = SolverBuilder::new(size).build().unwrap();
+            let footprint = solver.memory_footprint();
+            let estimate = MemoryFootprint::estimate(size);
+            assert_eq!(footprint.weak_links, estimate.weak_links);
+            assert_eq!(footprint.cells, estimate.cells);
+            assert!(footprint.houses >= estimate.houses);
+            assert!(footprint.houses < estimate.houses * 2);
+            assert!(footprint.exclusive_cells < estimate.exclusive_cells);
+            assert!(footprint.total() > 0);
+        }
+        assert!(MemoryFootprint::estimate(25).weak_links > MemoryFootprint::estimate(16).weak_links * 10);
+    }
+
+    #[test]
+    fn test_exclusive_cells_are_counted_once_computed() {
+        let solver = Solver::default();
+        let before = solver.memory_footprint().exclusive_cells;
+        let cu = solver.cell_utility();
+        assert!(solver.board().is_exclusive(cu.cell(0, 0), cu.cell(0, 1)));
+        assert!(solver.memory_footprint().exclusive_cells > before);
+        assert!(solver.memory_footprint().to_string().contains("KiB (weak links "));
+    }
+}
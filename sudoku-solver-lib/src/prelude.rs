@@ -1,14 +1,30 @@
 pub use crate::board::*;
+pub use crate::board_observer::*;
+pub use crate::board_recorder::*;
+pub use crate::candidate_density::*;
 pub use crate::candidate_index::*;
 pub use crate::candidate_links::*;
+pub use crate::cell_fact::*;
 pub use crate::cell_index::*;
 pub use crate::cell_utility::*;
 pub use crate::constraint::*;
+pub use crate::constraint_conflict::*;
+#[cfg(feature = "profiling")]
+pub use crate::constraint_profile::*;
 pub use crate::elimination_list::*;
+pub use crate::filling_search::*;
+pub use crate::given_conflict::*;
 pub use crate::house::*;
+pub use crate::house_context::*;
+pub use crate::link_graph::*;
 pub use crate::logical_step::prelude::*;
 pub use crate::logical_step::*;
 pub use crate::math::*;
+pub use crate::memory_footprint::*;
+pub use crate::region_membership::*;
+pub use crate::solution_statistics::*;
 pub use crate::solver::prelude::*;
 pub use crate::solver::*;
+pub use crate::symmetry::*;
 pub use crate::value_mask::*;
+pub use crate::weak_link_stats::*;
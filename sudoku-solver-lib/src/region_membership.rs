@@ -0,0 +1,125 @@
+//! Contains [`RegionMembership`] for tracking which regions each cell can be in when the regions are unknown.
+
+use crate::prelude::*;
+
+/// The regions which each cell can still be in, for puzzles whose regions must be deduced,
+/// such as chaos construction.
+///
+/// A board has one when it is built with [`SolverBuilder::with_unknown_regions`]. The board then has
+/// no region houses, and constraints narrow down the regions of the cells as they narrow down
+/// the values. It is part of the board, so each branch of a brute force solve has its own.
+///
+/// Regions are numbered from 0 up to the size of the board. Which number a region gets has no meaning,
+/// so a cell is only allowed a region number up to its own index. That way the first cell of each
+/// region, in reading order, can take the lowest number not yet used.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegionMembership {
+    size: usize,
+    /// For each cell, region `r` is possible if the mask has the value `r + 1`.
+    regions: Vec<ValueMask>,
+}
+
+impl RegionMembership {
+    /// Creates the membership of a board of the given size where no region is known yet.
+    pub fn new(size: usize) -> Self {
+        let regions = (0..size * size).map(|cell| ValueMask::from_lower_equal((cell + 1).min(size))).collect();
+        Self { size, regions }
+    }
+
+    /// The size of the board, which is also the number of regions.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Whether the cell can be in the region.
+    pub fn can_be_in(&self, cell: CellIndex, region: usize) -> bool {
+        self.regions[cell.index()].has(region + 1)
+    }
+
+    /// The regions the cell can be in.
+    pub fn possible_regions(&self, cell: CellIndex) -> impl Iterator<Item = usize> {
+        self.regions[cell.index()].into_iter().map(|value| value - 1)
+    }
+
+    /// The number of regions the cell can be in.
+    pub fn num_possible_regions(&self, cell: CellIndex) -> usize {
+        self.regions[cell.index()].count()
+    }
+
+    /// The region of the cell, if it is known.
+    pub fn region(&self, cell: CellIndex) -> Option<usize> {
+        let mask = self.regions[cell.index()];
+        if mask.is_single() {
+            Some(mask.value() - 1)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the region of every cell is known.
+    pub fn is_determined(&self) -> bool {
+        self.regions.iter().all(|mask| mask.is_single())
+    }
+
+    /// The cells which are known to be in the region.
+    pub fn cells_in(&self, region: usize) -> Vec<CellIndex> {
+        let cu = CellUtility::new(self.size);
+        cu.all_cells().filter(|&cell| self.region(cell) == Some(region)).collect()
+    }
+
+    /// The cells which can be in the region.
+    pub fn cells_possibly_in(&self, region: usize) -> Vec<CellIndex> {
+        let cu = CellUtility::new(self.size);
+        cu.all_cells().filter(|&cell| self.can_be_in(cell, region)).collect()
+    }
+
+    /// The region of each cell, indexed by [`CellIndex::index`], once they are all known.
+    pub fn to_regions(&self) -> Option<Vec<usize>> {
+        self.regions.iter().map(|mask| mask.is_single().then(|| mask.value() - 1)).collect()
+    }
+
+    /// Removes the region from the cell.
+    ///
+    /// Returns false if the cell can no longer be in any region.
+    pub fn remove_region(&mut self, cell: CellIndex, region: usize) -> bool {
+        let mask = &mut self.regions[cell.index()];
+        *mask = mask.without(region + 1);
+        !mask.is_empty()
+    }
+
+    /// Puts the cell in the region.
+    ///
+    /// Returns false if the cell could not be in the region.
+    pub fn set_region(&mut self, cell: CellIndex, region: usize) -> bool {
+        let mask = &mut self.regions[cell.index()];
+        if !mask.has(region + 1) {
+            return false;
+        }
+        *mask = ValueMask::from_value(region + 1);
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_region_membership() {
+        let mut membership = RegionMembership::new(4);
+        let cu = CellUtility::new(4);
+        assert_eq!(membership.region(cu.cell(0, 0)), Some(0));
+        assert_eq!(membership.possible_regions(cu.cell(0, 2)).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(membership.num_possible_regions(cu.cell(3, 3)), 4);
+        assert!(!membership.is_determined());
+
+        assert!(membership.set_region(cu.cell(0, 1), 1));
+        assert!(!membership.set_region(cu.cell(0, 1), 0));
+        assert_eq!(membership.cells_in(1), vec![cu.cell(0, 1)]);
+        assert!(membership.remove_region(cu.cell(0, 2), 2));
+        assert!(membership.remove_region(cu.cell(0, 2), 1));
+        assert!(!membership.remove_region(cu.cell(0, 2), 0));
+        assert!(!membership.cells_possibly_in(2).contains(&cu.cell(0, 2)));
+        assert!(membership.to_regions().is_none());
+    }
+}
@@ -0,0 +1,236 @@
+//! Contains [`SolutionStatistics`] for describing the properties of a solved grid.
+
+use crate::prelude::*;
+use std::sync::Arc;
+
+/// Properties of a solved grid, for finding grids which are compatible with a ruleset.
+///
+/// Bands, stacks and disjoint groups are based on the default boxes of the grid size,
+/// even if the grid has other regions.
+///
+/// # Example
+/// ```
+/// # use sudoku_solver_lib::prelude::*;
+/// let solution = Solver::default().find_first_solution().board().unwrap();
+/// let statistics = SolutionStatistics::new(&solution).unwrap();
+/// assert_eq!(statistics.band_positions(0, 1), vec![0, 6, 3]);
+/// assert_eq!(statistics.diagonal_sums(), (45, 45));
+/// ```
+#[derive(Clone)]
+pub struct SolutionStatistics {
+    board: Board,
+    values: Vec<usize>,
+    box_height: usize,
+    box_width: usize,
+}
+
+impl SolutionStatistics {
+    /// Creates the statistics of a board. Every cell must have a single value, and every row
+    /// and column must have every value.
+    pub fn new(board: &Board) -> Result<Self, String> {
+        let values = board
+            .all_cell_masks()
+            .map(|(cell, mask)| if mask.is_single() { Ok(mask.value()) } else { Err(format!("{cell} is not filled")) })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // The positions of values within bands and stacks need each value once per row and column.
+        let size = board.size();
+        if let Some(row) = (0..size).find(|&row| !has_every_value((0..size).map(|col| values[row * size + col]), size))
+        {
+            return Err(format!("Row {} does not have every value", row + 1));
+        }
+        if let Some(col) = (0..size).find(|&col| !has_every_value((0..size).map(|row| values[row * size + col]), size))
+        {
+            return Err(format!("Column {} does not have every value", col + 1));
+        }
+
+        let regions = default_regions(size);
+        let box_width = regions.iter().take_while(|&&region| region == 0).count();
+        let box_height = size.checked_div(box_width).unwrap_or(0);
+
+        Ok(Self { board: board.clone(), values, box_height, box_width })
+    }
+
+    /// The size of the grid.
+    pub fn size(&self) -> usize {
+        self.board.size()
+    }
+
+    /// The value of the cell at the given row and column.
+    pub fn value(&self, row: usize, col: usize) -> usize {
+        self.values[row * self.size() + col]
+    }
+
+    /// The number of bands, which are rows of boxes.
+    pub fn num_bands(&self) -> usize {
+        self.size() / self.box_height
+    }
+
+    /// The number of stacks, which are columns of boxes.
+    pub fn num_stacks(&self) -> usize {
+        self.size() / self.box_width
+    }
+
+    /// The column of the value within each row of the band, from top to bottom.
+    ///
+    /// # Panics
+    /// Panics if the value is not between 1 and the size of the grid.
+    pub fn band_positions(&self, band: usize, value: usize) -> Vec<usize> {
+        (band * self.box_height..(band + 1) * self.box_height)
+            .map(|row| (0..self.size()).position(|col| self.value(row, col) == value).unwrap())
+            .collect()
+    }
+
+    /// The row of the value within each column of the stack, from left to right.
+    ///
+    /// # Panics
+    /// Panics if the value is not between 1 and the size of the grid.
+    pub fn stack_positions(&self, stack: usize, value: usize) -> Vec<usize> {
+        (stack * self.box_width..(stack + 1) * self.box_width)
+            .map(|col| (0..self.size()).position(|row| self.value(row, col) == value).unwrap())
+            .collect()
+    }
+
+    /// The sums of the positive diagonal, from bottom left to top right,
+    /// and of the negative diagonal, from top left to bottom right.
+    pub fn diagonal_sums(&self) -> (usize, usize) {
+        let size = self.size();
+        let positive = (0..size).map(|i| self.value(size - 1 - i, i)).sum();
+        let negative = (0..size).map(|i| self.value(i, i)).sum();
+        (positive, negative)
+    }
+
+    /// Whether the cells in the same position of every box all have different values.
+    pub fn has_disjoint_groups(&self) -> bool {
+        let size = self.size();
+        (0..self.box_height).all(|box_row| {
+            (0..self.box_width).all(|box_col| {
+                let mut seen = ValueMask::new();
+                for band in 0..self.num_bands() {
+                    for stack in 0..self.num_stacks() {
+                        let row = band * self.box_height + box_row;
+                        let col = stack * self.box_width + box_col;
+                        let value = self.value(row, col);
+                        if seen.has(value) {
+                            return false;
+                        }
+                        seen = seen.with(value);
+                    }
+                }
+                seen.count() == size
+            })
+        })
+    }
+
+    /// Whether the grid is a solution to the rules of the constraint.
+    /// See [`Solver::validate_solution`].
+    ///
+    /// The constraint is initialized for a new solver, so a constraint which is shared with another
+    /// builder or solver is copied with [`Constraint::clone_constraint`].
+    ///
+    /// Returns an error if a solver can't be built with the constraint.
+    pub fn satisfies(&self, constraint: Arc<dyn Constraint>) -> Result<bool, String> {
+        let solver = SolverBuilder::new(self.size()).with_constraint(constraint).build()?;
+        Ok(solver.validate_solution(&self.board).is_empty())
+    }
+
+    /// The names of the constraints whose rules the grid happens to follow. See [`SolutionStatistics::satisfies`].
+    pub fn satisfied_constraints(&self, constraints: Vec<Arc<dyn Constraint>>) -> Result<Vec<String>, String> {
+        let mut names = Vec::new();
+        for constraint in constraints {
+            let name = constraint.name().to_owned();
+            if self.satisfies(constraint)? {
+                names.push(name);
+            }
+        }
+        Ok(names)
+    }
+}
+
+/// Whether the values are every value of a grid of the given size.
+fn has_every_value(values: impl Iterator<Item = usize>, size: usize) -> bool {
+    values.fold(ValueMask::new(), |mask, value| mask.with(value)) == ValueMask::from_all_values(size)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_statistics() {
+        let solution = Solver::default().find_first_solution().board().unwrap();
+        let statistics = SolutionStatistics::new(&solution).unwrap();
+        assert_eq!(statistics.num_bands(), 3);
+        assert_eq!(statistics.num_stacks(), 3);
+        assert_eq!(statistics.band_positions(1, 9), vec![7, 4, 1]);
+        assert_eq!(statistics.stack_positions(0, 1), vec![0, 3, 6]);
+        assert!(statistics.has_disjoint_groups());
+
+        let solution = SolverBuilder::new(6).build().unwrap().find_first_solution().board().unwrap();
+        let statistics = SolutionStatistics::new(&solution).unwrap();
+        assert_eq!(statistics.num_bands(), 3);
+        assert_eq!(statistics.num_stacks(), 2);
+        assert_eq!(statistics.band_positions(0, 1).len(), 2);
+        assert_eq!(statistics.stack_positions(0, 1).len(), 3);
+
+        let board = Solver::default().board().clone();
+        assert!(SolutionStatistics::new(&board).is_err());
+
+        // A filled grid with a repeated value in a row and column has no positions to report
+        let mut board = Solver::default().find_first_solution().board().unwrap();
+        let value = board.cell(CellIndex::new(1, 9)).value();
+        assert!(board.set_mask(0, ValueMask::from_value(value)));
+        assert_eq!(SolutionStatistics::new(&board).err().unwrap(), "Row 1 does not have every value");
+    }
+
+    #[test]
+    fn test_satisfies() {
+        /// Forbids a value in the first cell.
+        #[derive(Debug, Clone)]
+        struct FirstCellNot(usize);
+        impl Constraint for FirstCellNot {
+            fn name(&self) -> &str {
+                "First Cell Not"
+            }
+
+            fn clone_constraint(&self) -> Option<Arc<dyn Constraint>> {
+                Some(Arc::new(self.clone()))
+            }
+
+            fn enforce(&self, _board: &Board, cell: CellIndex, val: usize) -> LogicalStepResult {
+                if cell.index() == 0 && val == self.0 {
+                    LogicalStepResult::Invalid(None)
+                } else {
+                    LogicalStepResult::None
+                }
+            }
+        }
+
+        let solution = Solver::default().find_first_solution().board().unwrap();
+        let statistics = SolutionStatistics::new(&solution).unwrap();
+        let first_value = solution.cell(CellIndex::new(0, 9)).value();
+
+        // A constraint shared with a builder can still be checked.
+        let shared: Arc<dyn Constraint> = Arc::new(FirstCellNot(first_value % 9 + 1));
+        let _builder = SolverBuilder::default().with_constraint(shared.clone());
+        assert_eq!(statistics.satisfies(shared.clone()), Ok(true));
+        assert_eq!(statistics.satisfies(Arc::new(FirstCellNot(first_value))), Ok(false));
+        assert_eq!(
+            statistics.satisfied_constraints(vec![shared, Arc::new(FirstCellNot(first_value))]),
+            Ok(vec!["First Cell Not".to_owned()])
+        );
+
+        /// A constraint which can't be copied for another solver.
+        #[derive(Debug)]
+        struct Unshareable;
+        impl Constraint for Unshareable {
+            fn name(&self) -> &str {
+                "Unshareable"
+            }
+        }
+
+        let shared: Arc<dyn Constraint> = Arc::new(Unshareable);
+        let _builder = SolverBuilder::default().with_constraint(shared.clone());
+        assert!(statistics.satisfies(shared).is_err());
+    }
+}
@@ -1,22 +1,56 @@
 //! Constains the [`Solver`] struct which is the main entry point for solving a puzzle.
 
+#[cfg(feature = "batch")]
+pub mod batch;
+mod board_arena;
+mod board_pool;
 pub mod cancellation;
+mod deadly_pattern;
+pub mod givens_encoding;
+pub mod grid_relation;
 pub mod logical_solve_result;
+pub mod multi_grid_builder;
+pub mod multi_grid_solver;
 pub mod prelude;
+pub mod progress_report;
+#[cfg(feature = "generator")]
+pub mod puzzle_analysis;
+#[cfg(feature = "generator")]
+pub mod puzzle_generator;
 pub mod single_solution_result;
+pub mod singles_solve_result;
 pub mod solution_count_result;
+pub mod solution_counter;
+pub mod solution_order;
 pub mod solution_receiver;
+pub mod solution_violation;
 pub mod solver_builder;
+pub mod solver_observer;
+pub mod solver_options;
+pub mod solver_template;
+pub mod true_candidates_comparison;
 pub mod true_candidates_count_result;
 
 use itertools::Itertools;
 
+use self::{board_pool::BoardPool, deadly_pattern::DeadlyPatterns};
 use crate::prelude::*;
+use rand::Rng;
 use std::{
     collections::{HashMap, HashSet},
     sync::Arc,
 };
 
+/// The [`tracing`] target used to report each logical step as it is applied.
+pub const STEPS_TRACE_TARGET: &str = "sudoku_solver::steps";
+
+/// The [`tracing`] target used to report statistics about brute force searches.
+pub const BRUTE_FORCE_TRACE_TARGET: &str = "sudoku_solver::brute_force";
+
+/// The [`tracing`] target used to report the weak links of each constraint when
+/// [`SolverBuilder::with_weak_link_report`] is enabled.
+pub const WEAK_LINKS_TRACE_TARGET: &str = "sudoku_solver::weak_links";
+
 /// The main entry point for solving a puzzle.
 ///
 /// Use the [`SolverBuilder`] struct to create a [`Solver`].
@@ -32,15 +66,35 @@ use std::{
 /// Additionally, the [`Solver`] struct contains a list of [`Constraint`]s which define the rules of the puzzle.
 /// This library does not provide any implementations of this trait, and instead relies on the
 /// consumer of this library to provide the constraints for the puzzle to be solved.
+///
+/// Cloning a solver shares its logical steps and custom info, which are only copied if the clone modifies them.
 #[derive(Clone)]
 pub struct Solver {
     board: Board,
-    logical_solve_steps: Vec<Arc<dyn LogicalStep>>,
-    brute_force_steps: Vec<Arc<dyn LogicalStep>>,
-    custom_info: HashMap<String, String>,
+    logical_solve_steps: Arc<[Arc<dyn LogicalStep>]>,
+    brute_force_steps: Arc<[Arc<dyn LogicalStep>]>,
+    custom_info: Arc<HashMap<String, String>>,
+    board_arena: bool,
+    /// Whether brute force breaks ties by weak link degree. See [`SolverBuilder::with_weak_link_tie_break`].
+    weak_link_tie_break: bool,
+    /// The most bytes of boards a solution count may keep. See [`SolverBuilder::with_search_memory_limit`].
+    search_memory_limit: Option<usize>,
+    /// The boards of the first attempt of a random solution search. See [`SolverBuilder::with_random_restart_boards`].
+    random_restart_boards: usize,
+    /// The rectangles to prune when assuming a unique solution, or `None` when not assuming it.
+    deadly_patterns: Option<Arc<DeadlyPatterns>>,
+    /// Records the changes made by logical steps, if set. See [`Solver::set_recorder`].
+    recorder: Option<Arc<BoardRecorder>>,
+    /// Told about the progress of logical solves, if set. See [`Solver::set_observer`].
+    observer: Option<Arc<dyn SolverObserver>>,
+    options: SolverOptions,
 }
 
 impl Solver {
+    /// The default number of boards a random solution search explores before its first restart.
+    /// See [`SolverBuilder::with_random_restart_boards`].
+    pub const DEFAULT_RANDOM_RESTART_BOARDS: usize = 256;
+
     pub fn board(&self) -> &Board {
         &self.board
     }
@@ -53,32 +107,246 @@ impl Solver {
         self.board.cell_utility()
     }
 
+    /// The region of each cell, indexed by [`CellIndex::index`], after the defaults are filled in.
+    /// See [`Board::regions`].
+    pub fn regions(&self) -> &[usize] {
+        self.board.regions()
+    }
+
+    /// An estimate of the bytes used by the puzzle, see [`MemoryFootprint`].
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        self.board.memory_footprint()
+    }
+
     pub fn set_custom_info(&mut self, key: String, value: String) {
-        self.custom_info.insert(key, value);
+        Arc::make_mut(&mut self.custom_info).insert(key, value);
     }
 
     pub fn get_custom_info(&self, key: &str) -> Option<&str> {
         self.custom_info.get(key).map(|s| s.as_str())
     }
 
+    /// The logical steps used by a logical solve, in the order they are tried.
+    pub fn logical_solve_steps(&self) -> &[Arc<dyn LogicalStep>] {
+        &self.logical_solve_steps
+    }
+
+    /// The options the solver was built with. See [`SolverBuilder::with_options`].
+    pub fn options(&self) -> &SolverOptions {
+        &self.options
+    }
+
+    /// Whether solution counts keep one board per search depth. See [`SolverBuilder::with_board_arena`].
+    pub fn board_arena(&self) -> bool {
+        self.board_arena
+    }
+
+    /// Choose how solution counts store their boards. See [`SolverBuilder::with_board_arena`].
+    pub fn set_board_arena(&mut self, board_arena: bool) {
+        self.board_arena = board_arena;
+    }
+
+    /// Whether brute force breaks ties by weak link degree. See [`SolverBuilder::with_weak_link_tie_break`].
+    pub fn weak_link_tie_break(&self) -> bool {
+        self.weak_link_tie_break
+    }
+
+    /// Choose how brute force breaks ties. See [`SolverBuilder::with_weak_link_tie_break`].
+    pub fn set_weak_link_tie_break(&mut self, weak_link_tie_break: bool) {
+        self.weak_link_tie_break = weak_link_tie_break;
+    }
+
+    /// The most bytes of boards a solution count may keep. See [`SolverBuilder::with_search_memory_limit`].
+    pub fn search_memory_limit(&self) -> Option<usize> {
+        self.search_memory_limit
+    }
+
+    /// Limit the bytes of boards a solution count may keep. See [`SolverBuilder::with_search_memory_limit`].
+    pub fn set_search_memory_limit(&mut self, search_memory_limit: Option<usize>) {
+        self.search_memory_limit = search_memory_limit;
+    }
+
+    /// The boards a random solution search explores before its first restart, or 0 if it never restarts.
+    /// See [`SolverBuilder::with_random_restart_boards`].
+    pub fn random_restart_boards(&self) -> usize {
+        self.random_restart_boards
+    }
+
+    /// Choose when random solution searches restart. See [`SolverBuilder::with_random_restart_boards`].
+    pub fn set_random_restart_boards(&mut self, random_restart_boards: usize) {
+        self.random_restart_boards = random_restart_boards;
+    }
+
+    /// Whether true candidates searches assume the puzzle has a unique solution.
+    /// See [`SolverBuilder::with_uniqueness_assumption`].
+    pub fn assumes_uniqueness(&self) -> bool {
+        self.deadly_patterns.is_some()
+    }
+
+    /// Choose whether true candidates searches assume a unique solution. See [`SolverBuilder::with_uniqueness_assumption`].
+    pub fn set_uniqueness_assumption(&mut self, assume_unique: bool) {
+        self.deadly_patterns = assume_unique.then(|| Arc::new(DeadlyPatterns::new(&self.board)));
+    }
+
+    /// Records every placement and elimination made by the logical steps of [`Solver::run_logical_solve`],
+    /// [`Solver::run_single_logical_step`] and [`Solver::run_singles_only`], with the step as their source,
+    /// and the values set with [`Solver::set_solved`], without a source. Brute force searches are not recorded.
+    ///
+    /// Clones of the solver record into the same recorder.
+    pub fn set_recorder(&mut self, recorder: Option<Arc<BoardRecorder>>) {
+        self.recorder = recorder;
+    }
+
+    /// The recorder set with [`Solver::set_recorder`].
+    pub fn recorder(&self) -> Option<&Arc<BoardRecorder>> {
+        self.recorder.as_ref()
+    }
+
+    /// Tells the observer about the progress of logical solves and values set on the solver, as they happen.
+    /// See [`SolverObserver`].
+    ///
+    /// Clones of the solver tell the same observer.
+    pub fn set_observer(&mut self, observer: Option<Arc<dyn SolverObserver>>) {
+        self.observer = observer;
+    }
+
+    /// The observer set with [`Solver::set_observer`].
+    pub fn observer(&self) -> Option<&Arc<dyn SolverObserver>> {
+        self.observer.as_ref()
+    }
+
+    /// Runs the function on the board, recording its changes if there is a recorder
+    /// and telling the observer about the cells it solves if there is one.
+    fn record<T>(
+        recorder: &Option<Arc<BoardRecorder>>,
+        observer: &Option<Arc<dyn SolverObserver>>,
+        board: &mut Board,
+        source: Option<&str>,
+        f: impl FnOnce(&mut Board) -> T,
+    ) -> T {
+        let record = |board: &mut Board| match recorder {
+            Some(recorder) => recorder.record(board, source, f),
+            None => f(board),
+        };
+        let Some(observer) = observer else {
+            return record(board);
+        };
+
+        let previous_observer = board.take_observer();
+        board.set_observer(Arc::new(ValueSetForwarder(observer.clone())));
+        let result = record(board);
+        board.take_observer();
+        if let Some(previous_observer) = previous_observer {
+            board.set_observer(previous_observer);
+        }
+        result
+    }
+
+    /// Tells the observer, if there is one, about a logical step which was found.
+    fn notify_step(&self, step_result: &LogicalStepResult) {
+        if let Some(observer) = &self.observer {
+            if step_result.is_invalid() {
+                observer.on_contradiction(step_result.description());
+            } else {
+                observer.on_step_applied(step_result);
+            }
+        }
+    }
+
+    /// Set a cell to a value, as if it were a given.
+    ///
+    /// Returns false if the value is not a candidate of the cell or setting it breaks a constraint.
+    /// The board is left in an unknown state in that case, so set values on a clone when
+    /// the previous state is still needed.
+    pub fn set_solved(&mut self, cell: CellIndex, value: usize) -> bool {
+        let result =
+            Self::record(&self.recorder, &self.observer, &mut self.board, None, |board| board.set_solved(cell, value));
+        if !result {
+            if let Some(observer) = &self.observer {
+                observer.on_contradiction(None);
+            }
+        }
+        result
+    }
+
+    /// Set several cells to values, as if they were givens. See [`Board::set_solved_many`].
+    pub fn set_solved_many(&mut self, placements: &[(CellIndex, usize)]) -> Result<(), (CellIndex, usize)> {
+        let result = Self::record(&self.recorder, &self.observer, &mut self.board, None, |board| {
+            board.set_solved_many(placements)
+        });
+        if result.is_err() {
+            if let Some(observer) = &self.observer {
+                observer.on_contradiction(None);
+            }
+        }
+        result
+    }
+
     /// Find a single logical step that can be applied to the puzzle.
     pub fn run_single_logical_step(&mut self) -> LogicalStepResult {
+        self.run_single_logical_step_with_cancellation(None)
+    }
+
+    /// Find a single logical step that can be applied to the puzzle, stopping early once it is cancelled.
+    ///
+    /// The cancellation is passed to each [`LogicalStep`] and [`Constraint::step_logic`], so a heavy step
+    /// can stop partway through. A cancelled search returns [`LogicalStepResult::None`].
+    pub fn run_single_logical_step_with_cancellation(
+        &mut self,
+        cancellation: impl Into<Cancellation>,
+    ) -> LogicalStepResult {
+        let cancellation = cancellation.into();
+        self.run_named_logical_step(&cancellation).map_or(LogicalStepResult::None, |(_, step_result)| step_result)
+    }
+
+    /// Finds a single logical step like [`Solver::run_single_logical_step_with_cancellation`], returning it
+    /// with the name of the [`LogicalStep`] which found it.
+    fn run_named_logical_step(&mut self, cancellation: &Cancellation) -> Option<(&'static str, LogicalStepResult)> {
         for step in self.logical_solve_steps.iter() {
-            let step_result = step.run(&mut self.board, true);
+            if cancellation.check() {
+                return None;
+            }
+
+            let step_result =
+                Self::record(&self.recorder, &self.observer, &mut self.board, Some(step.name()), |board| {
+                    step.run(board, true, cancellation)
+                });
             if !step_result.is_none() {
-                if step.has_own_prefix() {
-                    return step_result;
+                let step_result = if step.has_own_prefix() {
+                    step_result
                 } else {
-                    return step_result.with_prefix(format!("{}: ", step.name()).as_str());
+                    step_result.with_prefix(format!("{}: ", step.name()).as_str())
+                };
+                if let Some(desc) = step_result.description() {
+                    tracing::trace!(target: STEPS_TRACE_TARGET, invalid = step_result.is_invalid(), "{desc}");
                 }
+                self.notify_step(&step_result);
+                return Some((step.name(), step_result));
             }
         }
 
-        LogicalStepResult::None
+        None
     }
 
     /// Run a full logical solve. This mutates the solver's board.
     pub fn run_logical_solve(&mut self) -> LogicalSolveResult {
+        self.run_logical_solve_with_cancellation(None)
+    }
+
+    /// Run a full logical solve, stopping early once it is cancelled. This mutates the solver's board.
+    ///
+    /// A cancelled solve returns the steps found so far. See [`Solver::run_single_logical_step_with_cancellation`].
+    pub fn run_logical_solve_with_cancellation(&mut self, cancellation: impl Into<Cancellation>) -> LogicalSolveResult {
+        self.run_logical_solve_with_step_names(&cancellation.into(), |_| {})
+    }
+
+    /// Runs a full logical solve like [`Solver::run_logical_solve_with_cancellation`], calling the function
+    /// with the name of the [`LogicalStep`] of each step it makes.
+    pub(crate) fn run_logical_solve_with_step_names(
+        &mut self,
+        cancellation: &Cancellation,
+        mut on_step: impl FnMut(&'static str),
+    ) -> LogicalSolveResult {
         let mut desc_list = LogicalStepDescList::new();
         let mut changed = false;
         loop {
@@ -87,12 +355,12 @@ impl Solver {
                 return LogicalSolveResult::Solved(desc_list);
             }
 
-            let step_result = self.run_single_logical_step();
-            if step_result.is_none() {
+            let Some((name, step_result)) = self.run_named_logical_step(cancellation) else {
                 break;
-            }
+            };
 
             changed = true;
+            on_step(name);
 
             if let Some(desc) = step_result.description() {
                 desc_list.push(desc.clone());
@@ -110,9 +378,62 @@ impl Solver {
         }
     }
 
-    fn run_single_brute_force_step(&self, board: &mut Board) -> LogicalStepResult {
+    /// Run a logical solve which only places naked and hidden singles. This mutates the solver's board.
+    ///
+    /// This is much faster than [`Solver::run_logical_solve`], so it is useful for quickly sorting
+    /// out the puzzles which need no other logic.
+    ///
+    /// # Example
+    /// ```
+    /// # use sudoku_solver_lib::prelude::*;
+    /// let mut solver = SolverBuilder::default()
+    ///     .with_givens_string("8...62..125.....7..197...5........9.....28..3.....36.54...1..6...74...3.5.2......")
+    ///     .build()
+    ///     .unwrap();
+    /// let result = solver.run_singles_only();
+    /// assert!(!result.is_invalid());
+    /// assert_eq!(solver.board().solved_count(), 26 + result.singles_placed());
+    /// ```
+    pub fn run_singles_only(&mut self) -> SinglesSolveResult {
+        let steps: [&dyn LogicalStep; 2] = [&NakedSingle, &HiddenSingle];
+        let start_count = self.board.solved_count();
+        let mut desc_list = LogicalStepDescList::new();
+        let result = loop {
+            if self.board.is_solved() {
+                desc_list.push("Solved!".into());
+                break LogicalSolveResult::Solved(desc_list);
+            }
+
+            let step_result = steps.iter().find_map(|step| {
+                let step_result =
+                    Self::record(&self.recorder, &self.observer, &mut self.board, Some(step.name()), |board| {
+                        step.run(board, true, &Cancellation::default())
+                    });
+                (!step_result.is_none()).then(|| step_result.with_prefix(format!("{}: ", step.name()).as_str()))
+            });
+            let Some(step_result) = step_result else {
+                break if desc_list.is_empty() {
+                    LogicalSolveResult::None
+                } else {
+                    LogicalSolveResult::Changed(desc_list)
+                };
+            };
+
+            self.notify_step(&step_result);
+            if let Some(desc) = step_result.description() {
+                desc_list.push(desc.clone());
+            }
+            if step_result.is_invalid() {
+                break LogicalSolveResult::Invalid(desc_list);
+            }
+        };
+
+        SinglesSolveResult::new(result, self.board.solved_count() - start_count)
+    }
+
+    fn run_single_brute_force_step(&self, board: &mut Board, cancellation: &Cancellation) -> LogicalStepResult {
         for step in self.brute_force_steps.iter() {
-            let step_result = step.run(board, false);
+            let step_result = step.run(board, false, cancellation);
             if !step_result.is_none() {
                 return step_result;
             }
@@ -121,9 +442,9 @@ impl Solver {
         LogicalStepResult::None
     }
 
-    fn run_brute_force_logic(&self, board: &mut Board) -> bool {
+    fn run_brute_force_logic(&self, board: &mut Board, cancellation: &Cancellation) -> bool {
         loop {
-            let step_result = self.run_single_brute_force_step(board);
+            let step_result = self.run_single_brute_force_step(board, cancellation);
             if step_result.is_none() {
                 break;
             }
@@ -131,22 +452,52 @@ impl Solver {
                 return false;
             }
         }
-
         true
     }
 
+    /// Whether the board has a deadly pattern, when the solver assumes the puzzle has a unique solution.
+    fn has_deadly_pattern(&self, board: &Board) -> bool {
+        // The cells solved on the solver's own board are treated as givens.
+        self.deadly_patterns
+            .as_ref()
+            .is_some_and(|deadly_patterns| deadly_patterns.has_deadly_pattern(board, &self.board))
+    }
+
+    /// Use brute-force methods to find the solution which comes first in the given order.
+    ///
+    /// Callers which only need some solution should use [`SolutionOrder::Fastest`], and only ask for
+    /// [`SolutionOrder::Lexicographic`] when the same solution as other lexicographic solvers is needed.
+    ///
+    /// # Example
+    /// ```
+    /// # use sudoku_solver_lib::prelude::*;
+    /// let solver = Solver::default();
+    /// let solution = solver.find_solution_in_order(SolutionOrder::Lexicographic).board().unwrap();
+    /// assert!(solution.to_string().starts_with("123456789456789123"));
+    /// assert!(solver.find_solution_in_order(SolutionOrder::Fastest).is_solved());
+    /// ```
+    pub fn find_solution_in_order(&self, order: SolutionOrder) -> SingleSolutionResult {
+        match order {
+            SolutionOrder::Lexicographic => self.find_first_solution(),
+            SolutionOrder::Fastest => self.find_fastest_solution(),
+        }
+    }
+
     /// Use brute-force methods to find the first solution to the puzzle.
     ///
     /// The solution is the lexicographically first solution and is not
-    /// guaranteed to be the only solution.
+    /// guaranteed to be the only solution. Callers which only need some solution
+    /// should use [`Solver::find_fastest_solution`], which is much faster on hard puzzles.
+    /// This is the same as [`Solver::find_solution_in_order`] with [`SolutionOrder::Lexicographic`].
     pub fn find_first_solution(&self) -> SingleSolutionResult {
         let cu = self.cell_utility();
+        let cancellation = Cancellation::default();
         let mut board_stack = Vec::new();
         board_stack.push((Box::new(self.board.clone()), cu.cell(0, 0)));
 
         while !board_stack.is_empty() {
             let (mut board, mut cell) = board_stack.pop().unwrap();
-            if !self.run_brute_force_logic(&mut board) {
+            if !self.run_brute_force_logic(&mut board, &cancellation) {
                 continue;
             }
 
@@ -184,7 +535,9 @@ impl Solver {
         SingleSolutionResult::None
     }
 
-    fn find_best_brute_force_cell(board: &Board) -> Option<CellIndex> {
+    /// Chooses the cell to branch on: a powerful cell if there is one, otherwise the cell with the fewest candidates.
+    /// See [`SolverBuilder::with_weak_link_tie_break`] for how ties are broken.
+    fn find_best_brute_force_cell(&self, board: &Board) -> Option<CellIndex> {
         let mut best_cell = None;
         let mut best_cell_candidate_count = usize::MAX;
         let board_data = board.data();
@@ -209,6 +562,7 @@ impl Solver {
             return best_cell;
         }
 
+        let mut best_cell_degree = 0;
         for cell in board.all_cells() {
             let mask = board.cell(cell);
             if mask.is_solved() {
@@ -220,71 +574,247 @@ impl Solver {
                 continue;
             }
 
-            if cell_count == 2 {
-                return Some(cell);
+            if !self.weak_link_tie_break {
+                if cell_count == 2 {
+                    return Some(cell);
+                }
+
+                if cell_count < best_cell_candidate_count {
+                    best_cell = Some(cell);
+                    best_cell_candidate_count = cell_count;
+                }
+                continue;
             }
 
-            if cell_count < best_cell_candidate_count {
+            if cell_count > best_cell_candidate_count {
+                continue;
+            }
+
+            // Setting the cell eliminates more elsewhere the more its candidates are weakly linked.
+            let degree: usize = mask.into_iter().map(|value| board.weak_link_degree(cell.candidate(value))).sum();
+            if cell_count < best_cell_candidate_count || degree > best_cell_degree {
                 best_cell = Some(cell);
                 best_cell_candidate_count = cell_count;
+                best_cell_degree = degree;
             }
         }
 
         best_cell
     }
 
+    /// Searches for a random solution without restarting, for the true candidates searches which often have no solution.
+    /// Branches with a deadly pattern are pruned when the solver assumes the puzzle has a unique solution.
+    ///
+    /// Restarts only help to find a solution sooner, and repeat the work of proving there is none.
     fn find_random_solution_for_board(&self, board: &Board) -> SingleSolutionResult {
+        let mut rng = rand::thread_rng();
+        self.find_solution_for_board_with(board, usize::MAX, true, |mask| mask.random_with(&mut rng))
+            .expect("A search without a limit always finishes.")
+    }
+
+    fn find_random_solution_for_board_with(&self, board: &Board, rng: &mut impl Rng) -> SingleSolutionResult {
+        if self.random_restart_boards == 0 {
+            return self
+                .find_solution_for_board_with(board, usize::MAX, false, |mask| mask.random_with(rng))
+                .expect("A search without a limit always finishes.");
+        }
+
+        let mut attempt = 1;
+        loop {
+            let max_boards = luby(attempt).saturating_mul(self.random_restart_boards);
+            if let Some(result) =
+                self.find_solution_for_board_with(board, max_boards, false, |mask| mask.random_with(rng))
+            {
+                return result;
+            }
+
+            tracing::debug!(target: BRUTE_FORCE_TRACE_TARGET, attempt, max_boards, "Random solution search restarted");
+            attempt += 1;
+        }
+    }
+
+    /// Use brute-force methods to find a solution to the puzzle, always trying the lowest value first.
+    ///
+    /// This branches on the same cells as [`Solver::find_random_solution`], so it is just as fast on hard
+    /// puzzles, and always finds the same solution. The solution is not necessarily the lexicographically
+    /// first solution, which [`Solver::find_first_solution`] finds.
+    /// This is the same as [`Solver::find_solution_in_order`] with [`SolutionOrder::Fastest`].
+    ///
+    /// The solution is not guaranteed to be the only solution.
+    pub fn find_fastest_solution(&self) -> SingleSolutionResult {
+        self.find_solution_for_board_with(&self.board, usize::MAX, false, |mask| mask.min())
+            .expect("A search without a limit always finishes.")
+    }
+
+    /// Searches for a solution, branching on the best cell with the value chosen by the function.
+    /// Branches with a deadly pattern are pruned if `prune_deadly_patterns` is set and the solver assumes uniqueness.
+    ///
+    /// Returns `None` if the search explores `max_boards` boards without finishing.
+    fn find_solution_for_board_with(
+        &self,
+        board: &Board,
+        max_boards: usize,
+        prune_deadly_patterns: bool,
+        mut choose_value: impl FnMut(ValueMask) -> usize,
+    ) -> Option<SingleSolutionResult> {
         let mut board_stack = Vec::new();
         board_stack.push(Box::new(board.clone()));
+        let mut board_pool = BoardPool::default();
+        let mut boards_explored = 0;
+        let cancellation = Cancellation::default();
 
-        while !board_stack.is_empty() {
-            let mut board = board_stack.pop().unwrap();
-            if !self.run_brute_force_logic(&mut board) {
+        let result = loop {
+            if boards_explored == max_boards {
+                return None;
+            }
+
+            let mut board = match board_stack.pop() {
+                Some(board) => board,
+                None => break SingleSolutionResult::None,
+            };
+            boards_explored += 1;
+            if !self.run_brute_force_logic(&mut board, &cancellation)
+                || (prune_deadly_patterns && self.has_deadly_pattern(&board))
+            {
+                board_pool.recycle(board);
                 continue;
             }
 
             if board.is_solved() {
-                return SingleSolutionResult::Solved(board);
+                break SingleSolutionResult::Solved(board);
             }
 
-            let cell = Self::find_best_brute_force_cell(&board);
+            let cell = self.find_best_brute_force_cell(&board);
             if let Some(cell) = cell {
-                let mask = board.cell(cell);
-                let value = mask.random();
+                let value = choose_value(board.cell(cell));
 
                 // Push a copy of the board onto the stack with the value unset.
-                let mut board_copy = board.clone();
+                let mut board_copy = board_pool.copy_of(&board);
                 if board_copy.clear_value(cell, value) {
                     board_stack.push(board_copy);
+                } else {
+                    board_pool.recycle(board_copy);
                 }
 
                 // Push a the board onto the stack with the value solved.
                 if board.set_solved(cell, value) {
                     board_stack.push(board);
+                } else {
+                    board_pool.recycle(board);
                 }
             } else {
-                return SingleSolutionResult::Error("Internal error finding a cell to check.".to_owned());
+                break SingleSolutionResult::Error("Internal error finding a cell to check.".to_owned());
             }
-        }
+        };
 
-        SingleSolutionResult::None
+        tracing::debug!(
+            target: BRUTE_FORCE_TRACE_TARGET,
+            boards_explored,
+            solved = result.is_solved(),
+            "Solution search finished"
+        );
+        Some(result)
     }
+
     /// Use brute-force methods to find a random solution to the puzzle.
-    /// This can be faster than [`Solver::find_first_solution`] because it
-    /// is not forced to find the lexicographically first solution.
+    /// This searches the same way as [`Solver::find_fastest_solution`], but tries the values
+    /// of each cell in a random order.
+    ///
+    /// The search restarts with new random choices whenever an attempt explores too many boards,
+    /// so it doesn't get stuck in a subtree without solutions. See [`SolverBuilder::with_random_restart_boards`].
     ///
     /// The solution is not guaranteed to be the only solution.
     pub fn find_random_solution(&self) -> SingleSolutionResult {
-        self.find_random_solution_for_board(&self.board)
+        self.find_random_solution_for_board_with(&self.board, &mut rand::thread_rng())
+    }
+
+    /// Use brute-force methods to find up to `count` distinct random solutions to the puzzle.
+    ///
+    /// Each solution comes from its own search which picks a random value at every branch,
+    /// so the solutions are spread over the solution space much more than the solutions
+    /// of a single search would be. Fewer solutions are returned only if the puzzle has fewer.
+    ///
+    /// # Example
+    /// ```
+    /// # use sudoku_solver_lib::prelude::*;
+    /// # use rand::SeedableRng;
+    /// let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+    /// let solutions = Solver::default().find_random_solutions(5, &mut rng).unwrap();
+    /// assert_eq!(solutions.len(), 5);
+    /// ```
+    pub fn find_random_solutions(&self, count: usize, rng: &mut impl Rng) -> Result<Vec<Box<Board>>, String> {
+        // Random searches which keep finding known solutions suggest there are few solutions left to find.
+        const MAX_REPEATED_SOLUTIONS: usize = 10;
+
+        let solution_values = |board: &Board| board.all_cell_masks().map(|(_, mask)| mask.value()).collect_vec();
+        let mut solutions = Vec::new();
+        let mut seen = HashSet::new();
+        let mut repeated_solutions = 0;
+        while solutions.len() < count && repeated_solutions < MAX_REPEATED_SOLUTIONS {
+            match self.find_random_solution_for_board_with(&self.board, rng) {
+                SingleSolutionResult::None => return Ok(Vec::new()),
+                SingleSolutionResult::Error(error) => return Err(error),
+                SingleSolutionResult::Solved(solution) => {
+                    if seen.insert(solution_values(&solution)) {
+                        solutions.push(solution);
+                        repeated_solutions = 0;
+                    } else {
+                        repeated_solutions += 1;
+                    }
+                }
+            }
+        }
+
+        if solutions.len() < count {
+            // Any `count` solutions include enough unseen ones to fill the rest, if there are that many.
+            let mut receiver = VecSolutionReceiver::new();
+            if let SolutionCountResult::Error(error) = self.find_solution_count(count, Some(&mut receiver), None) {
+                return Err(error);
+            }
+            for solution in receiver.take_solutions() {
+                if solutions.len() < count && seen.insert(solution_values(&solution)) {
+                    solutions.push(Box::new(solution));
+                }
+            }
+        }
+
+        Ok(solutions)
     }
 
     /// Using brute force methods, return a board with only candidates which lead to a valid solution to the puzzle.
     /// These candidates are guaranteed to lead to at least one solution if given.
     pub fn find_true_candidates(&self) -> SingleSolutionResult {
+        self.find_true_candidates_in(None)
+    }
+
+    /// Like [`Solver::find_true_candidates`], but only checks the candidates of the given cells.
+    ///
+    /// The other cells of the returned board keep every candidate which wasn't removed by the brute force
+    /// logic, so they may include candidates which aren't in any solution. This is much cheaper when only
+    /// a few cells need checking, such as the row or box a player just changed.
+    ///
+    /// # Example
+    /// ```
+    /// # use sudoku_solver_lib::prelude::*;
+    /// let givens = ".............23.4.....452....1.3.....3...4...6..7....8..6.....9.5....62.7.9...1..";
+    /// let solver = SolverBuilder::default().with_givens_string(givens).build().unwrap();
+    /// let cu = solver.cell_utility();
+    /// let row = (0..9).map(|col| cu.cell(0, col)).collect::<Vec<_>>();
+    ///
+    /// let board = solver.find_true_candidates_for_cells(&row).board().unwrap();
+    /// let full = solver.find_true_candidates().board().unwrap();
+    /// assert!(row.iter().all(|&cell| board.cell(cell) == full.cell(cell)));
+    /// ```
+    pub fn find_true_candidates_for_cells(&self, cells: &[CellIndex]) -> SingleSolutionResult {
+        self.find_true_candidates_in(Some(cells))
+    }
+
+    /// Finds the true candidates of the given cells, or of every cell.
+    fn find_true_candidates_in(&self, cells: Option<&[CellIndex]>) -> SingleSolutionResult {
         let mut board = Box::new(self.board.clone());
 
         // Run the brute force logic to remove trivially invalid candidates.
-        if !self.run_brute_force_logic(&mut board) {
+        if !self.run_brute_force_logic(&mut board, &Cancellation::default()) {
             return SingleSolutionResult::None;
         }
 
@@ -292,6 +822,11 @@ impl Solver {
             return SingleSolutionResult::Solved(board);
         }
 
+        let checked_cells = match cells {
+            Some(cells) => cells.iter().copied().unique().collect_vec(),
+            None => board.all_cells().collect_vec(),
+        };
+
         let mut true_cell_values = board
             .all_cells()
             .map(|cell| {
@@ -304,7 +839,9 @@ impl Solver {
             })
             .collect_vec();
 
-        for (cell, mask) in board.all_cell_masks() {
+        let mut found_solution = false;
+        for &cell in checked_cells.iter() {
+            let mask = board.cell(cell);
             if mask.is_solved() {
                 continue;
             }
@@ -318,6 +855,7 @@ impl Solver {
 
                 let solution_result = self.find_random_solution_for_board(&new_board);
                 if let SingleSolutionResult::Solved(solution) = solution_result {
+                    found_solution = true;
                     for (cell, mask) in solution.all_cell_masks() {
                         true_cell_values[cell.index()] = true_cell_values[cell.index()] | mask.unsolved();
                     }
@@ -325,19 +863,99 @@ impl Solver {
             }
         }
 
-        for cell in board.all_cells() {
+        // When no cells were searched, nothing has shown that the puzzle has a solution.
+        if !found_solution {
+            match self.find_random_solution_for_board(&board) {
+                SingleSolutionResult::Solved(_) => {}
+                SingleSolutionResult::None => return SingleSolutionResult::None,
+                SingleSolutionResult::Error(error) => return SingleSolutionResult::Error(error),
+            }
+        }
+
+        for &cell in checked_cells.iter() {
             if !board.keep_mask(cell, true_cell_values[cell.index()]) {
                 return SingleSolutionResult::None;
             }
         }
 
-        if AllNakedSingles.run(&mut board, false).is_invalid() {
+        if AllNakedSingles.run(&mut board, false, &Cancellation::default()).is_invalid() {
             return SingleSolutionResult::None;
         }
 
         SingleSolutionResult::Solved(board)
     }
 
+    /// Check a player's partially solved grid for mistakes, without revealing anything else about the solution.
+    ///
+    /// Cells of the grid with a single value are placed values, and any other cell which doesn't have every
+    /// value has pencilmarks. A placed value is wrong if it is not in any solution. Pencilmarks are wrong if
+    /// they are missing a value which is in a solution that agrees with the correct placed values.
+    ///
+    /// Returns an error if the grid is a different size or the puzzle itself has no solution.
+    ///
+    /// # Example
+    /// ```
+    /// # use sudoku_solver_lib::prelude::*;
+    /// let solver = Solver::default();
+    /// let mut grid = solver.board().clone();
+    /// let cu = solver.cell_utility();
+    /// assert!(grid.set_solved(cu.cell(0, 0), 1));
+    /// assert!(solver.check_progress(&grid).unwrap().is_on_track());
+    ///
+    /// assert!(grid.keep_mask(cu.cell(0, 1), ValueMask::from_values(&[2, 3])));
+    /// let report = solver.check_progress(&grid).unwrap();
+    /// assert_eq!(report.wrong_pencilmarks.len(), 1);
+    /// ```
+    pub fn check_progress(&self, grid: &Board) -> Result<ProgressReport, String> {
+        if grid.size() != self.size() {
+            return Err(format!("The grid has size {} but the puzzle has size {}", grid.size(), self.size()));
+        }
+
+        let true_candidates = match self.find_true_candidates() {
+            SingleSolutionResult::Solved(board) => board,
+            SingleSolutionResult::None => return Err("The puzzle has no solution".to_owned()),
+            SingleSolutionResult::Error(error) => return Err(error),
+        };
+
+        let mut report = ProgressReport::default();
+        let mut placed_board = self.clone();
+        let mut placed_consistent = true;
+        for (cell, mask) in grid.all_cell_masks() {
+            if !mask.is_single() {
+                continue;
+            }
+
+            let value = mask.value();
+            if !true_candidates.cell(cell).has(value) {
+                report.wrong_values.push(cell.candidate(value));
+            } else if placed_consistent && !placed_board.board.cell(cell).is_solved() {
+                placed_consistent = placed_board.set_solved(cell, value);
+            }
+        }
+
+        // Correct values can still contradict each other, in which case only the puzzle's
+        // own solutions are known to be possible.
+        let possible = match placed_board.find_true_candidates() {
+            SingleSolutionResult::Solved(board) if placed_consistent => board,
+            _ => true_candidates,
+        };
+
+        let all_values = self.board.all_values_mask();
+        for (cell, mask) in grid.all_cell_masks() {
+            let mask = mask.unsolved();
+            if mask.is_single() || mask.is_empty() || mask == all_values {
+                continue;
+            }
+
+            let missing = possible.cell(cell).unsolved() & !mask;
+            if !missing.is_empty() {
+                report.wrong_pencilmarks.push((cell, missing));
+            }
+        }
+
+        Ok(report)
+    }
+
     /// Using brute force methods, return a board with only candidates which lead to a valid solution to the puzzle.
     /// These candidates are guaranteed to lead to at least one solution if given.
     pub fn find_true_candidates_with_count(
@@ -351,7 +969,7 @@ impl Solver {
         let cancellation = cancellation.into();
 
         // Run the brute force logic to remove trivially invalid candidates.
-        if !self.run_brute_force_logic(&mut board) {
+        if !self.run_brute_force_logic(&mut board, &cancellation) {
             return TrueCandidatesCountResult::None;
         }
 
@@ -362,15 +980,20 @@ impl Solver {
         struct TrueCandidatesCountReceiver {
             true_cell_values: Vec<ValueMask>,
             num_solutions_per_candidate: Vec<usize>,
-            solutions_seen: HashSet<Box<Board>>,
+            /// The solution hash and checksum of each solution received.
+            solutions_seen: HashSet<(u64, u64)>,
             maximum_count: usize,
             candidate: CandidateIndex,
         }
 
         impl SolutionReceiver for TrueCandidatesCountReceiver {
-            fn receive(&mut self, board: Box<Board>) -> bool {
-                if self.solutions_seen.contains(board.as_ref()) {
-                    return true;
+            fn receive(&mut self, board: Box<Board>, _statistics: &SearchStatistics) -> ReceiveAction {
+                // The checksum guards against the unlikely case of two solutions sharing a hash.
+                let checksum = board
+                    .all_cell_masks()
+                    .fold(0u64, |checksum, (_, mask)| checksum.wrapping_mul(31).wrapping_add(mask.value() as u64));
+                if !self.solutions_seen.insert((board.solution_hash(), checksum)) {
+                    return ReceiveAction::Continue;
                 }
 
                 for (cell, mask) in board.all_cell_masks() {
@@ -378,9 +1001,12 @@ impl Solver {
                     let candidate_index = cell.candidate(mask.value());
                     self.num_solutions_per_candidate[candidate_index.index()] += 1;
                 }
-                self.solutions_seen.insert(board);
 
-                self.num_solutions_per_candidate[self.candidate.index()] < self.maximum_count
+                if self.num_solutions_per_candidate[self.candidate.index()] < self.maximum_count {
+                    ReceiveAction::Continue
+                } else {
+                    ReceiveAction::Stop
+                }
             }
         }
 
@@ -396,6 +1022,10 @@ impl Solver {
             })
             .collect_vec();
 
+        // Whether each candidate's count includes every solution with it, rather than stopping at the maximum.
+        let mut is_exact = vec![false; num_candidates];
+        let mut all_exact = true;
+
         let mut solution_receiver = TrueCandidatesCountReceiver {
             true_cell_values,
             num_solutions_per_candidate: vec![0; num_candidates],
@@ -414,23 +1044,29 @@ impl Solver {
                 let cur_candidate = cell.candidate(value);
                 let cur_candidate_count = solution_receiver.num_solutions_per_candidate[cur_candidate.index()];
                 if cur_candidate_count >= maximum_count {
+                    all_exact = false;
                     continue;
                 }
                 let count_needed = maximum_count - cur_candidate_count;
 
                 let mut new_board = board.clone();
                 if !new_board.set_solved(cell, value) {
+                    is_exact[cur_candidate.index()] = true;
                     continue;
                 }
 
                 solution_receiver.candidate = cur_candidate;
-                if let SolutionCountResult::Error(e) = self.find_solution_count_for_board(
+                match self.find_solution_count_for_board(
                     &new_board,
                     count_needed,
                     Some(&mut solution_receiver),
                     cancellation.clone(),
                 ) {
-                    return TrueCandidatesCountResult::Error(e);
+                    SolutionCountResult::Error(e) => return TrueCandidatesCountResult::Error(e),
+                    SolutionCountResult::None | SolutionCountResult::ExactCount(_) => {
+                        is_exact[cur_candidate.index()] = true
+                    }
+                    SolutionCountResult::AtLeastCount(_) => all_exact = false,
                 }
             }
         }
@@ -442,17 +1078,104 @@ impl Solver {
             }
         }
 
-        if AllNakedSingles.run(&mut board, false).is_invalid() {
+        if AllNakedSingles.run(&mut board, false, &Cancellation::default()).is_invalid() {
             return TrueCandidatesCountResult::None;
         }
 
         if board.is_solved() {
             TrueCandidatesCountResult::Solved(board)
         } else {
-            TrueCandidatesCountResult::Candidates(board, solution_receiver.num_solutions_per_candidate)
+            // Solved cells are in every solution, so their counts are exact only if every count is.
+            let candidate_counts = solution_receiver
+                .num_solutions_per_candidate
+                .iter()
+                .enumerate()
+                .map(|(index, &count)| {
+                    let is_solved = board.cell(board.cell_utility().candidate_index(index).cell_index()).is_solved();
+                    if count == 0 || is_exact[index] || (is_solved && all_exact) {
+                        CandidateCount::Exact(count)
+                    } else {
+                        CandidateCount::AtLeast(count)
+                    }
+                })
+                .collect();
+            TrueCandidatesCountResult::Candidates(board, candidate_counts)
         }
     }
 
+    /// Finds the true candidates and compares them to the candidates which a logical solve leaves,
+    /// classifying each candidate. See [`CandidateClass`].
+    ///
+    /// This follows the [`SolverOptions`] of the solver:
+    /// - [`SolverOptions::true_candidates_colored`] counts the solutions of each candidate, up to
+    ///   [`SolverOptions::max_colored_count`].
+    /// - [`SolverOptions::true_candidates_logical`] runs the logical solve. Without it, the logical
+    ///   solve is skipped and no candidate is [`CandidateClass::LogicalOnly`].
+    ///
+    /// # Example
+    /// ```
+    /// # use sudoku_solver_lib::prelude::*;
+    /// let givens = ".............23.4.....452....1.3.....3...4...6..7....8..6.....9.5....62.7.9...1..";
+    /// let solver = SolverBuilder::default().with_givens_string(givens).build().unwrap();
+    /// let result = solver.find_true_candidates_with_logical_comparison(None);
+    /// let comparison = result.comparison().unwrap();
+    /// assert_eq!(comparison.classes.len(), 9 * 9 * 9);
+    /// assert!(!comparison.classes.contains(&CandidateClass::LogicalOnly));
+    /// ```
+    pub fn find_true_candidates_with_logical_comparison(
+        &self,
+        cancellation: impl Into<Cancellation>,
+    ) -> TrueCandidatesComparisonResult {
+        let cancellation = cancellation.into();
+        let size = self.size();
+
+        let logical_cells = if self.options.true_candidates_logical {
+            let mut logical_solver = self.clone();
+            if logical_solver.run_logical_solve_with_cancellation(cancellation.clone()).is_invalid() {
+                return TrueCandidatesComparisonResult::None;
+            }
+            if cancellation.check() {
+                return TrueCandidatesComparisonResult::Error("cancelled".into());
+            }
+            Some(logical_solver.board().all_cell_masks().map(|(_, mask)| mask).collect::<Vec<_>>())
+        } else {
+            None
+        };
+
+        let (board, candidate_counts) = if self.options.true_candidates_colored {
+            match self.find_true_candidates_with_count(self.options.max_colored_count, cancellation) {
+                TrueCandidatesCountResult::None => return TrueCandidatesComparisonResult::None,
+                TrueCandidatesCountResult::Error(error) => return TrueCandidatesComparisonResult::Error(error),
+                TrueCandidatesCountResult::Solved(board) => {
+                    // The only solution has exactly one solution with each of its values.
+                    let counts = board
+                        .all_cell_masks()
+                        .flat_map(|(_, mask)| {
+                            (1..=size).map(move |value| CandidateCount::Exact(mask.has(value) as usize))
+                        })
+                        .collect();
+                    (board, Some(counts))
+                }
+                TrueCandidatesCountResult::Candidates(board, counts) => (board, Some(counts)),
+            }
+        } else {
+            match self.find_true_candidates() {
+                SingleSolutionResult::None => return TrueCandidatesComparisonResult::None,
+                SingleSolutionResult::Error(error) => return TrueCandidatesComparisonResult::Error(error),
+                SingleSolutionResult::Solved(board) => (board, None),
+            }
+        };
+
+        let true_cells: Vec<ValueMask> = board.all_cell_masks().map(|(_, mask)| mask).collect();
+        let logical_cells = logical_cells.unwrap_or_else(|| true_cells.clone());
+        TrueCandidatesComparisonResult::Candidates(TrueCandidatesComparison::new(
+            size,
+            true_cells,
+            logical_cells,
+            candidate_counts.as_deref(),
+        ))
+    }
+
     fn find_solution_count_for_board(
         &self,
         board: &Board,
@@ -460,75 +1183,155 @@ impl Solver {
         mut solution_receiver: Option<&mut dyn SolutionReceiver>,
         cancellation: impl Into<Cancellation>,
     ) -> SolutionCountResult {
-        let mut board_stack = Vec::new();
         let cancellation = cancellation.into();
-        board_stack.push(Box::new(board.clone()));
+        let mut counter = SolutionCounter::new(self, board, maximum_count);
+        loop {
+            let solution_receiver =
+                solution_receiver.as_mut().map(|receiver| &mut **receiver as &mut dyn SolutionReceiver);
+            if let Some(result) = counter.run(usize::MAX, solution_receiver, &cancellation) {
+                return result;
+            }
+        }
+    }
 
-        let mut solution_count = 0;
-        let mut progress_count = 0;
+    // Find the solution count of the puzzle via brute force with an optional receiver for each solution.
+    pub fn find_solution_count(
+        &self,
+        maximum_count: usize,
+        solution_receiver: Option<&mut dyn SolutionReceiver>,
+        cancellation: impl Into<Cancellation>,
+    ) -> SolutionCountResult {
+        self.find_solution_count_for_board(&self.board, maximum_count, solution_receiver, cancellation)
+    }
 
-        while !board_stack.is_empty() {
-            if cancellation.check() {
-                return SolutionCountResult::Error("cancelled".into());
+    /// Find the solution count of the puzzle via brute force, starting from solutions which are already known.
+    ///
+    /// The known solutions which are still valid count towards the total without being sent to the
+    /// solution receiver, so the receiver only gets solutions which weren't known.
+    /// See [`SolutionCounter::with_known_solutions`] for the details.
+    ///
+    /// # Example
+    /// ```
+    /// # use sudoku_solver_lib::prelude::*;
+    /// let solver = SolverBuilder::default()
+    ///     .with_givens_string(".............23.4.....452....1.3.....3...4...6..7....8..6.....9.5....62.7.9...1..")
+    ///     .build()
+    ///     .unwrap();
+    /// let mut receiver = FirstSolutionsReceiver::new(3);
+    /// solver.find_solution_count(0, Some(&mut receiver), None);
+    ///
+    /// // The known solutions alone are enough to reach the maximum count
+    /// let result = solver.find_solution_count_with_known(receiver.solutions(), 3, None, None);
+    /// assert_eq!(result, SolutionCountResult::AtLeastCount(3));
+    /// ```
+    pub fn find_solution_count_with_known<'a>(
+        &self,
+        known_solutions: impl IntoIterator<Item = &'a Board>,
+        maximum_count: usize,
+        mut solution_receiver: Option<&mut dyn SolutionReceiver>,
+        cancellation: impl Into<Cancellation>,
+    ) -> SolutionCountResult {
+        let cancellation = cancellation.into();
+        let mut counter = self.solution_counter(maximum_count).with_known_solutions(known_solutions);
+        loop {
+            let solution_receiver =
+                solution_receiver.as_mut().map(|receiver| &mut **receiver as &mut dyn SolutionReceiver);
+            if let Some(result) = counter.run(usize::MAX, solution_receiver, &cancellation) {
+                return result;
             }
+        }
+    }
 
-            if let Some(solution_receiver) = solution_receiver.as_mut() {
-                progress_count += 1;
-                if progress_count % 50000 == 0 {
-                    solution_receiver.progress_ping(progress_count);
-                }
-            }
+    /// Create a [`SolutionCounter`] for counting the solutions of the puzzle a piece at a time.
+    ///
+    /// A `maximum_count` of 0 counts all solutions.
+    pub fn solution_counter(&self, maximum_count: usize) -> SolutionCounter<'_> {
+        SolutionCounter::new(self, &self.board, maximum_count)
+    }
+
+    /// Check whether a filled grid is a solution to the puzzle, without brute forcing.
+    ///
+    /// Every cell must have a single value which is still a candidate of the puzzle, no value may repeat
+    /// in a house, no two values may be weakly linked, and [`Constraint::enforce`] must accept every value.
+    ///
+    /// Returns every violation found, so an empty list means the grid is a solution.
+    ///
+    /// # Example
+    /// ```
+    /// # use sudoku_solver_lib::prelude::*;
+    /// let solver = Solver::default();
+    /// let solution = solver.find_first_solution().board().unwrap();
+    /// assert!(solver.validate_solution(&solution).is_empty());
+    /// ```
+    pub fn validate_solution(&self, solution: &Board) -> Vec<SolutionViolation> {
+        if solution.size() != self.size() {
+            return vec![SolutionViolation::WrongSize { expected: self.size(), actual: solution.size() }];
+        }
 
-            let mut board = board_stack.pop().unwrap();
-            if !self.run_brute_force_logic(&mut board) {
+        let mut violations = Vec::new();
+        let mut board = self.board.clone();
+        let mut candidates = Vec::new();
+        for (cell, mask) in solution.all_cell_masks() {
+            if !mask.is_single() {
+                violations.push(SolutionViolation::Unfilled(cell));
                 continue;
             }
 
-            if board.is_solved() {
-                solution_count += 1;
+            let value = mask.value();
+            if !self.board.cell(cell).has(value) {
+                violations.push(SolutionViolation::NotACandidate(cell.candidate(value)));
+            }
+            board.fill_unchecked(cell, value);
+            candidates.push(cell.candidate(value));
+        }
 
-                if let Some(solution_receiver) = solution_receiver.as_mut() {
-                    if !solution_receiver.receive(board) {
-                        return SolutionCountResult::AtLeastCount(solution_count);
-                    }
+        for house in self.board.houses() {
+            let mut cells_by_value = vec![Vec::new(); self.size() + 1];
+            for &cell in house.cells() {
+                let mask = board.cell(cell);
+                if mask.is_single() {
+                    cells_by_value[mask.value()].push(cell);
                 }
-
-                if maximum_count > 0 && solution_count >= maximum_count {
-                    return SolutionCountResult::AtLeastCount(solution_count);
+            }
+            for (value, cells) in cells_by_value.into_iter().enumerate() {
+                if cells.len() > 1 {
+                    violations.push(SolutionViolation::HouseRepeat { house: house.name().to_owned(), value, cells });
                 }
-                continue;
             }
+        }
 
-            let cell = Self::find_best_brute_force_cell(&board);
-            if let Some(cell) = cell {
-                let mask = board.cell(cell);
-                for value in mask {
-                    // Push a copy of the board onto the stack with each value set.
-                    let mut board_copy = board.clone();
-                    if board_copy.set_solved(cell, value) {
-                        board_stack.push(board_copy);
-                    }
+        let weak_links = self.board.weak_links();
+        for (i, &candidate0) in candidates.iter().enumerate() {
+            for &candidate1 in &candidates[i + 1..] {
+                if !weak_links[candidate0.index()].is_linked(candidate1) {
+                    continue;
+                }
+
+                // Repeats within a house are already reported
+                let (cell0, value0) = candidate0.cell_index_and_value();
+                let (cell1, value1) = candidate1.cell_index_and_value();
+                let shares_house = self.board.houses_for_cell(cell0).iter().any(|house| house.cells().contains(&cell1));
+                if value0 != value1 || !shares_house {
+                    violations.push(SolutionViolation::WeakLink(candidate0, candidate1));
                 }
-            } else {
-                return SolutionCountResult::Error("Internal error finding a cell to check.".to_owned());
             }
         }
 
-        if solution_count == 0 {
-            SolutionCountResult::None
-        } else {
-            SolutionCountResult::ExactCount(solution_count)
+        for constraint in self.board.constraints() {
+            for &candidate in &candidates {
+                let (cell, value) = candidate.cell_index_and_value();
+                let result = constraint.enforce(&board, cell, value);
+                if result.is_invalid() {
+                    violations.push(SolutionViolation::Constraint {
+                        constraint: constraint.name().to_owned(),
+                        candidate,
+                        reason: result.description().map(|desc| desc.to_string()),
+                    });
+                }
+            }
         }
-    }
 
-    // Find the solution count of the puzzle via brute force with an optional receiver for each solution.
-    pub fn find_solution_count(
-        &self,
-        maximum_count: usize,
-        solution_receiver: Option<&mut dyn SolutionReceiver>,
-        cancellation: impl Into<Cancellation>,
-    ) -> SolutionCountResult {
-        self.find_solution_count_for_board(&self.board, maximum_count, solution_receiver, cancellation)
+        violations
     }
 }
 
@@ -538,6 +1341,25 @@ impl Default for Solver {
     }
 }
 
+/// The `index`th term of the Luby sequence 1, 1, 2, 1, 1, 2, 4, 1, 1, 2, ..., counting from 1.
+///
+/// Restarting after this many units of work each time wastes at most a logarithmic factor
+/// compared to the best fixed restart length, without knowing that length in advance.
+fn luby(mut index: usize) -> usize {
+    loop {
+        // Find the smallest complete block of the sequence which contains the index.
+        let mut block = 1;
+        while block < index {
+            block = block * 2 + 1;
+        }
+        if index == block {
+            return block.div_ceil(2);
+        }
+        // The block is two copies of the previous block followed by its last term.
+        index -= block / 2;
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -554,6 +1376,65 @@ mod test {
 
         let solution = board.to_string();
         assert_eq!(solution, "123456789456789123789123456214365897365897214897214365531642978642978531978531642");
+
+        // The fastest solution is always the same, but isn't necessarily the lexicographically first
+        let board = solver.find_fastest_solution().board().unwrap();
+        assert!(solver.validate_solution(&board).is_empty());
+        assert_eq!(board.to_string(), solver.find_fastest_solution().board().unwrap().to_string());
+
+        let givens = ".............23.4.....452....1.3.....3...4...6..7....8..6.....9.5....62.7.9...1..";
+        let solver = SolverBuilder::default().with_givens_string(givens).build().unwrap();
+        let board = solver.find_fastest_solution().board().unwrap();
+        assert!(solver.validate_solution(&board).is_empty());
+        assert!(solver.find_first_solution().is_solved());
+    }
+
+    #[test]
+    fn test_validate_solution() {
+        let solver = SolverBuilder::default().with_givens_string(&format!("1{}", ".".repeat(80))).build().unwrap();
+        let solution = solver.find_first_solution().board().unwrap();
+        assert!(solver.validate_solution(&solution).is_empty());
+
+        let other = SolverBuilder::default().with_givens_string(&format!("2{}", ".".repeat(80))).build().unwrap();
+        let other_solution = other.find_first_solution().board().unwrap();
+        let violations = solver.validate_solution(&other_solution);
+        assert!(violations
+            .contains(&SolutionViolation::NotACandidate(CandidateIndex::from_cv(solver.cell_utility().cell(0, 0), 2))));
+
+        let empty = SolverBuilder::default().build().unwrap();
+        let violations = solver.validate_solution(empty.board());
+        assert_eq!(violations.len(), 81);
+        assert!(violations.iter().all(|violation| matches!(violation, SolutionViolation::Unfilled(_))));
+
+        let small = SolverBuilder::new(6).build().unwrap();
+        let violations = solver.validate_solution(small.board());
+        assert_eq!(violations, vec![SolutionViolation::WrongSize { expected: 9, actual: 6 }]);
+    }
+
+    #[test]
+    fn test_run_singles_only() {
+        let mut solver = SolverBuilder::default()
+            .with_givens_string("8...62..125.....7..197...5........9.....28..3.....36.54...1..6...74...3.5.2......")
+            .build()
+            .unwrap();
+        let result = solver.run_singles_only();
+        assert!(result.is_complete());
+        assert_eq!(result.singles_placed(), 81 - 26);
+        // One step per single, followed by "Solved!"
+        assert_eq!(result.result().description().unwrap().len(), result.singles_placed() + 1);
+
+        let mut solver = Solver::default();
+        let result = solver.run_singles_only();
+        assert!(result.result().is_none());
+        assert_eq!(result.singles_placed(), 0);
+
+        let mut solver = SolverBuilder::default()
+            .with_givens_string("123456789456789123789123456214365897365897214897214365531642978642978531978531...")
+            .build()
+            .unwrap();
+        let result = solver.run_singles_only();
+        assert!(result.is_complete());
+        assert_eq!(result.singles_placed(), 3);
     }
 
     #[test]
@@ -571,6 +1452,94 @@ mod test {
         assert!(!solution.chars().any(|c| !('1'..='9').contains(&c)));
     }
 
+    #[test]
+    fn test_random_restarts() {
+        let luby_terms = (1..=15).map(luby).collect_vec();
+        assert_eq!(luby_terms, vec![1, 1, 2, 1, 1, 2, 4, 1, 1, 2, 1, 1, 2, 4, 8]);
+
+        use rand::SeedableRng;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(5);
+        let givens = ".............23.4.....452....1.3.....3...4...6..7....8..6.....9.5....62.7.9...1..";
+        for restart_boards in [0, 1, Solver::DEFAULT_RANDOM_RESTART_BOARDS] {
+            let solver = SolverBuilder::default()
+                .with_givens_string(givens)
+                .with_random_restart_boards(restart_boards)
+                .build()
+                .unwrap();
+            assert_eq!(solver.random_restart_boards(), restart_boards);
+            let solution = solver.find_random_solution_for_board_with(solver.board(), &mut rng).board().unwrap();
+            assert!(solver.validate_solution(&solution).is_empty());
+
+            // Attempts keep growing until one can tell there is no solution
+            let mut solver = solver.clone();
+            assert!(solver.set_solved(CellIndex::new(0, 9), 1));
+            assert!(solver.set_solved(CellIndex::new(1, 9), 2));
+            let result = solver.find_random_solution_for_board_with(solver.board(), &mut rng);
+            assert_eq!(result.is_solved(), solver.find_first_solution().is_solved());
+            assert!(!result.is_error());
+        }
+    }
+
+    #[test]
+    fn test_random_solutions() {
+        use rand::SeedableRng;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+
+        let solutions = Solver::default().find_random_solutions(10, &mut rng).unwrap();
+        assert_eq!(solutions.len(), 10);
+        assert_eq!(solutions.iter().map(|solution| solution.to_string()).unique().count(), 10);
+
+        // A deadly pattern in r1c1, r1c2, r4c1 and r4c2 gives exactly two solutions
+        let solver = SolverBuilder::default()
+            .with_givens_string("..3456789456789123789123456..4365897365897214897214365531642978642978531978531642")
+            .build()
+            .unwrap();
+        let solutions = solver.find_random_solutions(3, &mut rng).unwrap();
+        assert_eq!(solutions.len(), 2);
+        assert_ne!(solutions[0].to_string(), solutions[1].to_string());
+    }
+
+    #[test]
+    fn test_check_progress() {
+        let solver = SolverBuilder::default()
+            .with_givens_string("8...62..125.....7..197...5........9.....28..3.....36.54...1..6...74...3.5.2......")
+            .build()
+            .unwrap();
+        let solution = solver.find_first_solution().board().unwrap();
+        assert!(solver.check_progress(&solution).unwrap().is_on_track());
+
+        let cu = solver.cell_utility();
+        let wrong_value = (solution.cell(cu.cell(0, 1)).value() % 9) + 1;
+        let mut grid = Solver::default().board().clone();
+        assert!(grid.keep_mask(cu.cell(0, 1), ValueMask::from_value(wrong_value)));
+        assert!(grid.keep_mask(cu.cell(0, 2), ValueMask::from_value(solution.cell(cu.cell(0, 2)).value())));
+        assert!(grid.keep_mask(cu.cell(0, 3), !solution.cell(cu.cell(0, 3)).unsolved()));
+        let report = solver.check_progress(&grid).unwrap();
+        assert_eq!(report.wrong_values, vec![cu.candidate(cu.cell(0, 1), wrong_value)]);
+        assert_eq!(report.wrong_pencilmarks, vec![(cu.cell(0, 3), solution.cell(cu.cell(0, 3)).unsolved())]);
+        assert!(!report.is_on_track());
+
+        assert!(solver.check_progress(SolverBuilder::new(6).build().unwrap().board()).is_err());
+    }
+
+    #[test]
+    fn test_uniqueness_assumption() {
+        let givens = "8...62..125.....7..197...5........9.....28..3.....36.54...1..6...74...3.5.2......";
+        let solver = SolverBuilder::default().with_givens_string(givens).build().unwrap();
+        let unique_solver =
+            SolverBuilder::default().with_givens_string(givens).with_uniqueness_assumption(true).build().unwrap();
+        assert!(unique_solver.assumes_uniqueness());
+        assert!(unique_solver.find_true_candidates().board() == solver.find_true_candidates().board());
+        assert_eq!(unique_solver.find_solution_count(0, None, None), SolutionCountResult::ExactCount(1));
+
+        // Only true candidates skip solutions with a deadly pattern, so counting a puzzle with many solutions is exact.
+        let givens = ".............23.4.....452....1.3.....3...4...6..7....8..6.....9.5....62.7.9...1..";
+        let mut solver = SolverBuilder::default().with_givens_string(givens).build().unwrap();
+        solver.set_uniqueness_assumption(true);
+        assert_eq!(solver.find_solution_count(0, None, None), SolutionCountResult::ExactCount(2357));
+        assert!(solver.find_first_solution().is_solved());
+    }
+
     #[test]
     fn test_true_candidates() {
         let solver = Solver::default();
@@ -604,6 +1573,61 @@ mod test {
         assert!(board.cell(cu.cell(7, 8)) == ValueMask::from_values(&[2, 3, 4, 6, 7]));
     }
 
+    #[test]
+    fn test_true_candidates_with_logical_comparison() {
+        // Logic can't find the phistomefel ring, so it leaves candidates which aren't true
+        let givens = "....................23456....4...2....5...3....6...4....74365....................";
+        let solver =
+            SolverBuilder::default().with_givens_string(givens).with_option_strings(&["logical"]).build().unwrap();
+        let result = solver.find_true_candidates_with_logical_comparison(None);
+        let comparison = result.comparison().unwrap();
+        let cu = solver.cell_utility();
+        let class = |value| comparison.classes[cu.cell(0, 0).candidate(value).index()];
+        assert_eq!(class(1), CandidateClass::LogicalOnly);
+        assert_eq!(class(2), CandidateClass::Removed);
+        assert_eq!(class(3), CandidateClass::True(None));
+        assert_eq!(comparison.true_cells[cu.cell(0, 0).index()], ValueMask::from_values(&[3, 4, 5, 6, 7]));
+
+        // Without the logical option nothing is only logical, and colored options count the solutions
+        let givens = ".............23.4.....452....1.3.....3...4...6..7....8..6.....9.5....62.7.9...1..";
+        let solver = SolverBuilder::default()
+            .with_givens_string(givens)
+            .with_option_strings(&["colored", "maxcolored=1"])
+            .build()
+            .unwrap();
+        let result = solver.find_true_candidates_with_logical_comparison(None);
+        let comparison = result.comparison().unwrap();
+        assert!(!comparison.classes.contains(&CandidateClass::LogicalOnly));
+        assert!(comparison.classes.iter().any(|class| matches!(class, CandidateClass::True(Some(_)))));
+        assert_eq!(comparison.true_cells, comparison.logical_cells);
+    }
+
+    #[test]
+    fn test_true_candidates_for_cells() {
+        // Only the top left box of the phistomefel ring is checked
+        let solver = SolverBuilder::default()
+            .with_givens_string("....................23456....4...2....5...3....6...4....74365....................")
+            .build()
+            .unwrap();
+        let cu = solver.cell_utility();
+        let cells = [cu.cell(0, 0), cu.cell(0, 1), cu.cell(1, 0), cu.cell(1, 1)];
+        let result = solver.find_true_candidates_for_cells(&cells);
+        assert!(result.is_solved());
+        let board = result.board().unwrap();
+        for cell in cells {
+            assert!(board.cell(cell) == ValueMask::from_values(&[3, 4, 5, 6, 7]));
+        }
+        assert!(board.cell(cu.cell(7, 7)).count() > 5);
+
+        // A 1 in the corner is not in any solution, even though it is not checked
+        let solver = SolverBuilder::default()
+            .with_givens_string("1...................23456....4...2....5...3....6...4....74365....................")
+            .build()
+            .unwrap();
+        assert!(solver.find_true_candidates_for_cells(&[cu.cell(0, 0)]).is_none());
+        assert!(solver.find_true_candidates_for_cells(&[]).is_none());
+    }
+
     #[test]
     fn test_true_candidates_with_count() {
         let solver = SolverBuilder::default()
@@ -630,6 +1654,13 @@ mod test {
         assert_eq!(candidates[candidate3r1c6.index()], 2);
         assert_eq!(candidates[candidate5r2c6.index()], 2);
         assert_eq!(candidates[candidate4r8c6.index()], 2);
+
+        let results = result.candidate_count_results().unwrap();
+        assert!(!results[candidate3r1c2.index()].is_exact());
+        assert_eq!(results[candidate5r1c2.index()], CandidateCount::Exact(2));
+        assert!(results[cu.cell(0, 0).candidate(1).index()].to_string().ends_with('+'));
+        assert_eq!(CandidateCount::AtLeast(8).to_string(), "8+");
+        assert_eq!(results[cu.cell(0, 0).candidate(2).index()], CandidateCount::Exact(0));
     }
 
     #[test]
@@ -683,6 +1714,17 @@ mod test {
                 == "873562941254891376619734852326157498945628713781943625438219567167485239592376184"));
     }
 
+    #[test]
+    fn test_set_solved() {
+        let mut solver = Solver::default();
+        let cu = solver.cell_utility();
+
+        assert!(solver.set_solved(cu.cell(0, 0), 5));
+        assert!(solver.board().cell(cu.cell(0, 0)).is_solved());
+        assert!(!solver.board().cell(cu.cell(0, 8)).has(5));
+        assert!(!solver.set_solved(cu.cell(8, 0), 5));
+    }
+
     #[test]
     fn test_single_logical_step() {
         let mut solver = SolverBuilder::default()
@@ -713,4 +1755,17 @@ mod test {
             "873562941254891376619734852326157498945628713781943625438219567167485239592376184"
         );
     }
+
+    #[test]
+    fn test_clone_shares_steps_and_custom_info() {
+        let solver = SolverBuilder::default().with_custom_info("key", "value").build().unwrap();
+        let mut clone = solver.clone();
+        assert!(Arc::ptr_eq(&solver.logical_solve_steps, &clone.logical_solve_steps));
+        assert!(Arc::ptr_eq(&solver.brute_force_steps, &clone.brute_force_steps));
+        assert!(Arc::ptr_eq(&solver.custom_info, &clone.custom_info));
+
+        clone.set_custom_info("key".to_owned(), "other".to_owned());
+        assert_eq!(solver.get_custom_info("key"), Some("value"));
+        assert_eq!(clone.get_custom_info("key"), Some("other"));
+    }
 }
@@ -0,0 +1,271 @@
+//! Contains [`solve_batch`] for solving many independent puzzles at once.
+
+use crate::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::{
+    sync::{
+        mpsc::{channel, Receiver},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// What [`solve_batch`] should do with each puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchWorkload {
+    /// Find a random solution with [`Solver::find_random_solution`].
+    Solve,
+    /// Count the solutions with [`Solver::find_solution_count`], stopping at the given maximum (0 for no limit).
+    Count(usize),
+}
+
+/// Options for [`solve_batch`].
+#[derive(Debug, Clone)]
+pub struct BatchOptions {
+    workload: BatchWorkload,
+    threads: Option<usize>,
+}
+
+impl BatchOptions {
+    /// Creates options which run the given workload on one thread per CPU.
+    pub fn new(workload: BatchWorkload) -> Self {
+        Self { workload, threads: None }
+    }
+
+    /// Sets the number of threads in the pool. 0 uses one thread per CPU.
+    #[must_use]
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = (threads > 0).then_some(threads);
+        self
+    }
+
+    pub fn workload(&self) -> BatchWorkload {
+        self.workload
+    }
+}
+
+/// The outcome of a single puzzle of a batch.
+#[derive(Clone)]
+pub enum BatchOutcome {
+    Solution(SingleSolutionResult),
+    Count(SolutionCountResult),
+    /// The solver could not be built.
+    Invalid(String),
+    /// The puzzle was cancelled before it was started.
+    Cancelled,
+}
+
+/// A finished puzzle of a batch.
+#[derive(Clone)]
+pub struct BatchResult {
+    /// The index of the puzzle in the slice given to [`solve_batch`] or [`solve_batch_with_template`].
+    pub index: usize,
+    pub outcome: BatchOutcome,
+    /// How long the puzzle took, including building the solver.
+    pub elapsed: Duration,
+}
+
+/// A batch of puzzles being solved in the background.
+///
+/// Iterating a [`Batch`] returns each [`BatchResult`] as soon as it is finished, which is not
+/// necessarily the order the puzzles were given in. The iterator ends once every puzzle
+/// has been reported, including cancelled ones.
+///
+/// Dropping a [`Batch`] cancels the puzzles which haven't finished.
+pub struct Batch {
+    results: Receiver<BatchResult>,
+    cancellations: Vec<Cancellation>,
+    // Keeps the threads alive until the batch is dropped.
+    _pool: ThreadPool,
+}
+
+impl Batch {
+    /// The number of puzzles in the batch.
+    pub fn len(&self) -> usize {
+        self.cancellations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cancellations.is_empty()
+    }
+
+    /// Cancels a single puzzle.
+    ///
+    /// A count which is already running stops early with an error result. A solve which is
+    /// already running can't be interrupted and finishes normally.
+    pub fn cancel(&self, index: usize) {
+        self.cancellations[index].cancel();
+    }
+
+    /// Cancels every puzzle which hasn't finished yet.
+    pub fn cancel_all(&self) {
+        self.cancellations.iter().for_each(Cancellation::cancel);
+    }
+}
+
+impl Drop for Batch {
+    fn drop(&mut self) {
+        self.cancel_all();
+    }
+}
+
+impl Iterator for Batch {
+    type Item = BatchResult;
+
+    fn next(&mut self) -> Option<BatchResult> {
+        self.results.recv().ok()
+    }
+}
+
+/// Solves many independent puzzles at once on a rayon thread pool.
+///
+/// Each builder is built and solved on one of the pool's threads. The results are streamed
+/// through the returned [`Batch`], which can also cancel individual puzzles.
+///
+/// Returns an error if the thread pool could not be created.
+///
+/// # Example
+/// ```
+/// # use sudoku_solver_lib::prelude::*;
+/// let builders = vec![
+///     SolverBuilder::default().with_givens_string(".............23.4.....452....1.3.....3...4...6..7....8..6.....9.5....62.7.9...1.."),
+///     SolverBuilder::default().with_givens_string("11..............................................................................."),
+/// ];
+///
+/// let mut results: Vec<_> = solve_batch(&builders, BatchOptions::new(BatchWorkload::Count(10))).unwrap().collect();
+/// results.sort_by_key(|result| result.index);
+/// assert!(matches!(results[0].outcome, BatchOutcome::Count(SolutionCountResult::AtLeastCount(10))));
+/// assert!(matches!(results[1].outcome, BatchOutcome::Invalid(_)));
+/// ```
+pub fn solve_batch(builders: &[SolverBuilder], options: BatchOptions) -> Result<Batch, String> {
+    let builders = builders.to_vec();
+    spawn_batch(builders.len(), options, move |index| builders[index].clone().build())
+}
+
+/// Solves many independent puzzles which share the rules of a [`SolverTemplate`] at once on a
+/// rayon thread pool.
+///
+/// This is the same as [`solve_batch`], except that the rules are initialized only once and
+/// each puzzle is given as a givens string. A givens string which can't be applied is reported
+/// as [`BatchOutcome::Invalid`].
+///
+/// # Example
+/// ```
+/// # use sudoku_solver_lib::prelude::*;
+/// let template = SolverBuilder::default().build_template().unwrap();
+/// let givens = [
+///     ".............23.4.....452....1.3.....3...4...6..7....8..6.....9.5....62.7.9...1..",
+///     "11...............................................................................",
+/// ];
+///
+/// let mut results: Vec<_> =
+///     solve_batch_with_template(&template, &givens, BatchOptions::new(BatchWorkload::Count(10))).unwrap().collect();
+/// results.sort_by_key(|result| result.index);
+/// assert!(matches!(results[0].outcome, BatchOutcome::Count(SolutionCountResult::AtLeastCount(10))));
+/// assert!(matches!(results[1].outcome, BatchOutcome::Invalid(_)));
+/// ```
+pub fn solve_batch_with_template(
+    template: &SolverTemplate,
+    givens: &[impl AsRef<str>],
+    options: BatchOptions,
+) -> Result<Batch, String> {
+    let template = template.clone();
+    let givens = givens.iter().map(|givens| givens.as_ref().to_owned()).collect::<Vec<_>>();
+    spawn_batch(givens.len(), options, move |index| template.solver_with_givens_string(&givens[index]))
+}
+
+/// Runs the workload on the solver created for each index on its own task of a new pool.
+fn spawn_batch(
+    count: usize,
+    options: BatchOptions,
+    create_solver: impl Fn(usize) -> Result<Solver, String> + Send + Sync + 'static,
+) -> Result<Batch, String> {
+    let mut pool_builder = ThreadPoolBuilder::new().thread_name(|index| format!("solve-batch-{index}"));
+    if let Some(threads) = options.threads {
+        pool_builder = pool_builder.num_threads(threads);
+    }
+    let pool = pool_builder.build().map_err(|error| format!("Failed to create thread pool: {error}"))?;
+
+    let create_solver = Arc::new(create_solver);
+    let (sender, results) = channel();
+    let cancellations = vec![Cancellation::default(); count];
+    for (index, cancellation) in cancellations.iter().enumerate() {
+        let create_solver = create_solver.clone();
+        let cancellation = cancellation.clone();
+        let sender = sender.clone();
+        let workload = options.workload;
+        pool.spawn(move || {
+            let start_time = Instant::now();
+            let outcome = run_workload(|| create_solver(index), workload, cancellation);
+            // The receiver is gone if the batch was dropped, in which case nobody wants the result.
+            let _ = sender.send(BatchResult { index, outcome, elapsed: start_time.elapsed() });
+        });
+    }
+
+    Ok(Batch { results, cancellations, _pool: pool })
+}
+
+fn run_workload(
+    create_solver: impl FnOnce() -> Result<Solver, String>,
+    workload: BatchWorkload,
+    cancellation: Cancellation,
+) -> BatchOutcome {
+    if cancellation.check() {
+        return BatchOutcome::Cancelled;
+    }
+
+    let solver = match create_solver() {
+        Ok(solver) => solver,
+        Err(error) => return BatchOutcome::Invalid(error),
+    };
+
+    match workload {
+        BatchWorkload::Solve => BatchOutcome::Solution(solver.find_random_solution()),
+        BatchWorkload::Count(maximum_count) => {
+            BatchOutcome::Count(solver.find_solution_count(maximum_count, None, cancellation))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use itertools::Itertools;
+
+    #[test]
+    fn test_solve_batch() {
+        let givens = [
+            ".............23.4.....452....1.3.....3...4...6..7....8..6.....9.5....62.7.9...1..",
+            "5.6....29.9....13..4...376.........232.5......5..186.32..64.38..1.37529....821.7.",
+        ];
+        let builders = givens.iter().map(|givens| SolverBuilder::default().with_givens_string(givens)).collect_vec();
+
+        let batch = solve_batch(&builders, BatchOptions::new(BatchWorkload::Count(0)).with_threads(2)).unwrap();
+        assert_eq!(batch.len(), 2);
+        let mut results = batch.collect_vec();
+        results.sort_by_key(|result| result.index);
+        assert!(matches!(results[0].outcome, BatchOutcome::Count(SolutionCountResult::ExactCount(2357))));
+        assert!(matches!(results[1].outcome, BatchOutcome::Count(SolutionCountResult::ExactCount(1))));
+
+        let results = solve_batch(&builders[1..], BatchOptions::new(BatchWorkload::Solve)).unwrap().collect_vec();
+        assert_eq!(results.len(), 1);
+        assert!(matches!(&results[0].outcome, BatchOutcome::Solution(result) if result.is_solved()));
+    }
+
+    #[test]
+    fn test_cancel_batch() {
+        // Counting every solution of an empty grid won't finish before it is cancelled.
+        let builders = vec![SolverBuilder::default(); 3];
+        let batch = solve_batch(&builders, BatchOptions::new(BatchWorkload::Count(0)).with_threads(1)).unwrap();
+        batch.cancel_all();
+
+        let results = batch.collect_vec();
+        assert_eq!(results.len(), 3);
+        for result in results {
+            assert!(matches!(
+                result.outcome,
+                BatchOutcome::Cancelled | BatchOutcome::Count(SolutionCountResult::Error(_))
+            ));
+        }
+    }
+}
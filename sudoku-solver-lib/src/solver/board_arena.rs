@@ -0,0 +1,126 @@
+//! Contains the [`BoardArena`] struct for depth first searches which reuse one board per depth.
+
+use crate::prelude::*;
+
+/// A board in the arena, along with the values of its branch cell which are still to be explored.
+struct ArenaFrame {
+    board: Board,
+    cell: CellIndex,
+    remaining: ValueMask,
+}
+
+/// The board stack of a depth first search, stored as one board per search depth.
+///
+/// Instead of copying the board for every value of the branch cell up front, only the
+/// values still to be explored are remembered. Each branch is copied into the board of the
+/// next depth when it is explored, so once the deepest depth has been reached the search
+/// no longer allocates.
+pub(crate) struct BoardArena {
+    frames: Vec<ArenaFrame>,
+    depth: usize,
+    /// True when the board at the current depth has not been returned by [`Self::next_board`] yet.
+    pending: bool,
+}
+
+impl BoardArena {
+    pub(crate) fn new(board: &Board) -> Self {
+        let frame =
+            ArenaFrame { board: board.clone(), cell: CellIndex::new(0, board.size()), remaining: ValueMask::new() };
+        // Every depth solves at least one more cell, so the search can't go deeper than the number of cells.
+        let mut frames = Vec::with_capacity(board.num_cells() + 1);
+        frames.push(frame);
+        Self { frames, depth: 0, pending: true }
+    }
+
+    /// Returns the next board to explore, or `None` once the search is finished.
+    ///
+    /// The board is owned by the arena and is reused once the next board is requested.
+    pub(crate) fn next_board(&mut self) -> Option<&mut Board> {
+        if !self.pending && !self.advance() {
+            return None;
+        }
+        self.pending = false;
+        Some(&mut self.frames[self.depth].board)
+    }
+
+    /// Branches on the values of a cell of the board last returned by [`Self::next_board`].
+    pub(crate) fn branch(&mut self, cell: CellIndex) {
+        let frame = &mut self.frames[self.depth];
+        frame.cell = cell;
+        frame.remaining = frame.board.cell(cell);
+    }
+
+    /// The number of boards the arena holds, which is one more than the deepest depth reached.
+    pub(crate) fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// The number of cells branched on to reach the board last returned by [`Self::next_board`].
+    pub(crate) fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Forgets the unexplored branches of the board at the given depth and of every board below it.
+    pub(crate) fn skip_subtree(&mut self, depth: usize) {
+        for frame in self.frames.iter_mut().take(self.depth + 1).skip(depth) {
+            frame.remaining = ValueMask::new();
+        }
+    }
+
+    /// Moves to the next unexplored branch, searching upwards from the current depth.
+    fn advance(&mut self) -> bool {
+        loop {
+            let frame = &mut self.frames[self.depth];
+            if frame.remaining.is_empty() {
+                if self.depth == 0 {
+                    return false;
+                }
+                self.depth -= 1;
+                continue;
+            }
+
+            let cell = frame.cell;
+            let value = frame.remaining.max();
+            frame.remaining = frame.remaining.without(value);
+
+            let depth = self.depth + 1;
+            if depth == self.frames.len() {
+                let board = self.frames[self.depth].board.clone();
+                self.frames.push(ArenaFrame { board, cell, remaining: ValueMask::new() });
+            } else {
+                let (parents, children) = self.frames.split_at_mut(depth);
+                children[0].board.clone_from(&parents[self.depth].board);
+                children[0].remaining = ValueMask::new();
+            }
+
+            if self.frames[depth].board.set_solved(cell, value) {
+                self.depth = depth;
+                self.pending = true;
+                return true;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_branches() {
+        let board = Board::default();
+        let cu = board.cell_utility();
+        let mut arena = BoardArena::new(&board);
+
+        assert_eq!(arena.next_board().unwrap().cell(cu.cell(0, 0)).count(), 9);
+        arena.branch(cu.cell(0, 0));
+
+        // Values are explored from highest to lowest, matching the order of the boxed stack.
+        for value in (1..=9).rev() {
+            let board = arena.next_board().unwrap();
+            assert_eq!(board.cell(cu.cell(0, 0)), ValueMask::from_value(value).solved());
+            assert!(!board.cell(cu.cell(0, 1)).has(value));
+        }
+        assert!(arena.next_board().is_none());
+    }
+}
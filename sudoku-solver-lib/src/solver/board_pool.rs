@@ -0,0 +1,37 @@
+//! Contains the [`BoardPool`] struct for reusing boards during brute force searches.
+
+use crate::prelude::*;
+
+/// Keeps boards which are no longer needed so that their cell storage can be reused.
+///
+/// Brute force searches copy the board for every branch they explore. Copying into a
+/// recycled board with [`Clone::clone_from`] avoids allocating a new board each time.
+#[derive(Default)]
+pub(crate) struct BoardPool {
+    // The boards stay boxed because the searches hold them as boxes, so reusing one saves both allocations.
+    #[allow(clippy::vec_box)]
+    boards: Vec<Box<Board>>,
+}
+
+impl BoardPool {
+    /// Returns a copy of the board, reusing a recycled board if there is one.
+    pub(crate) fn copy_of(&mut self, board: &Board) -> Box<Board> {
+        match self.boards.pop() {
+            Some(mut copy) => {
+                copy.as_mut().clone_from(board);
+                copy
+            }
+            None => Box::new(board.clone()),
+        }
+    }
+
+    /// The number of boards in the pool.
+    pub(crate) fn len(&self) -> usize {
+        self.boards.len()
+    }
+
+    /// Returns a board to the pool once it is no longer needed.
+    pub(crate) fn recycle(&mut self, board: Box<Board>) {
+        self.boards.push(board);
+    }
+}
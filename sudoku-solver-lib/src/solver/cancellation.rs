@@ -1,6 +1,6 @@
 //! Cancelling various solver operations requires a [`Cancellation`].
 
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 
 /// A Cancellation embodies a check for whether or not to abort a solve process
@@ -12,9 +12,36 @@ use std::sync::Arc;
 #[derive(Clone)]
 pub struct Cancellation {
     token: Arc<AtomicBool>,
+    expiry: Option<Arc<Expiry>>,
+}
+
+/// The function of [`Cancellation::with_expiry`], with what is needed to call it rarely.
+struct Expiry {
+    expired: Box<dyn Fn() -> bool + Send + Sync>,
+    checks: AtomicUsize,
+    has_expired: AtomicBool,
+}
+
+impl Expiry {
+    fn check(&self) -> bool {
+        if self.has_expired.load(Ordering::Relaxed) {
+            return true;
+        }
+        if !self.checks.fetch_add(1, Ordering::Relaxed).is_multiple_of(Cancellation::EXPIRY_CHECK_INTERVAL) {
+            return false;
+        }
+        let has_expired = (self.expired)();
+        if has_expired {
+            self.has_expired.store(true, Ordering::Relaxed);
+        }
+        has_expired
+    }
 }
 
 impl Cancellation {
+    /// How many checks of an expiring cancellation there are for each call of its function.
+    pub const EXPIRY_CHECK_INTERVAL: usize = 64;
+
     /// Create a new [`Cancellation`]
     ///
     /// ```
@@ -26,12 +53,45 @@ impl Cancellation {
     /// assert_eq!(cancellation.check(), true);
     /// ```
     pub fn new() -> Self {
-        Self { token: Arc::new(AtomicBool::from(false)) }
+        Self { token: Arc::new(AtomicBool::from(false)), expiry: None }
+    }
+
+    /// Also treat the operation as cancelled once the function returns true, such as when a deadline has passed.
+    ///
+    /// The returned cancellation shares its token with this one, so cancelling either cancels both,
+    /// but only the returned cancellation expires.
+    ///
+    /// Reading a clock costs far more than the rest of a check, and solvers check very often, so the
+    /// function is only called on the first of every [`Cancellation::EXPIRY_CHECK_INTERVAL`] checks.
+    /// Once it returns true, the cancellation stays expired without calling it again, even if reset.
+    ///
+    /// ```
+    /// # use sudoku_solver_lib::solver::cancellation::Cancellation;
+    /// # use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    /// # use std::sync::Arc;
+    /// let expired = Arc::new(AtomicBool::new(false));
+    /// let cancellation = Cancellation::default();
+    /// let expiring = cancellation.with_expiry({
+    ///     let expired = expired.clone();
+    ///     move || expired.load(Ordering::SeqCst)
+    /// });
+    /// assert_eq!(expiring.check(), false);
+    /// expired.store(true, Ordering::SeqCst);
+    /// assert!((0..Cancellation::EXPIRY_CHECK_INTERVAL).any(|_| expiring.check()));
+    /// expired.store(false, Ordering::SeqCst);
+    /// assert_eq!(expiring.check(), true);
+    /// assert_eq!(cancellation.check(), false);
+    /// ```
+    #[must_use]
+    pub fn with_expiry(&self, expired: impl Fn() -> bool + Send + Sync + 'static) -> Self {
+        let expiry =
+            Expiry { expired: Box::new(expired), checks: AtomicUsize::new(0), has_expired: AtomicBool::new(false) };
+        Self { token: self.token.clone(), expiry: Some(Arc::new(expiry)) }
     }
 
-    /// Check if the cancellation has been sent
+    /// Check if the cancellation has been sent or has expired
     pub fn check(&self) -> bool {
-        self.token.load(Ordering::SeqCst)
+        self.token.load(Ordering::SeqCst) || self.expiry.as_ref().is_some_and(|expiry| expiry.check())
     }
 
     /// Cancel the operation
@@ -0,0 +1,85 @@
+//! Contains [`DeadlyPatterns`] for pruning brute force branches which can't have a unique solution.
+
+use itertools::Itertools;
+
+use crate::prelude::*;
+
+/// The rectangles of a board which form a deadly pattern once their cells are down to the same two values.
+///
+/// A rectangle of cells in two rows and two columns whose values are `a b / b a` can swap to `b a / a b`
+/// and still be a solution, as long as no house or constraint tells the two apart. A puzzle with a
+/// unique solution can't have a solution like that, unless one of the cells is a given.
+///
+/// See [`SolverBuilder::with_uniqueness_assumption`].
+#[derive(Clone, Debug)]
+pub(crate) struct DeadlyPatterns {
+    rectangles: Vec<[CellIndex; 4]>,
+}
+
+impl DeadlyPatterns {
+    /// Finds the rectangles of the board which can swap their values.
+    ///
+    /// Every house must have none of the cells, two cells on the same row or column, or all four of them.
+    /// A constraint could tell the values apart, so no cell can be a relevant cell of a constraint,
+    /// and constraints which are relevant to every cell rule out every rectangle.
+    pub(crate) fn new(board: &Board) -> Self {
+        let size = board.size();
+        let cu = board.cell_utility();
+        let constraint_cells = board.constraints().iter().map(|constraint| constraint.relevant_cells()).collect_vec();
+        if constraint_cells.iter().any(|cells| cells.is_empty()) || board.region_membership().is_some() {
+            return Self { rectangles: Vec::new() };
+        }
+
+        let mut rectangles = Vec::new();
+        for (row0, row1) in (0..size).tuple_combinations() {
+            for (col0, col1) in (0..size).tuple_combinations() {
+                let cells = [cu.cell(row0, col0), cu.cell(row0, col1), cu.cell(row1, col0), cu.cell(row1, col1)];
+                let is_house_safe = board.houses().iter().all(|house| {
+                    let in_house = cells.map(|cell| house.cells().contains(&cell));
+                    match in_house.iter().filter(|&&is_in| is_in).count() {
+                        0 | 4 => true,
+                        // The two cells must share a row or column, so they have different values.
+                        2 => in_house[0] != in_house[3] && in_house[1] != in_house[2],
+                        _ => false,
+                    }
+                });
+                let is_constraint_safe = constraint_cells.iter().flatten().all(|cell| !cells.contains(cell));
+                if is_house_safe && is_constraint_safe {
+                    rectangles.push(cells);
+                }
+            }
+        }
+        Self { rectangles }
+    }
+
+    /// Whether the board has a rectangle whose cells are all down to the same two values,
+    /// none of which are solved on the given board.
+    pub(crate) fn has_deadly_pattern(&self, board: &Board, givens: &Board) -> bool {
+        self.rectangles.iter().any(|cells| {
+            let values = cells.iter().fold(ValueMask::new(), |values, &cell| values | board.cell(cell).unsolved());
+            values.count() == 2 && cells.iter().all(|&cell| !givens.cell(cell).is_solved())
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_deadly_patterns() {
+        let board = Board::default();
+        let patterns = DeadlyPatterns::new(&board);
+        // Rectangles with two rows of a band and columns in two stacks, the same turned around,
+        // and rectangles within a single box.
+        assert_eq!(patterns.rectangles.len(), 2 * 9 * 27 + 9 * 9);
+
+        let cu = board.cell_utility();
+        let mut pattern_board = board.clone();
+        for (cell, value) in [((0, 0), 1), ((0, 3), 2), ((1, 0), 2), ((1, 3), 1)] {
+            assert!(pattern_board.set_solved(cu.cell(cell.0, cell.1), value));
+        }
+        assert!(patterns.has_deadly_pattern(&pattern_board, &board));
+        assert!(!patterns.has_deadly_pattern(&pattern_board, &pattern_board));
+    }
+}
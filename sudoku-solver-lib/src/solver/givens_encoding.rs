@@ -0,0 +1,142 @@
+//! Contains [`GivensEncoding`] for reading and writing givens strings.
+
+use itertools::Itertools;
+
+/// How the values of a givens string are written, in row-major order with one entry per cell.
+///
+/// Both encodings write a grid of size 9 or less with one digit per cell and `.` for empty cells.
+/// They differ for larger grids:
+/// - [`GivensEncoding::Numeric`] writes every value with the same number of digits, such as `01` to `16`,
+///   with `00` for empty cells.
+/// - [`GivensEncoding::Alphanumeric`] writes one character per cell, using `A` for 10, `B` for 11 and so on,
+///   as many 16x16 datasets do with `1`-`9` and `A`-`G`. Empty cells are `.` or `0`.
+///
+/// # Example
+/// ```
+/// # use sudoku_solver_lib::prelude::*;
+/// let mut values = vec![0; 256];
+/// values[0] = 16;
+/// values[1] = 1;
+/// let text = GivensEncoding::Alphanumeric.encode(&values, 16);
+/// assert!(text.starts_with("G1.."));
+/// assert_eq!(GivensEncoding::detect(&text, 16), Some(GivensEncoding::Alphanumeric));
+/// assert_eq!(GivensEncoding::Alphanumeric.decode(&text, 16).unwrap(), values);
+///
+/// let text = GivensEncoding::Numeric.encode(&values, 16);
+/// assert!(text.starts_with("16010000"));
+/// assert_eq!(GivensEncoding::detect(&text, 16), Some(GivensEncoding::Numeric));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GivensEncoding {
+    /// Fixed width decimal numbers for grids larger than 9.
+    #[default]
+    Numeric,
+    /// One digit or letter per cell.
+    Alphanumeric,
+}
+
+impl GivensEncoding {
+    /// Finds the encoding of a givens string for a grid of the given size from its length.
+    ///
+    /// Grids of size 9 or less are always [`GivensEncoding::Numeric`], as both encodings are the same.
+    pub fn detect(text: &str, size: usize) -> Option<Self> {
+        let num_cells = size * size;
+        let length = text.chars().count();
+        if length == num_cells * Self::Numeric.width(size) {
+            Some(Self::Numeric)
+        } else if length == num_cells {
+            Some(Self::Alphanumeric)
+        } else {
+            None
+        }
+    }
+
+    /// Writes the values of every cell, with 0 for empty cells.
+    pub fn encode(self, values: &[usize], size: usize) -> String {
+        let width = self.width(size);
+        values
+            .iter()
+            .map(|&value| {
+                if width > 1 {
+                    format!("{value:0width$}")
+                } else if value == 0 {
+                    ".".to_owned()
+                } else {
+                    char::from_digit(value as u32, 36).unwrap_or('?').to_ascii_uppercase().to_string()
+                }
+            })
+            .join("")
+    }
+
+    /// Reads the values of every cell, with 0 for empty cells.
+    ///
+    /// Entries which aren't a value, such as `.`, are empty cells. Returns an error for values
+    /// larger than the size of the grid.
+    pub fn decode(self, text: &str, size: usize) -> Result<Vec<usize>, String> {
+        let width = self.width(size);
+        if text.chars().count() != size * size * width {
+            return Err("Invalid givens string length".to_owned());
+        }
+
+        let values: Vec<usize> = match self {
+            Self::Numeric if width > 1 => text
+                .chars()
+                .chunks(width)
+                .into_iter()
+                .map(|chunk| chunk.collect::<String>().parse::<usize>().unwrap_or(0))
+                .collect(),
+            Self::Numeric => text.chars().map(|c| c.to_digit(10).unwrap_or(0) as usize).collect(),
+            Self::Alphanumeric => text.chars().map(|c| c.to_digit(36).unwrap_or(0) as usize).collect(),
+        };
+        if let Some(value) = values.iter().find(|&&value| value > size) {
+            return Err(format!("Given value {value} is larger than the grid size {size}"));
+        }
+        Ok(values)
+    }
+
+    /// The number of characters of each cell.
+    fn width(self, size: usize) -> usize {
+        match self {
+            Self::Numeric if size > 9 => size.to_string().len(),
+            _ => 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_givens_encoding() {
+        let values = (0..81).map(|i| i % 10).collect::<Vec<_>>();
+        for encoding in [GivensEncoding::Numeric, GivensEncoding::Alphanumeric] {
+            let text = encoding.encode(&values, 9);
+            assert_eq!(text.len(), 81);
+            assert_eq!(GivensEncoding::detect(&text, 9), Some(GivensEncoding::Numeric));
+            assert_eq!(encoding.decode(&text, 9).unwrap(), values);
+        }
+
+        // Lower case letters and 0 for empty cells are accepted too
+        let values = GivensEncoding::Alphanumeric.decode(&("a0g".to_owned() + &".".repeat(253)), 16).unwrap();
+        assert_eq!(&values[..3], &[10, 0, 16]);
+
+        let values = (0..625).map(|i| i % 26).collect::<Vec<_>>();
+        let text = GivensEncoding::Alphanumeric.encode(&values, 25);
+        assert!(text.starts_with(".123456789ABCDEFGHIJKLMNOP."));
+        assert_eq!(GivensEncoding::Alphanumeric.decode(&text, 25).unwrap(), values);
+
+        assert_eq!(GivensEncoding::detect("123", 16), None);
+        assert!(GivensEncoding::Numeric.decode("123", 16).is_err());
+
+        // Values past the size of the grid are rejected
+        let text = "Z".to_owned() + &".".repeat(255);
+        assert_eq!(
+            GivensEncoding::Alphanumeric.decode(&text, 16).unwrap_err(),
+            "Given value 35 is larger than the grid size 16"
+        );
+        let text = "17".to_owned() + &"00".repeat(255);
+        assert!(GivensEncoding::Numeric.decode(&text, 16).is_err());
+        assert!(GivensEncoding::Numeric.decode(&("5".to_owned() + &".".repeat(15)), 4).is_err());
+    }
+}
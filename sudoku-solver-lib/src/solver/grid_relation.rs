@@ -0,0 +1,19 @@
+//! Contains the [`GridRelation`] enum for relating cells of different grids of a [`MultiGridSolver`].
+//!
+//! [`MultiGridSolver`]: crate::prelude::MultiGridSolver
+
+/// How the values of two cells in different grids of a [`MultiGridSolver`] relate to each other.
+///
+/// Relations link grids which don't overlap in the layout, such as twin puzzles published as a pair.
+/// Use [`MultiGridBuilder::with_cell_relation`] or [`MultiGridBuilder::with_grid_relation`] to add them.
+///
+/// [`MultiGridSolver`]: crate::prelude::MultiGridSolver
+/// [`MultiGridBuilder::with_cell_relation`]: crate::prelude::MultiGridBuilder::with_cell_relation
+/// [`MultiGridBuilder::with_grid_relation`]: crate::prelude::MultiGridBuilder::with_grid_relation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GridRelation {
+    /// The cells have the same value, as if they were the same cell of the layout.
+    Equal,
+    /// The cells have different values.
+    Different,
+}
@@ -0,0 +1,155 @@
+//! Contains the [`MultiGridBuilder`] struct for building a [`MultiGridSolver`].
+
+use crate::prelude::*;
+
+/// Builds a [`MultiGridSolver`] for puzzles made of overlapping grids, such as Samurai sudoku.
+///
+/// Each grid is placed at a row and column offset in a shared layout.
+/// Cells of different grids at the same position in the layout are the same cell.
+///
+/// # Example
+/// ```
+/// # use sudoku_solver_lib::prelude::*;
+/// // Two 9x9 grids sharing a single box, like a twodoku.
+/// let solver = MultiGridBuilder::new()
+///     .with_grid(SolverBuilder::default(), (0, 0))
+///     .with_grid(SolverBuilder::default(), (6, 6))
+///     .build()
+///     .unwrap();
+/// assert_eq!(solver.shared_cells().len(), 9);
+/// ```
+///
+/// Grids which don't overlap can still be linked with a [`GridRelation`].
+/// ```
+/// # use sudoku_solver_lib::prelude::*;
+/// // Twin grids whose corresponding cells all differ.
+/// let solver = MultiGridBuilder::new()
+///     .with_grid(SolverBuilder::default(), (0, 0))
+///     .with_grid(SolverBuilder::default(), (0, 10))
+///     .with_grid_relation(GridRelation::Different, 0, 1, None)
+///     .build()
+///     .unwrap();
+/// assert_eq!(solver.different_cells().len(), 81);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MultiGridBuilder {
+    grids: Vec<(SolverBuilder, (usize, usize))>,
+    cell_relations: Vec<(GridRelation, GridCell, GridCell)>,
+    grid_relations: Vec<(GridRelation, usize, usize, Option<Symmetry>)>,
+}
+
+impl MultiGridBuilder {
+    /// Creates a builder with no grids.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a builder for a Samurai sudoku from five 9x9 grids, in the order
+    /// top left, top right, center, bottom left and bottom right.
+    ///
+    /// The center grid shares each of its corner boxes with a corner box of one of the other grids.
+    pub fn samurai(grids: [SolverBuilder; 5]) -> Self {
+        let offsets = [(0, 0), (0, 12), (6, 6), (12, 0), (12, 12)];
+        grids.into_iter().zip(offsets).fold(Self::new(), |builder, (grid, offset)| builder.with_grid(grid, offset))
+    }
+
+    /// Adds a grid with its top left cell at the given row and column of the layout.
+    #[must_use]
+    pub fn with_grid(mut self, grid: SolverBuilder, offset: (usize, usize)) -> Self {
+        self.grids.push((grid, offset));
+        self
+    }
+
+    /// Relates a cell of one grid to a cell of another grid, whether or not the grids overlap.
+    ///
+    /// Grids are numbered from 0 in the order they were added.
+    #[must_use]
+    pub fn with_cell_relation(mut self, relation: GridRelation, cell_a: GridCell, cell_b: GridCell) -> Self {
+        self.cell_relations.push((relation, cell_a, cell_b));
+        self
+    }
+
+    /// Relates every cell of `grid_a` to the cell of `grid_b` which the symmetry maps it to,
+    /// or to the same cell of `grid_b` without a symmetry.
+    ///
+    /// For example, [`GridRelation::Equal`] with [`Symmetry::Rotational90`] makes `grid_b` a quarter turn of `grid_a`.
+    /// Both grids must be the same size.
+    #[must_use]
+    pub fn with_grid_relation(
+        mut self,
+        relation: GridRelation,
+        grid_a: usize,
+        grid_b: usize,
+        symmetry: Option<Symmetry>,
+    ) -> Self {
+        self.grid_relations.push((relation, grid_a, grid_b, symmetry));
+        self
+    }
+
+    /// Builds every grid and links their shared and related cells.
+    ///
+    /// Fails if any grid fails to build, a relation refers to a grid which doesn't exist or grids of different sizes,
+    /// or the givens of the grids contradict each other on the shared or related cells.
+    pub fn build(self) -> Result<MultiGridSolver, String> {
+        let mut solvers = Vec::with_capacity(self.grids.len());
+        let mut offsets = Vec::with_capacity(self.grids.len());
+        for (index, (grid, offset)) in self.grids.into_iter().enumerate() {
+            solvers.push(grid.build().map_err(|error| format!("Grid {}: {error}", index + 1))?);
+            offsets.push(offset);
+        }
+
+        let mut shared_cells = Vec::new();
+        for grid_b in 0..solvers.len() {
+            for grid_a in 0..grid_b {
+                let (size_a, size_b) = (solvers[grid_a].size(), solvers[grid_b].size());
+                let (offset_a, offset_b) = (offsets[grid_a], offsets[grid_b]);
+                let (cu_a, cu_b) = (CellUtility::new(size_a), CellUtility::new(size_b));
+                let rows = offset_a.0.max(offset_b.0)..(offset_a.0 + size_a).min(offset_b.0 + size_b);
+                for row in rows {
+                    let cols = offset_a.1.max(offset_b.1)..(offset_a.1 + size_a).min(offset_b.1 + size_b);
+                    for col in cols {
+                        let cell_a = cu_a.cell(row - offset_a.0, col - offset_a.1);
+                        let cell_b = cu_b.cell(row - offset_b.0, col - offset_b.1);
+                        shared_cells.push(((grid_a, cell_a), (grid_b, cell_b)));
+                    }
+                }
+            }
+        }
+
+        if shared_cells.iter().any(|((grid_a, _), (grid_b, _))| solvers[*grid_a].size() != solvers[*grid_b].size()) {
+            return Err("Overlapping grids must be the same size.".to_owned());
+        }
+
+        let mut cell_relations = self.cell_relations;
+        for (relation, grid_a, grid_b, symmetry) in self.grid_relations {
+            if grid_a >= solvers.len() || grid_b >= solvers.len() {
+                return Err(format!("Related grids {} and {} must both exist.", grid_a + 1, grid_b + 1));
+            }
+            if solvers[grid_a].size() != solvers[grid_b].size() {
+                return Err("Related grids must be the same size.".to_owned());
+            }
+            for cell in solvers[grid_a].cell_utility().all_cells() {
+                let partner = symmetry.map_or(cell, |symmetry| symmetry.partner(cell));
+                cell_relations.push((relation, (grid_a, cell), (grid_b, partner)));
+            }
+        }
+
+        let mut different_cells = Vec::new();
+        for (relation, (grid_a, cell_a), (grid_b, cell_b)) in cell_relations {
+            let in_grid = |grid: usize, cell: CellIndex| grid < solvers.len() && cell.size() == solvers[grid].size();
+            if !in_grid(grid_a, cell_a) || !in_grid(grid_b, cell_b) {
+                return Err(format!(
+                    "Related cells Grid {} {cell_a} and Grid {} {cell_b} must exist.",
+                    grid_a + 1,
+                    grid_b + 1
+                ));
+            }
+            match relation {
+                GridRelation::Equal => shared_cells.push(((grid_a, cell_a), (grid_b, cell_b))),
+                GridRelation::Different => different_cells.push(((grid_a, cell_a), (grid_b, cell_b))),
+            }
+        }
+
+        MultiGridSolver::new(solvers, offsets, shared_cells, different_cells)
+    }
+}
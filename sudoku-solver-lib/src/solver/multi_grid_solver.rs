@@ -0,0 +1,493 @@
+//! Contains the [`MultiGridSolver`] struct for solving puzzles made of overlapping grids.
+
+use crate::prelude::*;
+use itertools::Itertools;
+
+/// A cell of one of the grids of a [`MultiGridSolver`], as the index of the grid and the cell within that grid.
+pub type GridCell = (usize, CellIndex);
+
+/// Solves puzzles made of overlapping grids which share some of their cells, such as Samurai sudoku.
+///
+/// Each grid is an ordinary [`Solver`] with its own rules. Whenever the candidates of a shared cell change
+/// in one grid, the same candidates are removed from that cell in every other grid, so logic in one grid
+/// is carried over to the grids it overlaps.
+///
+/// Cells of different grids can also be related without overlapping, see [`GridRelation`].
+/// Cells which must be equal are treated just like shared cells, and once a cell which must differ
+/// from another cell has a single candidate, that value is removed from the other cell.
+///
+/// Use the [`MultiGridBuilder`] struct to create a [`MultiGridSolver`].
+#[derive(Clone)]
+pub struct MultiGridSolver {
+    solvers: Vec<Solver>,
+    offsets: Vec<(usize, usize)>,
+    shared_cells: Vec<(GridCell, GridCell)>,
+    different_cells: Vec<(GridCell, GridCell)>,
+}
+
+impl MultiGridSolver {
+    pub(crate) fn new(
+        solvers: Vec<Solver>,
+        offsets: Vec<(usize, usize)>,
+        shared_cells: Vec<(GridCell, GridCell)>,
+        different_cells: Vec<(GridCell, GridCell)>,
+    ) -> Result<Self, String> {
+        let mut multi_grid_solver = Self { solvers, offsets, shared_cells, different_cells };
+        let Self { solvers, shared_cells, different_cells, .. } = &mut multi_grid_solver;
+        let mut boards = solvers.iter_mut().map(|solver| &mut solver.board).collect_vec();
+        if propagate(shared_cells, different_cells, &mut boards, None).is_none() {
+            return Err("The givens of the grids contradict each other on a shared or related cell.".to_owned());
+        }
+        Ok(multi_grid_solver)
+    }
+
+    /// The solver of each grid.
+    pub fn grids(&self) -> &[Solver] {
+        &self.solvers
+    }
+
+    /// The row and column of the top left cell of each grid in the layout.
+    pub fn offsets(&self) -> &[(usize, usize)] {
+        &self.offsets
+    }
+
+    /// Each pair of grid cells which must have the same value, either because they are the same cell
+    /// of the layout or because of a [`GridRelation::Equal`].
+    pub fn shared_cells(&self) -> &[(GridCell, GridCell)] {
+        &self.shared_cells
+    }
+
+    /// Each pair of grid cells which must have different values because of a [`GridRelation::Different`].
+    pub fn different_cells(&self) -> &[(GridCell, GridCell)] {
+        &self.different_cells
+    }
+
+    /// Whether every grid is solved.
+    pub fn is_solved(&self) -> bool {
+        self.solvers.iter().all(|solver| solver.board.is_solved())
+    }
+
+    /// Run a full logical solve of every grid. This mutates the boards of the grids.
+    ///
+    /// Each logical step is tried on every grid before moving on to the next logical step,
+    /// so simple logic in any grid is found before complex logic.
+    /// The description of each step is prefixed with the grid it was found in, counting from 1.
+    pub fn run_logical_solve(&mut self) -> LogicalSolveResult {
+        let mut desc_list = LogicalStepDescList::new();
+        let mut changed = false;
+        loop {
+            if self.is_solved() {
+                desc_list.push("Solved!".into());
+                return LogicalSolveResult::Solved(desc_list);
+            }
+
+            let (shared_cells, different_cells) = (&self.shared_cells, &self.different_cells);
+            let mut boards = self.solvers.iter_mut().map(|solver| &mut solver.board).collect_vec();
+            match propagate(shared_cells, different_cells, &mut boards, Some(&mut desc_list)) {
+                None => return LogicalSolveResult::Invalid(desc_list),
+                Some(true) => {
+                    changed = true;
+                    continue;
+                }
+                Some(false) => {}
+            }
+
+            let step_result = self.run_single_logical_step();
+            if step_result.is_none() {
+                break;
+            }
+
+            changed = true;
+            if let Some(desc) = step_result.description() {
+                desc_list.push(desc.clone());
+            }
+            if step_result.is_invalid() {
+                return LogicalSolveResult::Invalid(desc_list);
+            }
+        }
+
+        if changed {
+            LogicalSolveResult::Changed(desc_list)
+        } else {
+            LogicalSolveResult::None
+        }
+    }
+
+    fn run_single_logical_step(&mut self) -> LogicalStepResult {
+        let num_steps = self.solvers.iter().map(|solver| solver.logical_solve_steps.len()).max().unwrap_or(0);
+        for step_index in 0..num_steps {
+            for (grid, solver) in self.solvers.iter_mut().enumerate() {
+                let Some(step) = solver.logical_solve_steps.get(step_index) else {
+                    continue;
+                };
+
+                let step_result = step.run(&mut solver.board, true, &Cancellation::default());
+                if !step_result.is_none() {
+                    let step_result = if step.has_own_prefix() {
+                        step_result
+                    } else {
+                        step_result.with_prefix(format!("{}: ", step.name()).as_str())
+                    };
+                    return step_result.with_prefix(format!("Grid {}: ", grid + 1).as_str());
+                }
+            }
+        }
+
+        LogicalStepResult::None
+    }
+
+    fn run_brute_force_logic(&self, boards: &mut [Board], cancellation: &Cancellation) -> bool {
+        loop {
+            for (solver, board) in self.solvers.iter().zip(boards.iter_mut()) {
+                if !solver.run_brute_force_logic(board, cancellation) {
+                    return false;
+                }
+            }
+
+            let mut board_refs = boards.iter_mut().collect_vec();
+            match self.propagate(&mut board_refs, None) {
+                None => return false,
+                Some(false) => return true,
+                Some(true) => {}
+            }
+        }
+    }
+
+    /// Use brute-force methods to find a solution to every grid.
+    ///
+    /// Returns the solved board of each grid, or `None` if there is no solution.
+    /// The solution is not guaranteed to be the only solution.
+    pub fn find_first_solution(&self) -> Option<Vec<Board>> {
+        let mut solution = None;
+        self.search(1, &Cancellation::default(), |boards| solution = Some(boards.to_vec()));
+        solution
+    }
+
+    /// Count the solutions of the puzzle via brute force, stopping at the given maximum (0 for no limit).
+    pub fn find_solution_count(
+        &self,
+        maximum_count: usize,
+        cancellation: impl Into<Cancellation>,
+    ) -> SolutionCountResult {
+        self.search(maximum_count, &cancellation.into(), |_| {})
+    }
+
+    fn search(
+        &self,
+        maximum_count: usize,
+        cancellation: &Cancellation,
+        mut receive: impl FnMut(&[Board]),
+    ) -> SolutionCountResult {
+        let mut board_stack = vec![self.solvers.iter().map(|solver| solver.board.clone()).collect_vec()];
+        let mut solution_count = 0;
+        while let Some(mut boards) = board_stack.pop() {
+            if cancellation.check() {
+                return SolutionCountResult::Error("cancelled".into());
+            }
+            if !self.run_brute_force_logic(&mut boards, cancellation) {
+                continue;
+            }
+
+            if boards.iter().all(Board::is_solved) {
+                solution_count += 1;
+                receive(&boards);
+                if solution_count == maximum_count {
+                    return SolutionCountResult::AtLeastCount(solution_count);
+                }
+                continue;
+            }
+
+            // Branch on the cell with the fewest candidates of any grid.
+            let best_cell = self
+                .solvers
+                .iter()
+                .zip(boards.iter())
+                .enumerate()
+                .filter_map(|(grid, (solver, board))| {
+                    solver.find_best_brute_force_cell(board).map(|cell| (board.cell(cell).count(), grid, cell))
+                })
+                .min();
+            let Some((_, grid, cell)) = best_cell else {
+                return SolutionCountResult::Error("Internal error finding a cell to check.".to_owned());
+            };
+
+            let value = boards[grid].cell(cell).min();
+            let mut boards_copy = boards.clone();
+            if boards_copy[grid].clear_value(cell, value) {
+                board_stack.push(boards_copy);
+            }
+            if boards[grid].set_solved(cell, value) {
+                board_stack.push(boards);
+            }
+        }
+
+        if solution_count == 0 {
+            SolutionCountResult::None
+        } else {
+            SolutionCountResult::ExactCount(solution_count)
+        }
+    }
+
+    fn propagate(&self, boards: &mut [&mut Board], desc_list: Option<&mut LogicalStepDescList>) -> Option<bool> {
+        propagate(&self.shared_cells, &self.different_cells, boards, desc_list)
+    }
+
+    /// Lays out the boards of the grids as they overlap, one row of the layout per line.
+    ///
+    /// Solved cells show their value, unsolved cells show `.` and positions outside of every grid show a space.
+    pub fn layout_string(&self, boards: &[Board]) -> String {
+        let height = self.offsets.iter().zip(boards).map(|(offset, board)| offset.0 + board.size()).max().unwrap_or(0);
+        let width = self.offsets.iter().zip(boards).map(|(offset, board)| offset.1 + board.size()).max().unwrap_or(0);
+        (0..height)
+            .map(|row| {
+                let line: String = (0..width)
+                    .map(|col| {
+                        let grid = self.offsets.iter().zip(boards).find(|((row_offset, col_offset), board)| {
+                            (*row_offset..row_offset + board.size()).contains(&row)
+                                && (*col_offset..col_offset + board.size()).contains(&col)
+                        });
+                        match grid {
+                            Some(((row_offset, col_offset), board)) => {
+                                let mask = board.cell(board.cell_utility().cell(row - row_offset, col - col_offset));
+                                if mask.is_solved() {
+                                    mask.value().to_string()
+                                } else {
+                                    ".".to_owned()
+                                }
+                            }
+                            None => " ".to_owned(),
+                        }
+                    })
+                    .collect();
+                line.trim_end().to_owned()
+            })
+            .join("\n")
+    }
+}
+
+/// Keeps only the candidates which a shared cell has in both of its grids, and removes the value of
+/// a cell with a single candidate from the cell it must differ from, until nothing changes.
+///
+/// Returns `None` if a cell has no candidates left, or otherwise whether any board changed.
+fn propagate(
+    shared_cells: &[(GridCell, GridCell)],
+    different_cells: &[(GridCell, GridCell)],
+    boards: &mut [&mut Board],
+    mut desc_list: Option<&mut LogicalStepDescList>,
+) -> Option<bool> {
+    let mut changed = false;
+    loop {
+        let mut changed_this_pass = false;
+        for &((grid_a, cell_a), (grid_b, cell_b)) in shared_cells {
+            let common = boards[grid_a].cell(cell_a).unsolved() & boards[grid_b].cell(cell_b).unsolved();
+            let mut cell_changed = false;
+            for (grid, cell) in [(grid_a, cell_a), (grid_b, cell_b)] {
+                let board = &mut *boards[grid];
+                if board.cell(cell).unsolved() != common {
+                    cell_changed = true;
+                    if !board.keep_mask(cell, common) {
+                        return None;
+                    }
+                }
+                if common.is_single() && !board.cell(cell).is_solved() {
+                    cell_changed = true;
+                    if !board.set_solved(cell, common.value()) {
+                        return None;
+                    }
+                }
+            }
+
+            if cell_changed {
+                changed_this_pass = true;
+                if let Some(desc_list) = desc_list.as_mut() {
+                    desc_list.push(
+                        format!(
+                            "Shared cell: Grid {} {cell_a} is Grid {} {cell_b} => {common}",
+                            grid_a + 1,
+                            grid_b + 1
+                        )
+                        .into(),
+                    );
+                }
+            }
+        }
+
+        for &((grid_a, cell_a), (grid_b, cell_b)) in different_cells {
+            for ((grid, cell), (other_grid, other_cell)) in
+                [((grid_a, cell_a), (grid_b, cell_b)), ((grid_b, cell_b), (grid_a, cell_a))]
+            {
+                let mask = boards[grid].cell(cell).unsolved();
+                if !mask.is_single() || !boards[other_grid].cell(other_cell).has(mask.value()) {
+                    continue;
+                }
+
+                changed_this_pass = true;
+                if let Some(desc_list) = desc_list.as_mut() {
+                    desc_list.push(
+                        format!(
+                            "Different cells: Grid {} {cell} is {} => Grid {} {other_cell} is not {}",
+                            grid + 1,
+                            mask.value(),
+                            other_grid + 1,
+                            mask.value()
+                        )
+                        .into(),
+                    );
+                }
+                if !boards[other_grid].clear_value(other_cell, mask.value()) {
+                    return None;
+                }
+            }
+        }
+
+        if !changed_this_pass {
+            return Some(changed);
+        }
+        changed = true;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn samurai_solution() -> Vec<Board> {
+        let grids = std::array::from_fn(|_| SolverBuilder::default());
+        let solver = MultiGridBuilder::samurai(grids).build().unwrap();
+        assert_eq!(solver.shared_cells().len(), 4 * 9);
+        solver.find_first_solution().unwrap()
+    }
+
+    #[test]
+    fn test_samurai_solution() {
+        let solution = samurai_solution();
+        let grids = std::array::from_fn(|_| SolverBuilder::default());
+        let solver = MultiGridBuilder::samurai(grids).build().unwrap();
+        for &((grid_a, cell_a), (grid_b, cell_b)) in solver.shared_cells() {
+            assert_eq!(solution[grid_a].cell(cell_a), solution[grid_b].cell(cell_b));
+        }
+        for (grid, board) in solution.iter().enumerate() {
+            assert!(solver.grids()[grid].validate_solution(board).is_empty());
+        }
+
+        let layout = solver.layout_string(&solution);
+        assert_eq!(layout.lines().count(), 21);
+        assert_eq!(layout.lines().nth(7).unwrap().len(), 21);
+        assert_eq!(layout.lines().nth(10).unwrap().trim().len(), 9);
+        assert!(solver.find_solution_count(2, None).is_at_least_count());
+    }
+
+    #[test]
+    fn test_samurai_shared_givens() {
+        // Give the center grid only the cells which aren't shared, so its corners come from the other grids.
+        let solution = samurai_solution();
+        let cu = CellUtility::new(9);
+        let grids = std::array::from_fn(|grid| {
+            let givens: String = solution[grid]
+                .all_cells()
+                .map(|cell| {
+                    let (row, col) = cell.rc();
+                    let is_center_corner = grid == 2 && row / 3 != 1 && col / 3 != 1;
+                    if is_center_corner {
+                        '.'
+                    } else {
+                        char::from_digit(solution[grid].cell(cell).value() as u32, 10).unwrap()
+                    }
+                })
+                .collect();
+            SolverBuilder::default().with_givens_string(&givens)
+        });
+
+        let mut solver = MultiGridBuilder::samurai(grids).build().unwrap();
+        assert!(solver.grids()[2].board().cell(cu.cell(0, 0)).is_solved());
+        assert!(solver.is_solved());
+        assert!(solver.run_logical_solve().is_solved());
+
+        let conflicting = MultiGridBuilder::new()
+            .with_grid(SolverBuilder::default().with_givens_string(&format!("{:.<81}", "1")), (0, 0))
+            .with_grid(SolverBuilder::default().with_givens_string(&format!("{:.<81}", "2")), (0, 0))
+            .build();
+        assert!(conflicting.is_err());
+    }
+
+    #[test]
+    fn test_different_grids() {
+        let solution = Solver::default().find_first_solution().board().unwrap();
+        let givens: String = solution.all_cell_masks().map(|(_, mask)| mask.value().to_string()).collect();
+        let mut solver = MultiGridBuilder::new()
+            .with_grid(SolverBuilder::default().with_givens_string(&givens), (0, 0))
+            .with_grid(SolverBuilder::default(), (0, 10))
+            .with_grid_relation(GridRelation::Different, 0, 1, None)
+            .build()
+            .unwrap();
+        assert!(solver.shared_cells().is_empty());
+        for cell in solution.all_cells() {
+            assert!(!solver.grids()[1].board().cell(cell).has(solution.cell(cell).value()));
+        }
+
+        let boards = solver.find_first_solution().unwrap();
+        for &((grid_a, cell_a), (grid_b, cell_b)) in solver.different_cells() {
+            assert_ne!(boards[grid_a].cell(cell_a), boards[grid_b].cell(cell_b));
+        }
+        assert!(!solver.run_logical_solve().is_invalid());
+
+        // Identical givens in a corresponding cell contradict each other
+        let given = format!("{:.<81}", "1");
+        let conflicting = MultiGridBuilder::new()
+            .with_grid(SolverBuilder::default().with_givens_string(&given), (0, 0))
+            .with_grid(SolverBuilder::default().with_givens_string(&given), (0, 10))
+            .with_cell_relation(GridRelation::Different, (0, CellIndex::new(0, 9)), (1, CellIndex::new(0, 9)))
+            .build();
+        assert!(conflicting.is_err());
+    }
+
+    #[test]
+    fn test_rotated_grid() {
+        let solution = Solver::default().find_first_solution().board().unwrap();
+        let givens: String = solution.all_cell_masks().map(|(_, mask)| mask.value().to_string()).collect();
+        let mut solver = MultiGridBuilder::new()
+            .with_grid(SolverBuilder::default(), (0, 0))
+            .with_grid(SolverBuilder::default().with_givens_string(&givens), (0, 10))
+            .with_grid_relation(GridRelation::Equal, 0, 1, Some(Symmetry::Rotational90))
+            .build()
+            .unwrap();
+        assert_eq!(solver.shared_cells().len(), 81);
+        assert!(solver.is_solved());
+        assert!(solver.run_logical_solve().is_solved());
+        for cell in solution.all_cells() {
+            let rotated = Symmetry::Rotational90.partner(cell);
+            assert_eq!(solver.grids()[0].board().cell(cell), solution.cell(rotated));
+        }
+
+        let missing_grid = MultiGridBuilder::new()
+            .with_grid(SolverBuilder::default(), (0, 0))
+            .with_grid_relation(GridRelation::Equal, 0, 1, None)
+            .build();
+        assert!(missing_grid.is_err());
+        let different_sizes = MultiGridBuilder::new()
+            .with_grid(SolverBuilder::default(), (0, 0))
+            .with_grid(SolverBuilder::default().with_givens_string(&".".repeat(36)), (0, 10))
+            .with_grid_relation(GridRelation::Different, 0, 1, None)
+            .build();
+        assert!(different_sizes.is_err());
+    }
+
+    #[test]
+    fn test_logical_solve_across_grids() {
+        // r1c1 of the second grid is r7c7 of the first grid, so the 1 there rules out 1 in the rest of row 7.
+        let mut solver = MultiGridBuilder::new()
+            .with_grid(SolverBuilder::default(), (0, 0))
+            .with_grid(SolverBuilder::default().with_givens_string(&format!("{:.<81}", "1")), (6, 6))
+            .build()
+            .unwrap();
+        let cu = CellUtility::new(9);
+        assert!(solver.grids()[0].board().cell(cu.cell(6, 6)).is_solved());
+        assert!(!solver.grids()[0].board().cell(cu.cell(6, 0)).has(1));
+
+        let result = solver.run_logical_solve();
+        assert!(!result.is_invalid());
+        if let Some(desc) = result.description() {
+            assert!(desc.iter().all(|step| step.to_string().starts_with("Grid ")));
+        }
+    }
+}
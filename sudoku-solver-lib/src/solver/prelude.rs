@@ -1,7 +1,26 @@
+#[cfg(feature = "batch")]
+pub use super::batch::*;
 pub use super::cancellation::*;
+pub use super::givens_encoding::*;
+pub use super::grid_relation::*;
 pub use super::logical_solve_result::*;
+pub use super::multi_grid_builder::*;
+pub use super::multi_grid_solver::*;
+pub use super::progress_report::*;
+#[cfg(feature = "generator")]
+pub use super::puzzle_analysis::*;
+#[cfg(feature = "generator")]
+pub use super::puzzle_generator::*;
 pub use super::single_solution_result::*;
+pub use super::singles_solve_result::*;
 pub use super::solution_count_result::*;
+pub use super::solution_counter::*;
+pub use super::solution_order::*;
 pub use super::solution_receiver::*;
+pub use super::solution_violation::*;
 pub use super::solver_builder::*;
+pub use super::solver_observer::*;
+pub use super::solver_options::*;
+pub use super::solver_template::*;
+pub use super::true_candidates_comparison::*;
 pub use super::true_candidates_count_result::*;
@@ -0,0 +1,35 @@
+//! Contains [`ProgressReport`] for storing the result of [`Solver::check_progress`].
+
+use crate::prelude::*;
+
+/// The mistakes found in a partially solved grid by [`Solver::check_progress`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProgressReport {
+    /// The values which are not in any solution.
+    pub wrong_values: Vec<CandidateIndex>,
+    /// The cells whose pencilmarks are missing values which are still possible,
+    /// along with the missing values.
+    pub wrong_pencilmarks: Vec<(CellIndex, ValueMask)>,
+}
+
+impl ProgressReport {
+    /// Whether the grid has no mistakes.
+    pub fn is_on_track(&self) -> bool {
+        self.wrong_values.is_empty() && self.wrong_pencilmarks.is_empty()
+    }
+}
+
+impl std::fmt::Display for ProgressReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_on_track() {
+            return write!(f, "No mistakes found.");
+        }
+
+        let mistakes = self
+            .wrong_values
+            .iter()
+            .map(|candidate| format!("{candidate} is wrong"))
+            .chain(self.wrong_pencilmarks.iter().map(|(cell, missing)| format!("{cell} is missing {missing}")));
+        write!(f, "{}", mistakes.collect::<Vec<_>>().join(", "))
+    }
+}
@@ -0,0 +1,234 @@
+//! Contains [`PuzzleAnalysis`] and [`PuzzleSetAnalysis`] for gathering statistics about the logic puzzles need.
+
+use super::puzzle_generator::is_solved_by_singles;
+use crate::prelude::*;
+use std::collections::BTreeMap;
+
+/// The logic and brute force work a single puzzle needs.
+#[derive(Debug, Clone)]
+pub struct PuzzleAnalysis {
+    difficulty: Option<PuzzleDifficulty>,
+    technique_steps: BTreeMap<&'static str, usize>,
+    boards_explored: usize,
+}
+
+impl PuzzleAnalysis {
+    /// Analyzes the puzzle of the solver.
+    ///
+    /// The difficulty is rated the same way as [`PuzzleGenerator`] rates it. The steps of each technique are
+    /// counted from a full logical solve, and a brute force count of up to two solutions checks the puzzle is unique.
+    ///
+    /// Returns an error if the analysis is cancelled before it finishes.
+    pub fn new(solver: &Solver, cancellation: impl Into<Cancellation>) -> Result<Self, String> {
+        let cancellation = cancellation.into();
+        let mut counter = solver.solution_counter(2);
+        let count = loop {
+            if let Some(result) = counter.run(usize::MAX, None, &cancellation) {
+                break result;
+            }
+        };
+        if let Some(error) = count.error() {
+            return Err(error);
+        }
+
+        let mut technique_steps = BTreeMap::new();
+        let mut logical_solver = solver.clone();
+        let result = logical_solver.run_logical_solve_with_step_names(&cancellation, |name| {
+            *technique_steps.entry(name).or_default() += 1;
+        });
+        if cancellation.check() {
+            return Err("cancelled".into());
+        }
+
+        let difficulty = if !count.is_exact_count() || count.count() != Some(1) {
+            None
+        } else if !result.is_solved() {
+            Some(PuzzleDifficulty::Hard)
+        } else if is_solved_by_singles(solver) {
+            Some(PuzzleDifficulty::Easy)
+        } else {
+            Some(PuzzleDifficulty::Medium)
+        };
+
+        Ok(Self { difficulty, technique_steps, boards_explored: counter.boards_explored() })
+    }
+
+    /// The difficulty of the puzzle, or `None` if it doesn't have exactly one solution.
+    pub fn difficulty(&self) -> Option<PuzzleDifficulty> {
+        self.difficulty
+    }
+
+    /// The number of steps of each technique used by the logical solve, keyed by [`LogicalStep::name`].
+    pub fn technique_steps(&self) -> &BTreeMap<&'static str, usize> {
+        &self.technique_steps
+    }
+
+    /// Whether the puzzle has exactly one solution.
+    pub fn is_unique(&self) -> bool {
+        self.difficulty.is_some()
+    }
+
+    /// The number of boards the brute force count explored.
+    pub fn boards_explored(&self) -> usize {
+        self.boards_explored
+    }
+}
+
+/// Statistics aggregated over many puzzles, for checking that a difficulty model or
+/// the output of a [`PuzzleGenerator`] behaves as expected.
+///
+/// # Example
+/// ```
+/// # use sudoku_solver_lib::prelude::*;
+/// let solver = SolverBuilder::default()
+///     .with_givens_string("8...62..125.....7..197...5........9.....28..3.....36.54...1..6...74...3.5.2......")
+///     .build()
+///     .unwrap();
+/// let analysis = analyze_puzzles(&[solver], None).unwrap();
+/// assert_eq!(analysis.num_puzzles(), 1);
+/// assert_eq!(analysis.solve_rate(PuzzleDifficulty::Easy), 1.0);
+/// assert!(analysis.techniques().all(|technique| analysis.average_steps(technique) > 0.0));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PuzzleSetAnalysis {
+    num_puzzles: usize,
+    num_unique: usize,
+    difficulty_counts: BTreeMap<PuzzleDifficulty, usize>,
+    technique_steps: BTreeMap<&'static str, usize>,
+    technique_puzzles: BTreeMap<&'static str, usize>,
+    boards_explored: usize,
+}
+
+impl PuzzleSetAnalysis {
+    /// Adds the analysis of a single puzzle to the statistics.
+    pub fn add(&mut self, analysis: &PuzzleAnalysis) {
+        self.num_puzzles += 1;
+        if let Some(difficulty) = analysis.difficulty {
+            self.num_unique += 1;
+            *self.difficulty_counts.entry(difficulty).or_default() += 1;
+        }
+        for (&name, &steps) in analysis.technique_steps.iter() {
+            *self.technique_steps.entry(name).or_default() += steps;
+            *self.technique_puzzles.entry(name).or_default() += 1;
+        }
+        self.boards_explored += analysis.boards_explored;
+    }
+
+    /// The number of puzzles analyzed.
+    pub fn num_puzzles(&self) -> usize {
+        self.num_puzzles
+    }
+
+    /// The number of puzzles with exactly one solution.
+    pub fn num_unique(&self) -> usize {
+        self.num_unique
+    }
+
+    /// The number of puzzles of exactly this difficulty.
+    pub fn difficulty_count(&self, difficulty: PuzzleDifficulty) -> usize {
+        self.difficulty_counts.get(&difficulty).copied().unwrap_or(0)
+    }
+
+    /// The fraction of puzzles which the logic of this difficulty solves, which are
+    /// the puzzles of this difficulty or easier. [`PuzzleDifficulty::Hard`] puzzles are solved
+    /// by brute force, so its rate is the fraction of puzzles with exactly one solution.
+    pub fn solve_rate(&self, difficulty: PuzzleDifficulty) -> f64 {
+        let solved: usize = self.difficulty_counts.range(..=difficulty).map(|(_, count)| count).sum();
+        self.average(solved)
+    }
+
+    /// The names of every technique used by any of the puzzles.
+    pub fn techniques(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.technique_steps.keys().copied()
+    }
+
+    /// The average number of steps of the technique per puzzle, including the puzzles which don't use it.
+    pub fn average_steps(&self, technique: &str) -> f64 {
+        self.average(self.technique_steps.get(technique).copied().unwrap_or(0))
+    }
+
+    /// The number of puzzles which use the technique at least once.
+    pub fn puzzles_using(&self, technique: &str) -> usize {
+        self.technique_puzzles.get(technique).copied().unwrap_or(0)
+    }
+
+    /// The average number of boards explored by the brute force count of each puzzle.
+    pub fn average_boards_explored(&self) -> f64 {
+        self.average(self.boards_explored)
+    }
+
+    fn average(&self, total: usize) -> f64 {
+        if self.num_puzzles == 0 {
+            0.0
+        } else {
+            total as f64 / self.num_puzzles as f64
+        }
+    }
+}
+
+impl std::fmt::Display for PuzzleSetAnalysis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Puzzles: {} ({} unique)", self.num_puzzles, self.num_unique)?;
+        for difficulty in [PuzzleDifficulty::Easy, PuzzleDifficulty::Medium, PuzzleDifficulty::Hard] {
+            writeln!(
+                f,
+                "{difficulty}: {} (solve rate {:.1}%)",
+                self.difficulty_count(difficulty),
+                self.solve_rate(difficulty) * 100.0
+            )?;
+        }
+        for technique in self.techniques() {
+            writeln!(
+                f,
+                "{technique}: {:.2} steps per puzzle, used by {}",
+                self.average_steps(technique),
+                self.puzzles_using(technique)
+            )?;
+        }
+        write!(f, "Boards explored per puzzle: {:.1}", self.average_boards_explored())
+    }
+}
+
+/// Analyzes every puzzle and aggregates the results. See [`PuzzleAnalysis::new`].
+pub fn analyze_puzzles(solvers: &[Solver], cancellation: impl Into<Cancellation>) -> Result<PuzzleSetAnalysis, String> {
+    let cancellation = cancellation.into();
+    let mut analysis = PuzzleSetAnalysis::default();
+    for solver in solvers {
+        analysis.add(&PuzzleAnalysis::new(solver, cancellation.clone())?);
+    }
+    Ok(analysis)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_puzzle_set_analysis() {
+        let easy = SolverBuilder::default()
+            .with_givens_string("8...62..125.....7..197...5........9.....28..3.....36.54...1..6...74...3.5.2......")
+            .build()
+            .unwrap();
+        let analysis = PuzzleAnalysis::new(&easy, None).unwrap();
+        assert_eq!(analysis.difficulty(), Some(PuzzleDifficulty::Easy));
+        assert!(analysis.is_unique());
+        assert!(analysis.boards_explored() > 0);
+
+        let empty = Solver::default();
+        let set = analyze_puzzles(&[easy, empty], None).unwrap();
+        assert_eq!(set.num_puzzles(), 2);
+        assert_eq!(set.num_unique(), 1);
+        assert_eq!(set.difficulty_count(PuzzleDifficulty::Easy), 1);
+        assert_eq!(set.difficulty_count(PuzzleDifficulty::Hard), 0);
+        assert_eq!(set.solve_rate(PuzzleDifficulty::Medium), 0.5);
+        assert_eq!(set.solve_rate(PuzzleDifficulty::Hard), 0.5);
+        let (&technique, &steps) = analysis.technique_steps().iter().next().unwrap();
+        assert_eq!(
+            set.techniques().collect::<Vec<_>>(),
+            analysis.technique_steps().keys().copied().collect::<Vec<_>>()
+        );
+        assert_eq!(set.puzzles_using(technique), 1);
+        assert_eq!(set.average_steps(technique) * 2.0, steps as f64);
+        assert!(set.to_string().starts_with("Puzzles: 2 (1 unique)"));
+    }
+}
@@ -0,0 +1,294 @@
+//! Contains the [`PuzzleGenerator`] struct for generating new puzzles with a unique solution.
+
+use crate::prelude::*;
+use itertools::Itertools;
+use rand::seq::SliceRandom;
+use std::{collections::HashSet, sync::Arc};
+
+/// The difficulty of a puzzle, measured by the logic required to solve it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PuzzleDifficulty {
+    /// The puzzle can be solved using only singles and constraint logic.
+    Easy,
+    /// The puzzle can be solved using the logical steps of the solver.
+    Medium,
+    /// The puzzle cannot be solved using the logical steps of the solver.
+    Hard,
+}
+
+impl PuzzleDifficulty {
+    /// Returns the name of the difficulty for display purposes.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Easy => "Easy",
+            Self::Medium => "Medium",
+            Self::Hard => "Hard",
+        }
+    }
+}
+
+impl std::fmt::Display for PuzzleDifficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// A puzzle created by a [`PuzzleGenerator`].
+#[derive(Clone)]
+pub struct GeneratedPuzzle {
+    givens: Vec<(CellIndex, usize)>,
+    solution: Box<Board>,
+    difficulty: PuzzleDifficulty,
+}
+
+impl GeneratedPuzzle {
+    /// The givens of the puzzle, sorted by cell.
+    pub fn givens(&self) -> &[(CellIndex, usize)] {
+        &self.givens
+    }
+
+    /// The unique solution to the puzzle.
+    pub fn solution(&self) -> &Board {
+        &self.solution
+    }
+
+    /// The difficulty of the puzzle.
+    pub fn difficulty(&self) -> PuzzleDifficulty {
+        self.difficulty
+    }
+
+    /// The givens in the same format accepted by [`SolverBuilder::with_givens_string`].
+    pub fn givens_string(&self) -> String {
+        self.givens_string_with(GivensEncoding::Numeric)
+    }
+
+    /// The givens in the given encoding, which [`SolverBuilder::with_givens_string`] also accepts.
+    pub fn givens_string_with(&self, encoding: GivensEncoding) -> String {
+        let mut values = vec![0; self.solution.num_cells()];
+        for &(cell, value) in self.givens.iter() {
+            values[cell.index()] = value;
+        }
+        encoding.encode(&values, self.solution.size())
+    }
+}
+
+/// Generates puzzles which have a unique solution.
+///
+/// The rules of the generated puzzles are taken from the [`Solver`] passed in,
+/// including its constraints and any givens it already has.
+/// The logical steps of that solver are used to rate the difficulty of the puzzle.
+///
+/// Generation works by finding a random solution and then removing givens in a
+/// random order, keeping each removal only if the puzzle still has a unique solution
+/// and is not harder than the requested difficulty.
+#[derive(Clone)]
+pub struct PuzzleGenerator {
+    solver: Solver,
+    difficulty: Option<PuzzleDifficulty>,
+    max_difficulty: Option<PuzzleDifficulty>,
+    max_attempts: usize,
+    symmetry: Option<Symmetry>,
+}
+
+impl PuzzleGenerator {
+    /// Creates a new generator for puzzles with the rules of the given [`Solver`].
+    pub fn new(solver: Solver) -> Self {
+        Self { solver, difficulty: None, max_difficulty: None, max_attempts: 100, symmetry: None }
+    }
+
+    /// Only generate puzzles of exactly this difficulty.
+    #[must_use]
+    pub fn with_difficulty(mut self, difficulty: PuzzleDifficulty) -> Self {
+        self.difficulty = Some(difficulty);
+        self
+    }
+
+    /// Only generate puzzles of at most this difficulty.
+    #[must_use]
+    pub fn with_max_difficulty(mut self, max_difficulty: PuzzleDifficulty) -> Self {
+        self.max_difficulty = Some(max_difficulty);
+        self
+    }
+
+    /// Set the maximum number of random solutions to try before giving up
+    /// on finding a puzzle of the requested difficulty.
+    #[must_use]
+    pub fn with_max_attempts(mut self, max_attempts: usize) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Only generate puzzles whose givens have this symmetry.
+    ///
+    /// Givens are then removed together with their partners under the symmetry, so puzzles
+    /// usually end up with more givens. Givens of the solver passed to [`PuzzleGenerator::new`]
+    /// are always kept, so they should have the symmetry too.
+    #[must_use]
+    pub fn with_symmetry(mut self, symmetry: Symmetry) -> Self {
+        self.symmetry = Some(symmetry);
+        self
+    }
+
+    /// Generate a single puzzle.
+    pub fn generate(&self, cancellation: impl Into<Cancellation>) -> Result<GeneratedPuzzle, String> {
+        let cancellation = cancellation.into();
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..self.max_attempts {
+            let solution = match self.solver.find_random_solution() {
+                SingleSolutionResult::Solved(solution) => solution,
+                SingleSolutionResult::None => return Err("No solutions found.".to_owned()),
+                SingleSolutionResult::Error(error) => return Err(error),
+            };
+
+            let mut givens: Vec<(CellIndex, usize)> = solution
+                .all_cell_masks()
+                .filter(|(cell, _)| !self.solver.board.cell(*cell).is_solved())
+                .map(|(cell, mask)| (cell, mask.value()))
+                .collect();
+            givens.shuffle(&mut rng);
+
+            // Givens which must be removed together to keep the symmetry.
+            let mut groups = self.symmetric_groups(&givens);
+            let mut index = 0;
+            while index < groups.len() {
+                if cancellation.check() {
+                    return Err("cancelled".to_owned());
+                }
+
+                let removed = groups.remove(index);
+                let remaining = groups.iter().flatten().copied().collect_vec();
+                if !self.is_acceptable(&remaining, cancellation.clone()) {
+                    groups.insert(index, removed);
+                    index += 1;
+                }
+            }
+            let mut givens = groups.into_iter().flatten().collect_vec();
+
+            let difficulty = match self.solver_with_givens(&givens) {
+                Some(solver) => self.rate_solver(&solver),
+                None => continue,
+            };
+
+            let is_target_difficulty = self.difficulty.is_none() || self.difficulty == Some(difficulty);
+            let is_within_max = self.max_difficulty.is_none() || self.max_difficulty >= Some(difficulty);
+            if is_target_difficulty && is_within_max {
+                givens.sort();
+                return Ok(GeneratedPuzzle { givens, solution, difficulty });
+            }
+        }
+
+        Err(format!("Failed to generate a puzzle of the requested difficulty after {} attempts.", self.max_attempts))
+    }
+
+    /// Rate the difficulty of the puzzle formed by adding these givens to the generator's rules.
+    ///
+    /// Returns `None` if the givens are not valid. Uniqueness is not checked.
+    pub fn rate_difficulty(&self, givens: &[(CellIndex, usize)]) -> Option<PuzzleDifficulty> {
+        self.solver_with_givens(givens).map(|solver| self.rate_solver(&solver))
+    }
+
+    /// Groups the givens by their orbits under the symmetry, keeping the order of the first given of each group.
+    fn symmetric_groups(&self, givens: &[(CellIndex, usize)]) -> Vec<Vec<(CellIndex, usize)>> {
+        let Some(symmetry) = self.symmetry else {
+            return givens.iter().map(|&given| vec![given]).collect();
+        };
+
+        let mut grouped = HashSet::new();
+        let mut groups = Vec::new();
+        for &(cell, _) in givens {
+            if grouped.contains(&cell) {
+                continue;
+            }
+
+            let orbit = symmetry.orbit(cell);
+            grouped.extend(orbit.iter().copied());
+            groups.push(givens.iter().copied().filter(|(cell, _)| orbit.contains(cell)).collect());
+        }
+        groups
+    }
+
+    fn is_acceptable(&self, givens: &[(CellIndex, usize)], cancellation: Cancellation) -> bool {
+        let solver = match self.solver_with_givens(givens) {
+            Some(solver) => solver,
+            None => return false,
+        };
+
+        let count = solver.find_solution_count(2, None, cancellation);
+        if !count.is_exact_count() || count.count() != Some(1) {
+            return false;
+        }
+
+        let cap = match (self.difficulty, self.max_difficulty) {
+            (Some(difficulty), Some(max_difficulty)) => Some(difficulty.min(max_difficulty)),
+            (difficulty, max_difficulty) => difficulty.or(max_difficulty),
+        };
+        match cap {
+            Some(PuzzleDifficulty::Hard) | None => true,
+            Some(cap) => self.rate_solver(&solver) <= cap,
+        }
+    }
+
+    fn solver_with_givens(&self, givens: &[(CellIndex, usize)]) -> Option<Solver> {
+        let mut solver = self.solver.clone();
+        solver.board.set_solved_many(givens).ok()?;
+        Some(solver)
+    }
+
+    fn rate_solver(&self, solver: &Solver) -> PuzzleDifficulty {
+        if is_solved_by_singles(solver) {
+            return PuzzleDifficulty::Easy;
+        }
+
+        let mut full_solver = solver.clone();
+        if full_solver.run_logical_solve().is_solved() {
+            PuzzleDifficulty::Medium
+        } else {
+            PuzzleDifficulty::Hard
+        }
+    }
+}
+
+/// Whether a logical solve using only singles and constraint logic solves the puzzle.
+pub(crate) fn is_solved_by_singles(solver: &Solver) -> bool {
+    let mut singles_solver = solver.clone();
+    singles_solver.logical_solve_steps =
+        Arc::new([Arc::new(NakedSingle), Arc::new(HiddenSingle), Arc::new(StepConstraints)]);
+    singles_solver.run_logical_solve().is_solved()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_generate_easy() {
+        let generator = PuzzleGenerator::new(Solver::default()).with_difficulty(PuzzleDifficulty::Easy);
+        let puzzle = generator.generate(None).unwrap();
+        assert_eq!(puzzle.difficulty(), PuzzleDifficulty::Easy);
+        assert!(puzzle.solution().is_solved());
+
+        let givens_string = puzzle.givens_string();
+        assert_eq!(givens_string.len(), 81);
+
+        let solver = SolverBuilder::default().with_givens_string(&givens_string).build().unwrap();
+        let count = solver.find_solution_count(2, None, None);
+        assert!(count.is_exact_count());
+        assert_eq!(count.count().unwrap(), 1);
+
+        let mut solver = solver;
+        assert!(solver.run_logical_solve().is_solved());
+        assert_eq!(solver.board().to_string(), puzzle.solution().to_string());
+    }
+
+    #[test]
+    fn test_generate_symmetric() {
+        let generator = PuzzleGenerator::new(Solver::default()).with_symmetry(Symmetry::Rotational90);
+        let puzzle = generator.generate(None).unwrap();
+        let cells = puzzle.givens().iter().map(|&(cell, _)| cell).collect_vec();
+        assert!(Symmetry::Rotational90.is_symmetric(&cells));
+
+        let solver = SolverBuilder::default().with_givens_string(&puzzle.givens_string()).build().unwrap();
+        assert!(Symmetry::detect(solver.board()).contains(&Symmetry::Rotational180));
+    }
+}
@@ -0,0 +1,47 @@
+//! Contains [`SinglesSolveResult`] for storing the result of [`Solver::run_singles_only`].
+
+use crate::prelude::*;
+
+/// The result of placing only naked and hidden singles with [`Solver::run_singles_only`].
+#[derive(Debug, Clone)]
+pub struct SinglesSolveResult {
+    result: LogicalSolveResult,
+    singles_placed: usize,
+}
+
+impl SinglesSolveResult {
+    pub(crate) fn new(result: LogicalSolveResult, singles_placed: usize) -> Self {
+        Self { result, singles_placed }
+    }
+
+    /// The steps taken, as for a full logical solve.
+    pub fn result(&self) -> &LogicalSolveResult {
+        &self.result
+    }
+
+    /// Consumes this result to get the steps taken.
+    pub fn into_result(self) -> LogicalSolveResult {
+        self.result
+    }
+
+    /// The number of cells which were solved by singles.
+    pub fn singles_placed(&self) -> usize {
+        self.singles_placed
+    }
+
+    /// Whether the singles solved the entire board.
+    pub fn is_complete(&self) -> bool {
+        self.result.is_solved()
+    }
+
+    /// Whether the singles found that the board is invalid.
+    pub fn is_invalid(&self) -> bool {
+        self.result.is_invalid()
+    }
+}
+
+impl std::fmt::Display for SinglesSolveResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.result)
+    }
+}
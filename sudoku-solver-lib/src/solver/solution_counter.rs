@@ -0,0 +1,488 @@
+//! Contains the [`SolutionCounter`] struct for counting solutions a piece at a time.
+
+use super::{board_arena::BoardArena, board_pool::BoardPool};
+use crate::prelude::*;
+use std::collections::HashSet;
+
+/// The boards a [`SolutionCounter`] has yet to explore.
+enum BoardStack {
+    /// A boxed board and its depth for every unexplored branch, with spare boards kept in a pool.
+    Boxed { boards: Vec<(Box<Board>, usize)>, pool: BoardPool },
+    /// One board per search depth, see [`SolverBuilder::with_board_arena`].
+    Arena(BoardArena),
+}
+
+/// The outcome of exploring a single board.
+enum Step {
+    /// The board was invalid or was branched on.
+    Continue,
+    /// The board is a solution. Contains what the solution receiver asked to do next.
+    Solved(ReceiveAction),
+    /// The board is one of the known solutions, which are already counted.
+    Known,
+    /// There are no more boards to explore.
+    Finished,
+    Error(String),
+}
+
+/// What exploring a board needs to know about the count, besides the boards themselves.
+struct StepContext<'a> {
+    solver: &'a Solver,
+    solution_count: usize,
+    known_solutions: &'a HashSet<Vec<ValueMask>>,
+    cancellation: &'a Cancellation,
+}
+
+/// Counts the solutions of a puzzle via brute force, a limited amount of work at a time.
+///
+/// [`Solver::find_solution_count`] runs until the count is finished. A [`SolutionCounter`]
+/// instead returns to the caller after exploring a given number of boards, which allows
+/// reporting progress or yielding to an event loop between calls to [`SolutionCounter::run`].
+///
+/// Use [`Solver::solution_counter`] to create a [`SolutionCounter`].
+pub struct SolutionCounter<'a> {
+    solver: &'a Solver,
+    board_stack: BoardStack,
+    maximum_count: usize,
+    solution_count: usize,
+    progress_count: usize,
+    boards_explored: usize,
+    /// True once a solution receiver has skipped part of the search, so the count is not exact.
+    skipped: bool,
+    /// The most boards the search may keep, from [`Solver::search_memory_limit`].
+    max_stored_boards: Option<usize>,
+    /// The cell masks of the known solutions, see [`SolutionCounter::with_known_solutions`].
+    known_solutions: HashSet<Vec<ValueMask>>,
+}
+
+impl<'a> SolutionCounter<'a> {
+    pub(crate) fn new(solver: &'a Solver, board: &Board, maximum_count: usize) -> Self {
+        Self {
+            solver,
+            board_stack: if solver.board_arena() {
+                BoardStack::Arena(BoardArena::new(board))
+            } else {
+                BoardStack::Boxed { boards: vec![(Box::new(board.clone()), 0)], pool: BoardPool::default() }
+            },
+            maximum_count,
+            solution_count: 0,
+            progress_count: 0,
+            boards_explored: 0,
+            skipped: false,
+            max_stored_boards: solver.search_memory_limit().map(|bytes| bytes / board.memory_size().max(1)),
+            known_solutions: HashSet::new(),
+        }
+    }
+
+    /// Seed the count with solutions which are already known, such as those found by a previous count
+    /// before a small edit to the puzzle.
+    ///
+    /// Each known solution is checked with [`Solver::validate_solution`], and those which are no longer
+    /// solutions are ignored. The valid ones count towards the total straight away, so the count stops
+    /// without searching if they reach the maximum count. This is the only shortcut: otherwise the search
+    /// explores as many boards as it would without them, and only avoids counting a known solution again
+    /// or sending it to the solution receiver when it finds it.
+    ///
+    /// This must be called before the first call to [`SolutionCounter::run`].
+    #[must_use]
+    pub fn with_known_solutions<'b>(mut self, solutions: impl IntoIterator<Item = &'b Board>) -> Self {
+        for solution in solutions {
+            if self.solver.validate_solution(solution).is_empty() {
+                self.known_solutions.insert(Self::solution_key(solution));
+            }
+        }
+        self.solution_count = self.known_solutions.len();
+        self
+    }
+
+    /// The number of known solutions which are still valid, see [`SolutionCounter::with_known_solutions`].
+    pub fn known_solution_count(&self) -> usize {
+        self.known_solutions.len()
+    }
+
+    /// The number of solutions found so far.
+    pub fn solution_count(&self) -> usize {
+        self.solution_count
+    }
+
+    /// The number of boards explored so far.
+    pub fn boards_explored(&self) -> usize {
+        self.boards_explored
+    }
+
+    /// Explore up to `max_boards` more boards.
+    ///
+    /// Returns the result once the count is finished, or `None` if there is more work to do.
+    pub fn run(
+        &mut self,
+        max_boards: usize,
+        mut solution_receiver: Option<&mut dyn SolutionReceiver>,
+        cancellation: &Cancellation,
+    ) -> Option<SolutionCountResult> {
+        let mut boards_remaining = max_boards;
+        let result = loop {
+            if self.maximum_count > 0 && self.solution_count >= self.maximum_count {
+                // Only reachable when the known solutions alone reach the maximum count.
+                break SolutionCountResult::AtLeastCount(self.maximum_count);
+            }
+
+            if cancellation.check() {
+                break SolutionCountResult::Error("cancelled".into());
+            }
+
+            if boards_remaining == 0 {
+                return None;
+            }
+            boards_remaining -= 1;
+
+            if let Some(solution_receiver) = solution_receiver.as_mut() {
+                self.progress_count += 1;
+                if self.progress_count.is_multiple_of(50000) {
+                    solution_receiver.progress_ping(self.progress_count);
+                }
+            }
+
+            let context = StepContext {
+                solver: self.solver,
+                solution_count: self.solution_count,
+                known_solutions: &self.known_solutions,
+                cancellation,
+            };
+            let step = match &mut self.board_stack {
+                BoardStack::Boxed { boards, pool } => {
+                    Self::step_boxed(&context, boards, pool, &mut self.boards_explored, &mut solution_receiver)
+                }
+                BoardStack::Arena(arena) => {
+                    Self::step_arena(&context, arena, &mut self.boards_explored, &mut solution_receiver)
+                }
+            };
+            match step {
+                Step::Continue if self.max_stored_boards.is_some_and(|max| self.stored_boards() > max) => {
+                    tracing::debug!(
+                        target: BRUTE_FORCE_TRACE_TARGET,
+                        stored_boards = self.stored_boards(),
+                        "Solution count reached its memory limit"
+                    );
+                    break SolutionCountResult::AtLeastCount(self.solution_count);
+                }
+                Step::Continue | Step::Known => {}
+                Step::Solved(action) => {
+                    self.solution_count += 1;
+                    match action {
+                        ReceiveAction::Continue => {}
+                        ReceiveAction::SkipSubtree(depth) => self.skip_subtree(depth),
+                        ReceiveAction::Stop => break SolutionCountResult::AtLeastCount(self.solution_count),
+                    }
+                    if self.maximum_count > 0 && self.solution_count >= self.maximum_count {
+                        break SolutionCountResult::AtLeastCount(self.solution_count);
+                    }
+                }
+                Step::Finished if self.solution_count == 0 => break SolutionCountResult::None,
+                Step::Finished if self.skipped => break SolutionCountResult::AtLeastCount(self.solution_count),
+                Step::Finished => break SolutionCountResult::ExactCount(self.solution_count),
+                Step::Error(error) => break SolutionCountResult::Error(error),
+            }
+        };
+
+        tracing::debug!(
+            target: BRUTE_FORCE_TRACE_TARGET,
+            boards_explored = self.boards_explored,
+            solution_count = self.solution_count,
+            "Solution count finished"
+        );
+        Some(result)
+    }
+
+    /// The key of a solution in the known solutions.
+    fn solution_key(solution: &Board) -> Vec<ValueMask> {
+        solution.all_cell_masks().map(|(_, mask)| mask).collect()
+    }
+
+    /// The number of boards the search is keeping, including spare boards kept for reuse.
+    fn stored_boards(&self) -> usize {
+        match &self.board_stack {
+            BoardStack::Boxed { boards, pool } => boards.len() + pool.len(),
+            BoardStack::Arena(arena) => arena.len(),
+        }
+    }
+
+    /// Forgets the unexplored boards below the board at the given depth of the current branch.
+    fn skip_subtree(&mut self, depth: usize) {
+        self.skipped = true;
+        match &mut self.board_stack {
+            BoardStack::Boxed { boards, pool } => {
+                // The stack is depth first, so the boards of the current branch are on top.
+                while boards.last().is_some_and(|&(_, board_depth)| board_depth > depth) {
+                    let (board, _) = boards.pop().unwrap();
+                    pool.recycle(board);
+                }
+            }
+            BoardStack::Arena(arena) => arena.skip_subtree(depth),
+        }
+    }
+
+    fn step_boxed(
+        context: &StepContext,
+        boards: &mut Vec<(Box<Board>, usize)>,
+        pool: &mut BoardPool,
+        boards_explored: &mut usize,
+        solution_receiver: &mut Option<&mut dyn SolutionReceiver>,
+    ) -> Step {
+        let StepContext { solver, solution_count, known_solutions, cancellation } = *context;
+        let (mut board, depth) = match boards.pop() {
+            Some(board) => board,
+            None => return Step::Finished,
+        };
+        *boards_explored += 1;
+        if !solver.run_brute_force_logic(&mut board, cancellation) {
+            pool.recycle(board);
+            return Step::Continue;
+        }
+
+        if board.is_solved() {
+            if !known_solutions.is_empty() && known_solutions.contains(&Self::solution_key(&board)) {
+                pool.recycle(board);
+                return Step::Known;
+            }
+            return match solution_receiver.as_mut() {
+                Some(solution_receiver) => {
+                    let statistics = SearchStatistics {
+                        solution_count: solution_count + 1,
+                        boards_explored: *boards_explored,
+                        depth,
+                    };
+                    Step::Solved(solution_receiver.receive(board, &statistics))
+                }
+                None => {
+                    pool.recycle(board);
+                    Step::Solved(ReceiveAction::Continue)
+                }
+            };
+        }
+
+        let cell = match solver.find_best_brute_force_cell(&board) {
+            Some(cell) => cell,
+            None => return Step::Error("Internal error finding a cell to check.".to_owned()),
+        };
+        let mask = board.cell(cell);
+        let last_value = mask.max();
+        for value in mask.without(last_value) {
+            // Push a copy of the board onto the stack with each value set.
+            let mut board_copy = pool.copy_of(&board);
+            if board_copy.set_solved(cell, value) {
+                boards.push((board_copy, depth + 1));
+            } else {
+                pool.recycle(board_copy);
+            }
+        }
+
+        // The last value doesn't need a copy, so set it on the board itself.
+        if board.set_solved(cell, last_value) {
+            boards.push((board, depth + 1));
+        } else {
+            pool.recycle(board);
+        }
+        Step::Continue
+    }
+
+    fn step_arena(
+        context: &StepContext,
+        arena: &mut BoardArena,
+        boards_explored: &mut usize,
+        solution_receiver: &mut Option<&mut dyn SolutionReceiver>,
+    ) -> Step {
+        let StepContext { solver, solution_count, known_solutions, cancellation } = *context;
+        let board = match arena.next_board() {
+            Some(board) => board,
+            None => return Step::Finished,
+        };
+        *boards_explored += 1;
+        if !solver.run_brute_force_logic(board, cancellation) {
+            return Step::Continue;
+        }
+
+        if board.is_solved() {
+            if !known_solutions.is_empty() && known_solutions.contains(&Self::solution_key(board)) {
+                return Step::Known;
+            }
+            // The arena reuses the board, so the receiver gets its own copy.
+            return match solution_receiver.as_mut() {
+                Some(solution_receiver) => {
+                    let solution = Box::new(board.clone());
+                    let statistics = SearchStatistics {
+                        solution_count: solution_count + 1,
+                        boards_explored: *boards_explored,
+                        depth: arena.depth(),
+                    };
+                    Step::Solved(solution_receiver.receive(solution, &statistics))
+                }
+                None => Step::Solved(ReceiveAction::Continue),
+            };
+        }
+
+        match solver.find_best_brute_force_cell(board) {
+            Some(cell) => {
+                arena.branch(cell);
+                Step::Continue
+            }
+            None => Step::Error("Internal error finding a cell to check.".to_owned()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_count_in_chunks() {
+        let solver = SolverBuilder::default()
+            .with_givens_string(".............23.4.....452....1.3.....3...4...6..7....8..6.....9.5....62.7.9...1..")
+            .build()
+            .unwrap();
+        let cancellation = Cancellation::default();
+        let mut counter = solver.solution_counter(0);
+
+        let mut chunks = 0;
+        let result = loop {
+            chunks += 1;
+            if let Some(result) = counter.run(100, None, &cancellation) {
+                break result;
+            }
+        };
+
+        assert!(chunks > 1);
+        assert!(result.is_exact_count());
+        assert_eq!(result.count().unwrap(), 2357);
+        assert_eq!(counter.solution_count(), 2357);
+    }
+
+    #[test]
+    fn test_board_stacks_match() {
+        let givens = ".............23.4.....452....1.3.....3...4...6..7....8..6.....9.5....62.7.9...1..";
+        let cancellation = Cancellation::default();
+        for board_arena in [false, true] {
+            let solver =
+                SolverBuilder::default().with_givens_string(givens).with_board_arena(board_arena).build().unwrap();
+            let mut counter = solver.solution_counter(0);
+            let result = counter.run(usize::MAX, None, &cancellation).unwrap();
+            assert_eq!(result.count().unwrap(), 2357);
+            assert!(counter.boards_explored() > 2357);
+
+            let result = solver.solution_counter(10).run(usize::MAX, None, &cancellation).unwrap();
+            assert!(matches!(result, SolutionCountResult::AtLeastCount(10)));
+        }
+    }
+
+    #[test]
+    fn test_search_memory_limit() {
+        let givens = ".............23.4.....452....1.3.....3...4...6..7....8..6.....9.5....62.7.9...1..";
+        let board_size = Board::default().memory_size();
+        for board_arena in [false, true] {
+            let builder = || SolverBuilder::default().with_givens_string(givens).with_board_arena(board_arena);
+
+            // Too little memory for the search to go deep enough to find every solution
+            let solver = builder().with_search_memory_limit(board_size * 4).build().unwrap();
+            let result = solver.find_solution_count(0, None, None);
+            assert!(result.is_at_least_count());
+            assert!(result.count().unwrap() < 2357);
+
+            let solver = builder().with_search_memory_limit(board_size * 1000).build().unwrap();
+            assert_eq!(solver.find_solution_count(0, None, None), SolutionCountResult::ExactCount(2357));
+        }
+    }
+
+    /// Skips the other values of the last cell branched on for every solution.
+    struct SkipSiblingsReceiver {
+        depths: Vec<usize>,
+    }
+
+    impl SolutionReceiver for SkipSiblingsReceiver {
+        fn receive(&mut self, _result: Box<Board>, statistics: &SearchStatistics) -> ReceiveAction {
+            assert_eq!(statistics.solution_count, self.depths.len() + 1);
+            self.depths.push(statistics.depth);
+            ReceiveAction::SkipSubtree(statistics.depth - 1)
+        }
+    }
+
+    #[test]
+    fn test_receive_actions() {
+        let givens = ".............23.4.....452....1.3.....3...4...6..7....8..6.....9.5....62.7.9...1..";
+        let cancellation = Cancellation::default();
+        let mut skipped_counts = Vec::new();
+        for board_arena in [false, true] {
+            let solver =
+                SolverBuilder::default().with_givens_string(givens).with_board_arena(board_arena).build().unwrap();
+
+            let mut receiver = FirstSolutionsReceiver::new(5);
+            let result = solver.solution_counter(0).run(usize::MAX, Some(&mut receiver), &cancellation).unwrap();
+            assert_eq!(result, SolutionCountResult::AtLeastCount(5));
+            assert_eq!(receiver.solutions().len(), 5);
+
+            let mut receiver = SkipSiblingsReceiver { depths: Vec::new() };
+            let result = solver.solution_counter(0).run(usize::MAX, Some(&mut receiver), &cancellation).unwrap();
+            assert!(result.is_at_least_count());
+            assert!(result.count().unwrap() < 2357);
+            assert!(receiver.depths.iter().all(|&depth| depth > 0));
+            skipped_counts.push(result.count().unwrap());
+        }
+        assert_eq!(skipped_counts[0], skipped_counts[1]);
+    }
+
+    #[test]
+    fn test_known_solutions() {
+        let givens = ".............23.4.....452....1.3.....3...4...6..7....8..6.....9.5....62.7.9...1..";
+        let cancellation = Cancellation::default();
+        for board_arena in [false, true] {
+            let solver =
+                SolverBuilder::default().with_givens_string(givens).with_board_arena(board_arena).build().unwrap();
+            let mut receiver = FirstSolutionsReceiver::new(10);
+            solver.find_solution_count(0, Some(&mut receiver), None);
+            let known = receiver.solutions();
+
+            // Known solutions are counted but not received again
+            let mut receiver = VecSolutionReceiver::new();
+            let mut counter = solver.solution_counter(0).with_known_solutions(known.iter().chain(known.iter()));
+            assert_eq!(counter.known_solution_count(), 10);
+            assert_eq!(counter.solution_count(), 10);
+            let result = counter.run(usize::MAX, Some(&mut receiver), &cancellation).unwrap();
+            assert_eq!(result, SolutionCountResult::ExactCount(2357));
+            assert_eq!(receiver.solutions().len(), 2347);
+            assert!(receiver.solutions().iter().all(|solution| !known.contains(solution)));
+
+            // Enough known solutions finish the count without searching
+            let mut counter = solver.solution_counter(5).with_known_solutions(known);
+            let result = counter.run(usize::MAX, None, &cancellation).unwrap();
+            assert_eq!(result, SolutionCountResult::AtLeastCount(5));
+            assert_eq!(counter.boards_explored(), 0);
+
+            // Solutions of a different puzzle are ignored
+            let edited = SolverBuilder::default()
+                .with_givens_string(&givens.replacen('.', "1", 1))
+                .with_board_arena(board_arena)
+                .build()
+                .unwrap();
+            let result = edited.find_solution_count_with_known(known, 0, None, None);
+            let expected = known.iter().filter(|solution| edited.validate_solution(solution).is_empty()).count();
+            assert!(expected < 10);
+            assert_eq!(result, edited.find_solution_count(0, None, None));
+        }
+    }
+
+    #[test]
+    fn test_distinct_candidates_receiver() {
+        let solver = SolverBuilder::default()
+            .with_givens_string(".............23.4.....452....1.3.....3...4...6..7....8..6.....9.5....62.7.9...1..")
+            .build()
+            .unwrap();
+        let mut receiver = DistinctCandidatesReceiver::new();
+        let result = solver.find_solution_count(0, Some(&mut receiver), None);
+        assert_eq!(result, SolutionCountResult::ExactCount(2357));
+        assert!(receiver.solutions().len() < 2357);
+
+        let true_candidates = solver.find_true_candidates().board().unwrap();
+        for (cell, mask) in true_candidates.all_cell_masks() {
+            assert_eq!(receiver.candidates_seen()[cell.index()], mask.unsolved());
+        }
+    }
+}
@@ -0,0 +1,30 @@
+//! Contains [`SolutionOrder`] for choosing which solution
+//! [`Solver::find_solution_in_order`](crate::solver::Solver::find_solution_in_order) finds.
+
+/// Which solution a deterministic search finds when a puzzle has more than one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SolutionOrder {
+    /// The solution which comes first when its values are read in row-major order.
+    /// The cells are filled in order, which can be far slower on hard puzzles.
+    #[default]
+    Lexicographic,
+    /// Whichever solution is found first when branching on the best cell, trying the lowest value first.
+    /// This is always the same solution for the same puzzle.
+    Fastest,
+}
+
+impl SolutionOrder {
+    /// Returns the name of the order for display purposes.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Lexicographic => "lexicographic",
+            Self::Fastest => "fastest",
+        }
+    }
+}
+
+impl std::fmt::Display for SolutionOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
@@ -1,15 +1,51 @@
-//! Contains the [`SolutionReceiver`] trait for receiving solutions from a solver
-//! and a [`VecSolutionReceiver`] implementation for receiving solutions into a vector
-//! and a [`CountSolutionReceiver`] implementation for counting solutions as they come in.
+//! Contains the [`SolutionReceiver`] trait for receiving solutions from a solver,
+//! along with [`ReceiveAction`] and [`SearchStatistics`] which are passed between them.
+//!
+//! Also contains these implementations:
+//! - [`VecSolutionReceiver`] for receiving solutions into a vector.
+//! - [`CountSolutionReceiver`] for counting solutions as they come in.
+//! - [`FirstSolutionsReceiver`] for collecting the first solutions and then stopping.
+//! - [`DistinctCandidatesReceiver`] for collecting solutions which each use a candidate no earlier one did.
 
 use crate::prelude::*;
 
+/// What the solver should do after a solution is received.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReceiveAction {
+    /// Keep searching.
+    Continue,
+    /// Skip the rest of the subtree of the board at the given depth which led to this solution,
+    /// and keep searching the other branches.
+    ///
+    /// The depth must be less than [`SearchStatistics::depth`]. Skipping at one less than the depth
+    /// of the solution skips only the other values of the last cell branched on, and skipping at 0
+    /// skips everything, like [`ReceiveAction::Stop`].
+    ///
+    /// Since some solutions are never found, the solution count is reported as at least its count.
+    SkipSubtree(usize),
+    /// Stop the search.
+    Stop,
+}
+
+/// The progress of a search when a solution is received.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SearchStatistics {
+    /// The number of solutions found so far, including this one.
+    pub solution_count: usize,
+    /// The number of boards explored so far, including this one.
+    pub boards_explored: usize,
+    /// The number of cells branched on to reach this solution.
+    pub depth: usize,
+}
+
 /// A trait for receiving solutions from a solver.
 pub trait SolutionReceiver {
-    /// Receives a solution from a solver.
+    /// Receives a solution from a solver, and returns the [`ReceiveAction`] which tells the solver
+    /// whether to keep searching, skip the rest of a subtree or stop.
     ///
-    /// Return false to end the solution count early.
-    fn receive(&mut self, result: Box<Board>) -> bool;
+    /// The statistics give the progress of the search so far, such as the number of solutions found,
+    /// and the depth of the solution for choosing a [`ReceiveAction::SkipSubtree`].
+    fn receive(&mut self, result: Box<Board>, statistics: &SearchStatistics) -> ReceiveAction;
 
     /// Receive a ping every once in a while with a monotonically increasing number.
     ///
@@ -48,9 +84,9 @@ impl VecSolutionReceiver {
 }
 
 impl SolutionReceiver for VecSolutionReceiver {
-    fn receive(&mut self, result: Box<Board>) -> bool {
+    fn receive(&mut self, result: Box<Board>, _statistics: &SearchStatistics) -> ReceiveAction {
         self.solutions.push(result.as_ref().clone());
-        true
+        ReceiveAction::Continue
     }
 }
 
@@ -84,9 +120,9 @@ impl CountSolutionReceiver {
 }
 
 impl SolutionReceiver for CountSolutionReceiver {
-    fn receive(&mut self, _result: Box<Board>) -> bool {
+    fn receive(&mut self, _result: Box<Board>, _statistics: &SearchStatistics) -> ReceiveAction {
         self.count += 1;
-        true
+        ReceiveAction::Continue
     }
 }
 
@@ -95,3 +131,99 @@ impl Default for CountSolutionReceiver {
         Self::new()
     }
 }
+
+/// A [`SolutionReceiver`] that stores the first solutions found, then stops the search.
+pub struct FirstSolutionsReceiver {
+    maximum_count: usize,
+    solutions: Vec<Board>,
+}
+
+impl FirstSolutionsReceiver {
+    /// Creates a new [`FirstSolutionsReceiver`] which stops after `maximum_count` solutions.
+    pub fn new(maximum_count: usize) -> Self {
+        Self { maximum_count, solutions: Vec::new() }
+    }
+
+    /// Returns the solutions.
+    pub fn solutions(&self) -> &Vec<Board> {
+        &self.solutions
+    }
+
+    /// Consumes the [`FirstSolutionsReceiver`] and returns the solutions.
+    pub fn take_solutions(self) -> Vec<Board> {
+        self.solutions
+    }
+}
+
+impl SolutionReceiver for FirstSolutionsReceiver {
+    fn receive(&mut self, result: Box<Board>, _statistics: &SearchStatistics) -> ReceiveAction {
+        if self.solutions.len() < self.maximum_count {
+            self.solutions.push(*result);
+        }
+        if self.solutions.len() < self.maximum_count {
+            ReceiveAction::Continue
+        } else {
+            ReceiveAction::Stop
+        }
+    }
+}
+
+/// A [`SolutionReceiver`] that stores only the solutions which have a value in a cell
+/// that no solution stored before it has.
+///
+/// Once the search is finished, every value which is possible in a cell is in at least one
+/// of the solutions, so they are a witness for the true candidates of the puzzle.
+pub struct DistinctCandidatesReceiver {
+    candidates_seen: Vec<ValueMask>,
+    solutions: Vec<Board>,
+}
+
+impl DistinctCandidatesReceiver {
+    /// Creates a new [`DistinctCandidatesReceiver`].
+    pub fn new() -> Self {
+        Self { candidates_seen: Vec::new(), solutions: Vec::new() }
+    }
+
+    /// Returns the solutions.
+    pub fn solutions(&self) -> &Vec<Board> {
+        &self.solutions
+    }
+
+    /// Consumes the [`DistinctCandidatesReceiver`] and returns the solutions.
+    pub fn take_solutions(self) -> Vec<Board> {
+        self.solutions
+    }
+
+    /// The values which are in each cell of at least one solution so far, indexed by [`CellIndex::index`].
+    pub fn candidates_seen(&self) -> &Vec<ValueMask> {
+        &self.candidates_seen
+    }
+}
+
+impl SolutionReceiver for DistinctCandidatesReceiver {
+    fn receive(&mut self, result: Box<Board>, _statistics: &SearchStatistics) -> ReceiveAction {
+        if self.candidates_seen.is_empty() {
+            self.candidates_seen = vec![ValueMask::new(); result.num_cells()];
+        }
+
+        let mut is_distinct = false;
+        for (cell, mask) in result.all_cell_masks() {
+            let seen = &mut self.candidates_seen[cell.index()];
+            if !seen.has(mask.value()) {
+                *seen = seen.with(mask.value());
+                is_distinct = true;
+            }
+        }
+
+        if is_distinct {
+            self.solutions.push(*result);
+        }
+        ReceiveAction::Continue
+    }
+}
+
+impl Default for DistinctCandidatesReceiver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,45 @@
+//! Contains [`SolutionViolation`] for describing why a grid is not a solution to a puzzle.
+
+use crate::prelude::*;
+
+/// A reason that a grid is not a solution, as found by [`Solver::validate_solution`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SolutionViolation {
+    /// The grid is a different size than the puzzle.
+    WrongSize { expected: usize, actual: usize },
+    /// The cell does not have exactly one value.
+    Unfilled(CellIndex),
+    /// The value was already removed from the cell by the puzzle, such as by a given.
+    NotACandidate(CandidateIndex),
+    /// The value appears more than once in the house.
+    HouseRepeat { house: String, value: usize, cells: Vec<CellIndex> },
+    /// Both candidates are in the grid, but a constraint does not allow them together.
+    WeakLink(CandidateIndex, CandidateIndex),
+    /// The constraint is violated when the value is placed in the cell.
+    Constraint { constraint: String, candidate: CandidateIndex, reason: Option<String> },
+}
+
+impl std::fmt::Display for SolutionViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolutionViolation::WrongSize { expected, actual } => {
+                write!(f, "The grid has size {actual} but the puzzle has size {expected}")
+            }
+            SolutionViolation::Unfilled(cell) => write!(f, "{cell} is not filled"),
+            SolutionViolation::NotACandidate(candidate) => write!(f, "{candidate} is not possible in the puzzle"),
+            SolutionViolation::HouseRepeat { house, value, cells } => {
+                let cells = cells.iter().map(|cell| cell.to_string()).collect::<Vec<_>>().join(", ");
+                write!(f, "{value} repeats in {house} at {cells}")
+            }
+            SolutionViolation::WeakLink(candidate0, candidate1) => {
+                write!(f, "{candidate0} and {candidate1} cannot both be true")
+            }
+            SolutionViolation::Constraint { constraint, candidate, reason: Some(reason) } => {
+                write!(f, "{constraint} is violated by {candidate}: {reason}")
+            }
+            SolutionViolation::Constraint { constraint, candidate, reason: None } => {
+                write!(f, "{constraint} is violated by {candidate}")
+            }
+        }
+    }
+}
@@ -12,10 +12,25 @@ pub struct SolverBuilder {
     size: usize,
     regions: Vec<usize>,
     logical_steps: Vec<Arc<dyn LogicalStep>>,
+    enabled_logical_steps: Vec<String>,
+    disabled_logical_steps: Vec<String>,
     constraints: Vec<Arc<dyn Constraint>>,
+    /// The group of each constraint, if it has one.
+    constraint_groups: Vec<Option<String>>,
+    disabled_groups: Vec<String>,
     givens: Vec<(CellIndex, usize)>,
+    cell_masks: Vec<(CellIndex, ValueMask)>,
     errors: Vec<String>,
     custom_info: HashMap<String, String>,
+    board_arena: bool,
+    weak_link_tie_break: bool,
+    search_memory_limit: Option<usize>,
+    random_restart_boards: usize,
+    unknown_regions: bool,
+    uniqueness_assumption: bool,
+    step_house_kinds: Vec<HouseKind>,
+    options: SolverOptions,
+    weak_link_report: bool,
 }
 
 impl SolverBuilder {
@@ -25,13 +40,40 @@ impl SolverBuilder {
             size,
             regions: Vec::new(),
             logical_steps: Vec::new(),
+            enabled_logical_steps: Vec::new(),
+            disabled_logical_steps: Vec::new(),
             constraints: Vec::new(),
+            constraint_groups: Vec::new(),
+            disabled_groups: Vec::new(),
             givens: Vec::new(),
+            cell_masks: Vec::new(),
             errors: Vec::new(),
             custom_info: HashMap::new(),
+            board_arena: true,
+            weak_link_tie_break: true,
+            search_memory_limit: None,
+            random_restart_boards: Solver::DEFAULT_RANDOM_RESTART_BOARDS,
+            unknown_regions: false,
+            uniqueness_assumption: false,
+            step_house_kinds: HouseKind::ALL.to_vec(),
+            options: SolverOptions::default(),
+            weak_link_report: false,
         }
     }
 
+    /// Creates a solver builder for a latin square: a grid where no value repeats in a row or column,
+    /// with no regions. This is the same as [`Self::with_no_regions`].
+    ///
+    /// # Example
+    /// ```
+    /// # use sudoku_solver_lib::prelude::*;
+    /// let solver = SolverBuilder::latin_square(6).build().unwrap();
+    /// assert_eq!(solver.board().houses().len(), 12);
+    /// ```
+    pub fn latin_square(size: usize) -> Self {
+        Self::new(size).with_no_regions()
+    }
+
     /// Set the regions of the board.
     ///
     /// The vector is expected to be of length `size * size`.
@@ -84,6 +126,17 @@ impl SolverBuilder {
         self
     }
 
+    /// Set the board to have regions which are unknown and must be deduced, such as for chaos construction.
+    ///
+    /// The board has no region houses, and instead has a [`RegionMembership`] for constraints
+    /// to narrow down the region of each cell. This does not add the rules of the regions
+    /// themselves, which need a constraint such as a chaos construction constraint.
+    #[must_use]
+    pub fn with_unknown_regions(mut self) -> Self {
+        self.unknown_regions = true;
+        self.with_no_regions()
+    }
+
     /// Set the full list of logical steps to use.
     /// This will replace any existing logical steps.
     #[must_use]
@@ -92,6 +145,24 @@ impl SolverBuilder {
         self
     }
 
+    /// Use the logical steps of a [`LogicProfile`], so that solve paths don't change when new steps are
+    /// added to the standard logic. This will replace any existing logical steps.
+    ///
+    /// # Example
+    /// ```
+    /// # use sudoku_solver_lib::prelude::*;
+    /// let mut solver = SolverBuilder::default()
+    ///     .with_logic_profile(LogicProfile::V1Basic)
+    ///     .with_givens_string("8...62..125.....7..197...5........9.....28..3.....36.54...1..6...74...3.5.2......")
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(solver.run_logical_solve().description().unwrap().len(), 56);
+    /// ```
+    #[must_use]
+    pub fn with_logic_profile(self, profile: LogicProfile) -> Self {
+        self.with_logical_steps(profile.logical_steps())
+    }
+
     /// Add a logical step to the list of logical steps to use.
     /// This will not replace any existing logical steps and will append to the end.
     #[must_use]
@@ -100,10 +171,53 @@ impl SolverBuilder {
         self
     }
 
+    /// Enable opt-in logical steps of the standard logic by name, ignoring case and whitespace,
+    /// such as [`PointingClaiming`]. This has no effect when the logical steps are set. Unknown
+    /// names are ignored with a warning when the solver is built.
+    ///
+    /// # Example
+    /// ```
+    /// # use sudoku_solver_lib::prelude::*;
+    /// let mut solver = SolverBuilder::default()
+    ///     .with_enabled_logical_steps(&["Pointing Claiming"])
+    ///     .with_givens_string("8...62..125.....7..197...5........9.....28..3.....36.54...1..6...74...3.5.2......")
+    ///     .build()
+    ///     .unwrap();
+    /// assert!(solver.run_logical_solve().is_solved());
+    /// ```
+    #[must_use]
+    pub fn with_enabled_logical_steps(mut self, names: &[impl AsRef<str>]) -> Self {
+        self.enabled_logical_steps.extend(names.iter().map(|name| name.as_ref().to_owned()));
+        self
+    }
+
+    /// Disable logical steps by name, ignoring case and whitespace, such as the `disabledlogic` of f-puzzles data.
+    /// The steps are removed from the standard logic or from the logical steps which were set. Unknown
+    /// names, such as steps of other solvers, are ignored with a warning when the solver is built.
+    ///
+    /// The steps required by the solver are still used for brute force solves.
+    ///
+    /// # Example
+    /// ```
+    /// # use sudoku_solver_lib::prelude::*;
+    /// let mut solver = SolverBuilder::default()
+    ///     .with_disabled_logical_steps(&["hidden single"])
+    ///     .with_givens_string("8...62..125.....7..197...5........9.....28..3.....36.54...1..6...74...3.5.2......")
+    ///     .build()
+    ///     .unwrap();
+    /// assert!(!solver.run_logical_solve().to_string().contains("Hidden Single"));
+    /// ```
+    #[must_use]
+    pub fn with_disabled_logical_steps(mut self, names: &[impl AsRef<str>]) -> Self {
+        self.disabled_logical_steps.extend(names.iter().map(|name| name.as_ref().to_owned()));
+        self
+    }
+
     /// Set the full list of constraints to use.
     /// This will replace any existing constraints.
     #[must_use]
     pub fn with_constraints(mut self, constraints: Vec<Arc<dyn Constraint>>) -> Self {
+        self.constraint_groups = vec![None; constraints.len()];
         self.constraints = constraints;
         self
     }
@@ -113,11 +227,70 @@ impl SolverBuilder {
     #[must_use]
     pub fn with_constraint(mut self, constraint: Arc<dyn Constraint>) -> Self {
         self.constraints.push(constraint);
+        self.constraint_groups.push(None);
+        self
+    }
+
+    /// Add a constraint which belongs to a named group, so that the whole group can be
+    /// left out of the built solver with [`Self::with_group_disabled`].
+    ///
+    /// Clones of a builder share their constraints, and each clone can be built with different
+    /// groups disabled as long as the constraints support [`Constraint::clone_constraint`].
+    ///
+    /// # Example
+    /// ```
+    /// # use sudoku_solver_lib::prelude::*;
+    /// # use std::sync::Arc;
+    /// # #[derive(Debug, Clone)]
+    /// # struct Negative;
+    /// # impl Constraint for Negative {
+    /// #     fn name(&self) -> &str { "Negative" }
+    /// #     fn clone_constraint(&self) -> Option<Arc<dyn Constraint>> { Some(Arc::new(self.clone())) }
+    /// # }
+    /// let builder = SolverBuilder::default().with_constraint_in_group(Arc::new(Negative), "negative");
+    /// assert_eq!(builder.constraint_groups(), vec!["negative"]);
+    ///
+    /// // Check the puzzle without the negative constraint.
+    /// let solver = builder.clone().with_group_disabled("negative").build().unwrap();
+    /// assert!(solver.board().constraints().is_empty());
+    /// assert_eq!(builder.build().unwrap().board().constraints().len(), 1);
+    /// ```
+    #[must_use]
+    pub fn with_constraint_in_group(mut self, constraint: Arc<dyn Constraint>, group: &str) -> Self {
+        self.constraints.push(constraint);
+        self.constraint_groups.push(Some(group.to_owned()));
         self
     }
 
+    /// The names of the constraint groups, in the order their first constraint was added.
+    pub fn constraint_groups(&self) -> Vec<&str> {
+        self.constraint_groups.iter().flatten().map(String::as_str).unique().collect()
+    }
+
+    /// Leave the constraints of the group out of the built solver.
+    #[must_use]
+    pub fn with_group_disabled(mut self, group: &str) -> Self {
+        if !self.is_group_disabled(group) {
+            self.disabled_groups.push(group.to_owned());
+        }
+        self
+    }
+
+    /// Include the constraints of a group which was disabled with [`Self::with_group_disabled`].
+    #[must_use]
+    pub fn with_group_enabled(mut self, group: &str) -> Self {
+        self.disabled_groups.retain(|disabled_group| disabled_group != group);
+        self
+    }
+
+    /// Whether the constraints of the group are left out of the built solver.
+    pub fn is_group_disabled(&self, group: &str) -> bool {
+        self.disabled_groups.iter().any(|disabled_group| disabled_group == group)
+    }
+
     /// Set a single given to use.
     /// This will append to the list of givens.
+    /// Giving a cell a different value than it was already given is an error when building.
     #[must_use]
     pub fn with_given(mut self, cell_index: CellIndex, value: usize) -> Self {
         self.givens.push((cell_index, value));
@@ -132,66 +305,233 @@ impl SolverBuilder {
         self
     }
 
+    /// Restrict a cell to the values of the mask, such as for given pencilmarks.
+    ///
+    /// The mask is applied directly to the board when building, so unlike a constraint, it costs
+    /// nothing while solving. Masks for the same cell are combined, and a cell left with no values
+    /// is an error when building.
+    ///
+    /// # Example
+    /// ```
+    /// # use sudoku_solver_lib::prelude::*;
+    /// let cu = CellUtility::new(9);
+    /// let solver = SolverBuilder::default()
+    ///     .with_cell_mask(cu.cell(0, 0), ValueMask::from_values(&[1, 2, 3]))
+    ///     .with_cell_mask(cu.cell(0, 0), ValueMask::from_values(&[2, 3, 4]))
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(solver.board().cell(cu.cell(0, 0)), ValueMask::from_values(&[2, 3]));
+    /// ```
+    #[must_use]
+    pub fn with_cell_mask(mut self, cell: CellIndex, mask: ValueMask) -> Self {
+        self.cell_masks.push((cell, mask));
+        self
+    }
+
+    /// Restrict multiple cells to the values of their masks. See [`Self::with_cell_mask`].
+    #[must_use]
+    pub fn with_cell_masks(mut self, cell_masks: &[(CellIndex, ValueMask)]) -> Self {
+        self.cell_masks.extend(cell_masks);
+        self
+    }
+
     /// Set the givens from a given string, appending those to any existing givens.
     /// The string should be a sequence of numbers, with 0 or any non-digit representing an empty cell.
     /// The string should be in row-major order.
-    /// For grid sizes larger than 9, the each number takes the same number of characters, so use 01 for 1, for example.
+    /// For grid sizes larger than 9, the each number takes the same number of characters, so use 01 for 1, for example,
+    /// or each cell is a single digit or letter, such as 1-9 and A-G for a 16x16 grid.
+    /// The encoding is detected from the length of the string. See [`GivensEncoding`].
+    ///
+    /// # Example
+    /// ```
+    /// # use sudoku_solver_lib::prelude::*;
+    /// let givens = "G".to_owned() + &".".repeat(255);
+    /// let solver = SolverBuilder::new(16).with_givens_string(&givens).build().unwrap();
+    /// assert_eq!(solver.board().cell(CellIndex::new(0, 16)).value(), 16);
+    /// ```
     #[must_use]
     pub fn with_givens_string(mut self, givens: &str) -> Self {
         let cu = CellUtility::new(self.size);
-        if cu.size() <= 9 {
-            if givens.len() != self.size * self.size {
-                self.errors.push("Invalid givens string length".to_owned());
-                return self;
-            }
+        let Some(encoding) = GivensEncoding::detect(givens, self.size) else {
+            self.errors.push("Invalid givens string length".to_owned());
+            return self;
+        };
+
+        match encoding.decode(givens, self.size) {
+            Ok(values) => self.givens.extend(
+                values
+                    .into_iter()
+                    .enumerate()
+                    .filter(|&(_, value)| value != 0)
+                    .map(|(i, value)| (cu.cell_index(i), value)),
+            ),
+            Err(error) => self.errors.push(error),
+        }
+        self
+    }
 
-            self.givens.extend(givens.chars().enumerate().filter_map(|(i, c)| {
-                let value = c.to_digit(10)?;
-                if value == 0 {
-                    None
-                } else {
-                    Some((cu.cell_index(i), value as usize))
-                }
-            }));
-        } else {
-            let num_digits = cu.size().to_string().len();
-            if givens.len() != self.size * self.size * num_digits {
-                self.errors.push("Invalid givens string length".to_owned());
-                return self;
-            }
+    pub fn with_custom_info(mut self, key: &str, value: &str) -> Self {
+        self.custom_info.insert(key.to_owned(), value.to_owned());
+        self
+    }
+
+    /// Choose how solution counts store the boards they have yet to explore.
+    ///
+    /// With the arena (the default), the search keeps one board per depth and copies each
+    /// branch into it as the branch is explored. Otherwise every branch is copied into its
+    /// own boxed board as soon as its cell is chosen. Both find the same solutions in the
+    /// same order, so this only exists to compare their performance.
+    #[must_use]
+    pub fn with_board_arena(mut self, board_arena: bool) -> Self {
+        self.board_arena = board_arena;
+        self
+    }
+
+    /// Choose how brute force searches break ties between cells with the fewest candidates.
+    ///
+    /// With the tie break (the default), the search branches on the cell whose candidates have the
+    /// most weak links in total, since setting it eliminates the most candidates elsewhere. This
+    /// usually explores fewer boards on puzzles with many constraints, such as kropki dots, and
+    /// makes no difference on classic sudoku. Otherwise the first such cell is chosen.
+    #[must_use]
+    pub fn with_weak_link_tie_break(mut self, weak_link_tie_break: bool) -> Self {
+        self.weak_link_tie_break = weak_link_tie_break;
+        self
+    }
 
-            let givens_chunks_itr = givens.chars().chunks(num_digits);
-            self.givens.extend(givens_chunks_itr.into_iter().enumerate().filter_map(|(i, c)| {
-                // Convert the chunk into a string.
-                let val_str = c.collect::<String>();
+    /// Limit the bytes of boards which a solution count may keep for the branches it has yet to explore.
+    ///
+    /// Once the limit is reached, the count stops and returns [`SolutionCountResult::AtLeastCount`]
+    /// with the solutions found so far, rather than running out of memory. With the board arena,
+    /// the search keeps at most one board per cell, so this mostly matters without it on large grids.
+    /// There is no limit by default.
+    ///
+    /// # Example
+    /// ```
+    /// # use sudoku_solver_lib::prelude::*;
+    /// let solver = SolverBuilder::new(16).with_board_arena(false).with_search_memory_limit(64 * 1024).build().unwrap();
+    /// assert!(matches!(solver.find_solution_count(0, None, None), SolutionCountResult::AtLeastCount(_)));
+    /// ```
+    #[must_use]
+    pub fn with_search_memory_limit(mut self, bytes: usize) -> Self {
+        self.search_memory_limit = Some(bytes);
+        self
+    }
 
-                // Convert the string into a number.
-                let value = val_str.parse::<usize>().ok()?;
+    /// Choose how many boards a random solution search explores before its first restart, or 0 to never restart.
+    ///
+    /// A random search which picks bad values early can spend a long time in a subtree without solutions.
+    /// With restarts, [`Solver::find_random_solution`] gives up on an attempt after exploring a number of boards
+    /// and starts again from the top with new random choices. The number of boards of each attempt follows the
+    /// Luby sequence (1, 1, 2, 1, 1, 2, 4, ...) times this many boards, so the attempts eventually grow large
+    /// enough to finish and a puzzle without solutions is still reported as such.
+    /// The default is [`Solver::DEFAULT_RANDOM_RESTART_BOARDS`].
+    ///
+    /// # Example
+    /// ```
+    /// # use sudoku_solver_lib::prelude::*;
+    /// let solver = SolverBuilder::default().with_random_restart_boards(1).build().unwrap();
+    /// assert!(solver.find_random_solution().is_solved());
+    /// ```
+    #[must_use]
+    pub fn with_random_restart_boards(mut self, boards: usize) -> Self {
+        self.random_restart_boards = boards;
+        self
+    }
 
-                // If the value is 0, ignore it.
-                if value == 0 {
-                    None
-                } else {
-                    Some((cu.cell_index(i), value))
-                }
-            }));
+    /// Choose whether true candidates searches assume that the puzzle has a unique solution.
+    ///
+    /// With the assumption, a branch of a true candidates search is abandoned as soon as it has a deadly
+    /// pattern: a rectangle of cells which are not givens and are down to the same two values, which could
+    /// swap places in any solution. This makes true candidates much faster on grids with few givens.
+    /// Other searches, such as solution counts, are not affected.
+    ///
+    /// If the puzzle has more than one solution, candidates which are only in solutions with a deadly
+    /// pattern are not found, so only use this for puzzles which are known to be unique. Constraints may
+    /// tell the values of a rectangle apart, so rectangles with a relevant cell of a constraint are never pruned.
+    #[must_use]
+    pub fn with_uniqueness_assumption(mut self, uniqueness_assumption: bool) -> Self {
+        self.uniqueness_assumption = uniqueness_assumption;
+        self
+    }
+
+    /// Limit the houses which logical steps such as [`HiddenSingle`] search to the given kinds.
+    ///
+    /// By default every kind of house is searched. This does not change which houses must
+    /// contain every value, only where the steps look for deductions.
+    ///
+    /// # Example
+    /// ```
+    /// # use sudoku_solver_lib::prelude::*;
+    /// // Only find singles in rows and columns, like in a latin square.
+    /// let solver = SolverBuilder::default().with_step_house_kinds(&[HouseKind::Row, HouseKind::Column]).build().unwrap();
+    /// let board = solver.board();
+    /// assert_eq!(board.houses().iter().filter(|house| board.is_step_house(house)).count(), 18);
+    /// ```
+    #[must_use]
+    pub fn with_step_house_kinds(mut self, kinds: &[HouseKind]) -> Self {
+        self.step_house_kinds = kinds.to_vec();
+        self
+    }
+
+    /// Set the [`SolverOptions`] of the solver, which are available from [`Solver::options`].
+    ///
+    /// A [`LogicTier::Singles`] tier limits logical solves to naked singles, hidden singles and
+    /// the logic of the constraints. Brute force searches are not affected by the tier.
+    ///
+    /// # Example
+    /// ```
+    /// # use sudoku_solver_lib::prelude::*;
+    /// let options = SolverOptions { logic_tier: LogicTier::Singles, ..SolverOptions::default() };
+    /// let solver = SolverBuilder::default().with_options(options).build().unwrap();
+    /// assert_eq!(solver.options().logic_tier, LogicTier::Singles);
+    /// ```
+    #[must_use]
+    pub fn with_options(mut self, options: SolverOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Set the [`SolverOptions`] of the solver from `name` or `name=value` strings. See [`SolverOptions::parse`].
+    #[must_use]
+    pub fn with_option_strings(mut self, options: &[impl AsRef<str>]) -> Self {
+        match SolverOptions::parse(options) {
+            Ok(options) => self.options = options,
+            Err(error) => self.errors.push(error),
         }
         self
     }
 
-    pub fn with_custom_info(mut self, key: &str, value: &str) -> Self {
-        self.custom_info.insert(key.to_owned(), value.to_owned());
+    /// The size of the grid.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Choose whether to report how the weak links of each constraint were used when the solver is built.
+    ///
+    /// Each constraint's [`WeakLinkStats`] is logged at debug level to [`WEAK_LINKS_TRACE_TARGET`],
+    /// with a warning for constraints which return more duplicate links than new ones. The stats
+    /// are always available from [`Board::weak_link_stats`], so this is only a debugging aid for
+    /// constraint authors.
+    #[must_use]
+    pub fn with_weak_link_report(mut self, weak_link_report: bool) -> Self {
+        self.weak_link_report = weak_link_report;
         self
     }
 
-    fn standard_logic() -> Vec<Arc<dyn LogicalStep>> {
-        vec![
-            Arc::new(AllNakedSingles),
-            Arc::new(HiddenSingle),
-            Arc::new(NakedSingle),
-            Arc::new(StepConstraints),
-            Arc::new(SimpleCellForcing),
-        ]
+    /// Record an error found while describing the puzzle, such as by a parser, which [`Self::build`] returns.
+    #[must_use]
+    pub fn with_error(mut self, error: &str) -> Self {
+        self.errors.push(error.to_owned());
+        self
+    }
+
+    /// Finds one of the standard logical steps by its name, ignoring case and spaces.
+    ///
+    /// For example, both `"Hidden Single"` and `"hiddensingle"` find [`HiddenSingle`].
+    /// See [`logical_step_registry`].
+    pub fn standard_logical_step(name: &str) -> Option<Arc<dyn LogicalStep>> {
+        logical_step_registry().create(name)
     }
 
     pub fn build(mut self) -> Result<Solver, String> {
@@ -199,62 +539,169 @@ impl SolverBuilder {
             return Err(self.errors.join(", "));
         }
 
-        let mut board = Board::new(self.size, &self.regions, self.constraints);
+        ValueMask::check_size(self.size)?;
 
-        // Apply the givens.
-        for (cell, value) in self.givens {
-            if !board.cell(cell).is_solved() && !board.set_solved(cell, value) {
-                return Err(format!("Failed to set given {value}{cell}"));
+        // Report a cell which is given different values by cell, rather than as a failed given.
+        let given_conflicts = find_given_conflicts(&self.givens);
+        if !given_conflicts.is_empty() {
+            return Err(given_conflicts.iter().join(", "));
+        }
+
+        let constraints = self
+            .constraints
+            .into_iter()
+            .zip(self.constraint_groups.iter())
+            .filter(|(_, group)| !group.as_ref().is_some_and(|group| self.disabled_groups.contains(group)))
+            .map(|(constraint, _)| Self::unshared_constraint(constraint))
+            .collect();
+        let mut board = Board::new(self.size, &self.regions, constraints);
+        board.set_step_house_kinds(&self.step_house_kinds);
+
+        if self.weak_link_report {
+            for stats in board.weak_link_stats() {
+                tracing::debug!(
+                    target: WEAK_LINKS_TRACE_TARGET,
+                    constraint = stats.constraint,
+                    links = stats.links,
+                    added = stats.added,
+                    duplicates = stats.duplicates,
+                    self_eliminations = stats.self_eliminations,
+                    "{stats}"
+                );
+                if stats.duplicates > stats.added {
+                    tracing::warn!(target: WEAK_LINKS_TRACE_TARGET, "{} returns mostly duplicate weak links", stats.constraint);
+                }
+            }
+        }
+
+        // Report rules which contradict each other by name, rather than as an invalid board.
+        let conflicts = find_constraint_conflicts(&board);
+        if !conflicts.is_empty() {
+            return Err(conflicts.iter().join(", "));
+        }
+
+        if self.unknown_regions {
+            board.set_region_membership(Some(RegionMembership::new(self.size)));
+        }
+
+        // Apply the cell masks, before the givens so that a given outside of its cell's mask fails.
+        for &(cell, mask) in self.cell_masks.iter() {
+            if !board.keep_mask(cell, mask) {
+                return Err(format!("Failed to restrict {cell} to {mask}"));
             }
         }
 
+        // Apply the givens.
+        if let Err((cell, value)) = board.set_solved_many(&self.givens) {
+            return Err(format!("Failed to set given {value}{cell}"));
+        }
+
         // Initialize the constraints
         board.init_constraints()?;
 
+        // Report the enabled and disabled steps which don't exist, as they are often misspelled or from other solvers.
+        for name in self.disabled_logical_steps.iter() {
+            let normalized = normalize_name(name);
+            let is_known = logical_step_registry().names().any(|step| normalize_name(step) == normalized)
+                || self.logical_steps.iter().any(|step| normalize_name(step.name()) == normalized);
+            if !is_known {
+                tracing::warn!(target: STEPS_TRACE_TARGET, "Unknown logical step {name} can't be disabled");
+            }
+        }
+        for name in self.enabled_logical_steps.iter() {
+            let normalized = normalize_name(name);
+            if !logical_step_registry().names().any(|step| normalize_name(step) == normalized) {
+                tracing::warn!(target: STEPS_TRACE_TARGET, "Unknown logical step {name} can't be enabled");
+            }
+        }
+
         // Construct the logical step lists.
         if self.logical_steps.is_empty() {
-            self.logical_steps = Self::standard_logic();
-        } else {
-            // There are two required logical steps which must always be present:
-            // 1. AllNakedSingles is used by the brute force solver.
-            // 2. StepConstraints is used to apply constraint logic.
-
-            if !self.logical_steps.iter().any(|step| step.type_id() == TypeId::of::<AllNakedSingles>()) {
-                // The AllNakedSingles step is required by the brute force solver.
-                // Put it first in the list.
-                self.logical_steps.insert(0, Arc::new(AllNakedSingles));
-            }
+            self.logical_steps =
+                logical_step_registry().create_all_with(&self.enabled_logical_steps, &self.disabled_logical_steps);
+        } else if !self.disabled_logical_steps.is_empty() {
+            let disabled = self.disabled_logical_steps.iter().map(|name| normalize_name(name)).collect::<Vec<_>>();
+            self.logical_steps.retain(|step| !disabled.contains(&normalize_name(step.name())));
+        }
 
-            if !self.logical_steps.iter().any(|step| step.type_id() == TypeId::of::<StepConstraints>()) {
-                // The StepConstraints step is required to apply constraint logic.
-                // Put it in the list after any singles steps.
-                let naked_single_index =
-                    self.logical_steps.iter().position(|step| step.type_id() == TypeId::of::<NakedSingle>());
-                let hidden_single_index =
-                    self.logical_steps.iter().position(|step| step.type_id() == TypeId::of::<HiddenSingle>());
-
-                let index = match (naked_single_index, hidden_single_index) {
-                    (Some(naked_single_index), Some(hidden_single_index)) => {
-                        naked_single_index.max(hidden_single_index) + 1
-                    }
-                    (Some(naked_single_index), None) => naked_single_index + 1,
-                    (None, Some(hidden_single_index)) => hidden_single_index + 1,
-                    (None, None) => 0,
-                };
-                self.logical_steps.insert(index, Arc::new(StepConstraints));
-            }
+        // There are two required logical steps which must always be present:
+        // 1. AllNakedSingles is used by the brute force solver.
+        // 2. StepConstraints is used to apply constraint logic.
+
+        if !self.logical_steps.iter().any(|step| step.type_id() == TypeId::of::<AllNakedSingles>()) {
+            // The AllNakedSingles step is required by the brute force solver.
+            // Put it first in the list.
+            self.logical_steps.insert(0, Arc::new(AllNakedSingles));
         }
 
-        let logical_solve_steps =
-            self.logical_steps.iter().cloned().filter(|step| step.is_active_during_logical_solves()).collect();
+        if !self.logical_steps.iter().any(|step| step.type_id() == TypeId::of::<StepConstraints>()) {
+            // The StepConstraints step is required to apply constraint logic.
+            // Put it in the list after any singles steps.
+            let naked_single_index =
+                self.logical_steps.iter().position(|step| step.type_id() == TypeId::of::<NakedSingle>());
+            let hidden_single_index =
+                self.logical_steps.iter().position(|step| step.type_id() == TypeId::of::<HiddenSingle>());
+
+            let index = match (naked_single_index, hidden_single_index) {
+                (Some(naked_single_index), Some(hidden_single_index)) => {
+                    naked_single_index.max(hidden_single_index) + 1
+                }
+                (Some(naked_single_index), None) => naked_single_index + 1,
+                (None, Some(hidden_single_index)) => hidden_single_index + 1,
+                (None, None) => 0,
+            };
+            self.logical_steps.insert(index, Arc::new(StepConstraints));
+        }
 
-        let brute_force_steps =
-            self.logical_steps.iter().cloned().filter(|step| step.is_active_during_brute_force_solves()).collect();
+        let singles_only = self.options.logic_tier == LogicTier::Singles;
+        let singles_steps =
+            [TypeId::of::<NakedSingle>(), TypeId::of::<HiddenSingle>(), TypeId::of::<StepConstraints>()];
+        let logical_solve_steps = self
+            .logical_steps
+            .iter()
+            .filter(|&step| step.is_active_during_logical_solves())
+            .filter(|&step| !singles_only || singles_steps.contains(&(**step).type_id()))
+            .cloned()
+            .collect();
 
-        let solver = Solver { board, logical_solve_steps, brute_force_steps, custom_info: self.custom_info };
+        let brute_force_steps =
+            self.logical_steps.iter().filter(|&step| step.is_active_during_brute_force_solves()).cloned().collect();
+
+        let mut solver = Solver {
+            board,
+            logical_solve_steps,
+            brute_force_steps,
+            custom_info: Arc::new(self.custom_info),
+            board_arena: self.board_arena,
+            weak_link_tie_break: self.weak_link_tie_break,
+            search_memory_limit: self.search_memory_limit,
+            random_restart_boards: self.random_restart_boards,
+            deadly_patterns: None,
+            recorder: None,
+            observer: None,
+            options: self.options,
+        };
+        solver.set_uniqueness_assumption(self.uniqueness_assumption);
 
         Ok(solver)
     }
+
+    /// Builds a [`SolverTemplate`] which creates solvers with these rules for many puzzles,
+    /// initializing the constraints and weak links only once.
+    pub fn build_template(self) -> Result<SolverTemplate, String> {
+        self.build().map(SolverTemplate::new)
+    }
+
+    /// Copies a constraint which is shared with another builder or solver, since each solver
+    /// initializes its own constraints. See [`Constraint::clone_constraint`].
+    fn unshared_constraint(constraint: Arc<dyn Constraint>) -> Arc<dyn Constraint> {
+        if Arc::strong_count(&constraint) > 1 {
+            if let Some(copy) = constraint.clone_constraint() {
+                return copy;
+            }
+        }
+        constraint
+    }
 }
 
 impl Default for SolverBuilder {
@@ -280,6 +727,13 @@ mod test {
         assert!(!board.is_solved());
         assert_eq!(board.houses().len(), 27);
         assert_eq!(board.constraints().len(), 0);
+        assert_eq!(solver.regions(), default_regions(9));
+    }
+
+    #[test]
+    fn test_unsupported_size() {
+        let error = SolverBuilder::new(ValueMask::MAX_SIZE + 1).build().err().unwrap();
+        assert_eq!(error, "Grid size 32 is larger than the maximum of 31");
     }
 
     #[test]
@@ -293,6 +747,16 @@ mod test {
         assert!(!board.is_solved());
         assert_eq!(board.houses().len(), 18);
         assert_eq!(board.constraints().len(), 0);
+        assert!(solver.regions().iter().all(|&region| region == 0));
+    }
+
+    #[test]
+    fn test_standard_logical_step() {
+        let step = SolverBuilder::standard_logical_step("hidden single").unwrap();
+        assert_eq!(step.name(), "Hidden Single");
+        let step = SolverBuilder::standard_logical_step("SimpleCellForcing").unwrap();
+        assert_eq!(step.name(), "Simple Cell Forcing");
+        assert!(SolverBuilder::standard_logical_step("X-Wing").is_none());
     }
 
     #[test]
@@ -308,4 +772,108 @@ mod test {
             ["Hidden Single", "Step Constraints"],
         );
     }
+
+    #[test]
+    fn test_disabled_logical_steps() {
+        let solver = SolverBuilder::default()
+            .with_disabled_logical_steps(&["Hidden Single", "simplecellforcing", "X-Wing"])
+            .build()
+            .unwrap();
+        assert_equal(
+            solver.logical_solve_steps.iter().map(|s| s.name()).collect::<Vec<_>>(),
+            ["Naked Single", "Step Constraints"],
+        );
+
+        // Required steps are put back, and set steps are also filtered.
+        let solver = SolverBuilder::default()
+            .with_logic_profile(LogicProfile::V1Basic)
+            .with_disabled_logical_steps(&["All Naked Singles", "Step Constraints", "Naked Single"])
+            .build()
+            .unwrap();
+        assert_eq!(solver.brute_force_steps[0].name(), "All Naked Singles");
+        assert_equal(
+            solver.logical_solve_steps.iter().map(|s| s.name()).collect::<Vec<_>>(),
+            ["Hidden Single", "Step Constraints", "Simple Cell Forcing"],
+        );
+    }
+
+    #[test]
+    fn test_enabled_logical_steps() {
+        let solver = SolverBuilder::default()
+            .with_enabled_logical_steps(&["pointing claiming", "Swordfish"])
+            .with_disabled_logical_steps(&["Naked Single"])
+            .build()
+            .unwrap();
+        assert_equal(
+            solver.logical_solve_steps.iter().map(|s| s.name()).collect::<Vec<_>>(),
+            ["Hidden Single", "Step Constraints", "Pointing Claiming", "Simple Cell Forcing"],
+        );
+    }
+
+    #[test]
+    fn test_logic_profile() {
+        let solver = SolverBuilder::default().with_logic_profile(LogicProfile::V1Basic).build().unwrap();
+        assert_equal(
+            solver.logical_solve_steps.iter().map(|s| s.name()).collect::<Vec<_>>(),
+            ["Hidden Single", "Naked Single", "Step Constraints", "Simple Cell Forcing"],
+        );
+
+        let solver = SolverBuilder::default().build().unwrap();
+        let latest = SolverBuilder::default().with_logic_profile(LogicProfile::LATEST).build().unwrap();
+        assert_equal(
+            solver.logical_solve_steps.iter().map(|s| s.name()),
+            latest.logical_solve_steps.iter().map(|s| s.name()),
+        );
+    }
+
+    #[test]
+    fn test_logic_tier() {
+        let solver = SolverBuilder::default().with_option_strings(&["logic=singles"]).build().unwrap();
+        assert_equal(
+            solver.logical_solve_steps.iter().map(|s| s.name()).collect::<Vec<_>>(),
+            ["Hidden Single", "Naked Single", "Step Constraints"],
+        );
+        assert_eq!(solver.brute_force_steps.len(), SolverBuilder::default().build().unwrap().brute_force_steps.len());
+
+        assert!(SolverBuilder::default().with_option_strings(&["timeout=soon"]).build().is_err());
+    }
+
+    #[test]
+    fn test_constraint_groups() {
+        #[derive(Debug)]
+        struct TestConstraint;
+        impl Constraint for TestConstraint {
+            fn name(&self) -> &str {
+                "Test"
+            }
+        }
+
+        let builder = || {
+            SolverBuilder::default()
+                .with_constraint_in_group(Arc::new(TestConstraint), "b")
+                .with_constraint(Arc::new(TestConstraint))
+                .with_constraint_in_group(Arc::new(TestConstraint), "a")
+                .with_constraint_in_group(Arc::new(TestConstraint), "b")
+        };
+        assert_eq!(builder().constraint_groups(), vec!["b", "a"]);
+
+        let disabled = builder().with_group_disabled("b").with_group_disabled("a").with_group_enabled("a");
+        assert!(disabled.is_group_disabled("b"));
+        assert!(!disabled.is_group_disabled("a"));
+        assert_eq!(disabled.build().unwrap().board().constraints().len(), 2);
+        assert_eq!(builder().build().unwrap().board().constraints().len(), 4);
+    }
+
+    #[test]
+    fn test_cell_masks() {
+        let cu = CellUtility::new(9);
+        let cell = cu.cell(0, 0);
+        let builder = SolverBuilder::default().with_cell_masks(&[(cell, ValueMask::from_values(&[1, 2]))]);
+        assert_eq!(builder.clone().build().unwrap().board().cell(cell), ValueMask::from_values(&[1, 2]));
+        assert!(builder.clone().with_given(cell, 2).build().is_ok());
+        assert_eq!(builder.clone().with_given(cell, 3).build().err().unwrap(), "Failed to set given 3r1c1");
+
+        let error = builder.with_cell_mask(cell, ValueMask::from_value(3)).build().err().unwrap();
+        assert_eq!(error, format!("Failed to restrict r1c1 to {}", ValueMask::from_value(3)));
+    }
 }
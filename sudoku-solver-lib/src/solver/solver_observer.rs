@@ -0,0 +1,129 @@
+//! Contains the [`SolverObserver`] trait for being told about the progress of a [`Solver`] as it happens.
+
+use std::sync::Arc;
+
+use crate::prelude::*;
+
+/// A trait for observing the logical steps of a [`Solver`] while they run, such as to animate
+/// a solve in a GUI without polling the board or running the solve again.
+///
+/// An observer is attached with [`Solver::set_observer`]. Every method does nothing by default,
+/// so implement only the ones which are needed. Without an observer, the solver does no extra work.
+///
+/// The observer is told about the logical steps of [`Solver::run_logical_solve`],
+/// [`Solver::run_single_logical_step`] and [`Solver::run_singles_only`], and the values set with
+/// [`Solver::set_solved`]. Brute force searches are not observed.
+///
+/// # Example
+/// ```
+/// # use sudoku_solver_lib::prelude::*;
+/// # use std::sync::{Arc, Mutex};
+/// #[derive(Default)]
+/// struct Animation {
+///     frames: Mutex<Vec<String>>,
+/// }
+///
+/// impl SolverObserver for Animation {
+///     fn on_value_set(&self, cell: CellIndex, value: usize) {
+///         self.frames.lock().unwrap().push(format!("{cell}={value}"));
+///     }
+/// }
+///
+/// let mut solver = SolverBuilder::default()
+///     .with_givens_string("8...62..125.....7..197...5........9.....28..3.....36.54...1..6...74...3.5.2......")
+///     .build()
+///     .unwrap();
+/// let givens = solver.board().solved_count();
+/// let animation = Arc::new(Animation::default());
+/// solver.set_observer(Some(animation.clone()));
+/// assert!(solver.run_logical_solve().is_solved());
+/// assert_eq!(animation.frames.lock().unwrap().len(), 81 - givens);
+/// ```
+pub trait SolverObserver: Send + Sync {
+    /// Called after a logical step has changed the board, with the step's result.
+    fn on_step_applied(&self, result: &LogicalStepResult) {
+        let _ = result;
+    }
+
+    /// Called as soon as a cell is solved, including during a logical step.
+    fn on_value_set(&self, cell: CellIndex, value: usize) {
+        let _ = (cell, value);
+    }
+
+    /// Called when a logical step finds that the board is invalid, or a value set with
+    /// [`Solver::set_solved`] breaks the rules. Has the step's description, if there is one.
+    fn on_contradiction(&self, desc: Option<&LogicalStepDesc>) {
+        let _ = desc;
+    }
+}
+
+/// Passes the cells which are solved on a board to a [`SolverObserver`].
+pub(crate) struct ValueSetForwarder(pub(crate) Arc<dyn SolverObserver>);
+
+impl BoardObserver for ValueSetForwarder {
+    fn cell_changed(&self, change: &BoardChange) {
+        if change.new_mask.is_solved() && !change.old_mask.is_solved() {
+            self.0.on_value_set(change.cell, change.new_mask.value());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct EventLog {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl SolverObserver for EventLog {
+        fn on_step_applied(&self, result: &LogicalStepResult) {
+            self.events.lock().unwrap().push(format!("step {}", result.description().unwrap()));
+        }
+
+        fn on_value_set(&self, cell: CellIndex, value: usize) {
+            self.events.lock().unwrap().push(format!("set {cell}={value}"));
+        }
+
+        fn on_contradiction(&self, desc: Option<&LogicalStepDesc>) {
+            self.events.lock().unwrap().push(format!("contradiction {desc:?}"));
+        }
+    }
+
+    #[test]
+    fn test_observer() {
+        let mut solver = SolverBuilder::default()
+            .with_givens_string("8...62..125.....7..197...5........9.....28..3.....36.54...1..6...74...3.5.2......")
+            .build()
+            .unwrap();
+        let cu = solver.cell_utility();
+        let log = Arc::new(EventLog::default());
+        let recorder = Arc::new(BoardRecorder::default());
+        solver.set_observer(Some(log.clone()));
+        solver.set_recorder(Some(recorder.clone()));
+
+        let step_result = solver.run_single_logical_step();
+        let events = log.events.lock().unwrap().clone();
+        let placements =
+            recorder.script().changes().iter().filter(|change| matches!(change.action, ReplayAction::Place(_))).count();
+        assert!(placements > 0);
+        assert_eq!(events.len(), placements + 1);
+        assert!(events[..placements].iter().all(|event| event.starts_with("set ")));
+        assert_eq!(events[placements], format!("step {}", step_result.description().unwrap()));
+
+        // The solver's clones share the observer
+        let mut clone = solver.clone();
+        log.events.lock().unwrap().clear();
+        assert!(!clone.set_solved(cu.cell(0, 1), 8));
+        assert_eq!(log.events.lock().unwrap().last().unwrap(), "contradiction None");
+
+        // Nothing is told once the observer is removed
+        solver.set_observer(None);
+        log.events.lock().unwrap().clear();
+        assert!(solver.run_logical_solve().is_solved());
+        assert!(log.events.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,112 @@
+//! Contains [`SolverOptions`] for the settings which change how a puzzle is solved and reported.
+
+use std::time::Duration;
+
+/// How much logic logical solves may use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogicTier {
+    /// Only naked singles, hidden singles and the logic of the constraints.
+    Singles,
+    /// Every logical step of the solver.
+    #[default]
+    All,
+}
+
+impl LogicTier {
+    /// Returns the name of the tier as used by [`SolverOptions::parse`].
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Singles => "singles",
+            Self::All => "all",
+        }
+    }
+}
+
+impl std::fmt::Display for LogicTier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Settings for how a puzzle is solved and reported, which travel with the puzzle from
+/// the format it was parsed from. See [`SolverBuilder::with_options`](crate::prelude::SolverBuilder::with_options).
+///
+/// The options can be parsed from a list of `name` or `name=value` strings, such as the
+/// `truecandidatesoptions` of an f-puzzles board:
+/// - `colored`: sets [`SolverOptions::true_candidates_colored`]
+/// - `logical`: sets [`SolverOptions::true_candidates_logical`]
+/// - `maxcolored=8`: sets [`SolverOptions::max_colored_count`]
+/// - `logic=singles` or `logic=all`: sets [`SolverOptions::logic_tier`]
+/// - `timeout=5000`: sets [`SolverOptions::timeout`] in milliseconds
+///
+/// Unknown names are ignored, so that newer options don't break older solvers.
+///
+/// # Example
+/// ```
+/// # use sudoku_solver_lib::prelude::*;
+/// # use std::time::Duration;
+/// let options = SolverOptions::parse(&["colored", "maxcolored=4", "timeout=500"]).unwrap();
+/// assert!(options.true_candidates_colored);
+/// assert!(!options.true_candidates_logical);
+/// assert_eq!(options.max_colored_count, 4);
+/// assert_eq!(options.timeout, Some(Duration::from_millis(500)));
+/// assert!(SolverOptions::parse(&["logic=some"]).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SolverOptions {
+    /// True candidates report how many solutions each candidate is in, up to [`SolverOptions::max_colored_count`].
+    pub true_candidates_colored: bool,
+    /// True candidates also report the candidates which a logical solve cannot remove.
+    pub true_candidates_logical: bool,
+    /// The number of solutions at which colored true candidates stop counting for a candidate.
+    pub max_colored_count: usize,
+    /// The logical steps which logical solves may use.
+    pub logic_tier: LogicTier,
+    /// How long a request may run before it is cancelled, or `None` for no limit.
+    pub timeout: Option<Duration>,
+}
+
+impl SolverOptions {
+    /// Parses options from `name` or `name=value` strings.
+    pub fn parse(options: &[impl AsRef<str>]) -> Result<Self, String> {
+        let mut result = Self::default();
+        for option in options.iter().map(|option| option.as_ref().trim()) {
+            let (name, value) = match option.split_once('=') {
+                Some((name, value)) => (name.trim(), Some(value.trim())),
+                None => (option, None),
+            };
+            let invalid = || format!("Invalid value for option {name}: {}", value.unwrap_or_default());
+            match (name.to_lowercase().as_str(), value) {
+                ("colored", None) => result.true_candidates_colored = true,
+                ("logical", None) => result.true_candidates_logical = true,
+                ("maxcolored", Some(value)) => {
+                    result.max_colored_count = value.parse().ok().filter(|&count| count > 0).ok_or_else(invalid)?;
+                }
+                ("logic", Some(value)) => {
+                    result.logic_tier = [LogicTier::Singles, LogicTier::All]
+                        .into_iter()
+                        .find(|tier| tier.name().eq_ignore_ascii_case(value))
+                        .ok_or_else(invalid)?;
+                }
+                ("timeout", Some(value)) => {
+                    result.timeout = Some(Duration::from_millis(value.parse().map_err(|_| invalid())?));
+                }
+                ("colored" | "logical", Some(_)) | ("maxcolored" | "logic" | "timeout", None) => return Err(invalid()),
+                _ => {}
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl Default for SolverOptions {
+    fn default() -> Self {
+        Self {
+            true_candidates_colored: false,
+            true_candidates_logical: false,
+            max_colored_count: 8,
+            logic_tier: LogicTier::All,
+            timeout: None,
+        }
+    }
+}
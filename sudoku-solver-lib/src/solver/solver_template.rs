@@ -0,0 +1,131 @@
+//! Contains [`SolverTemplate`] for building many solvers which share the same rules.
+
+use crate::prelude::*;
+use itertools::Itertools;
+
+/// The rules of a variant, initialized once so that any number of puzzles with those rules
+/// can be solved without initializing them again.
+///
+/// Building a [`Solver`] initializes the constraints and finds every weak link, which can take
+/// far longer than solving a puzzle with few rules. A template does this once and each solver
+/// it creates shares the regions, constraints and weak links of the template's board, so only
+/// the givens of each puzzle are applied.
+///
+/// Constraints are initialized with only the givens of the builder the template was built from,
+/// so any logic they run in [`Constraint::init_board`] doesn't see the givens of each puzzle.
+/// The givens are applied afterwards as if they were placed by the solver.
+///
+/// # Example
+/// ```
+/// # use sudoku_solver_lib::prelude::*;
+/// # use std::sync::Arc;
+/// let template = SolverBuilder::default().build_template().unwrap();
+///
+/// let solver = template.solver_with_givens_string("5.6....29.9....13..4...376.........232.5......5..186.32..64.38..1.37529....821.7.").unwrap();
+/// assert!(matches!(solver.find_solution_count(0, None, Cancellation::default()), SolutionCountResult::ExactCount(1)));
+/// assert!(Arc::ptr_eq(&solver.board().data(), &template.solver().board().data()));
+///
+/// assert!(template.solver_with_givens_string("11...............................................................................").is_err());
+/// ```
+#[derive(Clone)]
+pub struct SolverTemplate {
+    solver: Solver,
+}
+
+impl SolverTemplate {
+    /// Creates a template from a built solver.
+    ///
+    /// Solvers created from the template start from this solver, including any values it already has.
+    pub fn new(solver: Solver) -> Self {
+        Self { solver }
+    }
+
+    /// The solver every solver of the template starts from.
+    pub fn solver(&self) -> &Solver {
+        &self.solver
+    }
+
+    /// The size of the grid.
+    pub fn size(&self) -> usize {
+        self.solver.board().size()
+    }
+
+    /// Creates a solver with the rules of the template and the given values.
+    pub fn solver_with_givens(&self, givens: &[(CellIndex, usize)]) -> Result<Solver, String> {
+        let given_conflicts = find_given_conflicts(givens);
+        if !given_conflicts.is_empty() {
+            return Err(given_conflicts.iter().join(", "));
+        }
+
+        let mut solver = self.solver.clone();
+        for &(cell, value) in givens {
+            let mask = solver.board().cell(cell);
+            if mask.is_solved() && mask.value() != value {
+                return Err(GivenConflict { cell, values: vec![mask.value(), value] }.to_string());
+            }
+        }
+        if let Err((cell, value)) = solver.set_solved_many(givens) {
+            return Err(format!("Failed to set given {value}{cell}"));
+        }
+
+        // Deadly patterns depend on which cells are givens, so they are found again for each puzzle.
+        if solver.assumes_uniqueness() {
+            solver.set_uniqueness_assumption(true);
+        }
+
+        Ok(solver)
+    }
+
+    /// Creates a solver with the rules of the template and the values of a givens string.
+    /// See [`SolverBuilder::with_givens_string`] for the formats which are accepted.
+    pub fn solver_with_givens_string(&self, givens: &str) -> Result<Solver, String> {
+        let size = self.size();
+        let encoding = GivensEncoding::detect(givens, size).ok_or_else(|| "Invalid givens string length".to_owned())?;
+        let cu = self.solver.cell_utility();
+        let givens = encoding
+            .decode(givens, size)?
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, value)| value != 0)
+            .map(|(index, value)| (cu.cell_index(index), value))
+            .collect::<Vec<_>>();
+        self.solver_with_givens(&givens)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_solver_template() {
+        let template = SolverBuilder::default().with_uniqueness_assumption(true).build_template().unwrap();
+        assert_eq!(template.size(), 9);
+
+        let solver = template
+            .solver_with_givens_string(
+                ".............23.4.....452....1.3.....3...4...6..7....8..6.....9.5....62.7.9...1..",
+            )
+            .unwrap();
+        assert!(solver.assumes_uniqueness());
+        assert!(!solver.board().cell(solver.cell_utility().cell(0, 0)).is_solved());
+
+        // The template itself is untouched by the solvers made from it
+        assert_eq!(template.solver().board().all_cell_masks().filter(|(_, mask)| mask.is_solved()).count(), 0);
+
+        let cu = template.solver().cell_utility();
+        let solver = template.solver_with_givens(&[(cu.cell(0, 0), 1), (cu.cell(0, 1), 2)]).unwrap();
+        assert!(solver.board().cell(cu.cell(0, 2)).count() == 7);
+        assert!(template.solver_with_givens(&[(cu.cell(0, 0), 1), (cu.cell(0, 1), 1)]).is_err());
+        assert!(template.solver_with_givens(&[(cu.cell(0, 0), 1), (cu.cell(0, 0), 2)]).is_err());
+
+        // A given which differs from a given of the template is a conflict too
+        let template = SolverBuilder::default().with_given(cu.cell(0, 0), 1).build_template().unwrap();
+        assert!(template.solver_with_givens(&[(cu.cell(0, 0), 1)]).is_ok());
+        assert_eq!(
+            template.solver_with_givens(&[(cu.cell(0, 0), 2)]).err().unwrap(),
+            "r1c1 is given conflicting values 1 and 2"
+        );
+        assert!(template.solver_with_givens_string("123").is_err());
+    }
+}
@@ -0,0 +1,83 @@
+//! Contains [`TrueCandidatesComparison`] for comparing the true candidates of a puzzle to the
+//! candidates a logical solve leaves.
+
+use crate::prelude::*;
+
+/// How a single candidate compares between the true candidates and a logical solve.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CandidateClass {
+    /// The candidate is not in any solution, and was removed by the logical solve.
+    Removed,
+    /// The candidate is not in any solution, but the logical solve could not remove it.
+    LogicalOnly,
+    /// The candidate is in at least one solution. Has the number of solutions found with it
+    /// when the solutions were counted.
+    True(Option<CandidateCount>),
+}
+
+/// The result of [`Solver::find_true_candidates_with_logical_comparison`].
+#[derive(Clone, Debug)]
+pub struct TrueCandidatesComparison {
+    /// The candidates of each cell which are in at least one solution.
+    pub true_cells: Vec<ValueMask>,
+    /// The candidates of each cell which remain after a logical solve.
+    /// This is the same as [`Self::true_cells`] when the logical solve was skipped.
+    pub logical_cells: Vec<ValueMask>,
+    /// The class of each candidate, indexed by [`CandidateIndex::index`].
+    pub classes: Vec<CandidateClass>,
+}
+
+impl TrueCandidatesComparison {
+    /// Classifies every candidate from the masks of each cell and the optional counts of each candidate.
+    pub fn new(
+        size: usize,
+        true_cells: Vec<ValueMask>,
+        logical_cells: Vec<ValueMask>,
+        candidate_counts: Option<&[CandidateCount]>,
+    ) -> Self {
+        let mut classes = Vec::with_capacity(true_cells.len() * size);
+        for (cell_index, (&true_mask, &logical_mask)) in true_cells.iter().zip(logical_cells.iter()).enumerate() {
+            for value in 1..=size {
+                let class = if true_mask.has(value) {
+                    CandidateClass::True(candidate_counts.map(|counts| counts[cell_index * size + value - 1]))
+                } else if logical_mask.has(value) {
+                    CandidateClass::LogicalOnly
+                } else {
+                    CandidateClass::Removed
+                };
+                classes.push(class);
+            }
+        }
+
+        Self { true_cells, logical_cells, classes }
+    }
+}
+
+/// The result of running [`Solver::find_true_candidates_with_logical_comparison`].
+#[derive(Clone, Debug)]
+pub enum TrueCandidatesComparisonResult {
+    None,
+    Candidates(TrueCandidatesComparison),
+    Error(String),
+}
+
+impl TrueCandidatesComparisonResult {
+    pub fn is_none(&self) -> bool {
+        matches!(self, TrueCandidatesComparisonResult::None)
+    }
+
+    pub fn is_candidates(&self) -> bool {
+        matches!(self, TrueCandidatesComparisonResult::Candidates(_))
+    }
+
+    pub fn is_error(&self) -> bool {
+        matches!(self, TrueCandidatesComparisonResult::Error(_))
+    }
+
+    pub fn comparison(&self) -> Option<&TrueCandidatesComparison> {
+        match self {
+            TrueCandidatesComparisonResult::Candidates(comparison) => Some(comparison),
+            _ => None,
+        }
+    }
+}
@@ -1,13 +1,46 @@
-//! Contains the [`TrueCandidatesCountResult`] enum.
+//! Contains the [`TrueCandidatesCountResult`] and [`CandidateCount`] enums.
 
 use crate::prelude::*;
 
+/// The number of solutions found with a candidate by [`Solver::find_true_candidates_with_count`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CandidateCount {
+    /// Every solution with the candidate was found.
+    Exact(usize),
+    /// The search stopped at the maximum count, so there may be more solutions with the candidate.
+    AtLeast(usize),
+}
+
+impl CandidateCount {
+    /// The number of solutions found, whether or not it is exact.
+    pub fn count(self) -> usize {
+        match self {
+            CandidateCount::Exact(count) | CandidateCount::AtLeast(count) => count,
+        }
+    }
+
+    /// Whether every solution with the candidate was found, rather than stopping at the maximum count.
+    pub fn is_exact(self) -> bool {
+        matches!(self, CandidateCount::Exact(_))
+    }
+}
+
+impl std::fmt::Display for CandidateCount {
+    /// Displays capped counts with a trailing `+`, such as `8+`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CandidateCount::Exact(count) => write!(f, "{count}"),
+            CandidateCount::AtLeast(count) => write!(f, "{count}+"),
+        }
+    }
+}
+
 /// The result of running the true candidates with count solve.
 #[derive(Clone)]
 pub enum TrueCandidatesCountResult {
     None,
     Solved(Box<Board>),
-    Candidates(Box<Board>, Vec<usize>),
+    Candidates(Box<Board>, Vec<CandidateCount>),
     Error(String),
 }
 
@@ -32,12 +65,18 @@ impl TrueCandidatesCountResult {
         }
     }
 
+    /// The number of solutions found with each candidate, indexed by [`CandidateIndex::index`].
     pub fn candidate_counts(&self) -> Option<Vec<usize>> {
+        self.candidate_count_results().map(|counts| counts.iter().map(|count| count.count()).collect())
+    }
+
+    /// Like [`Self::candidate_counts`], but also tells which counts stopped at the maximum.
+    pub fn candidate_count_results(&self) -> Option<&[CandidateCount]> {
         match self {
             TrueCandidatesCountResult::None
             | TrueCandidatesCountResult::Error(_)
             | TrueCandidatesCountResult::Solved(_) => None,
-            TrueCandidatesCountResult::Candidates(_, candidate_counts) => Some(candidate_counts.clone()),
+            TrueCandidatesCountResult::Candidates(_, candidate_counts) => Some(candidate_counts),
         }
     }
 }
@@ -0,0 +1,136 @@
+//! Contains [`Symmetry`] for detecting and describing the symmetry of the givens of a puzzle.
+
+use crate::prelude::*;
+use std::collections::HashSet;
+
+/// A symmetry of the grid which maps each cell to a partner cell.
+///
+/// The givens of a puzzle have a symmetry if the partner of every given is also a given.
+/// Only the positions of the givens matter, not their values.
+///
+/// # Example
+/// ```
+/// # use sudoku_solver_lib::prelude::*;
+/// let solver = SolverBuilder::default()
+///     .with_givens_string("1.......2.......................................................................3")
+///     .build()
+///     .unwrap();
+/// let cu = solver.cell_utility();
+/// assert_eq!(Symmetry::Rotational180.partner(cu.cell(0, 0)), cu.cell(8, 8));
+/// assert_eq!(Symmetry::detect(solver.board()), vec![Symmetry::DiagonalAnti]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    /// Unchanged by a half turn of the grid.
+    Rotational180,
+    /// Unchanged by a quarter turn of the grid.
+    Rotational90,
+    /// Mirrored top to bottom.
+    MirrorHorizontal,
+    /// Mirrored left to right.
+    MirrorVertical,
+    /// Mirrored across the diagonal from the top left to the bottom right.
+    DiagonalMain,
+    /// Mirrored across the diagonal from the top right to the bottom left.
+    DiagonalAnti,
+}
+
+impl Symmetry {
+    /// Every symmetry.
+    pub const ALL: [Symmetry; 6] = [
+        Self::Rotational180,
+        Self::Rotational90,
+        Self::MirrorHorizontal,
+        Self::MirrorVertical,
+        Self::DiagonalMain,
+        Self::DiagonalAnti,
+    ];
+
+    /// Returns the name of the symmetry for display purposes.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Rotational180 => "Rotational (180°)",
+            Self::Rotational90 => "Rotational (90°)",
+            Self::MirrorHorizontal => "Horizontal Mirror",
+            Self::MirrorVertical => "Vertical Mirror",
+            Self::DiagonalMain => "Main Diagonal",
+            Self::DiagonalAnti => "Anti Diagonal",
+        }
+    }
+
+    /// The cell which the symmetry maps the cell to.
+    pub fn partner(self, cell: CellIndex) -> CellIndex {
+        let size = cell.size();
+        let last = size - 1;
+        let (row, col) = cell.rc();
+        let (row, col) = match self {
+            Self::Rotational180 => (last - row, last - col),
+            Self::Rotational90 => (col, last - row),
+            Self::MirrorHorizontal => (last - row, col),
+            Self::MirrorVertical => (row, last - col),
+            Self::DiagonalMain => (col, row),
+            Self::DiagonalAnti => (last - col, last - row),
+        };
+        CellIndex::from_rc(row, col, size)
+    }
+
+    /// The cells reached by applying the symmetry to the cell until it returns to the cell, starting with the cell.
+    ///
+    /// These cells must either all be givens or all not be givens for the givens to have the symmetry.
+    pub fn orbit(self, cell: CellIndex) -> Vec<CellIndex> {
+        let mut orbit = vec![cell];
+        let mut partner = self.partner(cell);
+        while partner != cell {
+            orbit.push(partner);
+            partner = self.partner(partner);
+        }
+        orbit
+    }
+
+    /// Whether the partner of each of the cells is also one of the cells.
+    pub fn is_symmetric(self, cells: &[CellIndex]) -> bool {
+        let cell_set: HashSet<CellIndex> = cells.iter().copied().collect();
+        cells.iter().all(|&cell| cell_set.contains(&self.partner(cell)))
+    }
+
+    /// The symmetries of the solved cells of the board, which are the givens of a puzzle which hasn't been solved.
+    ///
+    /// A board without solved cells has every symmetry.
+    pub fn detect(board: &Board) -> Vec<Symmetry> {
+        let solved_cells: Vec<CellIndex> =
+            board.all_cell_masks().filter(|(_, mask)| mask.is_solved()).map(|(cell, _)| cell).collect();
+        Self::ALL.into_iter().filter(|symmetry| symmetry.is_symmetric(&solved_cells)).collect()
+    }
+}
+
+impl std::fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_symmetry() {
+        let cu = CellUtility::new(9);
+        for symmetry in Symmetry::ALL {
+            for cell in cu.all_cells() {
+                assert!(symmetry.orbit(cell).contains(&symmetry.partner(cell)));
+            }
+        }
+        assert_eq!(Symmetry::Rotational90.orbit(cu.cell(0, 1)).len(), 4);
+        assert_eq!(Symmetry::Rotational90.orbit(cu.cell(4, 4)), vec![cu.cell(4, 4)]);
+        assert_eq!(Symmetry::DiagonalMain.orbit(cu.cell(1, 2)), vec![cu.cell(1, 2), cu.cell(2, 1)]);
+
+        // A pinwheel has quarter turn symmetry and so half turn symmetry, but no mirror symmetry
+        let pinwheel = [cu.cell(0, 1), cu.cell(1, 8), cu.cell(8, 7), cu.cell(7, 0)];
+        let symmetries =
+            Symmetry::ALL.into_iter().filter(|symmetry| symmetry.is_symmetric(&pinwheel)).collect::<Vec<_>>();
+        assert_eq!(symmetries, vec![Symmetry::Rotational180, Symmetry::Rotational90]);
+
+        assert_eq!(Symmetry::detect(&Board::default()), Symmetry::ALL.to_vec());
+    }
+}
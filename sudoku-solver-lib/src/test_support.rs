@@ -0,0 +1,637 @@
+//! Contains [`PropertyTester`] and helpers for property testing constraints and logical steps
+//! against randomly generated puzzles, [`check_weak_links_match_enforce`] for testing the
+//! weak links of a constraint against its rule, and the [`assert_step!`](crate::assert_step)
+//! macro for testing what a logical step does to a board.
+//!
+//! Only available when built with the `test-support` feature, which is meant to be enabled
+//! in `[dev-dependencies]`.
+
+use crate::prelude::*;
+use itertools::Itertools;
+use rand::{seq::SliceRandom, SeedableRng};
+use std::{collections::BTreeSet, sync::Arc};
+
+// Re-exported so that builders of random rules don't need their own rand dependency.
+pub use rand::{rngs::StdRng, Rng};
+
+const NO_SOLUTION_ERROR: &str = "The rules have no solution";
+
+/// Creates a random puzzle with the rules of the builder.
+///
+/// A random solution is found and then the given number of its cells, chosen at random, are
+/// added to the builder as givens. The puzzle always has at least one solution, but it is not
+/// necessarily unique.
+///
+/// The rules are made twice, once to find the solution and once for the puzzle, because a
+/// builder's constraints can only be initialized by one solver.
+///
+/// Returns an error if the builder can't be built or has no solution.
+pub fn random_puzzle(
+    mut make_rules: impl FnMut() -> SolverBuilder,
+    num_givens: usize,
+    rng: &mut StdRng,
+) -> Result<SolverBuilder, String> {
+    let solver = make_rules().build()?;
+    let solution = match solver.find_random_solution() {
+        SingleSolutionResult::Solved(solution) => solution,
+        SingleSolutionResult::None => return Err(NO_SOLUTION_ERROR.to_owned()),
+        SingleSolutionResult::Error(error) => return Err(error),
+    };
+
+    let mut cells = solution.all_cells().collect::<Vec<_>>();
+    cells.shuffle(rng);
+    let givens = cells.into_iter().take(num_givens).map(|cell| (cell, solution.cell(cell).value())).collect::<Vec<_>>();
+    Ok(make_rules().with_givens(&givens))
+}
+
+/// Checks that the logical steps of the solver never remove a candidate which is part of a solution,
+/// and never report that a puzzle with a solution is invalid.
+///
+/// Runs a full logical solve on a copy of the solver, checking the board after every step.
+pub fn check_logic_keeps_solutions(solver: &Solver) -> Result<(), String> {
+    let true_candidates = match solver.find_true_candidates() {
+        SingleSolutionResult::Solved(board) => board,
+        // Logic can do anything to a puzzle without solutions
+        SingleSolutionResult::None => return Ok(()),
+        SingleSolutionResult::Error(error) => return Err(error),
+    };
+
+    let mut solver = solver.clone();
+    loop {
+        let step_result = solver.run_single_logical_step();
+        let desc = step_result.description().map(|desc| desc.to_string()).unwrap_or_default();
+        if step_result.is_invalid() {
+            return Err(format!("Logic found a puzzle with a solution invalid: {desc}"));
+        }
+
+        let board = solver.board();
+        for (cell, mask) in true_candidates.all_cell_masks() {
+            let missing = mask.unsolved() & !board.cell(cell).unsolved();
+            if !missing.is_empty() {
+                return Err(format!("Logic removed true candidates {missing}{cell}: {desc}"));
+            }
+        }
+
+        if step_result.is_none() || board.is_solved() {
+            return Ok(());
+        }
+    }
+}
+
+/// Checks that the brute force engines agree with each other about the puzzle.
+///
+/// The solution count must be the same whether or not the board arena is used, and the single
+/// solution searches must find a solution exactly when the count is not zero.
+pub fn check_counts_agree(solver: &Solver, maximum_count: usize) -> Result<(), String> {
+    let mut arena_solver = solver.clone();
+    arena_solver.set_board_arena(true);
+    let mut boxed_solver = solver.clone();
+    boxed_solver.set_board_arena(false);
+
+    let arena_count = arena_solver.find_solution_count(maximum_count, None, None);
+    let boxed_count = boxed_solver.find_solution_count(maximum_count, None, None);
+    if arena_count != boxed_count {
+        return Err(format!("Counts differ: {arena_count:?} with the arena and {boxed_count:?} without"));
+    }
+
+    let has_solution = match arena_count {
+        SolutionCountResult::None => false,
+        SolutionCountResult::ExactCount(count) | SolutionCountResult::AtLeastCount(count) => count > 0,
+        SolutionCountResult::Error(error) => return Err(error),
+    };
+    let searches = [
+        ("first solution", solver.find_first_solution()),
+        ("fastest solution", solver.find_fastest_solution()),
+        ("random solution", solver.find_random_solution()),
+        ("true candidates", solver.find_true_candidates()),
+    ];
+    for (name, result) in searches {
+        if let SingleSolutionResult::Error(error) = result {
+            return Err(format!("The {name} search failed: {error}"));
+        }
+        if result.is_solved() != has_solution {
+            return Err(format!("The {name} search disagrees with the count of {arena_count:?}"));
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses candidates written the way [`EliminationList`] displays them, such as `-5r1c23;-6r12c1`,
+/// which is 5 in r1c2 and r1c3, and 6 in r1c1 and r2c1.
+///
+/// The leading `-` is optional, so placements can be written as `5r1c2`. Groups of cells are
+/// separated by commas, such as `-5r1c12,r2c3`, and several values can share the cells, such as
+/// `-34r1c1`. On boards larger than 9x9, the values, rows and columns are separated by commas too,
+/// such as `-10,11r1c10,11`.
+///
+/// # Example
+/// ```
+/// # use sudoku_solver_lib::prelude::*;
+/// # use sudoku_solver_lib::test_support::parse_candidates;
+/// let cu = CellUtility::new(9);
+/// let candidates = parse_candidates(9, "-5r1c23;67r2c1").unwrap();
+/// let cells = [cu.cell(0, 1), cu.cell(0, 2), cu.cell(1, 0)];
+/// let expected = vec![cells[0].candidate(5), cells[1].candidate(5), cells[2].candidate(6), cells[2].candidate(7)];
+/// assert_eq!(candidates, expected);
+/// assert!(parse_candidates(9, "-5r1c0").is_err());
+/// ```
+pub fn parse_candidates(size: usize, text: &str) -> Result<Vec<CandidateIndex>, String> {
+    let cu = CellUtility::new(size);
+    let mut candidates = Vec::new();
+    for group in text.split(';').map(str::trim).filter(|group| !group.is_empty()) {
+        let error = || format!("Invalid candidates: {group}");
+        let group_lower = group.to_ascii_lowercase();
+        let group_lower = group_lower.strip_prefix('-').unwrap_or(&group_lower);
+        let (values, cells) = group_lower.split_at(group_lower.find('r').ok_or_else(error)?);
+        let values = parse_compact_indexes(size, values).ok_or_else(error)?;
+        let cells = parse_compact_cells(cu, cells).ok_or_else(error)?;
+        candidates.extend(values.into_iter().flat_map(|value| cells.iter().map(move |cell| cell.candidate(value))));
+    }
+    Ok(candidates)
+}
+
+/// Parses values, rows or columns from 1 to the size, which are single digits on boards up to 9x9.
+fn parse_compact_indexes(size: usize, text: &str) -> Option<Vec<usize>> {
+    let indexes: Option<Vec<usize>> = if size <= 9 {
+        text.chars().map(|c| c.to_digit(10).map(|digit| digit as usize)).collect()
+    } else {
+        text.split(',').map(|index| index.parse().ok()).collect()
+    };
+    indexes.filter(|indexes| !indexes.is_empty() && indexes.iter().all(|index| (1..=size).contains(index)))
+}
+
+/// Parses cells written the way [`CellUtility::compact_name`] writes them.
+fn parse_compact_cells(cu: CellUtility, text: &str) -> Option<Vec<CellIndex>> {
+    let size = cu.size();
+    let mut parts = text.split('r');
+    if parts.next() != Some("") {
+        return None;
+    }
+
+    let mut cells = Vec::new();
+    for part in parts {
+        let (rows, cols) = part.strip_suffix(',').unwrap_or(part).split_once('c')?;
+        for row in parse_compact_indexes(size, rows)? {
+            for &col in parse_compact_indexes(size, cols)?.iter() {
+                cells.push(cu.cell(row - 1, col - 1));
+            }
+        }
+    }
+    (!cells.is_empty()).then_some(cells)
+}
+
+/// Describes candidates the way [`parse_candidates`] reads them.
+fn describe_candidates(candidates: &BTreeSet<CandidateIndex>) -> String {
+    if candidates.is_empty() {
+        "nothing".to_owned()
+    } else {
+        candidates.iter().copied().collect::<EliminationList>().to_string()
+    }
+}
+
+/// Runs the logical step once on the board and checks that it places exactly the given values and
+/// eliminates exactly the given candidates, written as [`parse_candidates`] reads them.
+///
+/// The eliminations are only those the step makes beyond what its placements rule out, so a step
+/// which only places values eliminates nothing. The board is left as the step changed it, so the
+/// next step can be checked in turn. This is what [`assert_step!`](crate::assert_step) calls.
+pub fn check_step(
+    board: &mut Board,
+    step: &dyn LogicalStep,
+    eliminates: &[&str],
+    places: &[&str],
+) -> Result<(), String> {
+    let size = board.size();
+    let expected_eliminations = parse_candidates(size, &eliminates.join(";"))?.into_iter().collect::<BTreeSet<_>>();
+    let expected_placements = parse_candidates(size, &places.join(";"))?.into_iter().collect::<BTreeSet<_>>();
+
+    let before = board.clone();
+    let result = step.run(board, true, &Cancellation::default());
+    if result.is_invalid() {
+        return Err(format!("{} found the board invalid: {result}", step.name()));
+    }
+
+    let placements = board
+        .all_cells()
+        .filter(|&cell| !before.cell(cell).is_solved() && board.cell(cell).is_solved())
+        .map(|cell| cell.candidate(board.cell(cell).value()))
+        .collect::<BTreeSet<_>>();
+    if placements != expected_placements {
+        return Err(format!(
+            "{} placed {} instead of {}: {result}",
+            step.name(),
+            describe_candidates(&placements),
+            describe_candidates(&expected_placements)
+        ));
+    }
+
+    // Leave out what the placements alone rule out
+    let mut placed = before;
+    for candidate in placements.iter() {
+        let (cell, value) = candidate.cell_index_and_value();
+        if !placed.set_solved(cell, value) {
+            return Err(format!("{} placed {candidate}, which breaks the rules: {result}", step.name()));
+        }
+    }
+    let eliminations = placed
+        .all_cells()
+        .filter(|&cell| !placed.cell(cell).is_solved())
+        .flat_map(|cell| {
+            let removed = placed.cell(cell).unsolved() & !board.cell(cell).unsolved();
+            removed.into_iter().map(move |value| cell.candidate(value))
+        })
+        .collect::<BTreeSet<_>>();
+    if eliminations != expected_eliminations {
+        return Err(format!(
+            "{} eliminated {} instead of {}: {result}",
+            step.name(),
+            describe_candidates(&eliminations),
+            describe_candidates(&expected_eliminations)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Asserts what a logical step does when it runs once on a board, with the eliminations and
+/// placements written as [`parse_candidates`](crate::test_support::parse_candidates) reads them.
+///
+/// See [`check_step`](crate::test_support::check_step), which panics with its error message here.
+///
+/// # Example
+/// ```
+/// # use sudoku_solver_lib::prelude::*;
+/// # use sudoku_solver_lib::{assert_step, test_support::parse_candidates};
+/// let mut board = Board::default();
+/// board.clear_candidates(parse_candidates(9, "-3456789r1c12").unwrap().into_iter());
+/// assert_step!(board, NakedTuple, eliminates: ["-12r1c3456789"], places: []);
+/// assert_step!(board, NakedTuple, eliminates: ["-12r2c123,r3c123"], places: []);
+/// assert_step!(board, NakedTuple, eliminates: [], places: []);
+/// ```
+#[macro_export]
+macro_rules! assert_step {
+    ($board:expr, $step:expr, eliminates: [$($elim:expr),* $(,)?], places: [$($place:expr),* $(,)?] $(,)?) => {
+        if let Err(error) = $crate::test_support::check_step(&mut $board, &$step, &[$($elim),*], &[$($place),*]) {
+            panic!("{}", error);
+        }
+    };
+}
+
+/// Creates a board of the given size with only the constraint, initialized.
+fn constraint_board(constraint: Arc<dyn Constraint>, size: usize) -> Result<Board, String> {
+    let mut board = Board::new(size, &[], vec![constraint]);
+    board.init_constraints()?;
+    Ok(board)
+}
+
+/// Finds the pairs of candidates of the cells which [`Constraint::enforce`] rejects when both are set.
+///
+/// Candidates which the board no longer has are left out.
+fn find_enforced_pairs(board: &Board, cells: &[CellIndex]) -> Vec<(CandidateIndex, CandidateIndex)> {
+    let constraint = &board.constraints()[0];
+    let candidates = cells
+        .iter()
+        .flat_map(|&cell| board.cell(cell).unsolved().into_iter().map(move |value| cell.candidate(value)))
+        .collect::<Vec<_>>();
+
+    let mut pairs = Vec::new();
+    for (index, &candidate0) in candidates.iter().enumerate() {
+        let (cell0, value0) = candidate0.cell_index_and_value();
+        for &candidate1 in &candidates[index + 1..] {
+            let (cell1, value1) = candidate1.cell_index_and_value();
+            if cell0 == cell1 {
+                continue;
+            }
+
+            let mut pair_board = board.clone();
+            pair_board.fill_unchecked(cell0, value0);
+            pair_board.fill_unchecked(cell1, value1);
+            if constraint.enforce(&pair_board, cell0, value0).is_invalid()
+                || constraint.enforce(&pair_board, cell1, value1).is_invalid()
+            {
+                pairs.push((candidate0, candidate1));
+            }
+        }
+    }
+    pairs
+}
+
+/// Finds every pair of candidates which [`Constraint::enforce`] rejects when both cells are set
+/// on an otherwise empty grid of the given size.
+///
+/// These are the weak links which a constraint with a rule between pairs of cells should have,
+/// so the result can be used to write [`Constraint::get_weak_links`]. Only the relevant cells of
+/// the constraint are checked. Each pair is listed once, with the lower candidate first.
+///
+/// The constraint is initialized on its own board, so the [`Arc`] must not be shared.
+pub fn enforced_pairs(
+    constraint: Arc<dyn Constraint>,
+    size: usize,
+) -> Result<Vec<(CandidateIndex, CandidateIndex)>, String> {
+    let board = constraint_board(constraint, size)?;
+    let cells =
+        board.constraints()[0].relevant_cells().into_iter().sorted_by_key(|cell| cell.index()).dedup().collect_vec();
+    Ok(find_enforced_pairs(&board, &cells))
+}
+
+/// Checks that the weak links of the constraint are exactly the pairs of candidates which
+/// [`Constraint::enforce`] rejects, on an otherwise empty grid of the given size.
+///
+/// A weak link which enforce allows removes solutions, and a pair which enforce rejects without
+/// a weak link leaves logic to find out late. Both are reported, with the first few pairs
+/// of each. Links within a cell and links to candidates which the constraint removes from
+/// the empty grid are ignored.
+///
+/// This is meant for constraints whose rule is between pairs of cells, which are expected to
+/// implement both. Use a small grid to keep the check fast, as every pair of candidates of the
+/// relevant and linked cells is tried. The constraint is initialized on its own board, so the
+/// [`Arc`] must not be shared.
+///
+/// # Example
+/// ```
+/// # use sudoku_solver_lib::prelude::*;
+/// # use sudoku_solver_lib::test_support::check_weak_links_match_enforce;
+/// # use std::sync::Arc;
+/// /// The first two cells of row 1 can't be consecutive.
+/// #[derive(Debug)]
+/// struct NonConsecutivePair {
+///     links: bool,
+/// }
+///
+/// impl Constraint for NonConsecutivePair {
+///     fn name(&self) -> &str {
+///         "Non-Consecutive Pair"
+///     }
+///
+///     fn enforce(&self, board: &Board, _cell: CellIndex, _val: usize) -> LogicalStepResult {
+///         let cu = board.cell_utility();
+///         let (mask0, mask1) = (board.cell(cu.cell(0, 0)), board.cell(cu.cell(0, 1)));
+///         if mask0.is_solved() && mask1.is_solved() && mask0.value().abs_diff(mask1.value()) == 1 {
+///             LogicalStepResult::Invalid(None)
+///         } else {
+///             LogicalStepResult::None
+///         }
+///     }
+///
+///     fn relevant_cells(&self) -> Vec<CellIndex> {
+///         let cu = CellUtility::new(4);
+///         vec![cu.cell(0, 0), cu.cell(0, 1)]
+///     }
+///
+///     fn get_weak_links(&self, size: usize) -> Vec<(CandidateIndex, CandidateIndex)> {
+///         let cu = CellUtility::new(size);
+///         let (cell0, cell1) = (cu.cell(0, 0), cu.cell(0, 1));
+///         let max_value = if self.links { size } else { 0 };
+///         (1..max_value)
+///             .flat_map(|value| [(cell0.candidate(value), cell1.candidate(value + 1)), (cell0.candidate(value + 1), cell1.candidate(value))])
+///             .collect()
+///     }
+/// }
+///
+/// assert!(check_weak_links_match_enforce(Arc::new(NonConsecutivePair { links: true }), 4).is_ok());
+/// assert!(check_weak_links_match_enforce(Arc::new(NonConsecutivePair { links: false }), 4).is_err());
+/// ```
+pub fn check_weak_links_match_enforce(constraint: Arc<dyn Constraint>, size: usize) -> Result<(), String> {
+    const MAX_REPORTED: usize = 5;
+
+    let board = constraint_board(constraint, size)?;
+    let constraint = &board.constraints()[0];
+    let has_candidate = |candidate: CandidateIndex| board.cell(candidate.cell_index()).has(candidate.value());
+    let links = constraint
+        .get_weak_links(size)
+        .into_iter()
+        .filter(|(candidate0, candidate1)| candidate0.cell_index() != candidate1.cell_index())
+        .filter(|&(candidate0, candidate1)| has_candidate(candidate0) && has_candidate(candidate1))
+        .map(|(candidate0, candidate1)| {
+            (candidate0.index().min(candidate1.index()), candidate0.index().max(candidate1.index()))
+        })
+        .collect::<BTreeSet<_>>();
+
+    let cells = constraint
+        .relevant_cells()
+        .into_iter()
+        .chain(links.iter().flat_map(|&(index0, index1)| {
+            [CandidateIndex::new(index0, size).cell_index(), CandidateIndex::new(index1, size).cell_index()]
+        }))
+        .sorted_by_key(|cell| cell.index())
+        .dedup()
+        .collect_vec();
+    let enforced = find_enforced_pairs(&board, &cells)
+        .into_iter()
+        .map(|(candidate0, candidate1)| (candidate0.index(), candidate1.index()))
+        .collect::<BTreeSet<_>>();
+
+    let describe = |pairs: Vec<&(usize, usize)>, problem: &str| {
+        let count = pairs.len();
+        let mut text = pairs
+            .into_iter()
+            .take(MAX_REPORTED)
+            .map(|&(index0, index1)| {
+                format!("{} and {}", CandidateIndex::new(index0, size), CandidateIndex::new(index1, size))
+            })
+            .join(", ");
+        if count > MAX_REPORTED {
+            text += &format!(" and {} more", count - MAX_REPORTED);
+        }
+        (count > 0).then(|| format!("{} {problem}: {text}", constraint.name()))
+    };
+    let mismatches = [
+        describe(links.difference(&enforced).collect(), "has weak links which enforce allows"),
+        describe(enforced.difference(&links).collect(), "rejects pairs in enforce which aren't weakly linked"),
+    ]
+    .into_iter()
+    .flatten()
+    .collect_vec();
+
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(mismatches.join("; "))
+    }
+}
+
+/// Runs the property checks of this module against many random puzzles.
+///
+/// The puzzles are reproducible from the seed, and a failure reports the givens of the puzzle
+/// which failed.
+///
+/// # Example
+/// ```
+/// # use sudoku_solver_lib::prelude::*;
+/// # use sudoku_solver_lib::test_support::PropertyTester;
+/// PropertyTester::new(1).with_cases(3).run(|_| SolverBuilder::default());
+/// ```
+pub struct PropertyTester {
+    rng: StdRng,
+    seed: u64,
+    cases: usize,
+    num_givens: Option<usize>,
+    maximum_count: usize,
+}
+
+impl PropertyTester {
+    /// Creates a tester which runs 20 cases, seeded with the given value.
+    pub fn new(seed: u64) -> Self {
+        Self { rng: StdRng::seed_from_u64(seed), seed, cases: 20, num_givens: None, maximum_count: 100 }
+    }
+
+    /// Sets the number of random puzzles to check.
+    #[must_use]
+    pub fn with_cases(mut self, cases: usize) -> Self {
+        self.cases = cases;
+        self
+    }
+
+    /// Sets the number of givens of each puzzle. By default, each puzzle has a random number of
+    /// givens between a quarter and a half of the cells.
+    #[must_use]
+    pub fn with_num_givens(mut self, num_givens: usize) -> Self {
+        self.num_givens = Some(num_givens);
+        self
+    }
+
+    /// Sets the maximum number of solutions to count. Lower counts make each case faster.
+    #[must_use]
+    pub fn with_maximum_count(mut self, maximum_count: usize) -> Self {
+        self.maximum_count = maximum_count;
+        self
+    }
+
+    /// Checks every invariant for each case, panicking on the first failure.
+    ///
+    /// The function creates the rules for each case, and can use the random number generator
+    /// to create random constraints. It must not add givens, and must make the same rules
+    /// from the same state of the generator. Cases whose rules have no solution
+    /// are skipped, but rules which fail to build panic, as does skipping every case.
+    pub fn run(mut self, mut make_builder: impl FnMut(&mut StdRng) -> SolverBuilder) {
+        let mut skipped = 0;
+        for case in 0..self.cases {
+            // The same rules are made again from a copy of the generator's state
+            let rules_rng = self.rng.clone();
+            let size = make_builder(&mut self.rng).size();
+            let num_givens = self.num_givens.unwrap_or_else(|| {
+                let num_cells = size * size;
+                self.rng.gen_range(num_cells / 4..=num_cells / 2)
+            });
+            let make_rules = || make_builder(&mut rules_rng.clone());
+
+            // Rules without a solution can't make a puzzle, which isn't a failure of the checks
+            let puzzle = match random_puzzle(make_rules, num_givens, &mut self.rng) {
+                Ok(puzzle) => puzzle,
+                Err(error) if error == NO_SOLUTION_ERROR => {
+                    skipped += 1;
+                    continue;
+                }
+                Err(error) => panic!("Case {case} of seed {} failed to make a puzzle: {error}", self.seed),
+            };
+            let solver = puzzle.build().unwrap_or_else(|error| {
+                panic!("Case {case} of seed {} failed to build a puzzle with a solution: {error}", self.seed)
+            });
+
+            let result =
+                check_logic_keeps_solutions(&solver).and_then(|_| check_counts_agree(&solver, self.maximum_count));
+            if let Err(error) = result {
+                panic!("Case {case} of seed {} with givens {} failed: {error}", self.seed, solver.board());
+            }
+        }
+
+        if self.cases > 0 && skipped == self.cases {
+            panic!("Every case of seed {} had rules without a solution", self.seed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_random_puzzle() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let puzzle = random_puzzle(SolverBuilder::default, 30, &mut rng).unwrap().build().unwrap();
+        assert_eq!(puzzle.board().solved_count(), 30);
+        assert!(puzzle.find_first_solution().is_solved());
+    }
+
+    /// The first two cells of row 1 can't sum to 5, with an extra link from 1 to 1 when `extra_link` is set.
+    #[derive(Debug)]
+    struct NoFive {
+        extra_link: bool,
+    }
+
+    impl Constraint for NoFive {
+        fn name(&self) -> &str {
+            "No Five"
+        }
+
+        fn enforce(&self, board: &Board, _cell: CellIndex, _val: usize) -> LogicalStepResult {
+            let masks = self.relevant_cells().into_iter().map(|cell| board.cell(cell)).collect_vec();
+            let (mask0, mask1) = (masks[0], masks[1]);
+            if mask0.is_solved() && mask1.is_solved() && mask0.value() + mask1.value() == 5 {
+                LogicalStepResult::Invalid(None)
+            } else {
+                LogicalStepResult::None
+            }
+        }
+
+        fn relevant_cells(&self) -> Vec<CellIndex> {
+            let cu = CellUtility::new(4);
+            vec![cu.cell(0, 0), cu.cell(0, 1)]
+        }
+
+        fn get_weak_links(&self, _size: usize) -> Vec<(CandidateIndex, CandidateIndex)> {
+            let cells = self.relevant_cells();
+            let (cell0, cell1) = (cells[0], cells[1]);
+            let mut links = (1..=4).map(|value| (cell0.candidate(value), cell1.candidate(5 - value))).collect_vec();
+            if self.extra_link {
+                links.push((cell1.candidate(1), cell0.candidate(1)));
+            }
+            links
+        }
+    }
+
+    #[test]
+    fn test_check_weak_links_match_enforce() {
+        let pairs = enforced_pairs(Arc::new(NoFive { extra_link: false }), 4).unwrap();
+        let pairs = pairs.iter().map(|(candidate0, candidate1)| format!("{candidate0}-{candidate1}")).collect_vec();
+        assert_eq!(pairs, vec!["1r1c1-4r1c2", "2r1c1-3r1c2", "3r1c1-2r1c2", "4r1c1-1r1c2"]);
+
+        assert!(check_weak_links_match_enforce(Arc::new(NoFive { extra_link: false }), 4).is_ok());
+        let error = check_weak_links_match_enforce(Arc::new(NoFive { extra_link: true }), 4).unwrap_err();
+        assert_eq!(error, "No Five has weak links which enforce allows: 1r1c1 and 1r1c2");
+    }
+
+    #[test]
+    fn test_parse_candidates() {
+        let candidates = parse_candidates(16, "-10,11r1c10,r2c1").unwrap();
+        assert_eq!(candidates.iter().map(|candidate| candidate.to_string()).join(" "), "10r1c10 10r2c1 11r1c10 11r2c1");
+        for text in ["r1c1", "-5", "-5r1", "-5c1", "-17r1c1", "-5r0c1", "-5x1c1"] {
+            assert!(parse_candidates(16, text).is_err(), "{text}");
+        }
+        assert_eq!(parse_candidates(9, "").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_check_step() {
+        let mut board = Board::default();
+        board.clear_candidates(parse_candidates(9, "-12345678r1c1").unwrap().into_iter());
+        let error = check_step(&mut board.clone(), &NakedSingle, &["-9r1c2"], &["9r1c1"]).unwrap_err();
+        assert!(error.starts_with("Naked Single eliminated nothing instead of -9r1c2"), "{error}");
+        let error = check_step(&mut board.clone(), &NakedSingle, &[], &[]).unwrap_err();
+        assert!(error.starts_with("Naked Single placed -9r1c1 instead of nothing"), "{error}");
+        crate::assert_step!(board, NakedSingle, eliminates: [], places: ["9r1c1"]);
+    }
+
+    #[test]
+    fn test_standard_rules() {
+        PropertyTester::new(42).with_cases(5).run(|_| SolverBuilder::default());
+        PropertyTester::new(43).with_cases(2).with_num_givens(60).run(|_| SolverBuilder::new(6));
+    }
+
+    #[test]
+    #[should_panic(expected = "failed to make a puzzle")]
+    fn test_rules_which_fail_to_build() {
+        PropertyTester::new(44).with_cases(1).run(|_| SolverBuilder::new(9).with_givens_string("1"));
+    }
+}
@@ -69,6 +69,24 @@ impl ValueMask {
     /// ignoring the solved bit.
     pub const CANDIDATES_MASK: u32 = !Self::VALUE_SOLVED_MASK;
 
+    /// The largest grid size whose values fit in a mask beside the solved bit.
+    pub const MAX_SIZE: usize = 31;
+
+    /// Returns an error if a grid of the given size is larger than [`ValueMask::MAX_SIZE`].
+    ///
+    /// # Example
+    /// ```
+    /// # use sudoku_solver_lib::prelude::*;
+    /// assert!(ValueMask::check_size(16).is_ok());
+    /// assert_eq!(ValueMask::check_size(32).unwrap_err(), "Grid size 32 is larger than the maximum of 31");
+    /// ```
+    pub fn check_size(size: usize) -> Result<(), String> {
+        if size > Self::MAX_SIZE {
+            return Err(format!("Grid size {size} is larger than the maximum of {}", Self::MAX_SIZE));
+        }
+        Ok(())
+    }
+
     /// Create a new ValueMask with no values set.
     ///
     /// # Examples
@@ -354,6 +372,19 @@ impl ValueMask {
         self.mask
     }
 
+    /// Creates a mask from a raw `u32`, such as one returned by [`ValueMask::raw`].
+    ///
+    /// # Example
+    /// ```
+    /// # use sudoku_solver_lib::value_mask::ValueMask;
+    /// let mask = ValueMask::from_raw(0b10000 | ValueMask::VALUE_SOLVED_MASK);
+    /// assert!(mask.is_solved());
+    /// assert_eq!(mask.value(), 5);
+    /// ```
+    pub fn from_raw(mask: u32) -> Self {
+        ValueMask { mask }
+    }
+
     /// Returns just the mask of value bits without the value set bit.
     ///
     /// # Example
@@ -703,7 +734,11 @@ impl ValueMask {
 
     /// Get a random value.
     pub fn random(self) -> usize {
-        let mut rng = rand::thread_rng();
+        self.random_with(&mut rand::thread_rng())
+    }
+
+    /// Get a random value chosen by the given random number generator.
+    pub fn random_with(self, rng: &mut impl Rng) -> usize {
         let count = rng.gen_range(0..self.count());
         self.nth(count).unwrap()
     }
@@ -0,0 +1,33 @@
+//! Contains [`WeakLinkStats`] for reporting the weak links each constraint added to a board.
+
+/// How the weak links returned by a constraint's
+/// [`Constraint::get_weak_links`](crate::constraint::Constraint::get_weak_links) were used.
+///
+/// Links which the board already has, whether from the sudoku rules, an earlier constraint or
+/// the same constraint, are ignored as duplicates. A large number of duplicates usually means
+/// the constraint generates every pair of candidates where far fewer would do.
+///
+/// See [`Board::weak_link_stats`](crate::board::Board::weak_link_stats).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeakLinkStats {
+    /// The name of the constraint.
+    pub constraint: String,
+    /// The number of pairs the constraint returned.
+    pub links: usize,
+    /// The pairs which weren't already linked.
+    pub added: usize,
+    /// The pairs which were already linked, and so changed nothing.
+    pub duplicates: usize,
+    /// The pairs of a candidate with itself, which remove the candidate from the board.
+    pub self_eliminations: usize,
+}
+
+impl std::fmt::Display for WeakLinkStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} weak links, {} added, {} duplicates ignored, {} self-eliminations",
+            self.constraint, self.links, self.added, self.duplicates, self.self_eliminations
+        )
+    }
+}
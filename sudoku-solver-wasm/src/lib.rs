@@ -1,9 +1,57 @@
+//! WebAssembly bindings for the solver.
+//!
+//! Every entry point takes a JSON message in the same format as the websocket listener
+//! and reports each response as a JSON string through the `receive_result` callback.
+//!
+//! [`find_solution`], [`count_solutions`] and [`find_true_candidates`] instead take the
+//! f-puzzles data directly and return the final response as a JS object, throwing an
+//! `Error` if the puzzle is invalid.
+//!
+//! [`WasmSolver`] skips the message protocol entirely and lets the page set cells and
+//! run logical steps on a puzzle it keeps in memory.
+//!
+//! [`solve`] and [`solve_cancellable`] block until the operation is done, so they are
+//! best run inside a web worker. [`solve_async`] returns a `Promise` and hands control
+//! back to the event loop while counting solutions, so it can also run on the page itself.
+//!
+//! A typical worker wraps [`solve_async`] so that it can receive cancel messages
+//! while a count is running:
+//!
+//! ```js
+//! // worker.js
+//! importScripts('sudoku_solver_wasm.js');
+//! const { solve_async, CancellationHandle } = wasm_bindgen;
+//!
+//! wasm_bindgen('sudoku_solver_wasm_bg.wasm').then(() => {
+//!     let cancellation = new CancellationHandle();
+//!     self.onmessage = async event => {
+//!         if (JSON.parse(event.data).command === 'cancel') {
+//!             cancellation.cancel();
+//!             return;
+//!         }
+//!         cancellation = new CancellationHandle();
+//!         await solve_async(event.data, response => self.postMessage(response), cancellation);
+//!     };
+//! });
+//!
+//! // page.js
+//! const worker = new Worker('worker.js');
+//! worker.onmessage = event => console.log(JSON.parse(event.data));
+//! worker.postMessage(JSON.stringify({ nonce: 1, command: 'count', dataType: 'fpuzzles', data: puzzle }));
+//! ```
+
 mod utils;
+mod wasm_solver;
 
+use serde::Serialize;
 use standard_constraints::message_handler::*;
+use std::{cell::RefCell, rc::Rc, time::Duration};
 use sudoku_solver_lib::prelude::Cancellation;
 use utils::set_panic_hook;
 use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+pub use wasm_solver::WasmSolver;
 
 // When the `wee_alloc` feature is enabled, use `wee_alloc` as the global
 // allocator.
@@ -29,11 +77,199 @@ impl SendResult for SendResultWasm {
     }
 }
 
+/// The time since the Unix epoch from the JS clock, as wasm has no clock in the standard library.
+fn js_clock() -> Duration {
+    Duration::from_secs_f64(js_sys::Date::now() / 1000.0)
+}
+
+/// Creates a message handler which can time out puzzles with the JS clock.
+fn new_message_handler(send_result: Box<dyn SendResult>) -> MessageHandler {
+    MessageHandler::new(send_result).with_clock(js_clock)
+}
+
 #[wasm_bindgen]
 pub fn solve(message: &str, receive_result: &js_sys::Function) {
     set_panic_hook();
 
     let send_result = Box::new(SendResultWasm::new(receive_result));
-    let mut message_handler = MessageHandler::new(send_result);
+    let mut message_handler = new_message_handler(send_result);
     message_handler.handle_message(message, Cancellation::default());
 }
+
+/// A handle which JS can use to cancel a long running [`solve_cancellable`] call,
+/// such as counting solutions or finding true candidates.
+///
+/// WASM runs on a single thread, so a cancel only takes effect once the solver
+/// hands control back to JS. Long operations report progress through the
+/// `receive_result` callback, so calling [`CancellationHandle::cancel`] from
+/// inside that callback stops the operation at the next check.
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct CancellationHandle {
+    cancellation: Cancellation,
+}
+
+#[wasm_bindgen]
+impl CancellationHandle {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the operation using this handle.
+    pub fn cancel(&self) {
+        self.cancellation.cancel();
+    }
+
+    /// Clear a previous cancel so the handle can be used again.
+    pub fn reset(&self) {
+        self.cancellation.reset();
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn cancelled(&self) -> bool {
+        self.cancellation.check()
+    }
+}
+
+/// The same as [`solve`], but stops early when the given handle is cancelled.
+#[wasm_bindgen]
+pub fn solve_cancellable(message: &str, receive_result: &js_sys::Function, cancellation: &CancellationHandle) {
+    set_panic_hook();
+
+    let send_result = Box::new(SendResultWasm::new(receive_result));
+    let mut message_handler = new_message_handler(send_result);
+    message_handler.handle_message(message, cancellation.cancellation.clone());
+}
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_name = setTimeout)]
+    fn set_timeout(handler: &js_sys::Function, timeout: i32);
+}
+
+/// Lets the browser run other tasks, such as rendering, before continuing.
+async fn yield_to_event_loop() {
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| set_timeout(&resolve, 0));
+    let _ = JsFuture::from(promise).await;
+}
+
+/// The same as [`solve_cancellable`], but returns a `Promise` which resolves once the
+/// final response has been sent.
+///
+/// Counts are done in chunks, yielding to the event loop and reporting the current
+/// count through `receive_result` between chunks. Other operations yield once before
+/// starting and then run to completion.
+#[wasm_bindgen]
+pub fn solve_async(
+    message: String,
+    receive_result: js_sys::Function,
+    cancellation: &CancellationHandle,
+) -> js_sys::Promise {
+    set_panic_hook();
+
+    let cancellation = cancellation.cancellation.clone();
+    wasm_bindgen_futures::future_to_promise(async move {
+        let send_result = Box::new(SendResultWasm::new(&receive_result));
+        let mut message_handler = new_message_handler(send_result);
+        message_handler.handle_message_async(&message, cancellation, yield_to_event_loop).await;
+        Ok(JsValue::UNDEFINED)
+    })
+}
+
+/// Keeps the most recent response, which is the final one once the message has been handled.
+#[derive(Clone, Default)]
+struct SendResultLast {
+    result: Rc<RefCell<Option<String>>>,
+}
+
+impl SendResult for SendResultLast {
+    fn send_result(&mut self, result: &str) {
+        *self.result.borrow_mut() = Some(result.to_owned());
+    }
+}
+
+/// Runs a command on f-puzzles data and converts the final response into a JS object.
+///
+/// `invalid` responses are turned into an `Error` so that JS callers can use try/catch.
+fn handle_structured(command: &str, fpuzzles_data: &str, cancellation: Cancellation) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+
+    let message = serde_json::json!({
+        "nonce": 0,
+        "command": command,
+        "dataType": "fpuzzles",
+        "data": fpuzzles_data,
+    });
+
+    let send_result = SendResultLast::default();
+    let mut message_handler = new_message_handler(Box::new(send_result.clone()));
+    message_handler.handle_message(&message.to_string(), cancellation);
+
+    let result = send_result.result.take().ok_or_else(|| js_sys::Error::new("No response was sent."))?;
+    let response: serde_json::Value =
+        serde_json::from_str(&result).map_err(|error| js_sys::Error::new(&error.to_string()))?;
+    if response["type"] == "invalid" {
+        let message = response["message"].as_str().unwrap_or("Invalid puzzle.");
+        return Err(js_sys::Error::new(message).into());
+    }
+
+    Ok(response.serialize(&serde_wasm_bindgen::Serializer::json_compatible())?)
+}
+
+/// Finds a random solution to the puzzle.
+///
+/// Returns `{ type: "solved", solution: number[] }`, with one value per cell.
+#[wasm_bindgen]
+pub fn find_solution(fpuzzles_data: &str) -> Result<JsValue, JsValue> {
+    handle_structured("solve", fpuzzles_data, Cancellation::default())
+}
+
+/// Counts the solutions to the puzzle.
+///
+/// Returns `{ type: "count", count: number, inProgress: false }`. Throws if the
+/// puzzle has no solutions or the count was cancelled.
+#[wasm_bindgen]
+pub fn count_solutions(fpuzzles_data: &str, cancellation: &CancellationHandle) -> Result<JsValue, JsValue> {
+    handle_structured("count", fpuzzles_data, cancellation.cancellation.clone())
+}
+
+/// Finds the candidates which appear in at least one solution.
+///
+/// Returns `{ type: "truecandidates", solutionsPerCandidate: number[], cappedPerCandidate: boolean[] }`,
+/// with `size` entries per cell. A capped count stopped at the maximum, so there may be more solutions.
+#[wasm_bindgen]
+pub fn find_true_candidates(fpuzzles_data: &str, cancellation: &CancellationHandle) -> Result<JsValue, JsValue> {
+    handle_structured("truecandidates", fpuzzles_data, cancellation.cancellation.clone())
+}
+
+/// Generates a puzzle with a unique solution using the constraints in the f-puzzles data.
+///
+/// `difficulty` is "easy", "medium" or "hard", or undefined for any difficulty.
+/// Returns the givens string of the generated puzzle.
+///
+/// Only available when built with the `generator` feature.
+#[cfg(feature = "generator")]
+#[wasm_bindgen]
+pub fn generate_puzzle(fpuzzles_data: &str, difficulty: Option<String>) -> Result<String, JsValue> {
+    use standard_constraints::prelude::*;
+    use sudoku_solver_lib::prelude::*;
+
+    set_panic_hook();
+
+    let solver = PuzzleInputParser::new()
+        .parse_as(fpuzzles_data, PuzzleInputKind::FPuzzles)
+        .and_then(SolverBuilder::build)
+        .map_err(|error| js_sys::Error::new(&error))?;
+    let mut generator = PuzzleGenerator::new(solver);
+    if let Some(difficulty) = difficulty {
+        let difficulty = [PuzzleDifficulty::Easy, PuzzleDifficulty::Medium, PuzzleDifficulty::Hard]
+            .into_iter()
+            .find(|d| d.name().eq_ignore_ascii_case(&difficulty))
+            .ok_or_else(|| js_sys::Error::new(&format!("Unknown difficulty: {difficulty}")))?;
+        generator = generator.with_difficulty(difficulty);
+    }
+
+    let puzzle = generator.generate(None).map_err(|error| js_sys::Error::new(&error))?;
+    Ok(puzzle.givens_string())
+}
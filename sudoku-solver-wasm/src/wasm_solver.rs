@@ -0,0 +1,236 @@
+//! Contains the [`WasmSolver`] class for using the solver directly from JS.
+
+use crate::utils::set_panic_hook;
+use serde::Serialize;
+use standard_constraints::prelude::*;
+use sudoku_solver_lib::prelude::*;
+use wasm_bindgen::prelude::*;
+
+/// A puzzle which JS can modify and solve one step at a time, without going
+/// through the JSON message protocol.
+///
+/// Cells are addressed by zero based row and column. Candidate masks use bit
+/// `value - 1` for each value which is still possible in the cell.
+#[wasm_bindgen]
+pub struct WasmSolver {
+    /// The puzzle as it was created, before any values were set or steps were run.
+    initial_solver: Solver,
+    solver: Solver,
+    /// The values set from JS, or 0 for cells which have not been set.
+    values: Vec<usize>,
+}
+
+#[derive(Serialize)]
+struct StepResponse {
+    #[serde(rename = "type")]
+    response_type: &'static str,
+    description: String,
+}
+
+#[derive(Serialize)]
+struct HouseDensityResponse {
+    name: String,
+    kind: &'static str,
+    candidates: usize,
+    #[serde(rename = "unsolvedCells")]
+    unsolved_cells: usize,
+    #[serde(rename = "valuePositions")]
+    value_positions: Vec<usize>,
+}
+
+#[derive(Serialize)]
+struct CandidateDensityResponse {
+    #[serde(rename = "cellCounts")]
+    cell_counts: Vec<usize>,
+    houses: Vec<HouseDensityResponse>,
+}
+
+#[wasm_bindgen]
+impl WasmSolver {
+    /// Creates a solver from f-puzzles data, as used by the `load` parameter of an f-puzzles link.
+    pub fn from_fpuzzles(data: &str) -> Result<WasmSolver, JsValue> {
+        set_panic_hook();
+
+        let board = FPuzzlesBoard::from_lzstring_json(data).map_err(|error| js_sys::Error::new(&error))?;
+        let solver = FPuzzlesParser::new().parse_board(&board, false).map_err(|error| js_sys::Error::new(&error))?;
+        Ok(Self::new(solver))
+    }
+
+    /// Creates a solver from any puzzle text that the solver recognizes: a givens string,
+    /// f-puzzles data, an f-puzzles link or f-puzzles JSON.
+    pub fn from_input(input: &str) -> Result<WasmSolver, JsValue> {
+        set_panic_hook();
+
+        let solver = PuzzleInputParser::new()
+            .parse(input)
+            .and_then(SolverBuilder::build)
+            .map_err(|error| js_sys::Error::new(&error))?;
+        Ok(Self::new(solver))
+    }
+
+    /// Creates a classic sudoku of the given size from a givens string,
+    /// in the format accepted by [`SolverBuilder::with_givens_string`].
+    pub fn from_givens(size: usize, givens: &str) -> Result<WasmSolver, JsValue> {
+        set_panic_hook();
+
+        let solver =
+            SolverBuilder::new(size).with_givens_string(givens).build().map_err(|error| js_sys::Error::new(&error))?;
+        Ok(Self::new(solver))
+    }
+
+    fn new(solver: Solver) -> Self {
+        let values = vec![0; solver.board().num_cells()];
+        Self { initial_solver: solver.clone(), solver, values }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn size(&self) -> usize {
+        self.solver.size()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn solved(&self) -> bool {
+        self.solver.board().is_solved()
+    }
+
+    /// Sets a cell to a value.
+    ///
+    /// Returns false and leaves the puzzle unchanged if the value is not a candidate of the cell.
+    pub fn set_cell(&mut self, row: usize, col: usize, value: usize) -> Result<bool, JsValue> {
+        let cell = self.cell(row, col)?;
+        let previous_value = self.values[cell.index()];
+        if previous_value == value {
+            return Ok(true);
+        }
+        if previous_value != 0 {
+            self.clear_cell(row, col)?;
+        }
+
+        let mask = self.solver.board().cell(cell);
+        let set = if mask.is_solved() {
+            mask.value() == value
+        } else {
+            let mut solver = self.solver.clone();
+            let set = solver.set_solved(cell, value);
+            if set {
+                self.solver = solver;
+            }
+            set
+        };
+
+        if set {
+            self.values[cell.index()] = value;
+        } else if previous_value != 0 {
+            self.values[cell.index()] = previous_value;
+            self.rebuild();
+        }
+        Ok(set)
+    }
+
+    /// Clears a value previously set with [`WasmSolver::set_cell`].
+    ///
+    /// Candidates removed by steps are restored, since they may have depended on the cleared value.
+    pub fn clear_cell(&mut self, row: usize, col: usize) -> Result<(), JsValue> {
+        let cell = self.cell(row, col)?;
+        if self.values[cell.index()] != 0 {
+            self.values[cell.index()] = 0;
+            self.rebuild();
+        }
+        Ok(())
+    }
+
+    /// Clears all set values and steps, returning to the puzzle as it was created.
+    pub fn reset(&mut self) {
+        self.values.fill(0);
+        self.solver = self.initial_solver.clone();
+    }
+
+    /// Runs a single logical step.
+    ///
+    /// Returns `{ type, description }`, where type is "none", "changed", "invalid" or "solved".
+    pub fn step(&mut self) -> Result<JsValue, JsValue> {
+        let response = if self.solver.board().is_solved() {
+            StepResponse { response_type: "solved", description: "Solved!".to_owned() }
+        } else {
+            match self.solver.run_single_logical_step() {
+                LogicalStepResult::None => {
+                    StepResponse { response_type: "none", description: "No logical steps found.".to_owned() }
+                }
+                LogicalStepResult::Changed(desc) => StepResponse {
+                    response_type: "changed",
+                    description: desc.map(|desc| desc.to_string()).unwrap_or_default(),
+                },
+                LogicalStepResult::Invalid(desc) => StepResponse {
+                    response_type: "invalid",
+                    description: desc.map(|desc| desc.to_string()).unwrap_or_default(),
+                },
+            }
+        };
+        Ok(serde_wasm_bindgen::to_value(&response)?)
+    }
+
+    /// The candidate mask of every cell, in row order.
+    pub fn candidates(&self) -> Vec<u32> {
+        self.solver.board().all_cell_masks().map(|(_, mask)| mask.value_bits()).collect()
+    }
+
+    /// How constrained each area of the grid is, for drawing a heatmap.
+    ///
+    /// Returns `{ cellCounts, houses }`, where cellCounts is the number of candidates of every
+    /// cell in row order, and each house is `{ name, kind, candidates, unsolvedCells, valuePositions }`.
+    pub fn candidate_density(&self) -> Result<JsValue, JsValue> {
+        let density = self.solver.board().candidate_density();
+        let response = CandidateDensityResponse {
+            cell_counts: density.cell_counts().to_vec(),
+            houses: density
+                .houses()
+                .iter()
+                .map(|house| HouseDensityResponse {
+                    name: house.name.clone(),
+                    kind: house.kind.name(),
+                    candidates: house.candidates,
+                    unsolved_cells: house.unsolved_cells,
+                    value_positions: house.value_positions.clone(),
+                })
+                .collect(),
+        };
+        Ok(serde_wasm_bindgen::to_value(&response)?)
+    }
+
+    /// The value of every cell in row order, or 0 for unsolved cells.
+    pub fn values(&self) -> Vec<u32> {
+        self.solver
+            .board()
+            .all_cell_masks()
+            .map(|(_, mask)| if mask.is_solved() { mask.value() as u32 } else { 0 })
+            .collect()
+    }
+
+    fn cell(&self, row: usize, col: usize) -> Result<CellIndex, JsValue> {
+        let size = self.solver.size();
+        if row >= size || col >= size {
+            return Err(js_sys::Error::new(&format!("Cell ({row}, {col}) is outside of the grid.")).into());
+        }
+        Ok(self.solver.cell_utility().cell(row, col))
+    }
+
+    /// Starts again from the initial puzzle and applies the values which are still set.
+    fn rebuild(&mut self) {
+        let mut solver = self.initial_solver.clone();
+        let cu = solver.cell_utility();
+        for (index, value) in self.values.iter_mut().enumerate() {
+            let cell = cu.cell_index(index);
+            if *value == 0 || solver.board().cell(cell).is_solved() {
+                continue;
+            }
+
+            let mut next_solver = solver.clone();
+            if next_solver.set_solved(cell, *value) {
+                solver = next_solver;
+            } else {
+                *value = 0;
+            }
+        }
+        self.solver = solver;
+    }
+}
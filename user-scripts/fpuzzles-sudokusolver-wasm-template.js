@@ -507,6 +507,10 @@
         }
 
         const handleInvalid = function(response) {
+            if (response.type === 'unsupportedsize') {
+                log(response.message);
+                return true;
+            }
             if (response.type === 'invalid') {
                 if (response.message && response.message.length > 0) {
                     log(response.message);